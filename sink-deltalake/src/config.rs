@@ -7,11 +7,13 @@
 //! - Batch processing and performance tuning
 //! - Environment variable overrides
 
+use chrono::format::{Item, StrftimeItems};
 use danube_connect_core::{
     ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorError,
-    ConnectorResult,
+    ConnectorResult, SubscriptionType,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 
 /// Complete configuration for the Delta Lake Sink Connector
@@ -45,6 +47,25 @@ pub struct DeltaLakeConfig {
     #[serde(default)]
     pub s3_allow_http: bool,
 
+    /// Use path-style addressing (bucket in path instead of hostname)
+    /// Required for MinIO, Ceph, and other S3-compatible stores without virtual-host DNS
+    #[serde(default)]
+    pub s3_path_style: bool,
+
+    /// IAM role to assume via STS instead of using static access keys
+    /// Example: "arn:aws:iam::123456789012:role/delta-writer"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_role_arn: Option<String>,
+
+    /// Session name used when assuming `s3_role_arn` (default: connector_name)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_session_name: Option<String>,
+
+    /// Path to a web identity token file (IRSA / EKS pod identity)
+    /// Usually provided by the platform through AWS_WEB_IDENTITY_TOKEN_FILE
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_web_identity_token_file: Option<String>,
+
     /// Azure storage account name (required if storage_backend = "azure")
     /// Credentials from environment: AZURE_STORAGE_ACCOUNT_KEY or AZURE_STORAGE_SAS_TOKEN
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -54,11 +75,87 @@ pub struct DeltaLakeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub azure_container: Option<String>,
 
+    /// Azure AD tenant ID for service principal authentication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_tenant_id: Option<String>,
+
+    /// Azure AD application (client) ID for service principal authentication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_client_id: Option<String>,
+
+    /// Name of the environment variable holding the service principal secret
+    /// Example: "AZURE_CLIENT_SECRET"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_client_secret_env: Option<String>,
+
+    /// Name of the environment variable holding a SAS token
+    /// Example: "AZURE_STORAGE_SAS_TOKEN"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_sas_token_env: Option<String>,
+
+    /// Authenticate with the Azure CLI login of the current user (local development)
+    #[serde(default)]
+    pub azure_use_azure_cli: bool,
+
     /// GCP project ID (required if storage_backend = "gcs")
     /// Credentials from environment: GOOGLE_APPLICATION_CREDENTIALS
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gcp_project_id: Option<String>,
 
+    /// Path to a GCS service account JSON key file
+    /// Takes precedence over GOOGLE_APPLICATION_CREDENTIALS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcs_service_account_path: Option<String>,
+
+    /// Inline GCS service account JSON key
+    /// Prefer setting it through the GCS_SERVICE_ACCOUNT_KEY environment variable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcs_service_account_key: Option<String>,
+
+    /// Maximum number of Delta tables flushed concurrently
+    #[serde(default = "default_flush_parallelism")]
+    pub flush_parallelism: usize,
+
+    /// Maximum estimated payload bytes written per Delta commit for a route
+    /// Larger runtime batches are split into multiple commits (default: 64 MiB)
+    #[serde(default = "default_max_buffer_bytes")]
+    pub max_buffer_bytes: usize,
+
+    /// Global budget for payload bytes being converted and written at once
+    /// across all tables (default: 256 MiB)
+    #[serde(default = "default_memory_budget_bytes")]
+    pub memory_budget_bytes: usize,
+
+    /// Reload-and-retry attempts when a commit conflicts with another writer
+    #[serde(default = "default_commit_conflict_retries")]
+    pub commit_conflict_retries: u32,
+
+    /// Initial backoff between conflict retries in milliseconds (doubles per attempt)
+    #[serde(default = "default_commit_retry_backoff_ms")]
+    pub commit_retry_backoff_ms: u64,
+
+    /// Commit latency (smoothed, in milliseconds) above which writes to a table
+    /// are paused to slow consumption down (disabled when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_latency_threshold_ms: Option<u64>,
+
+    /// Pause applied before each write while a table's commits lag (default: 1000)
+    #[serde(default = "default_backpressure_pause_ms")]
+    pub backpressure_pause_ms: u64,
+
+    /// Local directory for the durable spill log (disabled when unset)
+    /// Converted batches are persisted here until committed and replayed on startup
+    /// Requires exactly_once on every route, so the replay and the runtime's
+    /// redelivery of the same records aren't both written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spill_dir: Option<String>,
+
+    /// Additional object store options passed through as-is
+    /// Applied last, so they take precedence over the typed settings above
+    /// Example: { "aws_conditional_put" = "etag", "timeout" = "60s" }
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub storage_options: HashMap<String, String>,
+
     /// Routes: Danube topics → Delta Lake tables
     #[serde(default)]
     pub routes: Vec<TopicMapping>,
@@ -92,6 +189,59 @@ impl Default for WriteMode {
     }
 }
 
+/// Payload encoding of a route's messages
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadFormat {
+    /// JSON payloads, already deserialized by the runtime (default)
+    Json,
+    /// Avro binary datums decoded with `avro_schema_path`
+    Avro,
+    /// Protobuf messages decoded with `protobuf_descriptor_path` / `protobuf_message`
+    Protobuf,
+}
+
+impl Default for PayloadFormat {
+    fn default() -> Self {
+        PayloadFormat::Json
+    }
+}
+
+/// Policy for existing tables whose schema doesn't match the route's mappings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaCheckMode {
+    /// Refuse to start writing to an incompatible table (default)
+    Fail,
+    /// Log incompatibilities and continue
+    Warn,
+    /// Skip the check
+    Off,
+}
+
+impl Default for SchemaCheckMode {
+    fn default() -> Self {
+        SchemaCheckMode::Fail
+    }
+}
+
+/// Record validation mode for field mappings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationMode {
+    /// Missing or mismatched fields are written as nulls where possible (default)
+    Lenient,
+    /// Records with missing required fields or type mismatches are dead-lettered
+    /// (to the route's `retry.dlq_topic`) or skipped; the rest of the batch is written
+    Strict,
+}
+
+impl Default for ValidationMode {
+    fn default() -> Self {
+        ValidationMode::Lenient
+    }
+}
+
 /// Delta Lake table schema field definition (DEPRECATED - use FieldMapping)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaField {
@@ -109,7 +259,8 @@ pub struct SchemaField {
 /// Field mapping: JSON path to Delta Lake column
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldMapping {
-    /// JSON path to extract value (e.g., "payment_id", "user.profile.name")
+    /// JSON path to extract value (e.g., "payment_id", "user.profile.name",
+    /// "$.payload.items[0].price", "$['order id']")
     pub json_path: String,
 
     /// Pre-split path parts for efficient extraction (not serialized)
@@ -118,20 +269,252 @@ pub struct FieldMapping {
     pub path_parts: Vec<String>,
 
     /// Delta Lake column name
+    /// Defaults to the last segment of json_path when omitted
+    #[serde(default)]
     pub column: String,
 
     /// Arrow data type (e.g., "Utf8", "Int64", "Float64", "Boolean", "Timestamp")
+    /// Use "Timestamp" for UTC-adjusted instants and "TimestampNtz" for naive wall-clock times
     pub data_type: String,
 
     /// Whether the field is nullable (default: true)
     #[serde(default = "default_true")]
     pub nullable: bool,
+
+    /// Coerce values to data_type before writing (e.g., "42" -> 42, 1 -> "1")
+    /// Values that can't be coerced are written as null
+    #[serde(default)]
+    pub coerce: bool,
+
+    /// Source format for Timestamp / TimestampNtz columns:
+    /// "unix_s", "unix_ms", "unix_us", "rfc3339", or a strftime pattern (e.g., "%d/%m/%Y %H:%M:%S")
+    /// Values that can't be parsed are written as null
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+
+    /// Value used when the field is missing or null: a literal in the column's
+    /// representation (e.g., 0, "unknown") or the expression "now()"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
 }
 
 impl FieldMapping {
     /// Initialize path_parts from json_path (called after deserialization)
     pub fn init_path_parts(&mut self) {
-        self.path_parts = self.json_path.split('.').map(String::from).collect();
+        self.path_parts = parse_json_path(&self.json_path);
+    }
+
+    /// Resolve the final column name for this field under a naming mode
+    pub fn resolved_column(&self, naming: ColumnNaming) -> String {
+        let last_segment = parse_json_path(&self.json_path)
+            .into_iter()
+            .rev()
+            .find(|part| part.parse::<usize>().is_err())
+            .unwrap_or_default();
+        let column = if self.column.is_empty() {
+            last_segment.as_str()
+        } else {
+            self.column.as_str()
+        };
+
+        match naming {
+            ColumnNaming::AsIs => column.to_string(),
+            ColumnNaming::SnakeCase => to_snake_case(column),
+        }
+    }
+}
+
+/// Column naming mode for field mappings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnNaming {
+    /// Use column names exactly as configured (default)
+    AsIs,
+    /// Normalize column names to snake_case ("Order ID" -> "order_id", "userName" -> "user_name")
+    SnakeCase,
+}
+
+impl Default for ColumnNaming {
+    fn default() -> Self {
+        ColumnNaming::AsIs
+    }
+}
+
+/// Placeholder in a route's `to` template replaced by the route_by value
+pub const ROUTE_PLACEHOLDER: &str = "{route}";
+
+/// Check that a routing value is safe to embed in a table path
+///
+/// Only letters, digits, '_' and '-' are allowed, so record content can't
+/// redirect writes outside the template (e.g., "../other").
+pub fn is_valid_route_value(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Split a source path into key / array-index segments
+///
+/// Supports dotted paths ("user.profile.name") and JSONPath-style syntax with an
+/// optional root ("$.payload.items[0].price") and bracket-quoted keys for names
+/// containing dots or spaces ("$['order id']"). Array indexes are kept as
+/// numeric segments and resolved against arrays during extraction.
+pub fn parse_json_path(path: &str) -> Vec<String> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+                let quote = chars.next_if(|q| *q == '\'' || *q == '"');
+                let mut segment = String::new();
+                while let Some(inner) = chars.next() {
+                    match quote {
+                        Some(q) if inner == q && chars.peek() == Some(&']') => {
+                            chars.next();
+                            break;
+                        }
+                        None if inner == ']' => break,
+                        _ => segment.push(inner),
+                    }
+                }
+                parts.push(segment.trim().to_string());
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+/// Convert an arbitrary field name to snake_case
+///
+/// Non-alphanumeric characters become separators and camelCase / PascalCase
+/// word boundaries are split; repeated separators are collapsed.
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let boundary = prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower);
+            if boundary && !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+        }
+
+        result.extend(c.to_lowercase());
+    }
+
+    result.trim_end_matches('_').to_string()
+}
+
+/// Partition column derived from an event timestamp field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampPartition {
+    /// JSON path of the event timestamp (RFC 3339 string or epoch milliseconds)
+    pub source_field: String,
+
+    /// Pre-split path parts for efficient extraction (not serialized)
+    #[serde(skip)]
+    pub path_parts: Vec<String>,
+
+    /// Partition column name (e.g., "event_date")
+    pub column: String,
+
+    /// chrono format string applied to the timestamp in UTC (default: "%Y-%m-%d")
+    #[serde(default = "default_partition_format")]
+    pub format: String,
+}
+
+impl TimestampPartition {
+    /// Initialize path_parts from source_field (called after deserialization)
+    pub fn init_path_parts(&mut self) {
+        self.path_parts = parse_json_path(&self.source_field);
+    }
+}
+
+/// Retry policy for transient storage failures on a route
+///
+/// Commits failing with retryable errors (throttling, timeouts, unavailable
+/// object store) are retried with exponential backoff and jitter. Once the
+/// attempts are exhausted the batch goes to `dlq_topic`, if configured, or the
+/// error is returned to the runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total commit attempts, including the first (default: 3)
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Backoff before the first retry in milliseconds, doubled per attempt (default: 500)
+    #[serde(default = "default_retry_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Upper bound on the backoff in milliseconds (default: 30000)
+    #[serde(default = "default_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// Random jitter as a fraction of the backoff, 0.0 to 1.0 (default: 0.2)
+    #[serde(default = "default_retry_jitter")]
+    pub jitter: f64,
+
+    /// Danube topic receiving the batch's records after the retries are exhausted,
+    /// and records failing strict validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dlq_topic: Option<String>,
+}
+
+/// Change-data-capture settings for a route
+///
+/// Each record carries an operation field; deletes remove matching rows and all
+/// other operations are upserted through a Delta MERGE on the key columns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdcConfig {
+    /// JSON path of the change operation field (e.g., "op")
+    pub op_field: String,
+
+    /// Pre-split path parts for efficient extraction (not serialized)
+    #[serde(skip)]
+    pub op_path_parts: Vec<String>,
+
+    /// Columns identifying a row (must be field mapping columns)
+    pub key_columns: Vec<String>,
+
+    /// Operation values treated as deletes, case-insensitive (default: ["d", "delete"])
+    #[serde(default = "default_delete_values")]
+    pub delete_values: Vec<String>,
+}
+
+impl CdcConfig {
+    /// Initialize op_path_parts from op_field (called after deserialization)
+    pub fn init_path_parts(&mut self) {
+        self.op_path_parts = parse_json_path(&self.op_field);
     }
 }
 
@@ -144,9 +527,28 @@ pub struct TopicMapping {
     /// Subscription name for this consumer
     pub subscription: String,
 
+    /// Subscription type: Exclusive, Shared, FailOver
+    #[serde(default = "default_subscription_type")]
+    pub subscription_type: SubscriptionType,
+
     /// Delta Lake table path (e.g., "s3://bucket/path/to/table")
+    /// With route_by, a template where "{route}" is replaced by the routing value
     pub to: String,
 
+    /// Content-based routing: JSON path whose value selects the destination table
+    /// Example: route_by = "event_type", to = "s3://bucket/tables/{route}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_by: Option<String>,
+
+    /// Pre-split route_by path parts for efficient extraction (not serialized)
+    #[serde(skip)]
+    pub route_by_path_parts: Vec<String>,
+
+    /// Routing value used when a record has no route_by value
+    /// Without it, such records are rejected as invalid data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_route: Option<String>,
+
     /// Expected schema subject for validation (schema already exists on topic)
     /// The runtime validates incoming messages match this schema
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -156,25 +558,195 @@ pub struct TopicMapping {
     /// Runtime provides pre-deserialized serde_json::Value based on schema
     pub field_mappings: Vec<FieldMapping>,
 
+    /// Payload format: json (default), avro, or protobuf
+    /// Binary formats are decoded into JSON before field mappings are applied
+    #[serde(default)]
+    pub payload_format: PayloadFormat,
+
+    /// Path to the Avro writer schema (.avsc) for payload_format = "avro"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avro_schema_path: Option<String>,
+
+    /// Path to a compiled Protobuf descriptor set for payload_format = "protobuf"
+    /// Generate with: protoc --include_imports --descriptor_set_out=events.desc events.proto
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protobuf_descriptor_path: Option<String>,
+
+    /// Fully-qualified Protobuf message name (e.g., "events.v1.Payment")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protobuf_message: Option<String>,
+
+    /// Column naming mode applied to field mapping columns (as_is or snake_case)
+    #[serde(default)]
+    pub column_naming: ColumnNaming,
+
+    /// Delta table properties applied when the connector creates the table
+    /// Example: { "delta.appendOnly" = "true", "delta.logRetentionDuration" = "interval 30 days" }
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub table_properties: HashMap<String, String>,
+
+    /// Derive a date partition column from an event timestamp field
+    /// Tables created by the connector are partitioned by this column
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_from_timestamp: Option<TimestampPartition>,
+
     /// Write mode (append or overwrite)
     #[serde(default)]
     pub write_mode: WriteMode,
 
+    /// Change-data-capture mode: apply insert/update/delete events via MERGE
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdc: Option<CdcConfig>,
+
+    /// Predicate limiting an overwrite to matching rows (requires write_mode = "overwrite")
+    /// Example: "event_date = '2024-01-01'"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace_where: Option<String>,
+
     /// Include Danube metadata as a JSON column (_danube_metadata)
     #[serde(default)]
     pub include_danube_metadata: bool,
+
+    /// Override the global max_buffer_bytes for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_buffer_bytes: Option<usize>,
+
+    /// Exactly-once delivery: tag each commit with the Danube offset of every topic
+    /// partition as a Delta transaction (app id + version) and skip already-committed
+    /// offsets on restart; requires an Exclusive or FailOver subscription
+    #[serde(default)]
+    pub exactly_once: bool,
+
+    /// Field validation mode (lenient or strict)
+    /// Strict rejects records as invalid data so the runtime can route them to a DLQ
+    #[serde(default)]
+    pub validation: ValidationMode,
+
+    /// Retry policy for transient storage failures, with optional DLQ topic
+    /// Without it, failures are returned to the runtime's [retry] handling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+
+    /// Schema compatibility check when opening an existing table (fail, warn, or off)
+    #[serde(default)]
+    pub schema_check: SchemaCheckMode,
+
+    /// Per-route storage overrides (region, endpoint, account, credentials)
+    /// Unset fields fall back to the global [deltalake] settings
+    #[serde(default)]
+    pub storage: StorageOverrides,
+}
+
+/// Per-route storage overrides
+///
+/// Allows a single connector instance to write to tables living in different
+/// buckets, regions, or accounts. The bucket itself is taken from the route's
+/// `to` URL; these settings only adjust how the object store is reached.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageOverrides {
+    /// Override S3 region for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_region: Option<String>,
+
+    /// Override S3 endpoint for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_endpoint: Option<String>,
+
+    /// Override IAM role to assume for this route (cross-account writes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_role_arn: Option<String>,
+
+    /// Override Azure storage account for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_storage_account: Option<String>,
+
+    /// Override Azure container for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_container: Option<String>,
+
+    /// Override GCP project ID for this route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcp_project_id: Option<String>,
+
+    /// Prefix for credential environment variables used by this route
+    /// Example: "ANALYTICS" reads ANALYTICS_AWS_ACCESS_KEY_ID instead of AWS_ACCESS_KEY_ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials_env_prefix: Option<String>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_delete_values() -> Vec<String> {
+    vec!["d".to_string(), "delete".to_string()]
+}
+
+fn default_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_retry_max_backoff_ms() -> u64 {
+    30_000
+}
+
+fn default_retry_jitter() -> f64 {
+    0.2
+}
+
+fn default_subscription_type() -> SubscriptionType {
+    SubscriptionType::Shared
+}
+
+fn default_partition_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_max_buffer_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+fn default_memory_budget_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
+fn default_flush_parallelism() -> usize {
+    4
+}
+
+fn default_commit_conflict_retries() -> u32 {
+    5
+}
+
+fn default_commit_retry_backoff_ms() -> u64 {
+    100
+}
+
+fn default_backpressure_pause_ms() -> u64 {
+    1000
+}
+
 impl DeltaLakeSinkConfig {
-    /// Initialize path_parts for all field mappings
+    /// Initialize path_parts and resolve column names for all field mappings
     fn init_path_parts(&mut self) {
         for mapping in &mut self.deltalake.routes {
+            let naming = mapping.column_naming;
             for field_mapping in &mut mapping.field_mappings {
                 field_mapping.init_path_parts();
+                field_mapping.column = field_mapping.resolved_column(naming);
+            }
+            if let Some(partition) = &mut mapping.partition_from_timestamp {
+                partition.init_path_parts();
+            }
+            if let Some(cdc) = &mut mapping.cdc {
+                cdc.init_path_parts();
+            }
+            if let Some(route_by) = &mapping.route_by {
+                mapping.route_by_path_parts = parse_json_path(route_by);
             }
         }
     }
@@ -208,6 +780,18 @@ impl DeltaLakeSinkConfig {
             self.deltalake.s3_endpoint = Some(endpoint);
         }
 
+        if let Ok(role_arn) = env::var("AWS_ROLE_ARN") {
+            self.deltalake.s3_role_arn = Some(role_arn);
+        }
+
+        if let Ok(session_name) = env::var("AWS_ROLE_SESSION_NAME") {
+            self.deltalake.s3_session_name = Some(session_name);
+        }
+
+        if let Ok(token_file) = env::var("AWS_WEB_IDENTITY_TOKEN_FILE") {
+            self.deltalake.s3_web_identity_token_file = Some(token_file);
+        }
+
         if let Ok(account) = env::var("AZURE_STORAGE_ACCOUNT") {
             self.deltalake.azure_storage_account = Some(account);
         }
@@ -215,6 +799,14 @@ impl DeltaLakeSinkConfig {
         if let Ok(project) = env::var("GCP_PROJECT_ID") {
             self.deltalake.gcp_project_id = Some(project);
         }
+
+        if let Ok(path) = env::var("GCS_SERVICE_ACCOUNT_PATH") {
+            self.deltalake.gcs_service_account_path = Some(path);
+        }
+
+        if let Ok(key) = env::var("GCS_SERVICE_ACCOUNT_KEY") {
+            self.deltalake.gcs_service_account_key = Some(key);
+        }
     }
 }
 
@@ -234,38 +826,100 @@ impl ConfigValidate for DeltaLakeSinkConfig {
             ));
         }
 
-        // Validate cloud provider configuration
-        match self.deltalake.storage_backend {
-            StorageBackend::S3 => {
-                if self.deltalake.s3_region.is_none() {
-                    return Err(ConnectorError::config(
-                        "s3_region is required when storage_backend = 's3'",
-                    ));
-                }
+        if self.deltalake.max_buffer_bytes == 0 || self.deltalake.memory_budget_bytes == 0 {
+            return Err(ConnectorError::config(
+                "max_buffer_bytes and memory_budget_bytes must be greater than 0",
+            ));
+        }
+
+        if self.deltalake.flush_parallelism == 0 {
+            return Err(ConnectorError::config(
+                "flush_parallelism must be greater than 0",
+            ));
+        }
+
+        if self.deltalake.commit_latency_threshold_ms == Some(0) {
+            return Err(ConnectorError::config(
+                "commit_latency_threshold_ms must be greater than 0",
+            ));
+        }
+
+        // Spilled records aren't acknowledged yet, so the runtime redelivers them
+        // as well; only the exactly-once filter keeps them from being written twice
+        if self.deltalake.spill_dir.is_some() {
+            if let Some(mapping) = self.deltalake.routes.iter().find(|m| !m.exactly_once) {
+                return Err(ConnectorError::config(format!(
+                    "spill_dir requires exactly_once = true on every route (route '{}')",
+                    mapping.from
+                )));
             }
-            StorageBackend::Azure => {
-                if self.deltalake.azure_storage_account.is_none() {
-                    return Err(ConnectorError::config(
-                        "azure_storage_account is required when storage_backend = 'azure'",
-                    ));
+        }
+
+        // Validate each topic mapping
+        for mapping in &self.deltalake.routes {
+            // Validate cloud provider configuration (route overrides take precedence)
+            let storage = &mapping.storage;
+            match self.deltalake.storage_backend {
+                StorageBackend::S3 => {
+                    if storage.s3_region.is_none() && self.deltalake.s3_region.is_none() {
+                        return Err(ConnectorError::config(format!(
+                            "s3_region is required when storage_backend = 's3' (route '{}')",
+                            mapping.from
+                        )));
+                    }
+                    if self.deltalake.s3_session_name.is_some()
+                        && storage.s3_role_arn.is_none()
+                        && self.deltalake.s3_role_arn.is_none()
+                    {
+                        return Err(ConnectorError::config(format!(
+                            "s3_session_name requires s3_role_arn (route '{}')",
+                            mapping.from
+                        )));
+                    }
                 }
-                if self.deltalake.azure_container.is_none() {
-                    return Err(ConnectorError::config(
-                        "azure_container is required when storage_backend = 'azure'",
-                    ));
+                StorageBackend::Azure => {
+                    if storage.azure_storage_account.is_none()
+                        && self.deltalake.azure_storage_account.is_none()
+                    {
+                        return Err(ConnectorError::config(format!(
+                            "azure_storage_account is required when storage_backend = 'azure' (route '{}')",
+                            mapping.from
+                        )));
+                    }
+                    if storage.azure_container.is_none() && self.deltalake.azure_container.is_none()
+                    {
+                        return Err(ConnectorError::config(format!(
+                            "azure_container is required when storage_backend = 'azure' (route '{}')",
+                            mapping.from
+                        )));
+                    }
+                    validate_azure_auth(&self.deltalake)?;
+                }
+                StorageBackend::GCS => {
+                    if storage.gcp_project_id.is_none() && self.deltalake.gcp_project_id.is_none() {
+                        return Err(ConnectorError::config(format!(
+                            "gcp_project_id is required when storage_backend = 'gcs' (route '{}')",
+                            mapping.from
+                        )));
+                    }
+                    if self.deltalake.gcs_service_account_path.is_some()
+                        && self.deltalake.gcs_service_account_key.is_some()
+                    {
+                        return Err(ConnectorError::config(
+                            "Only one of gcs_service_account_path or gcs_service_account_key can be configured",
+                        ));
+                    }
                 }
             }
-            StorageBackend::GCS => {
-                if self.deltalake.gcp_project_id.is_none() {
-                    return Err(ConnectorError::config(
-                        "gcp_project_id is required when storage_backend = 'gcs'",
-                    ));
+            if let Some(prefix) = &storage.credentials_env_prefix {
+                if prefix.is_empty() {
+                    return Err(ConnectorError::config(format!(
+                        "credentials_env_prefix cannot be empty for route '{}'",
+                        mapping.from
+                    )));
                 }
             }
-        }
 
-        // Validate each topic mapping
-        for mapping in &self.deltalake.routes {
             if mapping.from.is_empty() {
                 return Err(ConnectorError::config("Route 'from' cannot be empty"));
             }
@@ -275,6 +929,7 @@ impl ConfigValidate for DeltaLakeSinkConfig {
             if mapping.to.is_empty() {
                 return Err(ConnectorError::config("Route 'to' cannot be empty"));
             }
+            validate_content_routing(mapping)?;
             if mapping.field_mappings.is_empty() {
                 return Err(ConnectorError::config(format!(
                     "Field mappings cannot be empty for route '{}'. Please define at least one field mapping.",
@@ -282,9 +937,86 @@ impl ConfigValidate for DeltaLakeSinkConfig {
                 )));
             }
 
-            // Validate field mapping data types
+            if mapping.replace_where.is_some() && mapping.write_mode != WriteMode::Overwrite {
+                return Err(ConnectorError::config(format!(
+                    "replace_where requires write_mode = 'overwrite' for route '{}'",
+                    mapping.from
+                )));
+            }
+
+            if let Some(partition) = &mapping.partition_from_timestamp {
+                validate_timestamp_partition(mapping, partition)?;
+            }
+
+            if let Some(cdc) = &mapping.cdc {
+                validate_cdc(mapping, cdc)?;
+            }
+
+            if let Some(retry) = &mapping.retry {
+                validate_retry_policy(mapping, retry)?;
+            }
+
+            // Offsets only increase within a partition consumed by a single consumer
+            if mapping.exactly_once
+                && !matches!(
+                    mapping.subscription_type,
+                    SubscriptionType::Exclusive | SubscriptionType::FailOver
+                )
+            {
+                return Err(ConnectorError::config(format!(
+                    "exactly_once requires subscription_type = \"Exclusive\" or \"FailOver\" for route '{}'",
+                    mapping.from
+                )));
+            }
+
+            match mapping.payload_format {
+                PayloadFormat::Json => {}
+                PayloadFormat::Avro => {
+                    if mapping.avro_schema_path.is_none() {
+                        return Err(ConnectorError::config(format!(
+                            "avro_schema_path is required when payload_format = 'avro' for route '{}'",
+                            mapping.from
+                        )));
+                    }
+                }
+                PayloadFormat::Protobuf => {
+                    if mapping.protobuf_descriptor_path.is_none()
+                        || mapping.protobuf_message.is_none()
+                    {
+                        return Err(ConnectorError::config(format!(
+                            "protobuf_descriptor_path and protobuf_message are required when payload_format = 'protobuf' for route '{}'",
+                            mapping.from
+                        )));
+                    }
+                }
+            }
+
+            // Validate field mapping data types and resolved column names
+            let mut columns = std::collections::HashSet::new();
             for field_mapping in &mapping.field_mappings {
                 validate_arrow_type(&field_mapping.data_type)?;
+
+                if let Some(format) = &field_mapping.timestamp_format {
+                    validate_timestamp_format(mapping, field_mapping, format)?;
+                }
+
+                if let Some(default) = &field_mapping.default {
+                    validate_field_default(mapping, field_mapping, default)?;
+                }
+
+                let column = field_mapping.resolved_column(mapping.column_naming);
+                if column.is_empty() {
+                    return Err(ConnectorError::config(format!(
+                        "Field mapping '{}' resolves to an empty column name for route '{}'",
+                        field_mapping.json_path, mapping.from
+                    )));
+                }
+                if !columns.insert(column.clone()) {
+                    return Err(ConnectorError::config(format!(
+                        "Duplicate column '{}' in field mappings for route '{}'",
+                        column, mapping.from
+                    )));
+                }
             }
         }
 
@@ -292,6 +1024,231 @@ impl ConfigValidate for DeltaLakeSinkConfig {
     }
 }
 
+/// Validate content-based routing settings (route_by and the `to` template)
+fn validate_content_routing(mapping: &TopicMapping) -> ConnectorResult<()> {
+    let has_placeholder = mapping.to.contains(ROUTE_PLACEHOLDER);
+
+    match &mapping.route_by {
+        Some(route_by) => {
+            if route_by.is_empty() || !has_placeholder {
+                return Err(ConnectorError::config(format!(
+                    "route_by requires a non-empty field and a '{}' placeholder in 'to' for route '{}'",
+                    ROUTE_PLACEHOLDER, mapping.from
+                )));
+            }
+            if let Some(default_route) = &mapping.default_route {
+                if !is_valid_route_value(default_route) {
+                    return Err(ConnectorError::config(format!(
+                        "Invalid default_route '{}' for route '{}'. Use letters, digits, '_' or '-'",
+                        default_route, mapping.from
+                    )));
+                }
+            }
+        }
+        None => {
+            if has_placeholder || mapping.default_route.is_some() {
+                return Err(ConnectorError::config(format!(
+                    "'{}' placeholder and default_route require route_by for route '{}'",
+                    ROUTE_PLACEHOLDER, mapping.from
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Expression for a default evaluated to the current time per record
+pub const DEFAULT_NOW: &str = "now()";
+
+/// Validate a field's default value against its data type
+fn validate_field_default(
+    mapping: &TopicMapping,
+    field_mapping: &FieldMapping,
+    default: &serde_json::Value,
+) -> ConnectorResult<()> {
+    let data_type = field_mapping.data_type.as_str();
+    let valid = if default.as_str() == Some(DEFAULT_NOW) {
+        matches!(data_type, "Timestamp" | "TimestampNtz" | "Int64" | "Utf8")
+    } else {
+        crate::record::value_matches_type(default, data_type)
+    };
+
+    if !valid {
+        return Err(ConnectorError::config(format!(
+            "Invalid default {} for {} field '{}' in route '{}'",
+            default, data_type, field_mapping.json_path, mapping.from
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a field's timestamp_format against its data type
+fn validate_timestamp_format(
+    mapping: &TopicMapping,
+    field_mapping: &FieldMapping,
+    format: &str,
+) -> ConnectorResult<()> {
+    if !matches!(
+        field_mapping.data_type.as_str(),
+        "Timestamp" | "TimestampNtz"
+    ) {
+        return Err(ConnectorError::config(format!(
+            "timestamp_format on field '{}' requires data_type Timestamp or TimestampNtz for route '{}'",
+            field_mapping.json_path, mapping.from
+        )));
+    }
+
+    let is_named = matches!(format, "unix_s" | "unix_ms" | "unix_us" | "rfc3339");
+    if !is_named && !format.contains('%') {
+        return Err(ConnectorError::config(format!(
+            "Invalid timestamp_format '{}' on field '{}' for route '{}'. Use unix_s, unix_ms, unix_us, rfc3339, or a strftime pattern",
+            format, field_mapping.json_path, mapping.from
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a derived timestamp partition column
+fn validate_timestamp_partition(
+    mapping: &TopicMapping,
+    partition: &TimestampPartition,
+) -> ConnectorResult<()> {
+    if partition.source_field.is_empty() || partition.column.is_empty() {
+        return Err(ConnectorError::config(format!(
+            "partition_from_timestamp requires source_field and column for route '{}'",
+            mapping.from
+        )));
+    }
+
+    if mapping
+        .field_mappings
+        .iter()
+        .any(|f| f.resolved_column(mapping.column_naming) == partition.column)
+    {
+        return Err(ConnectorError::config(format!(
+            "Partition column '{}' conflicts with a field mapping column for route '{}'",
+            partition.column, mapping.from
+        )));
+    }
+
+    if StrftimeItems::new(&partition.format).any(|item| matches!(item, Item::Error)) {
+        return Err(ConnectorError::config(format!(
+            "Invalid partition format '{}' for route '{}'",
+            partition.format, mapping.from
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a route's retry policy
+fn validate_retry_policy(mapping: &TopicMapping, retry: &RetryPolicy) -> ConnectorResult<()> {
+    if retry.max_attempts == 0 {
+        return Err(ConnectorError::config(format!(
+            "retry.max_attempts must be at least 1 for route '{}'",
+            mapping.from
+        )));
+    }
+    if retry.initial_backoff_ms > retry.max_backoff_ms {
+        return Err(ConnectorError::config(format!(
+            "retry.initial_backoff_ms cannot exceed retry.max_backoff_ms for route '{}'",
+            mapping.from
+        )));
+    }
+    if !(0.0..=1.0).contains(&retry.jitter) {
+        return Err(ConnectorError::config(format!(
+            "retry.jitter must be between 0.0 and 1.0 for route '{}'",
+            mapping.from
+        )));
+    }
+    if let Some(dlq_topic) = &retry.dlq_topic {
+        if !dlq_topic.starts_with('/') || dlq_topic == &mapping.from {
+            return Err(ConnectorError::config(format!(
+                "retry.dlq_topic must be a topic path (e.g., '/dlq/payments') other than the route's own topic for route '{}'",
+                mapping.from
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate change-data-capture settings for a route
+fn validate_cdc(mapping: &TopicMapping, cdc: &CdcConfig) -> ConnectorResult<()> {
+    if cdc.op_field.is_empty() {
+        return Err(ConnectorError::config(format!(
+            "cdc.op_field cannot be empty for route '{}'",
+            mapping.from
+        )));
+    }
+
+    if mapping.write_mode != WriteMode::Append {
+        return Err(ConnectorError::config(format!(
+            "cdc requires write_mode = 'append' for route '{}'",
+            mapping.from
+        )));
+    }
+
+    if cdc.key_columns.is_empty() {
+        return Err(ConnectorError::config(format!(
+            "cdc.key_columns cannot be empty for route '{}'",
+            mapping.from
+        )));
+    }
+
+    for key in &cdc.key_columns {
+        let mapped = mapping
+            .field_mappings
+            .iter()
+            .any(|f| &f.resolved_column(mapping.column_naming) == key);
+        if !mapped {
+            return Err(ConnectorError::config(format!(
+                "cdc key column '{}' is not a field mapping column for route '{}'",
+                key, mapping.from
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate explicit Azure authentication settings
+fn validate_azure_auth(config: &DeltaLakeConfig) -> ConnectorResult<()> {
+    let service_principal = config.azure_tenant_id.is_some()
+        || config.azure_client_id.is_some()
+        || config.azure_client_secret_env.is_some();
+
+    if service_principal
+        && (config.azure_tenant_id.is_none()
+            || config.azure_client_id.is_none()
+            || config.azure_client_secret_env.is_none())
+    {
+        return Err(ConnectorError::config(
+            "Azure service principal auth requires azure_tenant_id, azure_client_id and azure_client_secret_env",
+        ));
+    }
+
+    let methods = [
+        service_principal,
+        config.azure_sas_token_env.is_some(),
+        config.azure_use_azure_cli,
+    ]
+    .iter()
+    .filter(|enabled| **enabled)
+    .count();
+
+    if methods > 1 {
+        return Err(ConnectorError::config(
+            "Only one of service principal, azure_sas_token_env or azure_use_azure_cli can be configured",
+        ));
+    }
+
+    Ok(())
+}
+
 /// Validate Arrow data type string
 fn validate_arrow_type(data_type: &str) -> ConnectorResult<()> {
     let valid_types = [
@@ -308,6 +1265,7 @@ fn validate_arrow_type(data_type: &str) -> ConnectorResult<()> {
         "Float64",
         "Boolean",
         "Timestamp",
+        "TimestampNtz",
         "Date32",
         "Date64",
         "Binary",
@@ -323,3 +1281,90 @@ fn validate_arrow_type(data_type: &str) -> ConnectorResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Order ID"), "order_id");
+        assert_eq!(to_snake_case("userName"), "user_name");
+        assert_eq!(to_snake_case("HTTPStatus"), "http_status");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+        assert_eq!(to_snake_case("price-in.USD"), "price_in_usd");
+        assert_eq!(to_snake_case("  Total  Amount "), "total_amount");
+    }
+
+    #[test]
+    fn test_is_valid_route_value() {
+        assert!(is_valid_route_value("orders"));
+        assert!(is_valid_route_value("order-refunds_v2"));
+        assert!(!is_valid_route_value(""));
+        assert!(!is_valid_route_value("../payments"));
+        assert!(!is_valid_route_value("a/b"));
+        assert!(!is_valid_route_value("order created"));
+    }
+
+    #[test]
+    fn test_parse_json_path() {
+        assert_eq!(parse_json_path("payment_id"), vec!["payment_id"]);
+        assert_eq!(
+            parse_json_path("user.profile.name"),
+            vec!["user", "profile", "name"]
+        );
+        assert_eq!(
+            parse_json_path("$.payload.items[0].price"),
+            vec!["payload", "items", "0", "price"]
+        );
+        assert_eq!(
+            parse_json_path("$['order id'].total"),
+            vec!["order id", "total"]
+        );
+        assert_eq!(parse_json_path("$[\"a.b\"]"), vec!["a.b"]);
+    }
+
+    #[test]
+    fn test_resolved_column() {
+        let mapping = FieldMapping {
+            json_path: "user.firstName".to_string(),
+            path_parts: vec![],
+            column: String::new(),
+            data_type: "Utf8".to_string(),
+            nullable: true,
+            coerce: false,
+            timestamp_format: None,
+            default: None,
+        };
+
+        assert_eq!(mapping.resolved_column(ColumnNaming::AsIs), "firstName");
+        assert_eq!(
+            mapping.resolved_column(ColumnNaming::SnakeCase),
+            "first_name"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_partition_conflict() {
+        let mut mapping: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/test",
+            "subscription": "test-sub",
+            "to": "test-path",
+            "column_naming": "snake_case",
+            "field_mappings": [{"json_path": "eventDate", "data_type": "Utf8"}],
+        }))
+        .unwrap();
+        let partition = TimestampPartition {
+            source_field: "eventTime".to_string(),
+            path_parts: vec![],
+            column: "event_date".to_string(),
+            format: default_partition_format(),
+        };
+
+        // eventDate is written to the event_date column under snake_case
+        assert!(validate_timestamp_partition(&mapping, &partition).is_err());
+
+        mapping.column_naming = ColumnNaming::AsIs;
+        assert!(validate_timestamp_partition(&mapping, &partition).is_ok());
+    }
+}
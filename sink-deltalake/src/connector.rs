@@ -3,18 +3,36 @@
 //! This connector streams events from Danube topics to Delta Lake tables,
 //! supporting S3, Azure Blob Storage, and Google Cloud Storage.
 
-use crate::config::{DeltaLakeSinkConfig, StorageBackend, TopicMapping};
-use crate::record::to_record_batch;
+use crate::config::{
+    DeltaLakeSinkConfig, RetryPolicy, SchemaCheckMode, StorageBackend, TopicMapping,
+    ValidationMode, WriteMode,
+};
+use crate::decode::PayloadDecoder;
+use crate::dlq::DeadLetterQueue;
+use crate::record::{
+    build_arrow_schema, chunk_limit, route_records, split_by_size, split_invalid,
+    to_cdc_record_batch, to_record_batch, uncommitted, validate_record, CDC_OP_COLUMN,
+    CDC_OP_DELETE, CDC_OP_UPSERT,
+};
+use crate::spill::SpillLog;
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
 use danube_connect_core::{
     ConnectorConfig, ConnectorError, ConnectorResult, ConsumerConfig, SinkConnector, SinkRecord,
-    SubscriptionType,
 };
+use deltalake::datafusion::prelude::SessionContext;
+use deltalake::kernel::transaction::{CommitProperties, TransactionError};
+use deltalake::kernel::Transaction;
 use deltalake::operations::create::CreateBuilder;
+use deltalake::protocol::SaveMode;
 use deltalake::writer::{DeltaWriter, RecordBatchWriter};
-use deltalake::{DeltaTable, DeltaTableError};
-use std::collections::HashMap;
-use tracing::{debug, info};
+use deltalake::{DeltaOps, DeltaTable, DeltaTableError};
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
 use url::Url;
 
 /// Delta Lake Sink Connector
@@ -24,30 +42,60 @@ pub struct DeltaLakeSinkConnector {
     /// Connector configuration
     config: DeltaLakeSinkConfig,
 
-    /// Delta tables cache (table_path -> DeltaTable)
-    tables: HashMap<String, DeltaTable>,
+    /// Delta table writers (table_path -> TableWriter)
+    tables: HashMap<String, TableWriter>,
+
+    /// Global memory budget for in-flight record conversion (1 permit = 1 KiB)
+    memory_budget: Arc<Semaphore>,
+
+    /// Binary payload decoders (topic -> decoder), for Avro / Protobuf routes
+    decoders: HashMap<String, PayloadDecoder>,
+
+    /// Durable spill log for batches awaiting commit (when spill_dir is set)
+    spill: Option<Arc<SpillLog>>,
+
+    /// Dead-letter queue for batches exhausting their retry policy (when any route sets dlq_topic)
+    dlq: Option<DeadLetterQueue>,
+}
+
+/// Write state for a single Delta table
+///
+/// Each table is owned by at most one flush task at a time, so independent
+/// tables can be written concurrently.
+struct TableWriter {
+    /// Loaded Delta table
+    table: DeltaTable,
+
+    /// Last committed Danube offset per Delta transaction app id (exactly-once
+    /// routes, one app id per topic partition), None when the table has none
+    committed_offsets: HashMap<String, Option<i64>>,
+
+    /// Smoothed commit latency in milliseconds (None until the first commit)
+    commit_latency_ms: Option<f64>,
 }
 
 impl DeltaLakeSinkConnector {
     /// Create a new Delta Lake Sink Connector with configuration
     pub fn with_config(config: DeltaLakeSinkConfig) -> Self {
+        let budget_permits = budget_permits(config.deltalake.memory_budget_bytes);
         Self {
             config,
             tables: HashMap::new(),
+            memory_budget: Arc::new(Semaphore::new(budget_permits as usize)),
+            decoders: HashMap::new(),
+            spill: None,
+            dlq: None,
         }
     }
 
-    /// Get or create a Delta table
-    async fn get_or_create_table(
-        &mut self,
-        mapping: &TopicMapping,
-    ) -> ConnectorResult<&mut DeltaTable> {
+    /// Open (or create) the Delta table for a route and cache its writer
+    async fn open_table(&mut self, mapping: &TopicMapping) -> ConnectorResult<()> {
         // Check if table already exists
         if !self.tables.contains_key(&mapping.to) {
             info!("Opening Delta table at path: {}", mapping.to);
 
             // Configure storage options based on backend
-            let storage_options = self.build_storage_options()?;
+            let storage_options = self.build_storage_options(mapping)?;
 
             // Parse table path as URL
             let table_url = Url::parse(&mapping.to).map_err(|e| {
@@ -63,6 +111,7 @@ impl DeltaLakeSinkConnector {
             {
                 Ok(table) => {
                     info!("Loaded existing Delta table: {}", mapping.to);
+                    check_table_schema(&table, mapping)?;
                     table
                 }
                 Err(DeltaTableError::NotATable(_)) => {
@@ -80,12 +129,87 @@ impl DeltaLakeSinkConnector {
                 }
             };
 
-            // Cache the table
-            self.tables.insert(mapping.to.clone(), table);
+            // Cache the table writer; committed offsets of exactly-once routes
+            // are read per topic partition as its records arrive
+            self.tables.insert(
+                mapping.to.clone(),
+                TableWriter {
+                    table,
+                    committed_offsets: HashMap::new(),
+                    commit_latency_ms: None,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Commit batches left in the spill log by a previous run
+    ///
+    /// Exactly-once batches whose transaction is already recorded in the table
+    /// were committed before the previous run stopped and are only removed.
+    async fn replay_spill(&mut self) -> ConnectorResult<()> {
+        let Some(spill) = self.spill.clone() else {
+            return Ok(());
+        };
+
+        let entries = spill.pending()?;
+        if !entries.is_empty() {
+            info!("Replaying {} spilled batches", entries.len());
+        }
+
+        for entry in entries {
+            let Some(mut mapping) = self
+                .config
+                .deltalake
+                .routes
+                .iter()
+                .find(|m| m.from == entry.route)
+                .cloned()
+            else {
+                warn!(
+                    "Keeping spilled batch {} for unconfigured route {}",
+                    entry.path.display(),
+                    entry.route
+                );
+                continue;
+            };
+            mapping.to = entry.table.clone();
+
+            self.open_table(&mapping).await?;
+            let writer = self.tables.get_mut(&mapping.to).ok_or_else(|| {
+                ConnectorError::fatal(format!("Delta table not opened: {}", mapping.to))
+            })?;
+
+            // A commit records all its transactions at once
+            let app_ids = entry.transactions.iter().map(|(app_id, _)| app_id.as_str());
+            writer.load_committed_offsets(app_ids).await?;
+            let committed = !entry.transactions.is_empty()
+                && entry.transactions.iter().all(|(app_id, version)| {
+                    writer
+                        .committed_offsets
+                        .get(app_id)
+                        .copied()
+                        .flatten()
+                        .is_some_and(|committed| committed >= *version)
+                });
+            if committed {
+                debug!("Spilled batch {} already committed", entry.path.display());
+            } else {
+                let rows = entry.batch.num_rows();
+                let version = writer
+                    .commit(&self.config, &mapping, entry.batch, entry.transactions)
+                    .await?;
+                info!(
+                    "Replayed {} spilled records to Delta table: {} (version: {})",
+                    rows, mapping.to, version
+                );
+            }
+
+            spill.remove(&entry.path)?;
         }
 
-        // Return mutable reference to the table
-        Ok(self.tables.get_mut(&mapping.to).unwrap())
+        Ok(())
     }
 
     /// Create a new Delta table with user-defined schema
@@ -95,7 +219,7 @@ impl DeltaLakeSinkConnector {
         storage_options: HashMap<String, String>,
     ) -> ConnectorResult<DeltaTable> {
         // Build Arrow schema from config
-        let schema = crate::record::build_arrow_schema(mapping)?;
+        let schema = build_arrow_schema(mapping)?;
 
         // Convert Arrow fields to Delta StructFields
         // Note: delta-rs 0.29 doesn't provide TryFrom traits for Arrow types
@@ -110,10 +234,27 @@ impl DeltaLakeSinkConnector {
             .collect();
 
         // Create Delta table
-        let table = CreateBuilder::new()
+        let mut builder = CreateBuilder::new()
             .with_location(&mapping.to)
             .with_storage_options(storage_options)
-            .with_columns(delta_fields)
+            .with_columns(delta_fields);
+
+        // Table properties (e.g., delta.appendOnly, delta.logRetentionDuration)
+        if !mapping.table_properties.is_empty() {
+            builder = builder.with_configuration(
+                mapping
+                    .table_properties
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Some(value.clone()))),
+            );
+        }
+
+        // Partition by the derived timestamp column if configured
+        if let Some(partition) = &mapping.partition_from_timestamp {
+            builder = builder.with_partition_columns(vec![partition.column.clone()]);
+        }
+
+        let table = builder
             .await
             .map_err(|e| ConnectorError::fatal(format!("Failed to create Delta table: {}", e)))?;
 
@@ -122,57 +263,233 @@ impl DeltaLakeSinkConnector {
     }
 
     /// Build storage options based on configured backend
-    fn build_storage_options(&self) -> ConnectorResult<HashMap<String, String>> {
+    ///
+    /// Route-level overrides in `mapping.storage` take precedence over the
+    /// global [deltalake] settings.
+    fn build_storage_options(
+        &self,
+        mapping: &TopicMapping,
+    ) -> ConnectorResult<HashMap<String, String>> {
         let mut options = HashMap::new();
+        let global = &self.config.deltalake;
+        let overrides = &mapping.storage;
+        let prefix = overrides.credentials_env_prefix.as_deref();
 
-        match self.config.deltalake.storage_backend {
+        match global.storage_backend {
             StorageBackend::S3 => {
                 // AWS credentials from environment (AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY)
-                if let Some(region) = &self.config.deltalake.s3_region {
+                if let Some(region) = overrides.s3_region.as_ref().or(global.s3_region.as_ref()) {
                     options.insert("region".to_string(), region.clone());
                 }
 
                 // Custom endpoint for MinIO or S3-compatible storage
-                if let Some(endpoint) = &self.config.deltalake.s3_endpoint {
+                if let Some(endpoint) = overrides
+                    .s3_endpoint
+                    .as_ref()
+                    .or(global.s3_endpoint.as_ref())
+                {
                     options.insert("endpoint".to_string(), endpoint.clone());
                 }
 
                 // Allow HTTP for local MinIO testing
-                if self.config.deltalake.s3_allow_http {
+                if global.s3_allow_http {
                     options.insert("allow_http".to_string(), "true".to_string());
                 }
 
-                info!("Using S3 storage backend");
+                // Path-style addressing for MinIO, Ceph, R2 and similar stores
+                if global.s3_path_style {
+                    options.insert(
+                        "virtual_hosted_style_request".to_string(),
+                        "false".to_string(),
+                    );
+                }
+
+                // Assume an IAM role via STS (static keys are not required)
+                if let Some(role_arn) = overrides
+                    .s3_role_arn
+                    .as_ref()
+                    .or(global.s3_role_arn.as_ref())
+                {
+                    let session_name = global
+                        .s3_session_name
+                        .clone()
+                        .unwrap_or_else(|| self.config.core.connector_name.clone());
+                    options.insert("AWS_IAM_ROLE_ARN".to_string(), role_arn.clone());
+                    options.insert("AWS_IAM_ROLE_SESSION_NAME".to_string(), session_name);
+                    info!("Assuming IAM role {} for {}", role_arn, mapping.to);
+                }
+
+                // Web identity token (IRSA) for AssumeRoleWithWebIdentity
+                if let Some(token_file) = &global.s3_web_identity_token_file {
+                    options.insert(
+                        "AWS_WEB_IDENTITY_TOKEN_FILE".to_string(),
+                        token_file.clone(),
+                    );
+                }
+
+                // Route-specific credentials from prefixed environment variables
+                if let Some(prefix) = prefix {
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "AWS_ACCESS_KEY_ID",
+                        "aws_access_key_id",
+                    );
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "AWS_SECRET_ACCESS_KEY",
+                        "aws_secret_access_key",
+                    );
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "AWS_SESSION_TOKEN",
+                        "aws_session_token",
+                    );
+                }
+
+                debug!("Using S3 storage backend for {}", mapping.to);
             }
             StorageBackend::Azure => {
                 // Azure credentials from environment (AZURE_STORAGE_ACCOUNT_KEY or AZURE_STORAGE_SAS_TOKEN)
-                if let Some(account) = &self.config.deltalake.azure_storage_account {
+                if let Some(account) = overrides
+                    .azure_storage_account
+                    .as_ref()
+                    .or(global.azure_storage_account.as_ref())
+                {
                     options.insert("account_name".to_string(), account.clone());
                 }
 
-                if let Some(container) = &self.config.deltalake.azure_container {
+                if let Some(container) = overrides
+                    .azure_container
+                    .as_ref()
+                    .or(global.azure_container.as_ref())
+                {
                     options.insert("container_name".to_string(), container.clone());
                 }
 
-                info!("Using Azure Blob Storage backend");
+                // Explicit authentication (service principal, SAS token, or Azure CLI)
+                if let (Some(tenant_id), Some(client_id), Some(secret_env)) = (
+                    &global.azure_tenant_id,
+                    &global.azure_client_id,
+                    &global.azure_client_secret_env,
+                ) {
+                    options.insert("tenant_id".to_string(), tenant_id.clone());
+                    options.insert("client_id".to_string(), client_id.clone());
+                    options.insert("client_secret".to_string(), required_env(secret_env)?);
+                    info!("Using Azure service principal authentication");
+                }
+
+                if let Some(sas_env) = &global.azure_sas_token_env {
+                    options.insert("sas_token".to_string(), required_env(sas_env)?);
+                    info!("Using Azure SAS token authentication");
+                }
+
+                if global.azure_use_azure_cli {
+                    options.insert("use_azure_cli".to_string(), "true".to_string());
+                    info!("Using Azure CLI authentication");
+                }
+
+                if let Some(prefix) = prefix {
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "AZURE_STORAGE_ACCOUNT_KEY",
+                        "account_key",
+                    );
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "AZURE_STORAGE_SAS_TOKEN",
+                        "sas_token",
+                    );
+                }
+
+                debug!("Using Azure Blob Storage backend for {}", mapping.to);
             }
             StorageBackend::GCS => {
                 // GCP credentials from environment (GOOGLE_APPLICATION_CREDENTIALS)
-                if let Some(project_id) = &self.config.deltalake.gcp_project_id {
+                if let Some(project_id) = overrides
+                    .gcp_project_id
+                    .as_ref()
+                    .or(global.gcp_project_id.as_ref())
+                {
                     options.insert("project_id".to_string(), project_id.clone());
                 }
 
-                info!("Using Google Cloud Storage backend");
+                // Explicit service account (otherwise GOOGLE_APPLICATION_CREDENTIALS is used)
+                if let Some(path) = &global.gcs_service_account_path {
+                    options.insert("service_account_path".to_string(), path.clone());
+                }
+
+                if let Some(key) = &global.gcs_service_account_key {
+                    options.insert("service_account_key".to_string(), key.clone());
+                }
+
+                if let Some(prefix) = prefix {
+                    insert_prefixed_env(
+                        &mut options,
+                        prefix,
+                        "GOOGLE_APPLICATION_CREDENTIALS",
+                        "service_account_path",
+                    );
+                }
+
+                debug!("Using Google Cloud Storage backend for {}", mapping.to);
             }
         }
 
+        // Pass-through options override anything derived above
+        for (key, value) in &global.storage_options {
+            options.insert(key.clone(), value.clone());
+        }
+
         Ok(options)
     }
+}
+
+impl TableWriter {
+    /// Pause before writing while recent commits to this table are slower than
+    /// the configured threshold
+    ///
+    /// process_batch doesn't return while paused, so the runtime stops pulling
+    /// new messages instead of piling them up behind a lagging table.
+    async fn apply_backpressure(&self, config: &DeltaLakeSinkConfig, table_path: &str) {
+        let (Some(threshold), Some(latency)) = (
+            config.deltalake.commit_latency_threshold_ms,
+            self.commit_latency_ms,
+        ) else {
+            return;
+        };
+
+        if latency > threshold as f64 {
+            let pause = Duration::from_millis(config.deltalake.backpressure_pause_ms);
+            warn!(
+                "Delta table {} commits lagging ({:.0} ms > {} ms), pausing {:?}",
+                table_path, latency, threshold, pause
+            );
+            tokio::time::sleep(pause).await;
+        }
+    }
+
+    /// Fold a commit duration into the smoothed commit latency
+    fn record_commit_latency(&mut self, elapsed: Duration) {
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        self.commit_latency_ms = Some(match self.commit_latency_ms {
+            Some(latency) => latency + COMMIT_LATENCY_SMOOTHING * (elapsed_ms - latency),
+            None => elapsed_ms,
+        });
+    }
 
     /// Write a batch of records to Delta Lake
     async fn write_batch(
         &mut self,
+        config: &DeltaLakeSinkConfig,
         mapping: &TopicMapping,
+        decoder: Option<&PayloadDecoder>,
+        spill: Option<&SpillLog>,
+        dlq: Option<&DeadLetterQueue>,
         records: Vec<SinkRecord>,
     ) -> ConnectorResult<()> {
         if records.is_empty() {
@@ -185,55 +502,632 @@ impl DeltaLakeSinkConnector {
             mapping.to
         );
 
+        // Exactly-once: drop records already committed by a previous run and
+        // tag this commit with the highest offset of each topic partition
+        let mut transactions = Vec::new();
+        let records = if mapping.exactly_once {
+            let record_transaction = |record: &SinkRecord| {
+                let partition = record.partition().map(|p| p.to_string());
+                let app_id =
+                    transaction_app_id(&config.core.connector_name, mapping, partition.as_deref());
+                (app_id, record.offset() as i64)
+            };
+
+            let app_ids: HashSet<String> = records
+                .iter()
+                .map(|record| record_transaction(record).0)
+                .collect();
+            self.load_committed_offsets(app_ids.iter().map(String::as_str))
+                .await?;
+
+            let (pending, versions) =
+                uncommitted(records, &self.committed_offsets, record_transaction);
+            if pending.is_empty() {
+                debug!(
+                    "Skipping batch for {}: all records already committed",
+                    mapping.to
+                );
+                return Ok(());
+            }
+
+            transactions = versions;
+            pending
+        } else {
+            records
+        };
+
+        // Strict validation: dead-letter (or skip) only the failing records
+        let records = if mapping.validation == ValidationMode::Strict {
+            let (valid, invalid) =
+                split_invalid(records, |record| validate_record(record, mapping, decoder));
+            let dlq_topic = mapping.retry.as_ref().and_then(|p| p.dlq_topic.as_deref());
+            for (record, e) in invalid {
+                match (dlq, dlq_topic) {
+                    (Some(dlq), Some(dlq_topic)) => {
+                        dlq.publish(dlq_topic, &mapping.to, std::slice::from_ref(&record), &e)
+                            .await?
+                    }
+                    _ => warn!(
+                        "Skipping record at offset {} for {}: {}",
+                        record.offset(),
+                        mapping.to,
+                        e
+                    ),
+                }
+            }
+            if valid.is_empty() {
+                return Ok(());
+            }
+            valid
+        } else {
+            records
+        };
+
         // Convert records to Arrow RecordBatch
-        let record_batch = to_record_batch(&records, mapping)?;
+        let record_batch = match &mapping.cdc {
+            Some(cdc) => to_cdc_record_batch(&records, mapping, cdc, decoder)?,
+            None => to_record_batch(&records, mapping, decoder)?,
+        };
 
-        // Get or create the table
-        let table = self.get_or_create_table(mapping).await?;
+        // Persist the converted batch until it is committed
+        let spilled = match spill {
+            Some(spill) => {
+                Some(spill.append(&mapping.from, &mapping.to, &transactions, &record_batch)?)
+            }
+            None => None,
+        };
 
-        // Create a fresh writer for this write operation
-        // Note: RecordBatchWriter is not Sync, so we can't cache it
-        let mut writer = RecordBatchWriter::for_table(table).map_err(|e| {
-            ConnectorError::fatal_with_source(
-                format!("Failed to create writer for Delta table: {}", mapping.to),
-                e,
-            )
-        })?;
+        let result = match &mapping.retry {
+            Some(policy) => {
+                self.commit_with_retry(config, mapping, policy, record_batch, transactions)
+                    .await
+            }
+            None => {
+                self.commit(config, mapping, record_batch, transactions)
+                    .await
+            }
+        };
 
-        // Write the record batch
-        writer.write(record_batch).await.map_err(|e| {
-            ConnectorError::retryable_with_source(
-                format!("Failed to write batch to Delta table: {}", mapping.to),
-                e,
+        let outcome = match result {
+            Ok(new_version) => {
+                info!(
+                    "Successfully wrote {} records to Delta table: {} (version: {})",
+                    records.len(),
+                    mapping.to,
+                    new_version
+                );
+                Ok(())
+            }
+            Err(e) => {
+                // Retries exhausted on a transient failure: hand the records to the DLQ
+                let dlq_topic = mapping.retry.as_ref().and_then(|p| p.dlq_topic.as_deref());
+                match (dlq, dlq_topic) {
+                    (Some(dlq), Some(dlq_topic)) if e.is_retryable() => {
+                        dlq.publish(dlq_topic, &mapping.to, &records, &e).await
+                    }
+                    _ => Err(e),
+                }
+            }
+        };
+
+        // The batch is now committed, dead-lettered, or failed back to the
+        // runtime, which redelivers its records; replaying the entry on the
+        // next startup would write them twice
+        if let (Some(spill), Some(path)) = (spill, spilled) {
+            spill.remove(&path)?;
+        }
+
+        outcome
+    }
+
+    /// Commit a converted batch under a route's retry policy
+    ///
+    /// Retryable failures are retried with exponential backoff and jitter;
+    /// the last error is returned once the attempts are exhausted.
+    async fn commit_with_retry(
+        &mut self,
+        config: &DeltaLakeSinkConfig,
+        mapping: &TopicMapping,
+        policy: &RetryPolicy,
+        record_batch: RecordBatch,
+        transactions: Vec<(String, i64)>,
+    ) -> ConnectorResult<i64> {
+        let mut attempt = 1;
+        loop {
+            match self
+                .commit(config, mapping, record_batch.clone(), transactions.clone())
+                .await
+            {
+                Err(e) if e.is_retryable() && attempt < policy.max_attempts => {
+                    let backoff = retry_backoff(policy, attempt);
+                    warn!(
+                        "Commit to Delta table {} failed (attempt {}/{}), retrying in {:?}: {}",
+                        mapping.to, attempt, policy.max_attempts, backoff, e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Commit a converted batch, returning the new table version
+    ///
+    /// Retries with a table reload when another writer won the commit race, and
+    /// records the exactly-once transactions (app id, offset) once committed.
+    async fn commit(
+        &mut self,
+        config: &DeltaLakeSinkConfig,
+        mapping: &TopicMapping,
+        record_batch: RecordBatch,
+        transactions: Vec<(String, i64)>,
+    ) -> ConnectorResult<i64> {
+        let commit_properties = (!transactions.is_empty()).then(|| {
+            transactions.iter().fold(
+                CommitProperties::default(),
+                |properties, (app_id, version)| {
+                    properties.with_application_transaction(Transaction::new(app_id, *version))
+                },
             )
-        })?;
+        });
 
-        // Flush and commit the write
-        let new_version = writer.flush_and_commit(table).await.map_err(|e| {
-            ConnectorError::retryable_with_source(
-                format!("Failed to commit to Delta table: {}", mapping.to),
+        let max_retries = config.deltalake.commit_conflict_retries;
+        let base_backoff_ms = config.deltalake.commit_retry_backoff_ms;
+        let mut attempt = 0;
+        let started = Instant::now();
+
+        // Write and commit, reloading and retrying when another writer won the commit race
+        let new_version = loop {
+            let table = &mut self.table;
+
+            let result = match (mapping.write_mode, commit_properties.clone()) {
+                _ if mapping.cdc.is_some() => {
+                    merge_cdc_batch(
+                        table,
+                        mapping,
+                        record_batch.clone(),
+                        commit_properties.clone(),
+                    )
+                    .await
+                }
+                (WriteMode::Append, None) => {
+                    append_batch(table, mapping, record_batch.clone()).await
+                }
+                (mode, commit_properties) => {
+                    write_with_operation(
+                        table,
+                        mapping,
+                        record_batch.clone(),
+                        mode,
+                        commit_properties,
+                    )
+                    .await
+                }
+            };
+
+            match result {
+                Ok(version) => break version,
+                Err(WriteFailure::Conflict(e)) if attempt < max_retries => {
+                    attempt += 1;
+                    let backoff = conflict_backoff(base_backoff_ms, attempt);
+                    warn!(
+                        "Commit conflict on Delta table {} (attempt {}/{}), retrying in {:?}: {}",
+                        mapping.to, attempt, max_retries, backoff, e
+                    );
+                    tokio::time::sleep(backoff).await;
+
+                    // Pick up the concurrent writer's commit before retrying
+                    table.load().await.map_err(|e| {
+                        ConnectorError::retryable_with_source(
+                            format!(
+                                "Failed to reload Delta table after conflict: {}",
+                                mapping.to
+                            ),
+                            e,
+                        )
+                    })?;
+                }
+                Err(WriteFailure::Conflict(e)) => {
+                    return Err(ConnectorError::retryable_with_source(
+                        format!(
+                            "Commit to Delta table {} still conflicting after {} retries",
+                            mapping.to, max_retries
+                        ),
+                        e,
+                    ))
+                }
+                Err(WriteFailure::Failed(e)) => return Err(e),
+            }
+        };
+
+        self.record_commit_latency(started.elapsed());
+
+        for (app_id, offset) in transactions {
+            self.committed_offsets.insert(app_id, Some(offset));
+        }
+
+        Ok(new_version)
+    }
+
+    /// Read the committed offsets of app ids not seen yet from the table
+    async fn load_committed_offsets<'a>(
+        &mut self,
+        app_ids: impl Iterator<Item = &'a str>,
+    ) -> ConnectorResult<()> {
+        for app_id in app_ids {
+            if self.committed_offsets.contains_key(app_id) {
+                continue;
+            }
+            let version = committed_transaction_version(&self.table, app_id).await?;
+            if let Some(version) = version {
+                info!(
+                    "Resuming after committed offset {} (app id: {})",
+                    version, app_id
+                );
+            }
+            self.committed_offsets.insert(app_id.to_string(), version);
+        }
+        Ok(())
+    }
+}
+
+/// Backoff before retry `attempt` (1-based): exponential, capped, with random jitter
+fn retry_backoff(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exponential = policy
+        .initial_backoff_ms
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(16))
+        .min(policy.max_backoff_ms) as f64;
+    let spread = exponential * policy.jitter;
+    let jittered = exponential + rand::random::<f64>() * 2.0 * spread - spread;
+    Duration::from_millis(jittered.max(0.0) as u64)
+}
+
+/// Weight of the newest commit in the smoothed commit latency
+const COMMIT_LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Convert a byte count into memory budget permits (1 permit = 1 KiB)
+fn budget_permits(bytes: usize) -> u32 {
+    let kib = bytes.div_ceil(1024).max(1);
+    kib.min(Semaphore::MAX_PERMITS).min(u32::MAX as usize) as u32
+}
+
+/// Delta transaction app id identifying a connector's writes for a topic
+/// partition (offsets are only ordered within a partition)
+fn transaction_app_id(
+    connector_name: &str,
+    mapping: &TopicMapping,
+    partition: Option<&str>,
+) -> String {
+    match partition {
+        Some(partition) => format!("danube:{}:{}:{}", connector_name, mapping.from, partition),
+        None => format!("danube:{}:{}", connector_name, mapping.from),
+    }
+}
+
+/// Failure of a single write attempt
+enum WriteFailure {
+    /// Another writer committed first; the write can be retried after a reload
+    Conflict(DeltaTableError),
+    /// Any other failure, already classified as a connector error
+    Failed(ConnectorError),
+}
+
+impl From<ConnectorError> for WriteFailure {
+    fn from(error: ConnectorError) -> Self {
+        WriteFailure::Failed(error)
+    }
+}
+
+/// Classify a commit error as a conflict or a retryable connector error
+fn commit_failure(error: DeltaTableError, message: String) -> WriteFailure {
+    if is_commit_conflict(&error) {
+        WriteFailure::Conflict(error)
+    } else {
+        WriteFailure::Failed(ConnectorError::retryable_with_source(message, error))
+    }
+}
+
+/// Whether an error was caused by a concurrent commit to the same table
+fn is_commit_conflict(error: &DeltaTableError) -> bool {
+    matches!(
+        error,
+        DeltaTableError::VersionAlreadyExists(_)
+            | DeltaTableError::Transaction {
+                source: TransactionError::VersionAlreadyExists(_)
+                    | TransactionError::CommitConflict(_)
+                    | TransactionError::MaxCommitAttempts(_),
+            }
+    )
+}
+
+/// Exponential backoff for conflict retries, capped at 10 seconds
+fn conflict_backoff(base_ms: u64, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis(base_ms.saturating_mul(factor).min(10_000))
+}
+
+/// Append a RecordBatch to the table and return the committed version
+async fn append_batch(
+    table: &mut DeltaTable,
+    mapping: &TopicMapping,
+    record_batch: RecordBatch,
+) -> Result<i64, WriteFailure> {
+    // Create a fresh writer for this write operation
+    // Note: RecordBatchWriter is not Sync, so we can't cache it
+    let mut writer = RecordBatchWriter::for_table(table).map_err(|e| {
+        ConnectorError::fatal_with_source(
+            format!("Failed to create writer for Delta table: {}", mapping.to),
+            e,
+        )
+    })?;
+
+    // Write the record batch
+    writer.write(record_batch).await.map_err(|e| {
+        ConnectorError::retryable_with_source(
+            format!("Failed to write batch to Delta table: {}", mapping.to),
+            e,
+        )
+    })?;
+
+    // Flush and commit the write
+    let new_version = writer.flush_and_commit(table).await.map_err(|e| {
+        commit_failure(
+            e,
+            format!("Failed to commit to Delta table: {}", mapping.to),
+        )
+    })?;
+
+    // CRITICAL: Reload the table to get the latest version
+    // The table reference is updated in place by flush_and_commit, but we should
+    // reload to ensure we have the latest state for subsequent writes
+    table.load().await.map_err(|e| {
+        ConnectorError::retryable_with_source(
+            format!("Failed to reload Delta table after commit: {}", mapping.to),
+            e,
+        )
+    })?;
+
+    Ok(new_version)
+}
+
+/// Write a RecordBatch through the Delta write operation
+///
+/// Used for overwrite mode (optionally scoped by `replace_where`) and for any
+/// write that must carry commit properties such as an application transaction.
+async fn write_with_operation(
+    table: &mut DeltaTable,
+    mapping: &TopicMapping,
+    record_batch: RecordBatch,
+    write_mode: WriteMode,
+    commit_properties: Option<CommitProperties>,
+) -> Result<i64, WriteFailure> {
+    let save_mode = match write_mode {
+        WriteMode::Append => SaveMode::Append,
+        WriteMode::Overwrite => SaveMode::Overwrite,
+    };
+
+    let mut builder = DeltaOps(table.clone())
+        .write(vec![record_batch])
+        .with_save_mode(save_mode);
+
+    // Partition-scoped overwrite: only rows matching the predicate are replaced
+    if let Some(predicate) = &mapping.replace_where {
+        builder = builder.with_replace_where(predicate.clone());
+    }
+
+    if let Some(commit_properties) = commit_properties {
+        builder = builder.with_commit_properties(commit_properties);
+    }
+
+    let updated = builder.await.map_err(|e| {
+        commit_failure(e, format!("Failed to write to Delta table: {}", mapping.to))
+    })?;
+
+    // The write operation returns the updated table state; replace the cached one
+    *table = updated;
+
+    Ok(table.version().unwrap_or_default())
+}
+
+/// Apply a batch of change events with a Delta MERGE on the CDC key columns
+///
+/// Matched rows flagged as deletes are removed, other matched rows are updated,
+/// and unmatched non-delete rows are inserted.
+async fn merge_cdc_batch(
+    table: &mut DeltaTable,
+    mapping: &TopicMapping,
+    source_batch: RecordBatch,
+    commit_properties: Option<CommitProperties>,
+) -> Result<i64, WriteFailure> {
+    let Some(cdc) = &mapping.cdc else {
+        return Err(ConnectorError::fatal("CDC merge requested for a non-CDC route").into());
+    };
+
+    let ctx = SessionContext::new();
+    let source = ctx.read_batch(source_batch).map_err(|e| {
+        ConnectorError::fatal(format!(
+            "Failed to prepare CDC merge source for {}: {}",
+            mapping.to, e
+        ))
+    })?;
+
+    // Target columns written by the merge (excludes the internal op column)
+    let columns: Vec<String> = build_arrow_schema(mapping)?
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect();
+
+    let predicate = cdc
+        .key_columns
+        .iter()
+        .map(|key| format!("target.{key} = source.{key}"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let is_delete = format!("source.{} = '{}'", CDC_OP_COLUMN, CDC_OP_DELETE);
+    let is_upsert = format!("source.{} = '{}'", CDC_OP_COLUMN, CDC_OP_UPSERT);
+
+    let mut builder = DeltaOps(table.clone())
+        .merge(source, predicate)
+        .with_source_alias("source")
+        .with_target_alias("target")
+        .when_matched_delete(|delete| delete.predicate(is_delete.clone()))
+        .and_then(|merge| {
+            merge.when_matched_update(|update| {
+                columns
+                    .iter()
+                    .fold(update.predicate(is_upsert.clone()), |update, column| {
+                        update.update(column.as_str(), format!("source.{column}"))
+                    })
+            })
+        })
+        .and_then(|merge| {
+            merge.when_not_matched_insert(|insert| {
+                columns
+                    .iter()
+                    .fold(insert.predicate(is_upsert.clone()), |insert, column| {
+                        insert.set(column.as_str(), format!("source.{column}"))
+                    })
+            })
+        })
+        .map_err(|e| {
+            ConnectorError::fatal_with_source(
+                format!("Failed to build CDC merge for Delta table: {}", mapping.to),
                 e,
             )
         })?;
 
-        // CRITICAL: Reload the table to get the latest version
-        // The table reference is updated in place by flush_and_commit, but we should
-        // reload to ensure we have the latest state for subsequent writes
-        table.load().await.map_err(|e| {
+    if let Some(commit_properties) = commit_properties {
+        builder = builder.with_commit_properties(commit_properties);
+    }
+
+    let (updated, metrics) = builder.await.map_err(|e| {
+        commit_failure(
+            e,
+            format!("Failed to merge into Delta table: {}", mapping.to),
+        )
+    })?;
+
+    debug!(
+        "CDC merge into {}: inserted={}, updated={}, deleted={}",
+        mapping.to,
+        metrics.num_target_rows_inserted,
+        metrics.num_target_rows_updated,
+        metrics.num_target_rows_deleted
+    );
+
+    // The merge operation returns the updated table state; replace the cached one
+    *table = updated;
+
+    Ok(table.version().unwrap_or_default())
+}
+
+/// Read the last committed transaction version for an app id, if any
+async fn committed_transaction_version(
+    table: &DeltaTable,
+    app_id: &str,
+) -> ConnectorResult<Option<i64>> {
+    let snapshot = table
+        .snapshot()
+        .map_err(|e| ConnectorError::fatal_with_source("Failed to read Delta table snapshot", e))?;
+
+    snapshot
+        .transaction_version(table.log_store().as_ref(), app_id)
+        .await
+        .map_err(|e| {
             ConnectorError::retryable_with_source(
-                format!("Failed to reload Delta table after commit: {}", mapping.to),
+                format!("Failed to read transaction version for app id: {}", app_id),
                 e,
             )
-        })?;
+        })
+}
 
-        info!(
-            "Successfully wrote {} records to Delta table: {} (version: {})",
-            records.len(),
-            mapping.to,
-            new_version
-        );
+/// Compare an existing table's schema with the route's mappings
+///
+/// Reports mapped columns the table lacks or types differently, and required
+/// table columns that the mapping can leave null, before anything is written.
+fn check_table_schema(table: &DeltaTable, mapping: &TopicMapping) -> ConnectorResult<()> {
+    if mapping.schema_check == SchemaCheckMode::Off {
+        return Ok(());
+    }
 
-        Ok(())
+    let snapshot = table
+        .snapshot()
+        .map_err(|e| ConnectorError::fatal_with_source("Failed to read Delta table snapshot", e))?;
+    let table_schema = snapshot.schema();
+    let expected = build_arrow_schema(mapping)?;
+
+    let mut problems = Vec::new();
+    for field in expected.fields() {
+        let expected_type = arrow_to_delta_datatype(field.data_type());
+        match table_schema.field(field.name()) {
+            None => problems.push(format!(
+                "column '{}' is missing from the table",
+                field.name()
+            )),
+            Some(existing) if existing.data_type() != &expected_type => problems.push(format!(
+                "column '{}' is {:?} in the table but {:?} in the mapping",
+                field.name(),
+                existing.data_type(),
+                expected_type
+            )),
+            Some(existing) if !existing.is_nullable() && field.is_nullable() => {
+                problems.push(format!(
+                    "column '{}' is NOT NULL in the table but nullable in the mapping",
+                    field.name()
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    for existing in table_schema.fields() {
+        if !existing.is_nullable() && expected.field_with_name(existing.name()).is_err() {
+            problems.push(format!(
+                "required column '{}' is not populated by the mapping",
+                existing.name()
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "Delta table {} is incompatible with route '{}': {}",
+        mapping.to,
+        mapping.from,
+        problems.join("; ")
+    );
+    match mapping.schema_check {
+        SchemaCheckMode::Warn => {
+            warn!("{}", message);
+            Ok(())
+        }
+        _ => Err(ConnectorError::config(message)),
+    }
+}
+
+/// Read a credential from a required environment variable
+fn required_env(name: &str) -> ConnectorResult<String> {
+    std::env::var(name).map_err(|_| {
+        ConnectorError::config(format!(
+            "Environment variable '{}' is not set (required for storage credentials)",
+            name
+        ))
+    })
+}
+
+/// Read `{prefix}_{name}` from the environment into the given storage option key
+fn insert_prefixed_env(
+    options: &mut HashMap<String, String>,
+    prefix: &str,
+    name: &str,
+    option_key: &str,
+) {
+    if let Ok(value) = std::env::var(format!("{}_{}", prefix, name)) {
+        options.insert(option_key.to_string(), value);
     }
 }
 
@@ -259,10 +1153,10 @@ fn arrow_to_delta_datatype(arrow_type: &arrow::datatypes::DataType) -> deltalake
         ArrowType::Float64 => DeltaType::Primitive(PrimitiveType::Double),
         ArrowType::Boolean => DeltaType::Primitive(PrimitiveType::Boolean),
         ArrowType::Binary | ArrowType::LargeBinary => DeltaType::Primitive(PrimitiveType::Binary),
-        ArrowType::Timestamp(TimeUnit::Microsecond, _) => {
-            DeltaType::Primitive(PrimitiveType::Timestamp)
+        ArrowType::Timestamp(TimeUnit::Microsecond | TimeUnit::Millisecond, None) => {
+            DeltaType::Primitive(PrimitiveType::TimestampNtz)
         }
-        ArrowType::Timestamp(TimeUnit::Millisecond, _) => {
+        ArrowType::Timestamp(TimeUnit::Microsecond | TimeUnit::Millisecond, Some(_)) => {
             DeltaType::Primitive(PrimitiveType::Timestamp)
         }
         ArrowType::Date32 | ArrowType::Date64 => DeltaType::Primitive(PrimitiveType::Date),
@@ -293,6 +1187,53 @@ impl SinkConnector for DeltaLakeSinkConnector {
                 mapping.field_mappings.len(),
                 schema_info
             );
+
+            // Load Avro schemas / Protobuf descriptors once, up front
+            if let Some(decoder) = PayloadDecoder::from_mapping(mapping)? {
+                info!(
+                    "Route {} decodes {:?} payloads",
+                    mapping.from, mapping.payload_format
+                );
+                self.decoders.insert(mapping.from.clone(), decoder);
+            }
+        }
+
+        // Open statically-routed tables up front so storage and schema
+        // problems surface at startup rather than at the first write
+        let static_routes: Vec<TopicMapping> = self
+            .config
+            .deltalake
+            .routes
+            .iter()
+            .filter(|mapping| mapping.route_by.is_none())
+            .cloned()
+            .collect();
+        for mapping in &static_routes {
+            self.open_table(mapping).await?;
+        }
+
+        // Connect the DLQ producer client if any route dead-letters failed batches
+        let uses_dlq = self.config.deltalake.routes.iter().any(|mapping| {
+            mapping
+                .retry
+                .as_ref()
+                .is_some_and(|retry| retry.dlq_topic.is_some())
+        });
+        if uses_dlq {
+            self.dlq = Some(
+                DeadLetterQueue::connect(
+                    &self.config.core.danube_service_url,
+                    &self.config.core.connector_name,
+                )
+                .await?,
+            );
+        }
+
+        // Commit anything a previous run converted but didn't confirm
+        if let Some(dir) = &self.config.deltalake.spill_dir {
+            info!("Spill log enabled at {}", dir);
+            self.spill = Some(Arc::new(SpillLog::open(dir)?));
+            self.replay_spill().await?;
         }
 
         info!("Delta Lake Sink Connector initialized successfully");
@@ -312,7 +1253,7 @@ impl SinkConnector for DeltaLakeSinkConnector {
                     "{}-{}",
                     self.config.core.connector_name, mapping.subscription
                 ),
-                subscription_type: SubscriptionType::Shared,
+                subscription_type: mapping.subscription_type.clone(),
                 // Runtime validates schema and provides pre-deserialized data
                 expected_schema_subject: mapping.expected_schema_subject.clone(),
             })
@@ -334,6 +1275,8 @@ impl SinkConnector for DeltaLakeSinkConnector {
             by_topic.entry(topic).or_insert_with(Vec::new).push(record);
         }
 
+        // Group routes by destination table: each table is written by a single task
+        let mut by_table: HashMap<String, Vec<(TopicMapping, Vec<SinkRecord>)>> = HashMap::new();
         for (topic, topic_records) in by_topic {
             let mapping = self
                 .config
@@ -346,10 +1289,92 @@ impl SinkConnector for DeltaLakeSinkConnector {
                     ConnectorError::fatal(format!("No mapping found for topic: {}", topic))
                 })?;
 
-            self.write_batch(&mapping, topic_records).await?;
+            if mapping.route_by.is_none() {
+                self.open_table(&mapping).await?;
+                by_table
+                    .entry(mapping.to.clone())
+                    .or_default()
+                    .push((mapping, topic_records));
+                continue;
+            }
+
+            // Content-based routing: fan out to one table per route_by value
+            let decoder = self.decoders.get(&mapping.from);
+            for (table_path, routed_records) in route_records(topic_records, &mapping, decoder)? {
+                let mut routed = mapping.clone();
+                routed.to = table_path;
+                self.open_table(&routed).await?;
+                by_table
+                    .entry(routed.to.clone())
+                    .or_default()
+                    .push((routed, routed_records));
+            }
         }
 
-        Ok(())
+        // Take ownership of the writers so independent tables can flush concurrently
+        let mut jobs = Vec::with_capacity(by_table.len());
+        for (table_path, routes) in by_table {
+            if let Some(writer) = self.tables.remove(&table_path) {
+                jobs.push((table_path, writer, routes));
+            }
+        }
+
+        let config = &self.config;
+        let decoders = &self.decoders;
+        let spill = self.spill.as_deref();
+        let dlq = self.dlq.as_ref();
+        let memory_budget = &self.memory_budget;
+        let budget_total = budget_permits(config.deltalake.memory_budget_bytes);
+        let results: Vec<(String, TableWriter, ConnectorResult<()>)> = stream::iter(jobs)
+            .map(|(table_path, mut writer, routes)| async move {
+                let mut result = Ok(());
+                'routes: for (mapping, records) in routes {
+                    let decoder = decoders.get(&mapping.from);
+                    let max_bytes = mapping
+                        .max_buffer_bytes
+                        .unwrap_or(config.deltalake.max_buffer_bytes);
+
+                    // Write in byte-bounded chunks so large payloads don't
+                    // accumulate into a single oversized RecordBatch
+                    let max_bytes = chunk_limit(&mapping, max_bytes);
+                    for (chunk, chunk_bytes) in
+                        split_by_size(records, max_bytes, SinkRecord::payload)
+                    {
+                        // Slow down before taking budget while this table's commits lag
+                        writer.apply_backpressure(config, &table_path).await;
+
+                        // Chunks larger than the whole budget still proceed, alone
+                        let permits = budget_permits(chunk_bytes).min(budget_total);
+                        let _permit = memory_budget.acquire_many(permits).await.ok();
+
+                        result = writer
+                            .write_batch(config, &mapping, decoder, spill, dlq, chunk)
+                            .await;
+                        if result.is_err() {
+                            break 'routes;
+                        }
+                    }
+                }
+                (table_path, writer, result)
+            })
+            .buffer_unordered(config.deltalake.flush_parallelism)
+            .collect()
+            .await;
+
+        // Return writers to the cache and surface the first failure
+        let mut first_error = None;
+        for (table_path, writer, result) in results {
+            if let Err(e) = result {
+                warn!("Failed to flush Delta table {}: {}", table_path, e);
+                first_error.get_or_insert(e);
+            }
+            self.tables.insert(table_path, writer);
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
     }
 
     async fn shutdown(&mut self) -> ConnectorResult<()> {
@@ -0,0 +1,197 @@
+//! Payload decoding module for Delta Lake Sink Connector
+//!
+//! Decodes binary Avro and Protobuf payloads into JSON values before Arrow
+//! conversion, so field mappings work the same way for every payload format.
+//!
+//! Schemas come from local files rather than the Danube Schema Registry: sink
+//! records don't carry the schema id/version they were written with, so a
+//! registry lookup couldn't pick the right writer schema per message.
+//!
+//! Binary payloads are accepted in the shapes used across Danube connectors:
+//! - `{"data": "<base64>", "encoding": "base64"}` envelopes
+//! - Plain base64 strings
+//! - Arrays of byte values
+
+use crate::config::{PayloadFormat, TopicMapping};
+use apache_avro::Schema as AvroSchema;
+use base64::Engine;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde_json::Value;
+use std::fs;
+
+/// Avro single-object encoding marker (followed by an 8-byte schema fingerprint)
+const AVRO_SINGLE_OBJECT_MARKER: [u8; 2] = [0xC3, 0x01];
+
+/// Decoder for a route's binary payload format
+pub enum PayloadDecoder {
+    /// Avro datum decoded with a writer schema
+    Avro(AvroSchema),
+    /// Protobuf message decoded with a message descriptor
+    Protobuf(MessageDescriptor),
+}
+
+impl PayloadDecoder {
+    /// Build the decoder for a route, or None for JSON routes
+    pub fn from_mapping(mapping: &TopicMapping) -> ConnectorResult<Option<Self>> {
+        match mapping.payload_format {
+            PayloadFormat::Json => Ok(None),
+            PayloadFormat::Avro => {
+                let path = mapping.avro_schema_path.as_deref().ok_or_else(|| {
+                    ConnectorError::config(format!(
+                        "avro_schema_path is required for Avro route '{}'",
+                        mapping.from
+                    ))
+                })?;
+                let definition = fs::read_to_string(path).map_err(|e| {
+                    ConnectorError::config(format!("Failed to read Avro schema '{}': {}", path, e))
+                })?;
+                let schema = AvroSchema::parse_str(&definition).map_err(|e| {
+                    ConnectorError::config(format!("Invalid Avro schema '{}': {}", path, e))
+                })?;
+                Ok(Some(PayloadDecoder::Avro(schema)))
+            }
+            PayloadFormat::Protobuf => {
+                let (path, message) = match (
+                    mapping.protobuf_descriptor_path.as_deref(),
+                    mapping.protobuf_message.as_deref(),
+                ) {
+                    (Some(path), Some(message)) => (path, message),
+                    _ => {
+                        return Err(ConnectorError::config(format!(
+                            "protobuf_descriptor_path and protobuf_message are required for Protobuf route '{}'",
+                            mapping.from
+                        )))
+                    }
+                };
+                let bytes = fs::read(path).map_err(|e| {
+                    ConnectorError::config(format!(
+                        "Failed to read Protobuf descriptor set '{}': {}",
+                        path, e
+                    ))
+                })?;
+                let pool = DescriptorPool::decode(bytes.as_slice()).map_err(|e| {
+                    ConnectorError::config(format!(
+                        "Invalid Protobuf descriptor set '{}': {}",
+                        path, e
+                    ))
+                })?;
+                let descriptor = pool.get_message_by_name(message).ok_or_else(|| {
+                    ConnectorError::config(format!(
+                        "Protobuf message '{}' not found in '{}'",
+                        message, path
+                    ))
+                })?;
+                Ok(Some(PayloadDecoder::Protobuf(descriptor)))
+            }
+        }
+    }
+
+    /// Decode a binary payload into a JSON value
+    pub fn decode(&self, payload: &Value) -> ConnectorResult<Value> {
+        let bytes = payload_bytes(payload)?;
+
+        match self {
+            PayloadDecoder::Avro(schema) => {
+                // Strip the single-object header if the producer used it
+                let mut datum = if bytes.len() > 10 && bytes[..2] == AVRO_SINGLE_OBJECT_MARKER {
+                    &bytes[10..]
+                } else {
+                    &bytes[..]
+                };
+                let value =
+                    apache_avro::from_avro_datum(schema, &mut datum, None).map_err(|e| {
+                        invalid_payload(format!("Failed to decode Avro: {}", e), &bytes)
+                    })?;
+                Value::try_from(value).map_err(|e| {
+                    invalid_payload(format!("Failed to convert Avro to JSON: {}", e), &bytes)
+                })
+            }
+            PayloadDecoder::Protobuf(descriptor) => {
+                let message = DynamicMessage::decode(descriptor.clone(), bytes.as_slice())
+                    .map_err(|e| {
+                        invalid_payload(format!("Failed to decode Protobuf: {}", e), &bytes)
+                    })?;
+                serde_json::to_value(&message).map_err(|e| {
+                    invalid_payload(format!("Failed to convert Protobuf to JSON: {}", e), &bytes)
+                })
+            }
+        }
+    }
+}
+
+/// Extract raw bytes from a binary payload representation
+fn payload_bytes(payload: &Value) -> ConnectorResult<Vec<u8>> {
+    let decode_base64 = |data: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| invalid_payload(format!("Invalid base64 payload: {}", e), data.as_bytes()))
+    };
+
+    match payload {
+        Value::Object(map) => match map.get("data") {
+            Some(Value::String(data)) => decode_base64(data),
+            _ => Err(invalid_payload(
+                "Binary payload object must contain a base64 'data' field",
+                payload.to_string().as_bytes(),
+            )),
+        },
+        Value::String(data) => decode_base64(data),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| {
+                invalid_payload(
+                    "Binary payload array must contain byte values",
+                    payload.to_string().as_bytes(),
+                )
+            }),
+        _ => Err(invalid_payload(
+            "Unsupported binary payload representation",
+            payload.to_string().as_bytes(),
+        )),
+    }
+}
+
+fn invalid_payload(message: impl Into<String>, payload: &[u8]) -> ConnectorError {
+    ConnectorError::invalid_data(message.into(), payload.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_bytes_representations() {
+        let envelope = json!({"data": "AQID", "encoding": "base64"});
+        assert_eq!(payload_bytes(&envelope).unwrap(), vec![1, 2, 3]);
+        assert_eq!(payload_bytes(&json!("AQID")).unwrap(), vec![1, 2, 3]);
+        assert_eq!(payload_bytes(&json!([1, 2, 3])).unwrap(), vec![1, 2, 3]);
+        assert!(payload_bytes(&json!([1, 256])).is_err());
+        assert!(payload_bytes(&json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_avro_decode() {
+        let schema = AvroSchema::parse_str(
+            r#"{"type": "record", "name": "Payment", "fields": [
+                {"name": "id", "type": "string"},
+                {"name": "amount", "type": "double"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let mut record = apache_avro::types::Record::new(&schema).unwrap();
+        record.put("id", "pay_1");
+        record.put("amount", 12.5);
+        let bytes = apache_avro::to_avro_datum(&schema, record).unwrap();
+
+        let decoder = PayloadDecoder::Avro(schema);
+        let payload = json!(base64::engine::general_purpose::STANDARD.encode(bytes));
+        let decoded = decoder.decode(&payload).unwrap();
+
+        assert_eq!(decoded, json!({"id": "pay_1", "amount": 12.5}));
+    }
+}
@@ -40,7 +40,10 @@
 
 pub mod config;
 pub mod connector;
+pub mod decode;
+pub mod dlq;
 pub mod record;
+pub mod spill;
 
 pub use config::DeltaLakeSinkConfig;
 pub use connector::DeltaLakeSinkConnector;
@@ -6,27 +6,35 @@
 //! Supports all Danube schema types (Json, String, Int64) and includes optional
 //! Danube metadata as a JSON column.
 
-use crate::config::TopicMapping;
+use crate::config::{
+    is_valid_route_value, CdcConfig, FieldMapping, TimestampPartition, TopicMapping, WriteMode,
+    DEFAULT_NOW, ROUTE_PLACEHOLDER,
+};
+use crate::decode::PayloadDecoder;
 use arrow::array::{ArrayRef, StringArray};
 use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
 use arrow::record_batch::RecordBatch;
 use arrow_json::ReaderBuilder;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::Cursor;
 use std::sync::Arc;
 
 /// Convert a batch of Danube SinkRecords into an Arrow RecordBatch
 ///
 /// This function uses arrow-json's ReaderBuilder for efficient, robust conversion:
-/// 1. Gets typed payloads from records (already deserialized by runtime)
+/// 1. Gets typed payloads from records (already deserialized by runtime, or
+///    decoded from Avro / Protobuf when a decoder is configured)
 /// 2. Transforms JSON based on field_mappings (supports nested JSON paths)
 /// 3. Uses arrow-json to build RecordBatch with proper null handling and type coercion
 /// 4. Optionally adds Danube metadata as a JSON column
 pub fn to_record_batch(
     records: &[SinkRecord],
     mapping: &TopicMapping,
+    decoder: Option<&PayloadDecoder>,
 ) -> ConnectorResult<RecordBatch> {
     if records.is_empty() {
         return Err(ConnectorError::fatal(
@@ -34,13 +42,15 @@ pub fn to_record_batch(
         ));
     }
 
+    let payloads = decode_payloads(records, decoder)?;
+
     // Build Arrow schema from field mappings (without metadata)
     let schema = build_arrow_schema_without_metadata(mapping)?;
 
     // Transform payloads to match target schema (handle JSON path remapping)
-    let transformed_json: Vec<Value> = records
+    let transformed_json: Vec<Value> = payloads
         .iter()
-        .map(|record| transform_payload_for_schema(record.payload(), mapping))
+        .map(|payload| transform_payload_for_schema(payload, mapping))
         .collect();
 
     // Use arrow-json to build RecordBatch efficiently
@@ -55,21 +65,584 @@ pub fn to_record_batch(
     Ok(batch)
 }
 
+/// Resolve record payloads, decoding binary formats when a decoder is configured
+fn decode_payloads<'a>(
+    records: &'a [SinkRecord],
+    decoder: Option<&PayloadDecoder>,
+) -> ConnectorResult<Vec<Cow<'a, Value>>> {
+    records
+        .iter()
+        .map(|record| match decoder {
+            Some(decoder) => decoder.decode(record.payload()).map(Cow::Owned),
+            None => Ok(Cow::Borrowed(record.payload())),
+        })
+        .collect()
+}
+
+/// Group a route's records by destination table path (content-based routing)
+///
+/// Records whose route_by value is missing (without a default_route) or unsafe
+/// for a table path are rejected as invalid data.
+pub fn route_records(
+    records: Vec<SinkRecord>,
+    mapping: &TopicMapping,
+    decoder: Option<&PayloadDecoder>,
+) -> ConnectorResult<HashMap<String, Vec<SinkRecord>>> {
+    let mut routed: HashMap<String, Vec<SinkRecord>> = HashMap::new();
+
+    for record in records {
+        let decoded;
+        let payload = match decoder {
+            Some(decoder) => {
+                decoded = decoder.decode(record.payload())?;
+                &decoded
+            }
+            None => record.payload(),
+        };
+
+        let table_path = routed_table_path(payload, mapping).map_err(|reason| {
+            ConnectorError::invalid_data(
+                format!("Routing failed for topic {}: {}", record.topic(), reason),
+                serde_json::to_vec(payload).unwrap_or_default(),
+            )
+        })?;
+        routed.entry(table_path).or_default().push(record);
+    }
+
+    Ok(routed)
+}
+
+/// Resolve the destination table path of a payload from the route's `to` template
+fn routed_table_path(payload: &Value, mapping: &TopicMapping) -> Result<String, String> {
+    let route_by = mapping.route_by.as_deref().unwrap_or_default();
+    let value = match extract_value_by_path_parts(payload, &mapping.route_by_path_parts) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Null) | None => mapping
+            .default_route
+            .clone()
+            .ok_or_else(|| format!("route_by field '{}' is missing", route_by))?,
+        Some(other) => {
+            return Err(format!(
+                "route_by field '{}' must be a string or number, got {}",
+                route_by, other
+            ))
+        }
+    };
+
+    if !is_valid_route_value(&value) {
+        return Err(format!(
+            "route_by value '{}' may only contain letters, digits, '_' or '-'",
+            value
+        ));
+    }
+
+    Ok(mapping.to.replace(ROUTE_PLACEHOLDER, &value))
+}
+
+/// Byte limit of the chunks a route's records are committed in
+///
+/// Overwrite routes are committed in a single chunk: every overwrite commit
+/// replaces the table (or its `replace_where` rows), so each chunk would
+/// discard the ones written before it.
+pub fn chunk_limit(mapping: &TopicMapping, max_bytes: usize) -> usize {
+    if mapping.write_mode == WriteMode::Overwrite && mapping.cdc.is_none() {
+        usize::MAX
+    } else {
+        max_bytes
+    }
+}
+
+/// Split records into chunks whose estimated payload size stays within `max_bytes`
+///
+/// Each chunk holds at least one record, so a single oversized record forms its
+/// own chunk. Returns each chunk with its estimated size in bytes.
+pub fn split_by_size<T>(
+    records: Vec<T>,
+    max_bytes: usize,
+    payload: impl Fn(&T) -> &Value,
+) -> Vec<(Vec<T>, usize)> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes: usize = 0;
+
+    for record in records {
+        let size = estimated_json_size(payload(&record));
+        if !current.is_empty() && current_bytes.saturating_add(size) > max_bytes {
+            chunks.push((std::mem::take(&mut current), current_bytes));
+            current_bytes = 0;
+        }
+        current_bytes += size;
+        current.push(record);
+    }
+
+    if !current.is_empty() {
+        chunks.push((current, current_bytes));
+    }
+
+    chunks
+}
+
+/// Drop records at or below the committed offset of their Delta transaction
+///
+/// `transaction` gives a record's app id (one per topic partition) and offset.
+/// Returns the remaining records and, per app id, the highest remaining offset
+/// to record as that transaction's new version.
+pub fn uncommitted<T>(
+    records: Vec<T>,
+    committed: &HashMap<String, Option<i64>>,
+    transaction: impl Fn(&T) -> (String, i64),
+) -> (Vec<T>, Vec<(String, i64)>) {
+    let mut versions: Vec<(String, i64)> = Vec::new();
+    let mut pending = Vec::new();
+
+    for record in records {
+        let (app_id, offset) = transaction(&record);
+        if committed
+            .get(&app_id)
+            .copied()
+            .flatten()
+            .is_some_and(|version| offset <= version)
+        {
+            continue;
+        }
+        match versions.iter_mut().find(|(id, _)| *id == app_id) {
+            Some((_, version)) => *version = (*version).max(offset),
+            None => versions.push((app_id, offset)),
+        }
+        pending.push(record);
+    }
+
+    (pending, versions)
+}
+
+/// Estimate the serialized size of a JSON value without serializing it
+pub fn estimated_json_size(value: &Value) -> usize {
+    match value {
+        Value::Null => 4,
+        Value::Bool(_) => 5,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len() + 2,
+        Value::Array(items) => {
+            2 + items
+                .iter()
+                .map(|v| estimated_json_size(v) + 1)
+                .sum::<usize>()
+        }
+        Value::Object(map) => {
+            2 + map
+                .iter()
+                .map(|(k, v)| k.len() + 4 + estimated_json_size(v))
+                .sum::<usize>()
+        }
+    }
+}
+
+/// Internal column carrying the change operation in CDC merge source batches
+pub const CDC_OP_COLUMN: &str = "_danube_cdc_op";
+
+/// CDC operation value for rows that should be deleted
+pub const CDC_OP_DELETE: &str = "delete";
+
+/// CDC operation value for rows that should be inserted or updated
+pub const CDC_OP_UPSERT: &str = "upsert";
+
+/// Convert a batch of change events into a MERGE source RecordBatch
+///
+/// Only the latest event per key is kept (MERGE rejects multiple source rows
+/// matching one target row). All mapped columns are nullable so delete events
+/// carrying only the key can be represented; the operation is stored in
+/// `CDC_OP_COLUMN`.
+pub fn to_cdc_record_batch(
+    records: &[SinkRecord],
+    mapping: &TopicMapping,
+    cdc: &CdcConfig,
+    decoder: Option<&PayloadDecoder>,
+) -> ConnectorResult<RecordBatch> {
+    if records.is_empty() {
+        return Err(ConnectorError::fatal(
+            "Cannot create RecordBatch from empty records",
+        ));
+    }
+
+    let payloads = decode_payloads(records, decoder)?;
+
+    // Transform payloads and tag each row with its operation
+    let mut rows: Vec<Value> = Vec::with_capacity(records.len());
+    let mut latest_by_key: HashMap<String, usize> = HashMap::new();
+    for (index, payload) in payloads.iter().enumerate() {
+        let operation = cdc_operation(payload, cdc);
+        let mut row = transform_payload_for_schema(payload, mapping);
+        let key: Vec<&Value> = cdc
+            .key_columns
+            .iter()
+            .map(|column| row.get(column).unwrap_or(&Value::Null))
+            .collect();
+        latest_by_key.insert(serde_json::to_string(&key).unwrap_or_default(), index);
+
+        if let Value::Object(map) = &mut row {
+            map.insert(
+                CDC_OP_COLUMN.to_string(),
+                Value::String(operation.to_string()),
+            );
+        }
+        rows.push(row);
+    }
+
+    // Keep the latest event per key, preserving arrival order
+    let mut kept: Vec<usize> = latest_by_key.into_values().collect();
+    kept.sort_unstable();
+
+    let kept_rows: Vec<Value> = kept.iter().map(|&i| rows[i].clone()).collect();
+
+    // Source schema: mapped columns (all nullable) plus the operation column
+    let base_schema = build_arrow_schema_without_metadata(mapping)?;
+    let mut fields: Vec<Field> = base_schema
+        .fields()
+        .iter()
+        .map(|f| f.as_ref().clone().with_nullable(true))
+        .collect();
+    fields.push(Field::new(CDC_OP_COLUMN, DataType::Utf8, false));
+
+    let batch = json_to_record_batch(Arc::new(Schema::new(fields)), &kept_rows)?;
+
+    if mapping.include_danube_metadata {
+        let metadata_array = build_metadata_array(kept.iter().map(|&i| &records[i]))?;
+        return add_metadata_column(batch, metadata_array);
+    }
+
+    Ok(batch)
+}
+
+/// Classify a change event as delete or upsert based on the configured op field
+fn cdc_operation(payload: &Value, cdc: &CdcConfig) -> &'static str {
+    let is_delete = extract_value_by_path_parts(payload, &cdc.op_path_parts)
+        .and_then(|value| value.as_str())
+        .map(|op| {
+            cdc.delete_values
+                .iter()
+                .any(|delete| delete.eq_ignore_ascii_case(op))
+        })
+        .unwrap_or(false);
+
+    if is_delete {
+        CDC_OP_DELETE
+    } else {
+        CDC_OP_UPSERT
+    }
+}
+
 /// Transform a JSON payload based on field mappings
 /// Extracts values using JSON paths and creates a flat JSON object matching the target schema
 fn transform_payload_for_schema(payload: &Value, mapping: &TopicMapping) -> Value {
     let mut transformed = serde_json::Map::new();
 
     for field_mapping in &mapping.field_mappings {
-        // Use pre-split path_parts for optimized extraction (avoids repeated string splitting)
-        if let Some(value) = extract_value_by_path_parts(payload, &field_mapping.path_parts) {
-            transformed.insert(field_mapping.column.clone(), value.clone());
+        if let Some(value) = extract_field_value(payload, field_mapping) {
+            transformed.insert(field_mapping.column.clone(), value);
+        }
+    }
+
+    // Derived partition column (e.g., event_date from event_time)
+    if let Some(partition) = &mapping.partition_from_timestamp {
+        if let Some(value) = derive_partition_value(payload, partition) {
+            transformed.insert(partition.column.clone(), Value::String(value));
         }
     }
 
     Value::Object(transformed)
 }
 
+/// Format the event timestamp of a payload as a partition value
+/// Accepts RFC 3339 strings or epoch milliseconds; returns None if missing or unparsable
+fn derive_partition_value(payload: &Value, partition: &TimestampPartition) -> Option<String> {
+    let timestamp = match extract_value_by_path_parts(payload, &partition.path_parts)? {
+        Value::String(s) => DateTime::parse_from_rfc3339(s).ok()?.with_timezone(&Utc),
+        Value::Number(n) => Utc.timestamp_millis_opt(n.as_i64()?).single()?,
+        _ => return None,
+    };
+
+    Some(timestamp.format(&partition.format).to_string())
+}
+
+/// Separate the records failing validation, with their error, from the valid ones
+pub fn split_invalid<T>(
+    records: Vec<T>,
+    validate: impl Fn(&T) -> ConnectorResult<()>,
+) -> (Vec<T>, Vec<(T, ConnectorError)>) {
+    let mut valid = Vec::with_capacity(records.len());
+    let mut invalid = Vec::new();
+    for record in records {
+        match validate(&record) {
+            Ok(()) => valid.push(record),
+            Err(e) => invalid.push((record, e)),
+        }
+    }
+    (valid, invalid)
+}
+
+/// Validate a record against the route's field mappings (strict mode)
+///
+/// CDC deletes only need their key, so only upserts are checked.
+pub fn validate_record(
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+    decoder: Option<&PayloadDecoder>,
+) -> ConnectorResult<()> {
+    let payload = match decoder {
+        Some(decoder) => Cow::Owned(decoder.decode(record.payload())?),
+        None => Cow::Borrowed(record.payload()),
+    };
+    let is_delete = mapping
+        .cdc
+        .as_ref()
+        .is_some_and(|cdc| cdc_operation(&payload, cdc) == CDC_OP_DELETE);
+    if is_delete {
+        return Ok(());
+    }
+    validate_payload(&payload, record.topic(), mapping)
+}
+
+/// Validate a (decoded) payload against the route's field mappings (strict mode)
+///
+/// Returns an invalid_data error carrying the original payload, so the record
+/// can be dead-lettered instead of writing nulls.
+fn validate_payload(payload: &Value, topic: &str, mapping: &TopicMapping) -> ConnectorResult<()> {
+    for field_mapping in &mapping.field_mappings {
+        if let Err(reason) = validate_field(payload, field_mapping) {
+            return Err(ConnectorError::invalid_data(
+                format!("Strict validation failed for topic {}: {}", topic, reason),
+                serde_json::to_vec(payload).unwrap_or_default(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a single mapped field is present (if required) and type-compatible
+///
+/// Values that fail timestamp parsing or coercion are errors, even in nullable
+/// fields, rather than the nulls lenient mode writes for them.
+fn validate_field(payload: &Value, field_mapping: &FieldMapping) -> Result<(), String> {
+    match convert_field_value(payload, field_mapping)? {
+        None | Some(Value::Null) => {
+            if field_mapping.nullable {
+                Ok(())
+            } else {
+                Err(format!(
+                    "required field '{}' is missing or null",
+                    field_mapping.json_path
+                ))
+            }
+        }
+        Some(value) => {
+            if value_matches_type(&value, &field_mapping.data_type) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "field '{}' value {} does not match type {}",
+                    field_mapping.json_path, value, field_mapping.data_type
+                ))
+            }
+        }
+    }
+}
+
+/// Extract a mapped field from a payload, applying type coercion if enabled
+///
+/// Values that can't be parsed or coerced become null.
+fn extract_field_value(payload: &Value, field_mapping: &FieldMapping) -> Option<Value> {
+    convert_field_value(payload, field_mapping).unwrap_or(Some(Value::Null))
+}
+
+/// Extract a mapped field from a payload, applying timestamp parsing and type coercion
+///
+/// Returns an error when the value can't be parsed or coerced.
+fn convert_field_value(
+    payload: &Value,
+    field_mapping: &FieldMapping,
+) -> Result<Option<Value>, String> {
+    // Use pre-split path_parts for optimized extraction (avoids repeated string splitting)
+    let value = match extract_value_by_path_parts(payload, &field_mapping.path_parts) {
+        Some(value) if !value.is_null() => value,
+        // Defaults are already in the column's representation
+        missing => {
+            return Ok(match &field_mapping.default {
+                Some(default) => Some(resolve_default(default, &field_mapping.data_type)),
+                None => missing.cloned(),
+            })
+        }
+    };
+
+    if let Some(format) = &field_mapping.timestamp_format {
+        let utc_adjusted = field_mapping.data_type == "Timestamp";
+        return parse_timestamp(value, format, utc_adjusted)
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "field '{}' value {} does not match timestamp_format '{}'",
+                    field_mapping.json_path, value, format
+                )
+            });
+    }
+
+    if field_mapping.coerce {
+        coerce_value(value, &field_mapping.data_type)
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "field '{}' value {} can't be coerced to {}",
+                    field_mapping.json_path, value, field_mapping.data_type
+                )
+            })
+    } else {
+        Ok(Some(value.clone()))
+    }
+}
+
+/// Resolve a field default, evaluating the "now()" expression for the column type
+fn resolve_default(default: &Value, data_type: &str) -> Value {
+    if default.as_str() != Some(DEFAULT_NOW) {
+        return default.clone();
+    }
+
+    let now = Utc::now();
+    match data_type {
+        "Timestamp" | "TimestampNtz" => Value::from(now.timestamp_micros()),
+        "Int64" => Value::from(now.timestamp_millis()),
+        _ => Value::String(now.to_rfc3339()),
+    }
+}
+
+/// Parse a timestamp value with a field's timestamp_format into epoch microseconds
+///
+/// For UTC-adjusted columns, offsets are converted to UTC. For naive (NTZ) columns,
+/// the wall-clock time is kept as written and any offset is dropped.
+/// Returns None if the value can't be parsed
+fn parse_timestamp(value: &Value, format: &str, utc_adjusted: bool) -> Option<Value> {
+    if value.is_null() {
+        return Some(Value::Null);
+    }
+
+    let epoch = |scale: f64| -> Option<i64> {
+        let n = match value {
+            Value::Number(n) => n.as_f64()?,
+            Value::String(s) => s.trim().parse::<f64>().ok()?,
+            _ => return None,
+        };
+        let micros = (n * scale).round();
+        (micros.is_finite() && micros.abs() < i64::MAX as f64).then_some(micros as i64)
+    };
+
+    let micros = match format {
+        "unix_s" => epoch(1_000_000.0)?,
+        "unix_ms" => epoch(1_000.0)?,
+        "unix_us" => epoch(1.0)?,
+        _ => {
+            let s = value.as_str()?.trim();
+            let with_offset = if format == "rfc3339" {
+                DateTime::parse_from_rfc3339(s).ok()
+            } else {
+                DateTime::parse_from_str(s, format).ok()
+            };
+
+            match with_offset {
+                Some(dt) if utc_adjusted => dt.timestamp_micros(),
+                Some(dt) => dt.naive_local().and_utc().timestamp_micros(),
+                // Patterns without an offset are read as UTC / wall-clock time
+                None if format != "rfc3339" => NaiveDateTime::parse_from_str(s, format)
+                    .ok()?
+                    .and_utc()
+                    .timestamp_micros(),
+                None => return None,
+            }
+        }
+    };
+
+    Some(Value::from(micros))
+}
+
+/// Coerce a JSON value into a representation compatible with the given Arrow type
+/// Returns None if the value can't be converted
+fn coerce_value(value: &Value, data_type: &str) -> Option<Value> {
+    if value.is_null() || value_matches_type(value, data_type) {
+        return Some(value.clone());
+    }
+
+    let coerced = match (data_type, value) {
+        ("Utf8", Value::Number(n)) => Value::String(n.to_string()),
+        ("Utf8", Value::Bool(b)) => Value::String(b.to_string()),
+        ("Utf8", Value::Array(_) | Value::Object(_)) => Value::String(value.to_string()),
+        (
+            "Int8" | "Int16" | "Int32" | "Int64" | "UInt8" | "UInt16" | "UInt32" | "UInt64",
+            Value::String(s),
+        ) => match s.trim().parse::<i64>() {
+            Ok(v) => Value::from(v),
+            Err(_) => Value::from(s.trim().parse::<u64>().ok()?),
+        },
+        (
+            "Int8" | "Int16" | "Int32" | "Int64" | "UInt8" | "UInt16" | "UInt32" | "UInt64",
+            Value::Number(n),
+        ) => {
+            // Accept floats without a fractional part (e.g., 42.0)
+            let f = n.as_f64()?;
+            if f.fract() != 0.0 {
+                return None;
+            }
+            Value::from(f as i64)
+        }
+        (
+            "Int8" | "Int16" | "Int32" | "Int64" | "UInt8" | "UInt16" | "UInt32" | "UInt64",
+            Value::Bool(b),
+        ) => Value::from(*b as i64),
+        ("Float32" | "Float64", Value::String(s)) => {
+            Value::from(s.trim().parse::<f64>().ok().filter(|f| f.is_finite())?)
+        }
+        ("Boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Value::Bool(true),
+            "false" | "0" | "no" => Value::Bool(false),
+            _ => return None,
+        },
+        ("Boolean", Value::Number(n)) => Value::Bool(n.as_f64()? != 0.0),
+        _ => return None,
+    };
+
+    // Re-check ranges after conversion (e.g., "300" into UInt8)
+    value_matches_type(&coerced, data_type).then_some(coerced)
+}
+
+/// Check whether a JSON value can be stored in a column of the given Arrow type
+pub(crate) fn value_matches_type(value: &Value, data_type: &str) -> bool {
+    let signed_in_range = |min: i64, max: i64| value.as_i64().is_some_and(|v| v >= min && v <= max);
+    let unsigned_in_range = |max: u64| value.as_u64().is_some_and(|v| v <= max);
+
+    match data_type {
+        "Utf8" | "Binary" => value.is_string(),
+        "Int8" => signed_in_range(i8::MIN as i64, i8::MAX as i64),
+        "Int16" => signed_in_range(i16::MIN as i64, i16::MAX as i64),
+        "Int32" => signed_in_range(i32::MIN as i64, i32::MAX as i64),
+        "Int64" => value.is_i64(),
+        "UInt8" => unsigned_in_range(u8::MAX as u64),
+        "UInt16" => unsigned_in_range(u16::MAX as u64),
+        "UInt32" => unsigned_in_range(u32::MAX as u64),
+        "UInt64" => value.is_u64(),
+        "Float32" | "Float64" => value.is_number(),
+        "Boolean" => value.is_boolean(),
+        "Timestamp" | "TimestampNtz" => match value {
+            Value::String(s) => DateTime::parse_from_rfc3339(s).is_ok(),
+            Value::Number(n) => n.is_i64(),
+            _ => false,
+        },
+        "Date32" | "Date64" => match value {
+            Value::String(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+            Value::Number(n) => n.is_i64(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 /// Convert JSON values to Arrow RecordBatch using arrow-json
 /// This is more efficient and robust than manual array building
 fn json_to_record_batch(
@@ -131,6 +704,10 @@ fn build_arrow_schema_without_metadata(mapping: &TopicMapping) -> ConnectorResul
         fields.push(field);
     }
 
+    if let Some(partition) = &mapping.partition_from_timestamp {
+        fields.push(Field::new(&partition.column, DataType::Utf8, true));
+    }
+
     Ok(Arc::new(Schema::new(fields)))
 }
 
@@ -145,6 +722,11 @@ pub fn build_arrow_schema(mapping: &TopicMapping) -> ConnectorResult<Arc<Schema>
         fields.push(field);
     }
 
+    // Add derived partition column if configured
+    if let Some(partition) = &mapping.partition_from_timestamp {
+        fields.push(Field::new(&partition.column, DataType::Utf8, true));
+    }
+
     // Add metadata field if configured
     if mapping.include_danube_metadata {
         fields.push(Field::new("_danube_metadata", DataType::Utf8, false));
@@ -168,7 +750,8 @@ fn parse_arrow_type(type_str: &str) -> ConnectorResult<DataType> {
         "Float32" => DataType::Float32,
         "Float64" => DataType::Float64,
         "Boolean" => DataType::Boolean,
-        "Timestamp" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        "Timestamp" => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        "TimestampNtz" => DataType::Timestamp(TimeUnit::Microsecond, None),
         "Binary" => DataType::Binary,
         _ => {
             return Err(ConnectorError::fatal(format!(
@@ -187,16 +770,22 @@ fn extract_value_by_path_parts<'a>(value: &'a Value, path_parts: &[String]) -> O
     let mut current = value;
 
     for part in path_parts {
-        current = current.get(part.as_str())?;
+        current = match current {
+            // Numeric segments index into arrays (e.g., "items[0]")
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => current.get(part.as_str())?,
+        };
     }
 
     Some(current)
 }
 
 /// Build metadata array with Danube message metadata as JSON
-fn build_metadata_array(records: &[SinkRecord]) -> ConnectorResult<ArrayRef> {
+fn build_metadata_array<'a>(
+    records: impl IntoIterator<Item = &'a SinkRecord>,
+) -> ConnectorResult<ArrayRef> {
     let metadata_strings: Vec<String> = records
-        .iter()
+        .into_iter()
         .map(|record| {
             let metadata = serde_json::json!({
                 "topic": record.topic(),
@@ -255,6 +844,26 @@ mod tests {
         assert_eq!(extract_value_by_path_parts(&value, &missing_parts), None);
     }
 
+    #[test]
+    fn test_extract_value_with_array_index() {
+        let value = json!({
+            "payload": {
+                "order": {
+                    "items": [{"price": 10.5}, {"price": 4.0}]
+                }
+            }
+        });
+
+        let parts = crate::config::parse_json_path("$.payload.order.items[1].price");
+        assert_eq!(
+            extract_value_by_path_parts(&value, &parts).and_then(|v| v.as_f64()),
+            Some(4.0)
+        );
+
+        let out_of_bounds = crate::config::parse_json_path("payload.order.items[5].price");
+        assert_eq!(extract_value_by_path_parts(&value, &out_of_bounds), None);
+    }
+
     #[test]
     fn test_parse_arrow_type() {
         assert!(matches!(parse_arrow_type("Utf8"), Ok(DataType::Utf8)));
@@ -266,8 +875,6 @@ mod tests {
 
     #[test]
     fn test_transform_payload_for_schema() {
-        use crate::config::FieldMapping;
-
         let payload = json!({
             "user": {
                 "name": "Alice",
@@ -283,6 +890,9 @@ mod tests {
             column: "name".to_string(),
             data_type: "Utf8".to_string(),
             nullable: false,
+            coerce: false,
+            timestamp_format: None,
+            default: None,
         };
         field_mapping1.init_path_parts();
 
@@ -292,21 +902,377 @@ mod tests {
             column: "status".to_string(),
             data_type: "Utf8".to_string(),
             nullable: false,
+            coerce: false,
+            timestamp_format: None,
+            default: None,
         };
         field_mapping2.init_path_parts();
 
         let mapping = TopicMapping {
             from: "/test".to_string(),
             subscription: "test-sub".to_string(),
+            subscription_type: danube_connect_core::SubscriptionType::Shared,
             to: "test-path".to_string(),
+            route_by: None,
+            route_by_path_parts: vec![],
+            default_route: None,
             expected_schema_subject: None,
             field_mappings: vec![field_mapping1, field_mapping2],
+            column_naming: crate::config::ColumnNaming::AsIs,
+            payload_format: crate::config::PayloadFormat::Json,
+            avro_schema_path: None,
+            protobuf_descriptor_path: None,
+            protobuf_message: None,
+            table_properties: std::collections::HashMap::new(),
+            partition_from_timestamp: None,
             write_mode: crate::config::WriteMode::Append,
+            cdc: None,
+            replace_where: None,
             include_danube_metadata: false,
+            max_buffer_bytes: None,
+            exactly_once: false,
+            validation: crate::config::ValidationMode::Lenient,
+            retry: None,
+            schema_check: crate::config::SchemaCheckMode::Fail,
+            storage: crate::config::StorageOverrides::default(),
         };
 
         let transformed = transform_payload_for_schema(&payload, &mapping);
         assert_eq!(transformed["name"].as_str(), Some("Alice"));
         assert_eq!(transformed["status"].as_str(), Some("active"));
     }
+
+    #[test]
+    fn test_derive_partition_value() {
+        let mut partition = TimestampPartition {
+            source_field: "meta.event_time".to_string(),
+            path_parts: vec![],
+            column: "event_date".to_string(),
+            format: "%Y-%m-%d".to_string(),
+        };
+        partition.init_path_parts();
+
+        let rfc3339 = json!({"meta": {"event_time": "2024-03-15T23:30:00-02:00"}});
+        assert_eq!(
+            derive_partition_value(&rfc3339, &partition),
+            Some("2024-03-16".to_string())
+        );
+
+        let epoch_millis = json!({"meta": {"event_time": 1_704_067_200_000i64}});
+        assert_eq!(
+            derive_partition_value(&epoch_millis, &partition),
+            Some("2024-01-01".to_string())
+        );
+
+        assert_eq!(derive_partition_value(&json!({}), &partition), None);
+        assert_eq!(
+            derive_partition_value(&json!({"meta": {"event_time": "not a date"}}), &partition),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cdc_operation() {
+        let mut cdc = CdcConfig {
+            op_field: "meta.op".to_string(),
+            op_path_parts: vec![],
+            key_columns: vec!["id".to_string()],
+            delete_values: vec!["d".to_string(), "delete".to_string()],
+        };
+        cdc.init_path_parts();
+
+        assert_eq!(
+            cdc_operation(&json!({"meta": {"op": "d"}}), &cdc),
+            CDC_OP_DELETE
+        );
+        assert_eq!(
+            cdc_operation(&json!({"meta": {"op": "DELETE"}}), &cdc),
+            CDC_OP_DELETE
+        );
+        assert_eq!(
+            cdc_operation(&json!({"meta": {"op": "u"}}), &cdc),
+            CDC_OP_UPSERT
+        );
+        assert_eq!(
+            cdc_operation(&json!({"meta": {"op": "c"}}), &cdc),
+            CDC_OP_UPSERT
+        );
+
+        // Missing op field is treated as an upsert
+        assert_eq!(cdc_operation(&json!({}), &cdc), CDC_OP_UPSERT);
+    }
+
+    #[test]
+    fn test_estimated_json_size() {
+        let small = json!({"id": 1});
+        let large = json!({"id": 1, "body": "x".repeat(10_000)});
+
+        assert!(estimated_json_size(&small) < 32);
+        assert!(estimated_json_size(&large) > 10_000);
+        assert!(estimated_json_size(&large) < 10_100);
+    }
+
+    #[test]
+    fn test_uncommitted_interleaved_partitions() {
+        // Offsets of two partitions, interleaved as a consumer receives them
+        let records = vec![
+            ("p0", 10),
+            ("p1", 3),
+            ("p0", 11),
+            ("p1", 4),
+            ("p0", 12),
+            ("p1", 5),
+        ];
+        let committed = HashMap::from([
+            ("app:p0".to_string(), Some(11)),
+            ("app:p1".to_string(), None),
+        ]);
+
+        let (pending, versions) = uncommitted(records, &committed, |(partition, offset)| {
+            (format!("app:{}", partition), *offset)
+        });
+
+        // p0's high offset doesn't filter p1's lower offsets
+        assert_eq!(pending, vec![("p1", 3), ("p1", 4), ("p0", 12), ("p1", 5)]);
+        assert_eq!(
+            versions,
+            vec![("app:p1".to_string(), 5), ("app:p0".to_string(), 12)]
+        );
+
+        let (pending, versions) =
+            uncommitted(vec![("p0", 9)], &committed, |(partition, offset)| {
+                (format!("app:{}", partition), *offset)
+            });
+        assert!(pending.is_empty());
+        assert!(versions.is_empty());
+    }
+
+    #[test]
+    fn test_overwrite_single_chunk() {
+        let mut mapping: TopicMapping = serde_json::from_value(json!({
+            "from": "/test",
+            "subscription": "test-sub",
+            "to": "test-path",
+            "field_mappings": [],
+        }))
+        .unwrap();
+        let payloads: Vec<Value> = (0..4)
+            .map(|id| json!({"id": id, "body": "x".repeat(100)}))
+            .collect();
+
+        // Appends are split into byte-bounded chunks
+        let chunks = split_by_size(payloads.clone(), chunk_limit(&mapping, 300), |p| p);
+        assert_eq!(chunks.len(), 2);
+
+        // Each overwrite replaces the table, so the batch must be one commit
+        mapping.write_mode = WriteMode::Overwrite;
+        let chunks = split_by_size(payloads.clone(), chunk_limit(&mapping, 300), |p| p);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0, payloads);
+
+        // CDC merges by key, so its chunks can still be split
+        mapping.cdc =
+            Some(serde_json::from_value(json!({"op_field": "op", "key_columns": ["id"]})).unwrap());
+        let chunks = split_by_size(payloads, chunk_limit(&mapping, 300), |p| p);
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_field_strict() {
+        let mut required = FieldMapping {
+            json_path: "user.age".to_string(),
+            path_parts: vec![],
+            column: "age".to_string(),
+            data_type: "Int32".to_string(),
+            nullable: false,
+            coerce: false,
+            timestamp_format: None,
+            default: None,
+        };
+        required.init_path_parts();
+
+        // Present and correctly typed
+        assert!(validate_field(&json!({"user": {"age": 30}}), &required).is_ok());
+
+        // Missing or null required field
+        assert!(validate_field(&json!({"user": {}}), &required).is_err());
+        assert!(validate_field(&json!({"user": {"age": null}}), &required).is_err());
+
+        // Type mismatch and out-of-range values
+        assert!(validate_field(&json!({"user": {"age": "thirty"}}), &required).is_err());
+        assert!(validate_field(&json!({"user": {"age": 5_000_000_000i64}}), &required).is_err());
+
+        // Nullable fields may be missing
+        required.nullable = true;
+        assert!(validate_field(&json!({}), &required).is_ok());
+
+        // Failed coercion isn't a null, even in a nullable field
+        required.coerce = true;
+        assert!(validate_field(&json!({"user": {"age": "31"}}), &required).is_ok());
+        assert!(validate_field(&json!({"user": {"age": "thirty"}}), &required).is_err());
+    }
+
+    #[test]
+    fn test_validate_unparseable_timestamp() {
+        let mut created = FieldMapping {
+            json_path: "created_at".to_string(),
+            path_parts: vec![],
+            column: "created_at".to_string(),
+            data_type: "Timestamp".to_string(),
+            nullable: true,
+            coerce: false,
+            timestamp_format: Some("rfc3339".to_string()),
+            default: None,
+        };
+        created.init_path_parts();
+
+        assert!(validate_field(&json!({"created_at": "2024-06-01T12:00:00Z"}), &created).is_ok());
+        assert!(validate_field(&json!({}), &created).is_ok());
+        assert!(validate_field(&json!({"created_at": null}), &created).is_ok());
+
+        // Strict mode rejects it; lenient mode writes a null
+        let payload = json!({"created_at": "not a timestamp"});
+        assert!(validate_field(&payload, &created).is_err());
+        assert_eq!(extract_field_value(&payload, &created), Some(Value::Null));
+    }
+
+    #[test]
+    fn test_split_invalid_mixed_batch() {
+        let mut mapping: TopicMapping = serde_json::from_value(json!({
+            "from": "/test",
+            "subscription": "test-sub",
+            "to": "test-path",
+            "field_mappings": [],
+        }))
+        .unwrap();
+        let mut id = FieldMapping {
+            json_path: "id".to_string(),
+            path_parts: vec![],
+            column: "id".to_string(),
+            data_type: "Int64".to_string(),
+            nullable: false,
+            coerce: false,
+            timestamp_format: None,
+            default: None,
+        };
+        id.init_path_parts();
+        mapping.field_mappings.push(id);
+
+        let payloads = vec![
+            json!({"id": 1}),
+            json!({"id": "two"}),
+            json!({"id": 3}),
+            json!({}),
+        ];
+        let (valid, invalid) = split_invalid(payloads, |p| validate_payload(p, "/test", &mapping));
+
+        // Only the failing records are split off; the rest are still written
+        assert_eq!(valid, vec![json!({"id": 1}), json!({"id": 3})]);
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(invalid[0].0, json!({"id": "two"}));
+        assert_eq!(invalid[1].0, json!({}));
+        assert!(!invalid[0].1.is_retryable());
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        assert_eq!(coerce_value(&json!("42"), "Int64"), Some(json!(42)));
+        assert_eq!(coerce_value(&json!(42.0), "Int32"), Some(json!(42)));
+        assert_eq!(coerce_value(&json!(42.5), "Int32"), None);
+        assert_eq!(coerce_value(&json!("300"), "UInt8"), None);
+        assert_eq!(coerce_value(&json!(7), "Utf8"), Some(json!("7")));
+        assert_eq!(coerce_value(&json!("2.5"), "Float64"), Some(json!(2.5)));
+        assert_eq!(coerce_value(&json!("yes"), "Boolean"), Some(json!(true)));
+        assert_eq!(coerce_value(&json!("maybe"), "Boolean"), None);
+        assert_eq!(coerce_value(&json!(null), "Int64"), Some(json!(null)));
+    }
+
+    #[test]
+    fn test_value_matches_type() {
+        assert!(value_matches_type(&json!("abc"), "Utf8"));
+        assert!(!value_matches_type(&json!(1), "Utf8"));
+        assert!(value_matches_type(&json!(255), "UInt8"));
+        assert!(!value_matches_type(&json!(256), "UInt8"));
+        assert!(!value_matches_type(&json!(-1), "UInt32"));
+        assert!(value_matches_type(&json!(1.5), "Float64"));
+        assert!(value_matches_type(&json!(true), "Boolean"));
+        assert!(value_matches_type(
+            &json!("2024-01-01T12:00:00Z"),
+            "Timestamp"
+        ));
+        assert!(!value_matches_type(&json!("yesterday"), "Timestamp"));
+    }
+
+    #[test]
+    fn test_field_default() {
+        let mut field_mapping = FieldMapping {
+            json_path: "status".to_string(),
+            path_parts: vec![],
+            column: "status".to_string(),
+            data_type: "Utf8".to_string(),
+            nullable: false,
+            coerce: false,
+            timestamp_format: None,
+            default: Some(json!("pending")),
+        };
+        field_mapping.init_path_parts();
+
+        assert_eq!(
+            extract_field_value(&json!({"status": "paid"}), &field_mapping),
+            Some(json!("paid"))
+        );
+        assert_eq!(
+            extract_field_value(&json!({}), &field_mapping),
+            Some(json!("pending"))
+        );
+        assert_eq!(
+            extract_field_value(&json!({"status": null}), &field_mapping),
+            Some(json!("pending"))
+        );
+
+        let now = resolve_default(&json!("now()"), "Timestamp");
+        assert!(now
+            .as_i64()
+            .is_some_and(|micros| micros > 1_700_000_000_000_000));
+        assert_eq!(resolve_default(&json!(0), "Int64"), json!(0));
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let micros = 1_704_110_400_000_000_i64; // 2024-01-01T12:00:00Z
+
+        assert_eq!(
+            parse_timestamp(&json!(1_704_110_400), "unix_s", true),
+            Some(json!(micros))
+        );
+        assert_eq!(
+            parse_timestamp(&json!("1704110400000"), "unix_ms", true),
+            Some(json!(micros))
+        );
+        assert_eq!(
+            parse_timestamp(&json!(micros), "unix_us", true),
+            Some(json!(micros))
+        );
+
+        // Offsets are converted for UTC-adjusted columns and dropped for NTZ columns
+        let with_offset = json!("2024-01-01T14:00:00+02:00");
+        assert_eq!(
+            parse_timestamp(&with_offset, "rfc3339", true),
+            Some(json!(micros))
+        );
+        assert_eq!(
+            parse_timestamp(&with_offset, "rfc3339", false),
+            Some(json!(micros + 7_200_000_000))
+        );
+
+        assert_eq!(
+            parse_timestamp(&json!("01/01/2024 12:00:00"), "%d/%m/%Y %H:%M:%S", false),
+            Some(json!(micros))
+        );
+        assert_eq!(parse_timestamp(&json!("not a date"), "rfc3339", true), None);
+        assert_eq!(
+            parse_timestamp(&Value::Null, "unix_ms", true),
+            Some(Value::Null)
+        );
+    }
 }
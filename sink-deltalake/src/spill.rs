@@ -0,0 +1,285 @@
+//! Durable local spill log for Delta Lake Sink Connector
+//!
+//! Converted batches are written to local disk before they are committed to
+//! Delta Lake and removed once the commit succeeds. Batches still on disk at
+//! startup (the connector stopped between conversion and commit) are replayed.
+//!
+//! Each entry is a single Arrow IPC file. Routing information is stored in the
+//! schema metadata, so an entry is self-describing:
+//! - `danube.route`: source topic of the route
+//! - `danube.table`: destination table path
+//! - `danube.txns`: exactly-once transactions, as a JSON list of [app id, version]
+
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const META_ROUTE: &str = "danube.route";
+const META_TABLE: &str = "danube.table";
+const META_TXNS: &str = "danube.txns";
+
+/// File extension of pending spill entries
+const ENTRY_EXTENSION: &str = "arrow";
+
+/// Disk-backed log of batches awaiting a Delta commit
+pub struct SpillLog {
+    /// Directory holding spill entries
+    dir: PathBuf,
+
+    /// Sequence number keeping entry names unique and ordered within a run
+    sequence: AtomicU64,
+}
+
+/// A batch recovered from the spill log
+pub struct SpillEntry {
+    /// Path of the entry file (remove once committed)
+    pub path: PathBuf,
+
+    /// Source topic of the route that produced the batch
+    pub route: String,
+
+    /// Destination table path
+    pub table: String,
+
+    /// Exactly-once transactions (app id, version) the commit must carry
+    pub transactions: Vec<(String, i64)>,
+
+    /// Converted batch, ready to commit
+    pub batch: RecordBatch,
+}
+
+impl SpillLog {
+    /// Open (creating if needed) the spill directory
+    pub fn open(dir: impl Into<PathBuf>) -> ConnectorResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            ConnectorError::config(format!(
+                "Failed to create spill directory '{}': {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            dir,
+            sequence: AtomicU64::new(0),
+        })
+    }
+
+    /// Durably write a batch before committing it
+    ///
+    /// The entry is written to a temporary file, synced, and renamed into place,
+    /// so a crash never leaves a partially written entry behind.
+    pub fn append(
+        &self,
+        route: &str,
+        table: &str,
+        transactions: &[(String, i64)],
+        batch: &RecordBatch,
+    ) -> ConnectorResult<PathBuf> {
+        let mut metadata = HashMap::from([
+            (META_ROUTE.to_string(), route.to_string()),
+            (META_TABLE.to_string(), table.to_string()),
+        ]);
+        if !transactions.is_empty() {
+            let transactions = serde_json::to_string(transactions).map_err(|e| {
+                ConnectorError::fatal(format!("Failed to encode spill transactions: {}", e))
+            })?;
+            metadata.insert(META_TXNS.to_string(), transactions);
+        }
+        let schema = Arc::new(batch.schema().as_ref().clone().with_metadata(metadata));
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{:020}-{:06}", timestamp, sequence);
+        let tmp_path = self.dir.join(format!("{}.tmp", name));
+        let path = self.dir.join(format!("{}.{}", name, ENTRY_EXTENSION));
+
+        let write = || -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let file = File::create(&tmp_path)?;
+            let mut writer = FileWriter::try_new(file, &schema)?;
+            writer.write(&RecordBatch::try_new(
+                schema.clone(),
+                batch.columns().to_vec(),
+            )?)?;
+            writer.finish()?;
+            writer.into_inner()?.sync_all()?;
+            fs::rename(&tmp_path, &path)?;
+            Ok(())
+        };
+
+        write().map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            ConnectorError::fatal(format!(
+                "Failed to write spill entry '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        Ok(path)
+    }
+
+    /// Remove an entry after its batch was committed
+    pub fn remove(&self, path: &Path) -> ConnectorResult<()> {
+        fs::remove_file(path).map_err(|e| {
+            ConnectorError::fatal(format!(
+                "Failed to remove spill entry '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Read all pending entries, oldest first
+    ///
+    /// Leftover temporary files from an interrupted append are discarded.
+    pub fn pending(&self) -> ConnectorResult<Vec<SpillEntry>> {
+        let read_dir = fs::read_dir(&self.dir).map_err(|e| {
+            ConnectorError::fatal(format!(
+                "Failed to read spill directory '{}': {}",
+                self.dir.display(),
+                e
+            ))
+        })?;
+
+        let mut paths = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ENTRY_EXTENSION) => paths.push(path),
+                Some("tmp") => {
+                    let _ = fs::remove_file(&path);
+                }
+                _ => {}
+            }
+        }
+        paths.sort();
+
+        paths.into_iter().map(|path| read_entry(&path)).collect()
+    }
+}
+
+/// Read a single spill entry from disk
+fn read_entry(path: &Path) -> ConnectorResult<SpillEntry> {
+    let corrupt = |reason: String| {
+        ConnectorError::fatal(format!(
+            "Corrupt spill entry '{}': {}",
+            path.display(),
+            reason
+        ))
+    };
+
+    let file = File::open(path).map_err(|e| corrupt(e.to_string()))?;
+    let reader = FileReader::try_new(file, None).map_err(|e| corrupt(e.to_string()))?;
+    let metadata = reader.schema().metadata().clone();
+
+    let mut batches = reader
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| corrupt(e.to_string()))?;
+    if batches.len() != 1 {
+        return Err(corrupt(format!(
+            "expected 1 batch, found {}",
+            batches.len()
+        )));
+    }
+    let batch = batches.remove(0);
+
+    let route = metadata
+        .get(META_ROUTE)
+        .cloned()
+        .ok_or_else(|| corrupt("missing route".to_string()))?;
+    let table = metadata
+        .get(META_TABLE)
+        .cloned()
+        .ok_or_else(|| corrupt("missing table".to_string()))?;
+    let transactions = match metadata.get(META_TXNS) {
+        Some(transactions) => serde_json::from_str(transactions)
+            .map_err(|e| corrupt(format!("invalid transactions: {}", e)))?,
+        None => Vec::new(),
+    };
+
+    // Strip the routing metadata so the batch matches the table schema again
+    let schema = Arc::new(
+        batch
+            .schema()
+            .as_ref()
+            .clone()
+            .with_metadata(HashMap::new()),
+    );
+    let batch = RecordBatch::try_new(schema, batch.columns().to_vec())
+        .map_err(|e| corrupt(e.to_string()))?;
+
+    Ok(SpillEntry {
+        path: path.to_path_buf(),
+        route,
+        table,
+        transactions,
+        batch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[test]
+    fn test_spill_round_trip() {
+        let dir = std::env::temp_dir().join(format!("danube-spill-test-{}", std::process::id()));
+        let log = SpillLog::open(&dir).unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("amount", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(Int64Array::from(vec![Some(1), None])),
+            ],
+        )
+        .unwrap();
+
+        let transactions = vec![
+            ("danube:test:/events/payments:0".to_string(), 42),
+            ("danube:test:/events/payments:1".to_string(), 7),
+        ];
+        let first = log
+            .append(
+                "/events/payments",
+                "s3://bucket/payments",
+                &transactions,
+                &batch,
+            )
+            .unwrap();
+        log.append("/events/orders", "s3://bucket/orders", &[], &batch)
+            .unwrap();
+
+        let pending = log.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].path, first);
+        assert_eq!(pending[0].route, "/events/payments");
+        assert_eq!(pending[0].table, "s3://bucket/payments");
+        assert_eq!(pending[0].transactions, transactions);
+        assert_eq!(pending[0].batch, batch);
+        assert!(pending[1].transactions.is_empty());
+
+        log.remove(&first).unwrap();
+        assert_eq!(log.pending().unwrap().len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+-- Tables for the MySQL sink example (run once by the mysql container)
+
+CREATE TABLE IF NOT EXISTS events (
+  event_id VARCHAR(64) PRIMARY KEY,
+  event_type VARCHAR(32) NOT NULL,
+  timestamp VARCHAR(32) NOT NULL,
+  user_id VARCHAR(32),
+  data JSON,
+  _danube_metadata JSON,
+  updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  INDEX idx_event_type (event_type)
+);
@@ -0,0 +1,569 @@
+//! Configuration module for MySQL Sink Connector
+//!
+//! This module handles all configuration aspects including:
+//! - MySQL/MariaDB connection settings (URL, database, credentials, pool, TLS)
+//! - Topic-to-table mappings with per-table column and write settings
+//! - Batch statement sizing and parallel flushes
+//! - Environment variable overrides
+
+use danube_connect_core::{
+    ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorError,
+    ConnectorResult, SubscriptionType,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+
+/// How rows are written to a table
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum InsertMode {
+    /// Plain `INSERT`; rows hitting an existing key fail (default)
+    Insert,
+    /// `INSERT ... ON DUPLICATE KEY UPDATE`: rows hitting a primary or unique key
+    /// update the existing row
+    Upsert,
+}
+
+/// What to do with records that can't be written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum ErrorPolicy {
+    /// Fail the batch (retryable) until it succeeds (default)
+    Fail,
+    /// Log and drop the failing records
+    Skip,
+    /// Publish the failing records to `dlq_topic`
+    Dlq,
+}
+
+/// Per-route handling of records that can't be written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorHandling {
+    /// Error policy: Fail, Skip, or Dlq
+    #[serde(default)]
+    pub policy: ErrorPolicy,
+
+    /// Failed batch attempts before failing records are skipped or dead-lettered
+    #[serde(default = "default_error_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Danube topic receiving failed records (required for the Dlq policy)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dlq_topic: Option<String>,
+}
+
+impl ErrorHandling {
+    /// Whether failing records are skipped or dead-lettered instead of failing the batch
+    pub fn isolates_records(&self) -> bool {
+        self.policy != ErrorPolicy::Fail
+    }
+}
+
+/// Complete configuration for the MySQL Sink Connector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MySqlSinkConfig {
+    /// Core connector configuration (Danube connection, etc.)
+    #[serde(flatten)]
+    pub core: ConnectorConfig,
+
+    /// MySQL-specific configuration
+    pub mysql: MySqlConfig,
+}
+
+/// MySQL-specific configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MySqlConfig {
+    /// Server URL (e.g., "mysql://localhost:3306"); the scheme is optional
+    pub url: String,
+
+    /// Database (schema) the tables live in
+    pub database: String,
+
+    /// Optional username for authentication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Optional password for authentication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// Connection timeout in seconds
+    #[serde(default = "default_connection_timeout")]
+    pub connection_timeout_secs: u64,
+
+    /// Maximum number of pooled connections
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
+
+    /// Maximum number of tables flushed concurrently per batch
+    #[serde(default = "default_max_parallel_flushes")]
+    pub max_parallel_flushes: usize,
+
+    /// Maximum rows per multi-row INSERT statement
+    /// Larger batches are split into several statements within one transaction
+    #[serde(default = "default_max_rows_per_statement")]
+    pub max_rows_per_statement: usize,
+
+    /// TLS settings (optional); the connection is encrypted when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+
+    /// Routes: Danube topics → MySQL tables
+    #[serde(default)]
+    pub routes: Vec<TopicMapping>,
+}
+
+/// TLS settings for encrypted connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to a PEM bundle of additional CA certificates (e.g., a private CA)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<String>,
+
+    /// Verify the server certificate (default: true)
+    /// Disabling this is insecure and intended for testing only
+    #[serde(default = "default_true")]
+    pub verify_certificates: bool,
+
+    /// Verify that the certificate matches the server hostname (default: true)
+    #[serde(default = "default_true")]
+    pub verify_hostname: bool,
+
+    /// Path to a PEM client certificate chain, for servers requiring X.509 authentication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<String>,
+
+    /// Path to the PEM private key of `client_cert_path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<String>,
+}
+
+/// Mapping from a Danube topic to a MySQL table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicMapping {
+    /// Danube topic to consume from
+    pub from: String,
+
+    /// Danube subscription name
+    pub subscription: String,
+
+    /// Subscription type: Exclusive, Shared, FailOver
+    #[serde(default = "default_subscription_type")]
+    pub subscription_type: SubscriptionType,
+
+    /// MySQL table to write to (optionally qualified, e.g., "analytics.events")
+    pub to: String,
+
+    /// Expected schema subject for validation (optional)
+    /// If set, the runtime validates and deserializes messages automatically
+    /// Schema must be registered in Danube Schema Registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_schema_subject: Option<String>,
+
+    /// Write mode: Insert (default) or Upsert
+    #[serde(default)]
+    pub insert_mode: InsertMode,
+
+    /// Key columns, left unchanged when an upsert updates an existing row
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_columns: Vec<String>,
+
+    /// Columns to write; payload fields of the same name fill them, missing ones are NULL
+    /// Without it, every top-level payload field is written to the column of its name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+
+    /// Add a `_danube_metadata` JSON column (topic, timestamp, producer)
+    #[serde(default)]
+    pub include_danube_metadata: bool,
+
+    /// Rename payload fields: source → target (dotted paths supported)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub field_map: HashMap<String, String>,
+
+    /// Payload fields to drop before writing (dotted paths supported)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_fields: Vec<String>,
+
+    /// Error handling for records that can't be written (default: fail the batch)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<ErrorHandling>,
+}
+
+// Default value functions
+fn default_connection_timeout() -> u64 {
+    30
+}
+
+fn default_max_connections() -> usize {
+    10
+}
+
+fn default_max_parallel_flushes() -> usize {
+    4
+}
+
+fn default_max_rows_per_statement() -> usize {
+    1000
+}
+
+fn default_error_max_attempts() -> u32 {
+    3
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_subscription_type() -> SubscriptionType {
+    SubscriptionType::Shared
+}
+
+impl Default for InsertMode {
+    fn default() -> Self {
+        InsertMode::Insert
+    }
+}
+
+impl Default for ErrorPolicy {
+    fn default() -> Self {
+        ErrorPolicy::Fail
+    }
+}
+
+impl MySqlConfig {
+    /// Connection URL, with the `mysql://` scheme added if missing
+    pub fn connection_url(&self) -> String {
+        if self.url.contains("://") {
+            self.url.clone()
+        } else {
+            format!("mysql://{}", self.url)
+        }
+    }
+}
+
+impl MySqlSinkConfig {
+    /// Load configuration from TOML file
+    ///
+    /// The config file path must be specified via CONNECTOR_CONFIG_PATH environment variable.
+    /// Environment variables can override secrets (username, password) and URLs.
+    pub fn load() -> ConnectorResult<Self> {
+        ConnectorConfigLoader::new().load()
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> ConnectorResult<()> {
+        self.validate_config()
+    }
+}
+
+impl ConfigEnvOverrides for MySqlSinkConfig {
+    fn apply_env_overrides(&mut self) -> ConnectorResult<()> {
+        if let Ok(danube_url) = env::var("DANUBE_SERVICE_URL") {
+            self.core.danube_service_url = danube_url;
+        }
+
+        if let Ok(connector_name) = env::var("CONNECTOR_NAME") {
+            self.core.connector_name = connector_name;
+        }
+
+        if let Ok(url) = env::var("MYSQL_URL") {
+            self.mysql.url = url;
+        }
+
+        if let Ok(username) = env::var("MYSQL_USERNAME") {
+            self.mysql.username = Some(username);
+        }
+        if let Ok(password) = env::var("MYSQL_PASSWORD") {
+            self.mysql.password = Some(password);
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigValidate for MySqlSinkConfig {
+    fn validate_config(&self) -> ConnectorResult<()> {
+        // Validate MySQL URL
+        if self.mysql.url.is_empty() {
+            return Err(ConnectorError::config("MYSQL_URL cannot be empty"));
+        }
+        if let Some((scheme, _)) = self.mysql.url.split_once("://") {
+            if scheme != "mysql" {
+                return Err(ConnectorError::config(format!(
+                    "Unsupported MySQL URL scheme '{}://', use mysql://",
+                    scheme
+                )));
+            }
+        }
+        if self.mysql.database.is_empty() {
+            return Err(ConnectorError::config("MySQL database cannot be empty"));
+        }
+
+        if let Some(tls) = &self.mysql.tls {
+            if tls.client_cert_path.is_some() != tls.client_key_path.is_some() {
+                return Err(ConnectorError::config(
+                    "TLS client_cert_path and client_key_path must be set together",
+                ));
+            }
+        }
+
+        if self.mysql.max_connections == 0 {
+            return Err(ConnectorError::config("max_connections must be at least 1"));
+        }
+        if self.mysql.max_parallel_flushes == 0 {
+            return Err(ConnectorError::config(
+                "max_parallel_flushes must be at least 1",
+            ));
+        }
+        if self.mysql.max_rows_per_statement == 0 {
+            return Err(ConnectorError::config(
+                "max_rows_per_statement must be at least 1",
+            ));
+        }
+
+        // Validate topic mappings
+        if self.mysql.routes.is_empty() {
+            return Err(ConnectorError::config("At least one route is required"));
+        }
+
+        for mapping in &self.mysql.routes {
+            if mapping.from.is_empty() {
+                return Err(ConnectorError::config("Route 'from' cannot be empty"));
+            }
+            if mapping.subscription.is_empty() {
+                return Err(ConnectorError::config("Subscription name cannot be empty"));
+            }
+            if mapping.to.is_empty() || mapping.to.split('.').any(str::is_empty) {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': invalid table name '{}'",
+                    mapping.from, mapping.to
+                )));
+            }
+            if self
+                .mysql
+                .routes
+                .iter()
+                .filter(|other| other.from == mapping.from)
+                .count()
+                > 1
+            {
+                return Err(ConnectorError::config(format!(
+                    "Topic '{}' is mapped more than once",
+                    mapping.from
+                )));
+            }
+
+            if let Some(column) = mapping.columns.iter().find(|column| column.is_empty()) {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': invalid column name '{}'",
+                    mapping.from, column
+                )));
+            }
+            if !mapping.key_columns.is_empty() && mapping.insert_mode != InsertMode::Upsert {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': key_columns require insert_mode 'Upsert'",
+                    mapping.from
+                )));
+            }
+            if !mapping.columns.is_empty() {
+                if let Some(key) = mapping
+                    .key_columns
+                    .iter()
+                    .find(|key| !mapping.columns.contains(key))
+                {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': key column '{}' is not in columns",
+                        mapping.from, key
+                    )));
+                }
+            }
+
+            let paths = mapping
+                .field_map
+                .iter()
+                .flat_map(|(source, target)| [source, target])
+                .chain(&mapping.exclude_fields);
+            for path in paths {
+                if path.is_empty() || path.split('.').any(str::is_empty) {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': invalid field path '{}' in field_map/exclude_fields",
+                        mapping.from, path
+                    )));
+                }
+            }
+            if let Some(target) = mapping
+                .field_map
+                .values()
+                .find(|target| mapping.field_map.contains_key(*target))
+            {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': field_map target '{}' is also a source",
+                    mapping.from, target
+                )));
+            }
+
+            if let Some(on_error) = &mapping.on_error {
+                if on_error.max_attempts == 0 {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': on_error max_attempts must be at least 1",
+                        mapping.from
+                    )));
+                }
+                let has_dlq_topic = on_error
+                    .dlq_topic
+                    .as_deref()
+                    .map_or(false, |t| !t.is_empty());
+                if (on_error.policy == ErrorPolicy::Dlq) != has_dlq_topic {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': dlq_topic is required for, and only used by, policy 'Dlq'",
+                        mapping.from
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> MySqlSinkConfig {
+        MySqlSinkConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(),
+            },
+            mysql: MySqlConfig {
+                url: "mysql://localhost:3306".to_string(),
+                database: "danube".to_string(),
+                username: None,
+                password: None,
+                connection_timeout_secs: 30,
+                max_connections: 10,
+                max_parallel_flushes: 4,
+                max_rows_per_statement: 1000,
+                tls: None,
+                routes: vec![TopicMapping {
+                    from: "/default/orders".to_string(),
+                    subscription: "mysql-sink".to_string(),
+                    subscription_type: SubscriptionType::Shared,
+                    to: "orders".to_string(),
+                    expected_schema_subject: None,
+                    insert_mode: InsertMode::Insert,
+                    key_columns: Vec::new(),
+                    columns: Vec::new(),
+                    include_danube_metadata: false,
+                    field_map: HashMap::new(),
+                    exclude_fields: Vec::new(),
+                    on_error: None,
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = test_config();
+        assert!(config.validate().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.mysql.url = "postgres://localhost".to_string();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.database = String::new();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.routes.clear();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.routes[0].to = "analytics.".to_string();
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.routes.push(invalid.mysql.routes[0].clone());
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.max_rows_per_statement = 0;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.mysql.tls = Some(TlsConfig {
+            ca_cert_path: None,
+            verify_certificates: true,
+            verify_hostname: true,
+            client_cert_path: Some("/certs/client.pem".to_string()),
+            client_key_path: None,
+        });
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config;
+        invalid.mysql.routes[0].on_error = Some(ErrorHandling {
+            policy: ErrorPolicy::Dlq,
+            max_attempts: 3,
+            dlq_topic: None,
+        });
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_upsert_validation() {
+        let mut config = test_config();
+        config.mysql.routes[0].key_columns = vec!["order_id".to_string()];
+        assert!(config.validate().is_err());
+
+        config.mysql.routes[0].insert_mode = InsertMode::Upsert;
+        assert!(config.validate().is_ok());
+
+        // Key columns must be written
+        config.mysql.routes[0].columns = vec!["status".to_string()];
+        assert!(config.validate().is_err());
+        config.mysql.routes[0].columns.push("order_id".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_connection_url() {
+        let mut config = test_config().mysql;
+        assert_eq!(config.connection_url(), "mysql://localhost:3306");
+
+        config.url = "mysql:3306".to_string();
+        assert_eq!(config.connection_url(), "mysql://mysql:3306");
+    }
+
+    #[test]
+    fn test_default_values() {
+        let mapping: TopicMapping = toml::from_str(
+            r#"
+            from = "/default/orders"
+            subscription = "mysql-sink"
+            to = "orders"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            mapping.subscription_type,
+            SubscriptionType::Shared
+        ));
+        assert_eq!(mapping.insert_mode, InsertMode::Insert);
+        assert!(!mapping.include_danube_metadata);
+        assert!(mapping.columns.is_empty());
+        assert_eq!(default_connection_timeout(), 30);
+        assert_eq!(default_max_connections(), 10);
+        assert_eq!(default_max_rows_per_statement(), 1000);
+        assert_eq!(ErrorPolicy::default(), ErrorPolicy::Fail);
+        assert_eq!(default_error_max_attempts(), 3);
+    }
+}
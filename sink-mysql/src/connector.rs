@@ -0,0 +1,592 @@
+//! MySQL Sink Connector implementation
+//!
+//! This module implements the core connector logic for streaming messages
+//! from Danube topics to MySQL/MariaDB tables with:
+//! - Multi-topic support with per-table batching, flushed concurrently
+//! - Multi-row INSERT statements, one transaction per table batch
+//! - Upserts through `INSERT ... ON DUPLICATE KEY UPDATE`
+//! - Automatic retry and error handling (fail, skip, or dead-letter bad records)
+//! - Pooled connections, replaced transparently when they drop
+
+use crate::config::{ErrorHandling, ErrorPolicy, MySqlConfig, MySqlSinkConfig, TopicMapping};
+use crate::dlq::DeadLetterQueue;
+use crate::record::{to_mysql_row, MySqlRow};
+use crate::sql::{insert_statement, quote_table, statement_chunks};
+use crate::tls::ssl_opts;
+use async_trait::async_trait;
+use danube_connect_core::{
+    ConnectorConfig, ConnectorError, ConnectorResult, ConsumerConfig, SinkConnector, SinkRecord,
+};
+use futures::stream::{self, StreamExt};
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, TxOpts};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Server errors that don't depend on the rows written, so they fail the whole
+/// batch instead of skipping records: transient ones (too many connections,
+/// lock wait timeout, deadlock) and ones needing the table or grants fixed
+/// (database access denied, unknown database, table or column access denied,
+/// table doesn't exist)
+const BATCH_SERVER_ERRORS: [u16; 8] = [1040, 1205, 1213, 1044, 1049, 1142, 1143, 1146];
+
+/// Context for managing a single MySQL table (per topic mapping)
+#[derive(Debug)]
+struct TableContext {
+    /// Topic mapping configuration
+    mapping: TopicMapping,
+
+    /// Statistics
+    records_inserted: u64,
+    records_failed: u64,
+    batches_flushed: u64,
+    last_error: Option<String>,
+
+    /// Consecutive failed attempts of the current batch (error policy)
+    failed_attempts: u32,
+}
+
+impl TableContext {
+    fn new(mapping: TopicMapping) -> Self {
+        Self {
+            mapping,
+            records_inserted: 0,
+            records_failed: 0,
+            batches_flushed: 0,
+            last_error: None,
+            failed_attempts: 0,
+        }
+    }
+}
+
+/// MySQL Sink Connector
+pub struct MySqlSinkConnector {
+    /// Configuration
+    config: MySqlSinkConfig,
+
+    /// Connection pool
+    pool: Option<Pool>,
+
+    /// Dead-letter queue (connected if any route uses the Dlq error policy)
+    dlq: Option<DeadLetterQueue>,
+
+    /// Table contexts (one per topic mapping)
+    tables: HashMap<String, TableContext>,
+}
+
+impl MySqlSinkConnector {
+    /// Create a new connector with the given configuration
+    pub fn with_config(config: MySqlSinkConfig) -> Self {
+        let tables = config
+            .mysql
+            .routes
+            .iter()
+            .map(|mapping| {
+                let context = TableContext::new(mapping.clone());
+                (mapping.from.clone(), context)
+            })
+            .collect();
+
+        Self {
+            config,
+            pool: None,
+            dlq: None,
+            tables,
+        }
+    }
+
+    /// Create a new connector (loads config automatically)
+    pub fn new() -> ConnectorResult<Self> {
+        let config = MySqlSinkConfig::load()?;
+        Ok(Self::with_config(config))
+    }
+}
+
+/// Shared state for flushing table batches, possibly concurrently
+///
+/// Each flush gets exclusive access to its own table context and takes its
+/// own connection from the pool, while the DLQ is shared.
+struct TableFlusher<'a> {
+    pool: &'a Pool,
+    mysql: &'a MySqlConfig,
+    dlq: Option<&'a DeadLetterQueue>,
+}
+
+impl TableFlusher<'_> {
+    /// Flush a route's batch, applying the route's error policy on failure
+    ///
+    /// With the Skip or Dlq policy, a batch still failing after `max_attempts` is
+    /// written record by record, so only the records MySQL rejects are skipped
+    /// or dead-lettered. Connection failures always fail the batch.
+    async fn flush_route(
+        &self,
+        context: &mut TableContext,
+        batch: Vec<(SinkRecord, MySqlRow)>,
+    ) -> ConnectorResult<()> {
+        let on_error = context
+            .mapping
+            .on_error
+            .clone()
+            .filter(ErrorHandling::isolates_records);
+        let Some(on_error) = on_error else {
+            let rows = batch.into_iter().map(|(_, row)| row).collect();
+            return self
+                .flush_table(context, rows)
+                .await
+                .map_err(|e| write_error(&context.mapping, e));
+        };
+
+        let rows = batch.iter().map(|(_, row)| row.clone()).collect();
+        let error = match self.flush_table(context, rows).await {
+            Ok(()) => {
+                context.failed_attempts = 0;
+                return Ok(());
+            }
+            Err(e) if !is_rejection(&e) => return Err(write_error(&context.mapping, e)),
+            Err(e) => write_error(&context.mapping, e),
+        };
+
+        context.failed_attempts += 1;
+        if context.failed_attempts < on_error.max_attempts {
+            return Err(error);
+        }
+        context.failed_attempts = 0;
+
+        warn!(
+            "Batch for table '{}' failed {} times, writing its {} records individually",
+            context.mapping.to,
+            on_error.max_attempts,
+            batch.len()
+        );
+        for (sink_record, row) in batch {
+            match self.flush_table(context, vec![row]).await {
+                Ok(()) => {}
+                Err(e) if !is_rejection(&e) => return Err(write_error(&context.mapping, e)),
+                Err(e) => {
+                    let e = write_error(&context.mapping, e);
+                    handle_failed_record(self.dlq, context, &on_error, &sink_record, &e).await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flush a specific table's batch to MySQL
+    async fn flush_table(
+        &self,
+        context: &mut TableContext,
+        rows: Vec<MySqlRow>,
+    ) -> Result<(), mysql_async::Error> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let table_name = &context.mapping.to;
+        let batch_size = rows.len();
+
+        debug!(
+            "Flushing {} records to MySQL table '{}'",
+            batch_size, table_name
+        );
+
+        if let Err(e) = self.write_rows(&context.mapping, rows).await {
+            error!(
+                "Failed to insert batch of {} records into '{}': {}",
+                batch_size, table_name, e
+            );
+            context.last_error = Some(format!("Insert error: {}", e));
+            return Err(e);
+        }
+
+        // Update statistics
+        context.records_inserted += batch_size as u64;
+        context.batches_flushed += 1;
+        context.last_error = None;
+
+        info!(
+            "Successfully flushed {} records to table '{}' (total: {}, batches: {})",
+            batch_size, table_name, context.records_inserted, context.batches_flushed
+        );
+
+        Ok(())
+    }
+
+    /// Write rows in a single transaction, so a failure leaves nothing behind
+    /// and the retried batch applies atomically
+    async fn write_rows(
+        &self,
+        mapping: &TopicMapping,
+        rows: Vec<MySqlRow>,
+    ) -> Result<(), mysql_async::Error> {
+        let mut conn = get_conn(self.pool, self.mysql).await?;
+        let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|row| (row.columns, row.values))
+            .collect();
+        for (columns, chunk) in statement_chunks(rows, self.mysql.max_rows_per_statement) {
+            let statement = insert_statement(
+                &mapping.to,
+                &columns,
+                chunk.len(),
+                mapping.insert_mode,
+                &mapping.key_columns,
+            );
+            let params: Vec<_> = chunk.into_iter().flatten().collect();
+            tx.exec_drop(statement, params).await?;
+        }
+
+        tx.commit().await
+    }
+}
+
+/// Whether MySQL rejected the written rows (as opposed to a connection,
+/// transient, table or privilege failure, which retrying the batch can fix)
+fn is_rejection(error: &mysql_async::Error) -> bool {
+    match error {
+        mysql_async::Error::Server(e) => !BATCH_SERVER_ERRORS.contains(&e.code),
+        _ => false,
+    }
+}
+
+/// Report a failed write as retryable
+fn write_error(mapping: &TopicMapping, error: mysql_async::Error) -> ConnectorError {
+    ConnectorError::retryable(format!(
+        "Failed to insert records into '{}': {}",
+        mapping.to, error
+    ))
+}
+
+/// Skip or dead-letter a record that can't be written
+async fn handle_failed_record(
+    dlq: Option<&DeadLetterQueue>,
+    context: &mut TableContext,
+    on_error: &ErrorHandling,
+    record: &SinkRecord,
+    error: &ConnectorError,
+) -> ConnectorResult<()> {
+    match (on_error.policy, &on_error.dlq_topic) {
+        (ErrorPolicy::Dlq, Some(dlq_topic)) => {
+            let dlq = dlq.ok_or_else(|| ConnectorError::fatal("DLQ client not initialized"))?;
+            dlq.publish(dlq_topic, &context.mapping.to, record, error)
+                .await?;
+        }
+        _ => warn!(
+            "Skipping record from {} for table '{}': {}",
+            context.mapping.from, context.mapping.to, error
+        ),
+    }
+
+    context.records_failed += 1;
+    Ok(())
+}
+
+/// Create the connection pool (connections are opened on demand)
+fn create_pool(mysql: &MySqlConfig) -> ConnectorResult<Pool> {
+    let opts = Opts::from_url(&mysql.connection_url())
+        .map_err(|e| ConnectorError::config(format!("Invalid MySQL URL '{}': {}", mysql.url, e)))?;
+    let constraints = PoolConstraints::new(1, mysql.max_connections)
+        .ok_or_else(|| ConnectorError::config("max_connections must be at least 1"))?;
+
+    let mut builder = OptsBuilder::from_opts(opts)
+        .db_name(Some(&mysql.database))
+        .pool_opts(PoolOpts::default().with_constraints(constraints));
+    if let Some(username) = &mysql.username {
+        builder = builder.user(Some(username));
+    }
+    if let Some(password) = &mysql.password {
+        builder = builder.pass(Some(password));
+    }
+    if let Some(tls) = &mysql.tls {
+        builder = builder.ssl_opts(ssl_opts(tls)?);
+    }
+
+    Ok(Pool::new(builder))
+}
+
+/// Take a connection from the pool, opening one within the connection timeout
+async fn get_conn(pool: &Pool, mysql: &MySqlConfig) -> Result<Conn, mysql_async::Error> {
+    let timeout = Duration::from_secs(mysql.connection_timeout_secs);
+    tokio::time::timeout(timeout, pool.get_conn())
+        .await
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("timed out connecting to MySQL after {:?}", timeout),
+            )
+        })?
+}
+
+#[async_trait]
+impl SinkConnector for MySqlSinkConnector {
+    async fn initialize(&mut self, _config: ConnectorConfig) -> ConnectorResult<()> {
+        info!("Initializing MySQL Sink Connector");
+        info!(
+            "Connecting to MySQL at: {} (database '{}', TLS: {})",
+            self.config.mysql.url,
+            self.config.mysql.database,
+            self.config.mysql.tls.is_some()
+        );
+
+        let pool = create_pool(&self.config.mysql)?;
+        let mut conn = get_conn(&pool, &self.config.mysql)
+            .await
+            .map_err(|e| ConnectorError::retryable(format!("Failed to connect to MySQL: {}", e)))?;
+
+        // Fail early on tables that don't exist or aren't accessible
+        for mapping in &self.config.mysql.routes {
+            conn.query_drop(format!(
+                "SELECT * FROM {} LIMIT 0",
+                quote_table(&mapping.to)
+            ))
+            .await
+            .map_err(|e| {
+                ConnectorError::fatal(format!(
+                    "Table '{}' of route '{}' is not accessible: {}",
+                    mapping.to, mapping.from, e
+                ))
+            })?;
+        }
+        drop(conn);
+
+        // Connect the DLQ if any route dead-letters failed records
+        let uses_dlq = self.config.mysql.routes.iter().any(|mapping| {
+            mapping
+                .on_error
+                .as_ref()
+                .map_or(false, |on_error| on_error.policy == ErrorPolicy::Dlq)
+        });
+        if uses_dlq {
+            self.dlq = Some(
+                DeadLetterQueue::connect(
+                    &self.config.core.danube_service_url,
+                    &self.config.core.connector_name,
+                )
+                .await?,
+            );
+            info!("Connected DLQ client");
+        }
+
+        self.pool = Some(pool);
+
+        info!("MySQL connection pool initialized successfully");
+        info!(
+            "Configured {} table mappings",
+            self.config.mysql.routes.len()
+        );
+
+        Ok(())
+    }
+
+    async fn consumer_configs(&self) -> ConnectorResult<Vec<ConsumerConfig>> {
+        let configs = self
+            .config
+            .mysql
+            .routes
+            .iter()
+            .map(|mapping| ConsumerConfig {
+                topic: mapping.from.clone(),
+                consumer_name: format!("{}-{}", self.config.core.connector_name, mapping.to),
+                subscription: mapping.subscription.clone(),
+                subscription_type: mapping.subscription_type.clone(),
+                expected_schema_subject: mapping.expected_schema_subject.clone(),
+            })
+            .collect();
+
+        Ok(configs)
+    }
+
+    async fn process_batch(&mut self, records: Vec<SinkRecord>) -> ConnectorResult<()> {
+        let mut batches: HashMap<String, Vec<(SinkRecord, MySqlRow)>> = HashMap::new();
+
+        for record in records {
+            let topic = record.topic().to_string();
+
+            let context = self.tables.get_mut(&topic).ok_or_else(|| {
+                ConnectorError::fatal(format!("No mapping configured for topic: {}", topic))
+            })?;
+
+            let on_error = context
+                .mapping
+                .on_error
+                .clone()
+                .filter(ErrorHandling::isolates_records);
+
+            match (to_mysql_row(&record, &context.mapping), on_error) {
+                (Ok(row), _) => {
+                    batches.entry(topic).or_default().push((record, row));
+                }
+                // Invalid records never succeed on retry, so they're handled right away
+                (Err(e), Some(on_error)) => {
+                    handle_failed_record(self.dlq.as_ref(), context, &on_error, &record, &e).await?
+                }
+                (Err(e), None) => return Err(e),
+            }
+        }
+
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("MySQL pool not initialized"))?;
+        let flusher = TableFlusher {
+            pool,
+            mysql: &self.config.mysql,
+            dlq: self.dlq.as_ref(),
+        };
+
+        // Tables are independent, so their batches are flushed concurrently;
+        // every flush runs to completion before the first error is reported
+        let results: Vec<_> =
+            stream::iter(self.tables.iter_mut().filter_map(|(topic, context)| {
+                batches.remove(topic).map(|batch| (context, batch))
+            }))
+            .map(|(context, batch)| flusher.flush_route(context, batch))
+            .buffer_unordered(self.config.mysql.max_parallel_flushes)
+            .collect()
+            .await;
+
+        results.into_iter().collect()
+    }
+
+    async fn shutdown(&mut self) -> ConnectorResult<()> {
+        info!("Shutting down MySQL Sink Connector");
+
+        // Print final statistics
+        info!("Final statistics:");
+        for (topic, context) in &self.tables {
+            info!(
+                "  Topic '{}' → Table '{}': {} records, {} failed ({} batches)",
+                topic,
+                context.mapping.to,
+                context.records_inserted,
+                context.records_failed,
+                context.batches_flushed
+            );
+        }
+
+        if let Some(pool) = self.pool.take() {
+            if let Err(e) = pool.disconnect().await {
+                warn!("Failed to close MySQL connections cleanly: {}", e);
+            }
+        }
+
+        info!("MySQL Sink Connector shutdown complete");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> ConnectorResult<()> {
+        let pool = self.pool.as_ref().ok_or_else(|| {
+            ConnectorError::fatal("MySQL pool not initialized. Call initialize() first.")
+        })?;
+
+        // Verify the server answers with a round trip
+        let mut conn = get_conn(pool, &self.config.mysql).await.map_err(|e| {
+            ConnectorError::retryable(format!("MySQL connection check failed: {}", e))
+        })?;
+        conn.ping().await.map_err(|e| {
+            ConnectorError::retryable(format!("MySQL connection check failed: {}", e))
+        })?;
+
+        // Check for recent errors
+        for (topic, context) in &self.tables {
+            if let Some(error) = &context.last_error {
+                warn!("Topic '{}' has recent error: {}", topic, error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MySqlSinkConnector {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default connector")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::InsertMode;
+    use danube_connect_core::SubscriptionType;
+
+    fn test_config() -> MySqlSinkConfig {
+        MySqlSinkConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(), // No schemas for sink connector test
+            },
+            mysql: MySqlConfig {
+                url: "localhost:3306".to_string(),
+                database: "danube".to_string(),
+                username: Some("danube".to_string()),
+                password: Some("secret".to_string()),
+                connection_timeout_secs: 30,
+                max_connections: 10,
+                max_parallel_flushes: 4,
+                max_rows_per_statement: 1000,
+                tls: None,
+                routes: vec![TopicMapping {
+                    from: "/test/topic".to_string(),
+                    subscription: "test-sub".to_string(),
+                    subscription_type: SubscriptionType::Shared,
+                    to: "events".to_string(),
+                    expected_schema_subject: None,
+                    insert_mode: InsertMode::Upsert,
+                    key_columns: vec!["id".to_string()],
+                    columns: Vec::new(),
+                    include_danube_metadata: false,
+                    field_map: HashMap::new(),
+                    exclude_fields: Vec::new(),
+                    on_error: None,
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_is_rejection() {
+        let server_error = |code| {
+            mysql_async::Error::Server(mysql_async::ServerError {
+                code,
+                message: String::new(),
+                state: String::new(),
+            })
+        };
+
+        // Duplicate key, data too long
+        assert!(is_rejection(&server_error(1062)));
+        assert!(is_rejection(&server_error(1406)));
+
+        // Deadlock, missing table, command denied: not the records' fault
+        assert!(!is_rejection(&server_error(1213)));
+        assert!(!is_rejection(&server_error(1146)));
+        assert!(!is_rejection(&server_error(1142)));
+    }
+
+    #[test]
+    fn test_connector_creation() {
+        let connector = MySqlSinkConnector::with_config(test_config());
+        assert_eq!(connector.tables.len(), 1);
+        assert!(connector.pool.is_none());
+        assert!(connector.dlq.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_pool() {
+        let config = test_config();
+        // Connections are opened on demand, so no server is needed
+        let pool = create_pool(&config.mysql).unwrap();
+        pool.disconnect().await.unwrap();
+
+        let mut invalid = config.mysql;
+        invalid.url = "mysql://localhost:notaport".to_string();
+        assert!(create_pool(&invalid).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Dead-letter queue for MySQL Sink Connector
+//!
+//! Records a route can't write (invalid data, or rejected by MySQL after
+//! the route's attempts are exhausted) are published to the route's DLQ topic
+//! with their original payload and attributes, so the topic keeps flowing and
+//! the records can be inspected or replayed later.
+//!
+//! Messages have the same shape as sink-surrealdb's DLQ (payload, attributes
+//! and producer names), so one consumer can replay both; connectors don't
+//! share crates, so changes to the shape go to both modules.
+
+use danube_client::{DanubeClient, Producer};
+use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Attribute carrying the source topic of a dead-lettered record
+pub const DLQ_SOURCE_TOPIC: &str = "danube.dlq.source_topic";
+/// Attribute carrying the destination table of a dead-lettered record
+pub const DLQ_TABLE: &str = "danube.dlq.table";
+/// Attribute carrying the error that failed the record
+pub const DLQ_ERROR: &str = "danube.dlq.error";
+
+/// Publishes failed records to DLQ topics
+pub struct DeadLetterQueue {
+    /// Danube client used to create DLQ producers
+    client: DanubeClient,
+
+    /// Producer name prefix (connector name)
+    connector_name: String,
+
+    /// DLQ producers, created on first use (topic -> producer)
+    producers: Mutex<HashMap<String, Producer>>,
+}
+
+impl DeadLetterQueue {
+    /// Connect to the Danube broker used for DLQ topics
+    pub async fn connect(service_url: &str, connector_name: &str) -> ConnectorResult<Self> {
+        let client = DanubeClient::builder()
+            .service_url(service_url)
+            .build()
+            .await
+            .map_err(|e| {
+                ConnectorError::retryable(format!("Failed to connect DLQ client: {}", e))
+            })?;
+
+        Ok(Self {
+            client,
+            connector_name: connector_name.to_string(),
+            producers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Publish a record that couldn't be written to a MySQL table
+    ///
+    /// The payload is sent as JSON with the record's original attributes plus
+    /// the source topic, table, and error.
+    pub async fn publish(
+        &self,
+        dlq_topic: &str,
+        table: &str,
+        record: &SinkRecord,
+        error: &ConnectorError,
+    ) -> ConnectorResult<()> {
+        let mut producers = self.producers.lock().await;
+        if !producers.contains_key(dlq_topic) {
+            let mut producer = self
+                .client
+                .new_producer()
+                .with_topic(dlq_topic)
+                .with_name(format!(
+                    "{}-dlq-{}",
+                    self.connector_name,
+                    dlq_topic.trim_start_matches('/').replace('/', "-")
+                ))
+                .build();
+            producer.create().await.map_err(|e| {
+                ConnectorError::retryable(format!(
+                    "Failed to create DLQ producer for {}: {}",
+                    dlq_topic, e
+                ))
+            })?;
+            info!("Created DLQ producer for topic {}", dlq_topic);
+            producers.insert(dlq_topic.to_string(), producer);
+        }
+        let producer = producers.get_mut(dlq_topic).ok_or_else(|| {
+            ConnectorError::fatal(format!("DLQ producer missing for {}", dlq_topic))
+        })?;
+
+        let payload = serde_json::to_vec(record.payload()).map_err(|e| {
+            ConnectorError::fatal(format!("Failed to serialize DLQ payload: {}", e))
+        })?;
+
+        let mut attributes = record.attributes().clone();
+        attributes.insert(DLQ_SOURCE_TOPIC.to_string(), record.topic().to_string());
+        attributes.insert(DLQ_TABLE.to_string(), table.to_string());
+        attributes.insert(DLQ_ERROR.to_string(), error.to_string());
+
+        producer
+            .send(payload, Some(attributes))
+            .await
+            .map_err(|e| {
+                ConnectorError::retryable(format!(
+                    "Failed to publish to DLQ topic {}: {}",
+                    dlq_topic, e
+                ))
+            })?;
+
+        warn!(
+            "Routed record from {} for table '{}' to DLQ topic {}: {}",
+            record.topic(),
+            table,
+            dlq_topic,
+            error
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! MySQL Sink Connector for Danube Connect
+//!
+//! This connector consumes messages from Danube topics and writes them to MySQL or MariaDB
+//! tables, with batched multi-row inserts and optional upserts.
+
+mod config;
+mod connector;
+mod dlq;
+mod record;
+mod sql;
+mod tls;
+
+use config::MySqlSinkConfig;
+use connector::MySqlSinkConnector;
+use danube_connect_core::{ConnectorResult, SinkRuntime};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> ConnectorResult<()> {
+    // Initialize logging first
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info,danube_sink_mysql=debug"));
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_target(true))
+        .try_init()
+        .ok(); // Ignore error if already initialized
+
+    tracing::info!("Starting MySQL Sink Connector");
+    tracing::info!("Version: {}", env!("CARGO_PKG_VERSION"));
+
+    // Load unified configuration from single file (TOML + ENV overrides)
+    let config = MySqlSinkConfig::load().map_err(|e| {
+        tracing::error!("Failed to load configuration: {}", e);
+        e
+    })?;
+
+    // Validate configuration
+    config.validate()?;
+
+    tracing::info!("Configuration loaded and validated successfully");
+    tracing::info!("Connector: {}", config.core.connector_name);
+    tracing::info!("Danube URL: {}", config.core.danube_service_url);
+    tracing::info!("MySQL URL: {}", config.mysql.url);
+    tracing::info!("MySQL Database: {}", config.mysql.database);
+    tracing::info!("Routes: {} configured", config.mysql.routes.len());
+
+    for (idx, mapping) in config.mysql.routes.iter().enumerate() {
+        tracing::info!(
+            "  Route {}: Topic '{}' → Table '{}' ({:?})",
+            idx + 1,
+            mapping.from,
+            mapping.to,
+            mapping.insert_mode
+        );
+    }
+
+    // Create connector instance with MySQL configuration
+    let connector = MySqlSinkConnector::with_config(config.clone());
+
+    // Create and run the sink runtime
+    tracing::info!("Initializing connector runtime...");
+    let mut runtime = SinkRuntime::new(connector, config.core).await?;
+
+    // Run until shutdown signal
+    runtime.run().await?;
+
+    tracing::info!("MySQL Sink Connector terminated");
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! Record processing module for MySQL Sink Connector
+//!
+//! This module converts Danube messages into table rows.
+//! Payloads are already deserialized as serde_json::Value by the runtime.
+//!
+//! A payload must be a JSON object; its top-level fields (or the route's
+//! `columns`) become columns. Scalars are bound as SQL values, nested objects
+//! and arrays as JSON text (for `JSON` columns).
+
+use crate::config::TopicMapping;
+use chrono::{DateTime, Utc};
+use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
+use mysql_async::Value as SqlValue;
+use serde_json::{json, Map, Value};
+
+/// Column holding Danube metadata when `include_danube_metadata` is set
+pub const METADATA_COLUMN: &str = "_danube_metadata";
+
+/// A table row ready for insertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlRow {
+    /// Column names
+    pub columns: Vec<String>,
+
+    /// Values, in column order
+    pub values: Vec<SqlValue>,
+}
+
+/// Convert a Danube SinkRecord into a table row
+///
+/// Records that can't become a row (e.g., non-object payloads) are reported
+/// as invalid data.
+pub fn to_mysql_row(record: &SinkRecord, mapping: &TopicMapping) -> ConnectorResult<MySqlRow> {
+    let metadata = mapping
+        .include_danube_metadata
+        .then(|| danube_metadata(record));
+
+    payload_to_row(record.payload().clone(), mapping, metadata).map_err(|e| {
+        ConnectorError::invalid_data(
+            format!(
+                "Failed to convert message on topic {} for table '{}': {}",
+                record.topic(),
+                mapping.to,
+                e
+            ),
+            serde_json::to_vec(record.payload()).unwrap_or_default(),
+        )
+    })
+}
+
+/// Build a row from a payload, applying the route's field and column settings
+fn payload_to_row(
+    mut data: Value,
+    mapping: &TopicMapping,
+    metadata: Option<Value>,
+) -> Result<MySqlRow, String> {
+    reshape_fields(&mut data, mapping);
+    let Value::Object(mut fields) = data else {
+        return Err("payload must be a JSON object".to_string());
+    };
+    if let Some(metadata) = metadata {
+        fields.insert(METADATA_COLUMN.to_string(), metadata);
+    }
+
+    let row = if mapping.columns.is_empty() {
+        if fields.is_empty() {
+            return Err("payload has no fields".to_string());
+        }
+        let (columns, values) = fields
+            .into_iter()
+            .map(|(column, value)| (column, to_sql_value(value)))
+            .unzip();
+        MySqlRow { columns, values }
+    } else {
+        let mut columns = mapping.columns.clone();
+        if fields.contains_key(METADATA_COLUMN) && !columns.iter().any(|c| c == METADATA_COLUMN) {
+            columns.push(METADATA_COLUMN.to_string());
+        }
+        let values = columns
+            .iter()
+            .map(|column| to_sql_value(take_field(&mut fields, column)))
+            .collect();
+        MySqlRow { columns, values }
+    };
+
+    Ok(row)
+}
+
+/// Remove a field from the payload, or Null if it is missing
+fn take_field(fields: &mut Map<String, Value>, name: &str) -> Value {
+    fields.remove(name).unwrap_or(Value::Null)
+}
+
+/// Convert a JSON value to a SQL value
+///
+/// Booleans become 0/1 (MySQL has no boolean type); objects and arrays are
+/// serialized as JSON text.
+fn to_sql_value(value: Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::NULL,
+        Value::Bool(b) => SqlValue::Int(b as i64),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                SqlValue::Int(i)
+            } else if let Some(u) = n.as_u64() {
+                SqlValue::UInt(u)
+            } else {
+                SqlValue::Double(n.as_f64().unwrap_or_default())
+            }
+        }
+        Value::String(s) => SqlValue::Bytes(s.into_bytes()),
+        nested => SqlValue::Bytes(nested.to_string().into_bytes()),
+    }
+}
+
+/// Apply the route's `exclude_fields` and `field_map` to the payload
+///
+/// Exclusions are applied first; mapped fields missing from the payload are skipped.
+fn reshape_fields(data: &mut Value, mapping: &TopicMapping) {
+    for field in &mapping.exclude_fields {
+        remove_path(data, field);
+    }
+
+    for (source, target) in &mapping.field_map {
+        if let Some(value) = remove_path(data, source) {
+            insert_path(data, target, value);
+        }
+    }
+}
+
+/// Remove a field (dotted path) from an object, returning its value
+fn remove_path(data: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(data, |current, part| current.get_mut(part))?,
+            key,
+        ),
+        None => (data, path),
+    };
+
+    parent.as_object_mut()?.remove(key)
+}
+
+/// Insert a field (dotted path) into an object, creating intermediate objects
+fn insert_path(data: &mut Value, path: &str, value: Value) {
+    let mut current = data;
+    let mut parts = path.split('.').peekable();
+
+    while let Some(part) = parts.next() {
+        let Value::Object(map) = current else {
+            return;
+        };
+        if parts.peek().is_none() {
+            map.insert(part.to_string(), value);
+            return;
+        }
+        current = map
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+}
+
+/// Danube metadata of a record
+fn danube_metadata(record: &SinkRecord) -> Value {
+    // Convert publish_time (microseconds) to DateTime<Utc>
+    let publish_time_secs = record.publish_time() / 1_000_000;
+    let publish_time_nanos = ((record.publish_time() % 1_000_000) * 1000) as u32;
+    let datetime = DateTime::from_timestamp(publish_time_secs as i64, publish_time_nanos)
+        .unwrap_or_else(Utc::now);
+
+    json!({
+        "danube_topic": record.topic(),
+        "danube_timestamp": datetime.to_rfc3339(),
+        "danube_producer": record.producer_name(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // SinkRecord can't be constructed outside danube-connect-core, so the
+    // conversion is tested on payloads.
+
+    use super::*;
+    use crate::config::InsertMode;
+    use danube_connect_core::SubscriptionType;
+    use std::collections::HashMap;
+
+    fn mapping() -> TopicMapping {
+        TopicMapping {
+            from: "/default/orders".to_string(),
+            subscription: "mysql-sink".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            to: "orders".to_string(),
+            expected_schema_subject: None,
+            insert_mode: InsertMode::Insert,
+            key_columns: Vec::new(),
+            columns: Vec::new(),
+            include_danube_metadata: false,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            on_error: None,
+        }
+    }
+
+    fn bytes(s: &str) -> SqlValue {
+        SqlValue::Bytes(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_payload_fields_as_columns() {
+        let payload = json!({
+            "order_id": 42,
+            "paid": true,
+            "total": 9.5,
+            "status": "new",
+            "items": [{"sku": "a1"}],
+            "note": null,
+        });
+
+        let row = payload_to_row(payload, &mapping(), None).unwrap();
+        assert_eq!(
+            row.columns,
+            vec!["items", "note", "order_id", "paid", "status", "total"]
+        );
+        assert_eq!(
+            row.values,
+            vec![
+                bytes(r#"[{"sku":"a1"}]"#),
+                SqlValue::NULL,
+                SqlValue::Int(42),
+                SqlValue::Int(1),
+                bytes("new"),
+                SqlValue::Double(9.5),
+            ]
+        );
+
+        assert!(payload_to_row(json!("text"), &mapping(), None).is_err());
+        assert!(payload_to_row(json!({}), &mapping(), None).is_err());
+    }
+
+    #[test]
+    fn test_configured_columns() {
+        let mut mapping = mapping();
+        mapping.columns = vec!["order_id".to_string(), "customer".to_string()];
+        mapping.field_map = HashMap::from([("customer.name".to_string(), "customer".to_string())]);
+        mapping.include_danube_metadata = true;
+
+        let payload = json!({"order_id": 7, "customer": {"name": "Alice"}, "debug": 1});
+        let metadata = json!({"danube_topic": "/default/orders"});
+        let row = payload_to_row(payload, &mapping, Some(metadata)).unwrap();
+
+        assert_eq!(row.columns, vec!["order_id", "customer", METADATA_COLUMN]);
+        assert_eq!(
+            row.values,
+            vec![
+                SqlValue::Int(7),
+                bytes("Alice"),
+                bytes(r#"{"danube_topic":"/default/orders"}"#),
+            ]
+        );
+
+        // Missing fields are written as NULL
+        let row = payload_to_row(json!({"order_id": 8}), &mapping, None).unwrap();
+        assert_eq!(row.values, vec![SqlValue::Int(8), SqlValue::NULL]);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert_eq!(to_sql_value(json!(u64::MAX)), SqlValue::UInt(u64::MAX));
+        assert_eq!(to_sql_value(json!(-3)), SqlValue::Int(-3));
+    }
+}
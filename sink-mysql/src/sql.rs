@@ -0,0 +1,170 @@
+//! SQL statement building for MySQL Sink Connector
+//!
+//! Rows are written with multi-row `INSERT` statements using `?` placeholders.
+//! Upserts append `ON DUPLICATE KEY UPDATE col = VALUES(col)` for every column
+//! except the route's key columns. The `VALUES()` form is deprecated in recent
+//! MySQL releases but, unlike row aliases, is supported by both MySQL and MariaDB.
+
+use crate::config::InsertMode;
+
+/// Maximum placeholders in one prepared statement (MySQL protocol limit)
+const MAX_PLACEHOLDERS: usize = 65_535;
+
+/// Quote an identifier with backticks, escaping embedded backticks
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Quote a table reference, qualified (`database.table`) or not
+pub fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Build a multi-row INSERT (or upsert) statement for `rows` rows of `columns`
+pub fn insert_statement(
+    table: &str,
+    columns: &[String],
+    rows: usize,
+    mode: InsertMode,
+    key_columns: &[String],
+) -> String {
+    let column_list = columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let row = format!("({})", vec!["?"; columns.len()].join(", "));
+    let mut statement = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        quote_table(table),
+        column_list,
+        vec![row.as_str(); rows].join(", ")
+    );
+
+    if mode == InsertMode::Upsert {
+        let mut updates: Vec<_> = columns
+            .iter()
+            .filter(|column| !key_columns.contains(column))
+            .map(|column| {
+                let column = quote_identifier(column);
+                format!("{} = VALUES({})", column, column)
+            })
+            .collect();
+        // Rows holding only key columns leave existing rows unchanged
+        if updates.is_empty() {
+            let column = quote_identifier(&columns[0]);
+            updates.push(format!("{} = {}", column, column));
+        }
+        statement.push_str(" ON DUPLICATE KEY UPDATE ");
+        statement.push_str(&updates.join(", "));
+    }
+
+    statement
+}
+
+/// Split rows into statements: consecutive rows with the same columns share a
+/// statement of at most `max_rows` rows (and the placeholder limit)
+///
+/// Row order is preserved, so later writes to a key still win.
+pub fn statement_chunks<V>(
+    rows: Vec<(Vec<String>, Vec<V>)>,
+    max_rows: usize,
+) -> Vec<(Vec<String>, Vec<Vec<V>>)> {
+    let mut chunks: Vec<(Vec<String>, Vec<Vec<V>>)> = Vec::new();
+
+    for (columns, values) in rows {
+        let limit = max_rows.min(MAX_PLACEHOLDERS / columns.len().max(1));
+        match chunks.last_mut() {
+            Some((chunk_columns, chunk_rows))
+                if *chunk_columns == columns && chunk_rows.len() < limit =>
+            {
+                chunk_rows.push(values)
+            }
+            _ => chunks.push((columns, vec![values])),
+        }
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_insert_statement() {
+        assert_eq!(
+            insert_statement(
+                "orders",
+                &columns(&["id", "status"]),
+                2,
+                InsertMode::Insert,
+                &[]
+            ),
+            "INSERT INTO `orders` (`id`, `status`) VALUES (?, ?), (?, ?)"
+        );
+
+        assert_eq!(
+            insert_statement(
+                "shop.orders",
+                &columns(&["id", "status", "total"]),
+                1,
+                InsertMode::Upsert,
+                &columns(&["id"])
+            ),
+            "INSERT INTO `shop`.`orders` (`id`, `status`, `total`) VALUES (?, ?, ?) \
+             ON DUPLICATE KEY UPDATE `status` = VALUES(`status`), `total` = VALUES(`total`)"
+        );
+
+        // Only key columns: duplicates are left as they are
+        assert_eq!(
+            insert_statement(
+                "tags",
+                &columns(&["name"]),
+                1,
+                InsertMode::Upsert,
+                &columns(&["name"])
+            ),
+            "INSERT INTO `tags` (`name`) VALUES (?) ON DUPLICATE KEY UPDATE `name` = `name`"
+        );
+    }
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("order"), "`order`");
+        assert_eq!(quote_identifier("we`ird"), "`we``ird`");
+        assert_eq!(quote_table("db.t"), "`db`.`t`");
+    }
+
+    #[test]
+    fn test_statement_chunks() {
+        let row = |names: &[&str], value: u32| (columns(names), vec![value; names.len()]);
+        let rows = vec![
+            row(&["a", "b"], 1),
+            row(&["a", "b"], 2),
+            row(&["a", "b"], 3),
+            row(&["a"], 4),
+            row(&["a", "b"], 5),
+        ];
+
+        let chunks = statement_chunks(rows, 2);
+        let shape: Vec<_> = chunks
+            .iter()
+            .map(|(columns, rows)| (columns.len(), rows.len()))
+            .collect();
+        assert_eq!(shape, vec![(2, 2), (2, 1), (1, 1), (2, 1)]);
+        assert_eq!(chunks[1].1, vec![vec![3, 3]]);
+
+        // Wide rows are limited by the placeholder count
+        let wide: Vec<_> = (0..3).map(|i| (columns(&["c"; 30_000]), vec![i])).collect();
+        assert_eq!(statement_chunks(wide, 1000).len(), 2);
+    }
+}
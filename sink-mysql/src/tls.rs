@@ -0,0 +1,88 @@
+//! TLS module for MySQL Sink Connector
+//!
+//! Builds the driver's TLS options from `[mysql.tls]`: public web roots plus an
+//! optional custom CA bundle (e.g., for managed or self-signed deployments),
+//! an optional client certificate for X.509 authentication, and opt-outs of
+//! certificate and hostname verification.
+
+use crate::config::TlsConfig;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use mysql_async::{ClientIdentity, SslOpts};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Build the TLS options for the connection pool
+///
+/// The certificate files are read when connecting, so their presence is
+/// checked here to report a misconfiguration at startup.
+pub fn ssl_opts(tls: &TlsConfig) -> ConnectorResult<SslOpts> {
+    let mut opts = SslOpts::default();
+
+    if let Some(path) = &tls.ca_cert_path {
+        opts = opts.with_root_certs(vec![existing_file(path, "CA bundle")?.into()]);
+    }
+
+    if let (Some(cert), Some(key)) = (&tls.client_cert_path, &tls.client_key_path) {
+        let identity = ClientIdentity::new(
+            existing_file(cert, "client certificate")?.into(),
+            existing_file(key, "client key")?.into(),
+        );
+        opts = opts.with_client_identity(Some(identity));
+    }
+
+    if !tls.verify_certificates {
+        warn!("TLS certificate verification is disabled for MySQL, use only for testing");
+        opts = opts.with_danger_accept_invalid_certs(true);
+    }
+    if !tls.verify_hostname {
+        warn!("TLS hostname verification is disabled for MySQL");
+        opts = opts.with_danger_skip_domain_validation(true);
+    }
+
+    Ok(opts)
+}
+
+/// Check that a configured certificate file exists
+fn existing_file(path: &str, what: &str) -> ConnectorResult<PathBuf> {
+    if !Path::new(path).is_file() {
+        return Err(ConnectorError::config(format!(
+            "TLS {} '{}' not found",
+            what, path
+        )));
+    }
+    Ok(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssl_opts() {
+        let tls = TlsConfig {
+            ca_cert_path: None,
+            verify_certificates: true,
+            verify_hostname: true,
+            client_cert_path: None,
+            client_key_path: None,
+        };
+        let opts = ssl_opts(&tls).unwrap();
+        assert!(!opts.accept_invalid_certs());
+        assert!(!opts.skip_domain_validation());
+
+        let insecure = TlsConfig {
+            verify_certificates: false,
+            verify_hostname: false,
+            ..tls.clone()
+        };
+        let opts = ssl_opts(&insecure).unwrap();
+        assert!(opts.accept_invalid_certs());
+        assert!(opts.skip_domain_validation());
+
+        let missing_ca = TlsConfig {
+            ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+            ..tls
+        };
+        assert!(ssl_opts(&missing_ca).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Qdrant client for the Qdrant Sink Connector
+//!
+//! `qdrant_client::Qdrant` only trusts the platform certificate store. With
+//! `tls_ca_path`, the connector instead calls Qdrant's gRPC services over its
+//! own tonic channel, whose TLS configuration trusts the configured CA; this
+//! keeps the custom CA scoped to the Qdrant connection.
+
+use crate::config::QdrantConfig;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use qdrant_client::qdrant::collections_client::CollectionsClient;
+use qdrant_client::qdrant::points_client::PointsClient;
+use qdrant_client::qdrant::{
+    CreateCollection, CreateFieldIndexCollection, CreateShardKeyRequest, DeletePoints,
+    ListCollectionsRequest, ListCollectionsResponse, UpsertPoints,
+};
+use qdrant_client::Qdrant;
+use std::time::Duration;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+use tonic::{Request, Status};
+
+/// Channel of the gRPC services, adding the API key to every request
+type GrpcChannel = InterceptedService<Channel, ApiKey>;
+
+/// Qdrant client used by the connector
+pub enum QdrantClient {
+    /// qdrant-client's client, trusting the platform certificate store
+    Platform(Qdrant),
+    /// gRPC services on a channel trusting a custom CA
+    CustomCa {
+        collections: CollectionsClient<GrpcChannel>,
+        points: PointsClient<GrpcChannel>,
+    },
+}
+
+impl QdrantClient {
+    /// Create the client for the configured URL, API key and CA
+    ///
+    /// Connections are opened lazily, on the first request.
+    pub fn new(config: &QdrantConfig) -> ConnectorResult<Self> {
+        let Some(ca_path) = &config.tls_ca_path else {
+            let client = Qdrant::new(config.qdrant_client_config()).map_err(|e| {
+                ConnectorError::fatal(format!("Failed to create Qdrant client: {}", e))
+            })?;
+            return Ok(QdrantClient::Platform(client));
+        };
+
+        let ca = std::fs::read(ca_path).map_err(|e| {
+            ConnectorError::config(format!("Failed to read tls_ca_path '{}': {}", ca_path, e))
+        })?;
+        let tls = ClientTlsConfig::new().ca_certificate(Certificate::from_pem(ca));
+
+        let channel = Channel::from_shared(config.url.clone())
+            .map_err(|e| ConnectorError::config(format!("Invalid Qdrant URL: {}", e)))?
+            .tls_config(tls)
+            .map_err(|e| {
+                ConnectorError::config(format!("Invalid TLS configuration for Qdrant: {}", e))
+            })?
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_lazy();
+
+        let api_key = config
+            .api_key
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|_| ConnectorError::config("Qdrant API key contains invalid characters"))?;
+        let interceptor = ApiKey(api_key);
+
+        Ok(QdrantClient::CustomCa {
+            collections: CollectionsClient::with_interceptor(channel.clone(), interceptor.clone()),
+            points: PointsClient::with_interceptor(channel, interceptor),
+        })
+    }
+
+    pub async fn list_collections(&self) -> Result<ListCollectionsResponse, String> {
+        match self {
+            QdrantClient::Platform(client) => {
+                client.list_collections().await.map_err(|e| e.to_string())
+            }
+            QdrantClient::CustomCa { collections, .. } => collections
+                .clone()
+                .list(ListCollectionsRequest {})
+                .await
+                .map(tonic::Response::into_inner)
+                .map_err(status_error),
+        }
+    }
+
+    pub async fn create_collection(
+        &self,
+        request: impl Into<CreateCollection>,
+    ) -> Result<(), String> {
+        match self {
+            QdrantClient::Platform(client) => client
+                .create_collection(request)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+            QdrantClient::CustomCa { collections, .. } => collections
+                .clone()
+                .create(request.into())
+                .await
+                .map(drop)
+                .map_err(status_error),
+        }
+    }
+
+    pub async fn create_shard_key(
+        &self,
+        request: impl Into<CreateShardKeyRequest>,
+    ) -> Result<(), String> {
+        match self {
+            QdrantClient::Platform(client) => client
+                .create_shard_key(request)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+            QdrantClient::CustomCa { collections, .. } => collections
+                .clone()
+                .create_shard_key(request.into())
+                .await
+                .map(drop)
+                .map_err(status_error),
+        }
+    }
+
+    pub async fn create_field_index(
+        &self,
+        request: impl Into<CreateFieldIndexCollection>,
+    ) -> Result<(), String> {
+        match self {
+            QdrantClient::Platform(client) => client
+                .create_field_index(request)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+            QdrantClient::CustomCa { points, .. } => points
+                .clone()
+                .create_field_index(request.into())
+                .await
+                .map(drop)
+                .map_err(status_error),
+        }
+    }
+
+    pub async fn upsert_points(&self, request: impl Into<UpsertPoints>) -> Result<(), String> {
+        match self {
+            QdrantClient::Platform(client) => client
+                .upsert_points(request)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+            QdrantClient::CustomCa { points, .. } => points
+                .clone()
+                .upsert(request.into())
+                .await
+                .map(drop)
+                .map_err(status_error),
+        }
+    }
+
+    pub async fn delete_points(&self, request: impl Into<DeletePoints>) -> Result<(), String> {
+        match self {
+            QdrantClient::Platform(client) => client
+                .delete_points(request)
+                .await
+                .map(drop)
+                .map_err(|e| e.to_string()),
+            QdrantClient::CustomCa { points, .. } => points
+                .clone()
+                .delete(request.into())
+                .await
+                .map(drop)
+                .map_err(status_error),
+        }
+    }
+}
+
+/// Adds the `api-key` header Qdrant Cloud expects, if configured
+#[derive(Clone)]
+pub struct ApiKey(Option<MetadataValue<Ascii>>);
+
+impl Interceptor for ApiKey {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(api_key) = &self.0 {
+            request.metadata_mut().insert("api-key", api_key.clone());
+        }
+        Ok(request)
+    }
+}
+
+fn status_error(status: Status) -> String {
+    format!("{:?}: {}", status.code(), status.message())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_interceptor() {
+        let mut interceptor = ApiKey(Some("secret".parse().unwrap()));
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(request.metadata().get("api-key").unwrap(), "secret");
+
+        let mut interceptor = ApiKey(None);
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert!(request.metadata().get("api-key").is_none());
+    }
+}
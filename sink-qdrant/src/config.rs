@@ -51,6 +51,23 @@ impl ConfigEnvOverrides for QdrantSinkConfig {
             self.qdrant.api_key = Some(api_key);
         }
 
+        if let Ok(ca_path) = env::var("QDRANT_TLS_CA_PATH") {
+            self.qdrant.tls_ca_path = Some(ca_path);
+        }
+
+        // OpenAI embedding routes without an explicit key use OPENAI_API_KEY
+        if let Ok(api_key) = env::var("OPENAI_API_KEY") {
+            for embedding in self
+                .qdrant
+                .routes
+                .iter_mut()
+                .filter_map(|mapping| mapping.embedding.as_mut())
+                .filter(|embedding| embedding.provider == EmbeddingProvider::OpenAI)
+            {
+                embedding.api_key.get_or_insert_with(|| api_key.clone());
+            }
+        }
+
         Ok(())
     }
 }
@@ -72,6 +89,11 @@ pub struct QdrantConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
 
+    /// CA certificate file (PEM) for a Qdrant server behind a private CA (https:// URLs)
+    /// Replaces the system trust store for the Qdrant connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_ca_path: Option<String>,
+
     /// Routes: Danube topic → Qdrant collection configuration
     pub routes: Vec<TopicMapping>,
 
@@ -93,10 +115,13 @@ pub struct TopicMapping {
     #[serde(default = "default_subscription_type")]
     pub subscription_type: SubscriptionType,
 
-    /// Target Qdrant collection name
+    /// Target Qdrant collection name, or a template resolved per message
+    /// from payload fields or attributes (e.g., "docs_{tenant}")
     pub to: String,
 
     /// Vector dimension (must match embedding model for this topic)
+    /// Not used by collections with named vectors (`vectors`)
+    #[serde(default)]
     pub vector_dimension: usize,
 
     /// Distance metric for this collection
@@ -111,11 +136,360 @@ pub struct TopicMapping {
     #[serde(default = "default_include_metadata")]
     pub include_danube_metadata: bool,
 
+    /// Wait until writes are applied before acknowledging them (read-your-writes, higher latency)
+    #[serde(default)]
+    pub wait: bool,
+
+    /// Ordering guarantee of writes across replicas (Qdrant default: weak)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_ordering: Option<WriteOrdering>,
+
+    /// Message field holding the vector (dotted paths supported, e.g. "data.embedding")
+    #[serde(default = "default_vector_field")]
+    pub vector_field: String,
+
+    /// Message field holding the point ID (default: "id")
+    #[serde(default = "default_id_field")]
+    pub id_field: String,
+
+    /// Message field holding the point payload (default: "payload")
+    #[serde(default = "default_payload_field")]
+    pub payload_field: String,
+
     /// Expected schema subject for validation (optional)
     /// If set, the runtime validates and deserializes messages automatically
     /// Schema must be registered in Danube Schema Registry
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expected_schema_subject: Option<String>,
+
+    /// Named vectors of a multi-vector collection (e.g., `title_vec`, `body_vec`)
+    /// If set, messages carry a `vectors` map instead of a single `vector`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vectors: Vec<NamedVector>,
+
+    /// Sparse vectors of a hybrid dense+sparse collection (e.g., `text_sparse` for BM25 or SPLADE)
+    /// Messages carry them in `sparse_vectors` (or `sparse_vector` if only one is declared)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sparse_vectors: Vec<SparseVector>,
+
+    /// Store vectors (and sparse vector indexes) on disk (memmap) instead of RAM,
+    /// for auto-created collections
+    #[serde(default)]
+    pub on_disk: bool,
+
+    /// Store payloads on disk instead of RAM, for auto-created collections
+    #[serde(default)]
+    pub on_disk_payload: bool,
+
+    /// Segment size in KB above which vectors are memmapped, for auto-created collections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memmap_threshold_kb: Option<u64>,
+
+    /// HNSW index settings for auto-created collections (Qdrant defaults if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hnsw: Option<HnswSettings>,
+
+    /// Vector quantization for auto-created collections (none if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<Quantization>,
+
+    /// Number of shards for auto-created collections (Qdrant default if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_number: Option<u32>,
+
+    /// Copies of each shard across cluster nodes, for auto-created collections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replication_factor: Option<u32>,
+
+    /// Replicas that must acknowledge a write, for auto-created collections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_consistency_factor: Option<u32>,
+
+    /// Payload indexes created on the collection at startup (for filtered search)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payload_indexes: Vec<PayloadIndex>,
+
+    /// Copy each message's tenant into the payload for per-tenant filtering (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<TenantConfig>,
+
+    /// Treat some messages as point deletes (optional, e.g. `{"op": "delete", "id": "42"}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<DeleteConfig>,
+
+    /// Generate the vector from a text field before upsert (optional)
+    /// If set, messages are plain documents instead of carrying a `vector`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<EmbeddingConfig>,
+}
+
+impl TopicMapping {
+    /// Whether the collection name is a template resolved per message
+    pub fn is_dynamic(&self) -> bool {
+        self.to.contains('{')
+    }
+}
+
+/// Named vector of a multi-vector collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedVector {
+    /// Vector name
+    pub name: String,
+
+    /// Vector dimension
+    pub dimension: usize,
+
+    /// Distance metric for this vector
+    #[serde(default = "default_distance")]
+    pub distance: Distance,
+}
+
+/// Sparse vector of a hybrid collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseVector {
+    /// Vector name
+    pub name: String,
+
+    /// Apply the IDF modifier at search time (for BM25-style term weights)
+    #[serde(default)]
+    pub idf: bool,
+}
+
+/// Tenant partitioning of a multi-tenant route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfig {
+    /// Message field holding the tenant (dotted paths supported)
+    /// Falls back to the message attribute of the same name
+    pub field: String,
+
+    /// Payload key the tenant is stored under (default: "tenant_id")
+    #[serde(default = "default_tenant_payload_key")]
+    pub payload_key: String,
+
+    /// Also use the tenant as Qdrant shard key (collections created with custom sharding)
+    #[serde(default)]
+    pub shard_key: bool,
+}
+
+/// Delete convention of a route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteConfig {
+    /// Payload field holding the operation (default: "op")
+    #[serde(default = "default_delete_op_field")]
+    pub op_field: String,
+
+    /// Value of `op_field` that marks a delete (default: "delete")
+    #[serde(default = "default_delete_op_value")]
+    pub op_value: String,
+
+    /// Treat messages with an `id` but a null or missing vector as deletes (default: false)
+    #[serde(default)]
+    pub on_null_vector: bool,
+}
+
+/// HNSW index settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswSettings {
+    /// Edges per node (higher: better recall, more memory)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub m: Option<u64>,
+
+    /// Neighbours considered while building the index (higher: better recall, slower build)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ef_construct: Option<u64>,
+
+    /// Store the HNSW index on disk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_disk: Option<bool>,
+}
+
+impl HnswSettings {
+    pub fn to_qdrant(&self) -> qdrant_client::qdrant::HnswConfigDiff {
+        qdrant_client::qdrant::HnswConfigDiff {
+            m: self.m,
+            ef_construct: self.ef_construct,
+            on_disk: self.on_disk,
+            ..Default::default()
+        }
+    }
+}
+
+/// Vector quantization method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Quantization {
+    /// float32 → int8 (4x smaller, small accuracy loss)
+    Scalar {
+        /// Quantile excluding outliers from the value range (e.g., 0.99)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quantile: Option<f32>,
+        /// Keep quantized vectors in RAM
+        #[serde(default)]
+        always_ram: bool,
+    },
+    /// Product quantization (up to 64x smaller, larger accuracy loss)
+    Product {
+        /// Compression ratio
+        compression: CompressionRatio,
+        /// Keep quantized vectors in RAM
+        #[serde(default)]
+        always_ram: bool,
+    },
+    /// One bit per dimension (32x smaller, for high-dimensional embeddings)
+    Binary {
+        /// Keep quantized vectors in RAM
+        #[serde(default)]
+        always_ram: bool,
+    },
+}
+
+/// Product quantization compression ratio
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionRatio {
+    /// 4x smaller
+    X4,
+    /// 8x smaller
+    X8,
+    /// 16x smaller
+    X16,
+    /// 32x smaller
+    X32,
+    /// 64x smaller
+    X64,
+}
+
+impl Quantization {
+    pub fn to_qdrant(&self) -> qdrant_client::qdrant::QuantizationConfig {
+        use qdrant_client::qdrant::quantization_config::Quantization as Method;
+        use qdrant_client::qdrant::{
+            BinaryQuantizationBuilder, CompressionRatio as Ratio, ProductQuantization,
+            ScalarQuantization, ScalarType,
+        };
+
+        let quantization = match self {
+            Quantization::Scalar {
+                quantile,
+                always_ram,
+            } => Method::Scalar(ScalarQuantization {
+                r#type: ScalarType::Int8.into(),
+                quantile: *quantile,
+                always_ram: Some(*always_ram),
+            }),
+            Quantization::Product {
+                compression,
+                always_ram,
+            } => Method::Product(ProductQuantization {
+                compression: match compression {
+                    CompressionRatio::X4 => Ratio::X4,
+                    CompressionRatio::X8 => Ratio::X8,
+                    CompressionRatio::X16 => Ratio::X16,
+                    CompressionRatio::X32 => Ratio::X32,
+                    CompressionRatio::X64 => Ratio::X64,
+                }
+                .into(),
+                always_ram: Some(*always_ram),
+            }),
+            Quantization::Binary { always_ram } => {
+                Method::Binary(BinaryQuantizationBuilder::new(*always_ram).build())
+            }
+        };
+
+        qdrant_client::qdrant::QuantizationConfig {
+            quantization: Some(quantization),
+        }
+    }
+}
+
+/// Payload index on a collection field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadIndex {
+    /// Payload field to index (e.g., "category", "_danube_timestamp")
+    pub field: String,
+
+    /// Index type
+    #[serde(rename = "type")]
+    pub index_type: PayloadIndexType,
+}
+
+/// Payload index type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadIndexType {
+    /// Exact match on strings
+    Keyword,
+    /// Integer match and range
+    Integer,
+    /// Float range
+    Float,
+    /// Geo radius / bounding box (`{"lat", "lon"}` values)
+    Geo,
+    /// Full-text match
+    Text,
+    /// Boolean match
+    Bool,
+    /// Datetime range (RFC 3339 values)
+    Datetime,
+}
+
+impl PayloadIndexType {
+    pub fn to_qdrant(&self) -> qdrant_client::qdrant::FieldType {
+        match self {
+            PayloadIndexType::Keyword => qdrant_client::qdrant::FieldType::Keyword,
+            PayloadIndexType::Integer => qdrant_client::qdrant::FieldType::Integer,
+            PayloadIndexType::Float => qdrant_client::qdrant::FieldType::Float,
+            PayloadIndexType::Geo => qdrant_client::qdrant::FieldType::Geo,
+            PayloadIndexType::Text => qdrant_client::qdrant::FieldType::Text,
+            PayloadIndexType::Bool => qdrant_client::qdrant::FieldType::Bool,
+            PayloadIndexType::Datetime => qdrant_client::qdrant::FieldType::Datetime,
+        }
+    }
+}
+
+/// Embedding stage configuration: text field → vector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    /// Embedding provider: "openai", "http", "local", or "onnx"
+    pub provider: EmbeddingProvider,
+
+    /// Embedding model name (e.g., "text-embedding-3-small", "nomic-embed-text",
+    /// or "sentence-transformers/all-MiniLM-L6-v2" for onnx)
+    pub model: String,
+
+    /// Document field holding the text to embed (supports dotted paths, e.g., "doc.body")
+    pub text_field: String,
+
+    /// Provider endpoint (defaults: OpenAI API, or http://localhost:11434 for local;
+    /// required for http)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// API key sent as a bearer token (OpenAI falls back to OPENAI_API_KEY)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+
+    /// Timeout for embedding requests in seconds
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+
+    /// Directory holding downloaded onnx models (pre-populate it for air-gapped deployments)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
+}
+
+/// Embedding provider
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// OpenAI embeddings API (or an OpenAI-compatible endpoint)
+    OpenAI,
+    /// Generic HTTP endpoint: `{"model", "input": [...]}` → `{"embeddings": [[...]]}`
+    Http,
+    /// Locally hosted embedding server with the Ollama API (`/api/embed`)
+    Local,
+    /// In-process ONNX Runtime inference of a sentence-transformer model
+    /// (requires the `onnx` cargo feature)
+    Onnx,
 }
 
 fn default_distance() -> Distance {
@@ -138,6 +512,30 @@ fn default_subscription_type() -> SubscriptionType {
     SubscriptionType::Exclusive
 }
 
+fn default_vector_field() -> String {
+    "vector".to_string()
+}
+
+fn default_id_field() -> String {
+    "id".to_string()
+}
+
+fn default_payload_field() -> String {
+    "payload".to_string()
+}
+
+fn default_tenant_payload_key() -> String {
+    "tenant_id".to_string()
+}
+
+fn default_delete_op_field() -> String {
+    "op".to_string()
+}
+
+fn default_delete_op_value() -> String {
+    "delete".to_string()
+}
+
 /// Distance metric for vector similarity
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Distance {
@@ -162,6 +560,45 @@ impl Distance {
     }
 }
 
+/// Write ordering guarantee across replicas
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteOrdering {
+    /// Writes may be applied in any order across replicas
+    Weak,
+    /// Writes go through the shard leader (may be reordered during leader changes)
+    Medium,
+    /// Writes go through the consensus (strict order, slowest)
+    Strong,
+}
+
+impl WriteOrdering {
+    pub fn to_qdrant(&self) -> qdrant_client::qdrant::WriteOrdering {
+        let ordering = match self {
+            WriteOrdering::Weak => qdrant_client::qdrant::WriteOrderingType::Weak,
+            WriteOrdering::Medium => qdrant_client::qdrant::WriteOrderingType::Medium,
+            WriteOrdering::Strong => qdrant_client::qdrant::WriteOrderingType::Strong,
+        };
+        qdrant_client::qdrant::WriteOrdering {
+            r#type: ordering.into(),
+        }
+    }
+}
+
+/// Check that every `{placeholder}` of a collection template is closed and named
+fn is_valid_template(template: &str) -> bool {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        match rest[start + 1..].find(['{', '}']) {
+            Some(end) if end > 0 && rest[start + 1 + end..].starts_with('}') => {
+                rest = &rest[start + end + 2..];
+            }
+            _ => return false,
+        }
+    }
+    !rest.contains('}')
+}
+
 impl QdrantConfig {
     /// Validate the configuration
     pub fn validate(&self) -> ConnectorResult<()> {
@@ -171,6 +608,20 @@ impl QdrantConfig {
             ));
         }
 
+        if let Some(ca_path) = &self.tls_ca_path {
+            if !self.url.starts_with("https://") {
+                return Err(danube_connect_core::ConnectorError::config(
+                    "tls_ca_path requires an https:// Qdrant URL",
+                ));
+            }
+            if !std::path::Path::new(ca_path).is_file() {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "tls_ca_path '{}' is not a readable file",
+                    ca_path
+                )));
+            }
+        }
+
         if self.routes.is_empty() {
             return Err(danube_connect_core::ConnectorError::config(
                 "At least one route is required",
@@ -193,19 +644,197 @@ impl QdrantConfig {
                 )));
             }
 
-            if mapping.vector_dimension == 0 {
+            if mapping.is_dynamic() && !is_valid_template(&mapping.to) {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} has an invalid collection template '{}'",
+                    idx, mapping.to
+                )));
+            }
+
+            if mapping.vector_field.is_empty()
+                || mapping.id_field.is_empty()
+                || mapping.payload_field.is_empty()
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} has an empty 'vector_field', 'id_field', or 'payload_field'",
+                    idx
+                )));
+            }
+
+            if mapping.vectors.is_empty() && mapping.vector_dimension == 0 {
                 return Err(danube_connect_core::ConnectorError::config(format!(
                     "Topic mapping {} has zero vector dimension",
                     idx
                 )));
             }
 
+            if !mapping.vectors.is_empty() {
+                if mapping.vector_dimension != 0 {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} sets both 'vector_dimension' and named 'vectors'",
+                        idx
+                    )));
+                }
+                if mapping.embedding.is_some() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} embedding requires a single unnamed vector",
+                        idx
+                    )));
+                }
+                for (i, vector) in mapping.vectors.iter().enumerate() {
+                    if vector.name.is_empty() || vector.dimension == 0 {
+                        return Err(danube_connect_core::ConnectorError::config(format!(
+                            "Route {} has a named vector without name or dimension",
+                            idx
+                        )));
+                    }
+                    if mapping.vectors[..i].iter().any(|v| v.name == vector.name) {
+                        return Err(danube_connect_core::ConnectorError::config(format!(
+                            "Route {} declares vector '{}' twice",
+                            idx, vector.name
+                        )));
+                    }
+                }
+            }
+
+            if !mapping.sparse_vectors.is_empty() && mapping.embedding.is_some() {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} embedding can't be combined with sparse vectors",
+                    idx
+                )));
+            }
+            for (i, sparse) in mapping.sparse_vectors.iter().enumerate() {
+                if sparse.name.is_empty() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} has a sparse vector without name",
+                        idx
+                    )));
+                }
+                if mapping.sparse_vectors[..i]
+                    .iter()
+                    .any(|v| v.name == sparse.name)
+                    || mapping.vectors.iter().any(|v| v.name == sparse.name)
+                {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} declares vector '{}' twice",
+                        idx, sparse.name
+                    )));
+                }
+            }
+
             if mapping.subscription.is_empty() {
                 return Err(danube_connect_core::ConnectorError::config(format!(
                     "Topic mapping {} has empty subscription",
                     idx
                 )));
             }
+
+            if let Some(Quantization::Scalar {
+                quantile: Some(quantile),
+                ..
+            }) = &mapping.quantization
+            {
+                if !(0.5..=1.0).contains(quantile) {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} scalar quantization quantile must be between 0.5 and 1.0",
+                        idx
+                    )));
+                }
+            }
+
+            if [
+                mapping.shard_number,
+                mapping.replication_factor,
+                mapping.write_consistency_factor,
+            ]
+            .contains(&Some(0))
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} shard_number, replication_factor and write_consistency_factor must be at least 1",
+                    idx
+                )));
+            }
+
+            if mapping.write_consistency_factor.unwrap_or(1)
+                > mapping.replication_factor.unwrap_or(1)
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} write_consistency_factor cannot exceed replication_factor",
+                    idx
+                )));
+            }
+
+            if mapping.memmap_threshold_kb == Some(0) {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} memmap_threshold_kb must be at least 1",
+                    idx
+                )));
+            }
+
+            if let Some(ef_construct) = mapping.hnsw.as_ref().and_then(|hnsw| hnsw.ef_construct) {
+                if ef_construct < 4 {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} hnsw ef_construct must be at least 4",
+                        idx
+                    )));
+                }
+            }
+
+            if mapping
+                .payload_indexes
+                .iter()
+                .any(|index| index.field.is_empty())
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route {} has a payload index with empty 'field'",
+                    idx
+                )));
+            }
+
+            if let Some(tenant) = &mapping.tenant {
+                if tenant.field.is_empty() || tenant.payload_key.is_empty() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} tenant requires non-empty 'field' and 'payload_key'",
+                        idx
+                    )));
+                }
+            }
+
+            if let Some(delete) = &mapping.delete {
+                if delete.op_field.is_empty() || delete.op_value.is_empty() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} delete requires non-empty 'op_field' and 'op_value'",
+                        idx
+                    )));
+                }
+                if delete.on_null_vector && mapping.embedding.is_some() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} embeds documents without vectors, 'on_null_vector' can't be used",
+                        idx
+                    )));
+                }
+            }
+
+            if let Some(embedding) = &mapping.embedding {
+                if embedding.model.is_empty() || embedding.text_field.is_empty() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} embedding requires 'model' and 'text_field'",
+                        idx
+                    )));
+                }
+                if embedding.provider == EmbeddingProvider::Http && embedding.url.is_none() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} embedding provider 'http' requires 'url'",
+                        idx
+                    )));
+                }
+                if embedding.provider == EmbeddingProvider::OpenAI && embedding.api_key.is_none() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route {} embedding provider 'openai' requires 'api_key' or OPENAI_API_KEY",
+                        idx
+                    )));
+                }
+            }
         }
 
         Ok(())
@@ -234,6 +863,7 @@ mod tests {
         let mut config = QdrantConfig {
             url: "http://localhost:6334".to_string(),
             api_key: None,
+            tls_ca_path: None,
             routes: vec![TopicMapping {
                 from: "/default/vectors".to_string(),
                 subscription: "qdrant-sink-sub".to_string(),
@@ -243,13 +873,50 @@ mod tests {
                 distance: Distance::Cosine,
                 auto_create_collection: true,
                 include_danube_metadata: true,
+                wait: false,
+                write_ordering: None,
+                vector_field: default_vector_field(),
+                id_field: default_id_field(),
+                payload_field: default_payload_field(),
                 expected_schema_subject: None,
+                vectors: vec![],
+                sparse_vectors: vec![],
+                on_disk: false,
+                on_disk_payload: false,
+                memmap_threshold_kb: None,
+                hnsw: None,
+                quantization: None,
+                shard_number: None,
+                replication_factor: None,
+                write_consistency_factor: None,
+                payload_indexes: vec![],
+                tenant: None,
+                delete: None,
+                embedding: None,
             }],
             timeout_secs: 30,
         };
 
         assert!(config.validate().is_ok());
 
+        // Writes can't wait for more replicas than exist
+        config.routes[0].replication_factor = Some(2);
+        config.routes[0].write_consistency_factor = Some(3);
+        assert!(config.validate().is_err());
+        config.routes[0].write_consistency_factor = Some(2);
+        assert!(config.validate().is_ok());
+        config.routes[0].shard_number = Some(0);
+        assert!(config.validate().is_err());
+        config.routes[0].shard_number = Some(3);
+        assert!(config.validate().is_ok());
+
+        // A custom CA needs TLS and an existing file
+        config.tls_ca_path = Some("/nonexistent/ca.pem".to_string());
+        assert!(config.validate().is_err());
+        config.url = "https://qdrant.internal:6334".to_string();
+        assert!(config.validate().is_err());
+        config.tls_ca_path = None;
+
         // Test empty URL
         config.url = "".to_string();
         assert!(config.validate().is_err());
@@ -260,6 +927,112 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_embedding_validation() {
+        let mut config = QdrantConfig {
+            url: "http://localhost:6334".to_string(),
+            api_key: None,
+            tls_ca_path: None,
+            routes: vec![serde_json::from_value(serde_json::json!({
+                "from": "/default/docs",
+                "subscription": "qdrant-sink-sub",
+                "to": "docs",
+                "vector_dimension": 768,
+                "embedding": {
+                    "provider": "local",
+                    "model": "nomic-embed-text",
+                    "text_field": "text"
+                }
+            }))
+            .unwrap()],
+            timeout_secs: 30,
+        };
+        assert!(config.validate().is_ok());
+
+        // OpenAI needs an API key
+        let embedding = config.routes[0].embedding.as_mut().unwrap();
+        embedding.provider = EmbeddingProvider::OpenAI;
+        assert!(config.validate().is_err());
+
+        // HTTP needs an endpoint
+        let embedding = config.routes[0].embedding.as_mut().unwrap();
+        embedding.provider = EmbeddingProvider::Http;
+        assert!(config.validate().is_err());
+        config.routes[0].embedding.as_mut().unwrap().url =
+            Some("http://embedder:8080/embed".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_named_vectors_validation() {
+        let mut config = QdrantConfig {
+            url: "http://localhost:6334".to_string(),
+            api_key: None,
+            tls_ca_path: None,
+            routes: vec![serde_json::from_value(serde_json::json!({
+                "from": "/default/docs",
+                "subscription": "qdrant-sink-sub",
+                "to": "docs",
+                "vectors": [
+                    {"name": "title_vec", "dimension": 384},
+                    {"name": "body_vec", "dimension": 768, "distance": "Dot"}
+                ]
+            }))
+            .unwrap()],
+            timeout_secs: 30,
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.routes[0].vectors[0].distance, Distance::Cosine);
+
+        // Duplicate names
+        config.routes[0].vectors[1].name = "title_vec".to_string();
+        assert!(config.validate().is_err());
+        config.routes[0].vectors[1].name = "body_vec".to_string();
+
+        // Sparse vectors share the namespace of dense vectors
+        config.routes[0].sparse_vectors = vec![SparseVector {
+            name: "title_vec".to_string(),
+            idf: false,
+        }];
+        assert!(config.validate().is_err());
+        config.routes[0].sparse_vectors[0].name = "text_sparse".to_string();
+        assert!(config.validate().is_ok());
+
+        // Either named vectors or a single vector dimension
+        config.routes[0].vector_dimension = 384;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_collection_template() {
+        assert!(is_valid_template("docs_{tenant}"));
+        assert!(is_valid_template("{tenant}_{meta.region}"));
+        assert!(!is_valid_template("docs_{}"));
+        assert!(!is_valid_template("docs_{tenant"));
+        assert!(!is_valid_template("docs_{{tenant}}"));
+        assert!(!is_valid_template("docs_tenant}"));
+    }
+
+    #[test]
+    fn test_write_options_parsing() {
+        let mapping: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/vectors",
+            "subscription": "sub",
+            "to": "vectors",
+            "vector_dimension": 384,
+            "wait": true,
+            "write_ordering": "medium"
+        }))
+        .unwrap();
+
+        assert!(mapping.wait);
+        assert_eq!(mapping.write_ordering, Some(WriteOrdering::Medium));
+        assert_eq!(
+            mapping.write_ordering.unwrap().to_qdrant().r#type,
+            i32::from(qdrant_client::qdrant::WriteOrderingType::Medium)
+        );
+    }
+
     #[test]
     fn test_distance_conversion() {
         assert_eq!(
@@ -271,4 +1044,59 @@ mod tests {
             qdrant_client::qdrant::Distance::Euclid
         );
     }
+
+    #[test]
+    fn test_quantization_parsing() {
+        let mapping: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/vectors",
+            "subscription": "sub",
+            "to": "vectors",
+            "vector_dimension": 1536,
+            "on_disk": true,
+            "memmap_threshold_kb": 20000,
+            "hnsw": {"m": 32, "ef_construct": 200},
+            "quantization": {"type": "product", "compression": "x16", "always_ram": true}
+        }))
+        .unwrap();
+
+        assert!(mapping.on_disk);
+        assert!(!mapping.on_disk_payload);
+        assert_eq!(mapping.memmap_threshold_kb, Some(20000));
+
+        let hnsw = mapping.hnsw.as_ref().unwrap().to_qdrant();
+        assert_eq!(hnsw.m, Some(32));
+        assert_eq!(hnsw.ef_construct, Some(200));
+        assert_eq!(hnsw.on_disk, None);
+
+        let quantization = mapping.quantization.as_ref().unwrap().to_qdrant();
+        assert!(matches!(
+            quantization.quantization,
+            Some(qdrant_client::qdrant::quantization_config::Quantization::Product(_))
+        ));
+
+        let scalar: Quantization =
+            serde_json::from_value(serde_json::json!({"type": "scalar", "quantile": 0.99}))
+                .unwrap();
+        assert!(matches!(
+            scalar,
+            Quantization::Scalar {
+                quantile: Some(_),
+                always_ram: false
+            }
+        ));
+    }
+
+    #[test]
+    fn test_payload_index_parsing() {
+        let index: PayloadIndex =
+            serde_json::from_value(serde_json::json!({"field": "category", "type": "keyword"}))
+                .unwrap();
+
+        assert_eq!(index.field, "category");
+        assert_eq!(index.index_type, PayloadIndexType::Keyword);
+        assert_eq!(
+            index.index_type.to_qdrant(),
+            qdrant_client::qdrant::FieldType::Keyword
+        );
+    }
 }
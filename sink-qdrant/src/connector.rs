@@ -1,15 +1,25 @@
 //! Qdrant sink connector implementation
 
+use crate::client::QdrantClient;
 use crate::config::{QdrantConfig, TopicMapping};
-use crate::record::transform_to_point;
+use crate::embedding::{extract_text, Embedder};
+use crate::record::{
+    delete_point_id, document_to_point, resolve_collection, resolve_tenant, transform_to_point,
+};
 use async_trait::async_trait;
 use danube_connect_core::{
     ConnectorConfig, ConnectorError, ConnectorResult, ConsumerConfig, SinkConnector, SinkRecord,
 };
 use qdrant_client::qdrant::PointStruct;
-use qdrant_client::qdrant::{CreateCollectionBuilder, UpsertPointsBuilder};
-use qdrant_client::Qdrant;
-use std::collections::HashMap;
+use qdrant_client::qdrant::{
+    payload_index_params, CreateCollectionBuilder, CreateFieldIndexCollectionBuilder,
+    CreateShardKeyBuilder, CreateShardKeyRequestBuilder, DeletePointsBuilder, FieldType,
+    KeywordIndexParams, Modifier, OptimizersConfigDiffBuilder, PayloadIndexParams, PointsIdsList,
+    ShardKey, ShardKeySelector, ShardingMethod, SparseIndexConfigBuilder,
+    SparseVectorParamsBuilder, SparseVectorsConfigBuilder, UpsertPointsBuilder,
+    VectorParamsBuilder, VectorsConfig, VectorsConfigBuilder,
+};
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, info};
 
 /// Qdrant Sink Connector
@@ -19,24 +29,73 @@ use tracing::{debug, info};
 struct CollectionContext {
     /// Topic mapping configuration for this collection
     mapping: TopicMapping,
+    /// Embedding client, if the route generates vectors from text
+    embedder: Option<Embedder>,
+    /// Collections known to exist (templated routes add theirs on first use)
+    ready_collections: HashSet<String>,
+    /// Shard keys known to exist, per collection (tenant shard keys are created on first use)
+    ready_shard_keys: HashSet<(String, String)>,
     /// Statistics
     points_inserted: u64,
+    points_deleted: u64,
     batches_flushed: u64,
 }
 
 impl CollectionContext {
-    fn new(mapping: TopicMapping) -> Self {
-        Self {
+    fn new(mapping: TopicMapping) -> ConnectorResult<Self> {
+        let embedder = mapping.embedding.clone().map(Embedder::new).transpose()?;
+
+        Ok(Self {
             mapping,
+            embedder,
+            ready_collections: HashSet::new(),
+            ready_shard_keys: HashSet::new(),
             points_inserted: 0,
+            points_deleted: 0,
             batches_flushed: 0,
-        }
+        })
+    }
+
+    /// Convert a topic's records into Qdrant points
+    ///
+    /// Routes with an embedding stage embed the whole batch's texts in one request.
+    async fn to_points(&self, records: &[SinkRecord]) -> ConnectorResult<Vec<PointStruct>> {
+        let Some(embedder) = &self.embedder else {
+            return records
+                .iter()
+                .map(|record| transform_to_point(record, &self.mapping))
+                .collect();
+        };
+
+        let texts = records
+            .iter()
+            .map(|record| {
+                extract_text(record.payload(), embedder.text_field()).ok_or_else(|| {
+                    ConnectorError::invalid_data(
+                        format!(
+                            "Document from topic {} has no text in field '{}'",
+                            record.topic(),
+                            embedder.text_field()
+                        ),
+                        vec![],
+                    )
+                })
+            })
+            .collect::<ConnectorResult<Vec<_>>>()?;
+
+        let vectors = embedder.embed(&texts).await?;
+
+        records
+            .iter()
+            .zip(vectors)
+            .map(|(record, vector)| document_to_point(record, vector, &self.mapping))
+            .collect()
     }
 }
 
 pub struct QdrantSinkConnector {
     config: QdrantConfig,
-    client: Option<Qdrant>,
+    client: Option<QdrantClient>,
     /// Collection contexts keyed by Danube topic
     collections: HashMap<String, CollectionContext>,
 }
@@ -57,6 +116,7 @@ impl QdrantSinkConnector {
             config: QdrantConfig {
                 url: String::new(),
                 api_key: None,
+                tls_ca_path: None,
                 routes: vec![],
                 timeout_secs: 30,
             },
@@ -69,6 +129,8 @@ impl QdrantSinkConnector {
     async fn flush_batch(
         &mut self,
         topic: &str,
+        collection: &str,
+        shard_key: Option<&str>,
         points_to_insert: Vec<PointStruct>,
     ) -> ConnectorResult<()> {
         let context = self.collections.get_mut(topic).ok_or_else(|| {
@@ -88,33 +150,190 @@ impl QdrantSinkConnector {
 
         info!(
             "Flushing batch of {} points to Qdrant collection '{}' (topic: {})",
-            count, context.mapping.to, topic
+            count, collection, topic
         );
 
         // Upsert points to Qdrant
-        client
-            .upsert_points(UpsertPointsBuilder::new(
-                &context.mapping.to,
-                points_to_insert,
-            ))
-            .await
-            .map_err(|e| {
-                ConnectorError::retryable(format!("Failed to upsert points to Qdrant: {}", e))
-            })?;
+        let mut upsert =
+            UpsertPointsBuilder::new(collection, points_to_insert).wait(context.mapping.wait);
+        if let Some(ordering) = context.mapping.write_ordering {
+            upsert = upsert.ordering(ordering.to_qdrant());
+        }
+        if let Some(shard_key) = shard_key {
+            upsert = upsert.shard_key_selector(shard_key_selector(shard_key));
+        }
+
+        client.upsert_points(upsert).await.map_err(|e| {
+            ConnectorError::retryable(format!("Failed to upsert points to Qdrant: {}", e))
+        })?;
 
         context.points_inserted += count as u64;
         context.batches_flushed += 1;
 
         info!(
             "Successfully inserted {} points to '{}' (total: {}, batches: {})",
-            count, context.mapping.to, context.points_inserted, context.batches_flushed
+            count, collection, context.points_inserted, context.batches_flushed
+        );
+
+        Ok(())
+    }
+
+    /// Transform a run of upsert records and flush them to the collection
+    async fn upsert_records(
+        &mut self,
+        topic: &str,
+        collection: &str,
+        shard_key: Option<&str>,
+        records: Vec<SinkRecord>,
+    ) -> ConnectorResult<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let context = self.collections.get(topic).ok_or_else(|| {
+            ConnectorError::fatal(format!("No collection context found for topic: {}", topic))
+        })?;
+
+        let points = context.to_points(&records).await?;
+
+        debug!(
+            "Transformed {} messages from topic {} into Qdrant points for collection '{}'",
+            points.len(),
+            topic,
+            collection
+        );
+
+        self.flush_batch(topic, collection, shard_key, points).await
+    }
+
+    /// Delete points from a collection of a specific topic
+    async fn delete_points(
+        &mut self,
+        topic: &str,
+        collection: &str,
+        shard_key: Option<&str>,
+        point_ids: Vec<u64>,
+    ) -> ConnectorResult<()> {
+        if point_ids.is_empty() {
+            return Ok(());
+        }
+
+        let context = self.collections.get_mut(topic).ok_or_else(|| {
+            ConnectorError::fatal(format!("No collection context found for topic: {}", topic))
+        })?;
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("Qdrant client not initialized"))?;
+
+        let count = point_ids.len();
+
+        let mut delete = DeletePointsBuilder::new(collection)
+            .points(PointsIdsList {
+                ids: point_ids.into_iter().map(Into::into).collect(),
+            })
+            .wait(context.mapping.wait);
+        if let Some(ordering) = context.mapping.write_ordering {
+            delete = delete.ordering(ordering.to_qdrant());
+        }
+        if let Some(shard_key) = shard_key {
+            delete = delete.shard_key_selector(shard_key_selector(shard_key));
+        }
+
+        client.delete_points(delete).await.map_err(|e| {
+            ConnectorError::retryable(format!("Failed to delete points from Qdrant: {}", e))
+        })?;
+
+        context.points_deleted += count as u64;
+
+        info!(
+            "Deleted {} points from '{}' (total: {})",
+            count, collection, context.points_deleted
+        );
+
+        Ok(())
+    }
+
+    /// Ensure a resolved collection of a topic exists before writing to it
+    ///
+    /// Each collection is checked (and created if needed) once; this is how
+    /// templated routes create their collections lazily.
+    async fn prepare_collection(&mut self, topic: &str, collection: &str) -> ConnectorResult<()> {
+        let context = self.collections.get(topic).ok_or_else(|| {
+            ConnectorError::fatal(format!("No collection context found for topic: {}", topic))
+        })?;
+
+        if context.ready_collections.contains(collection) {
+            return Ok(());
+        }
+
+        self.ensure_collection(&context.mapping, collection).await?;
+
+        if let Some(context) = self.collections.get_mut(topic) {
+            context.ready_collections.insert(collection.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a tenant's shard key exists on a collection before writing to it
+    async fn prepare_shard_key(
+        &mut self,
+        topic: &str,
+        collection: &str,
+        shard_key: &str,
+    ) -> ConnectorResult<()> {
+        let context = self.collections.get(topic).ok_or_else(|| {
+            ConnectorError::fatal(format!("No collection context found for topic: {}", topic))
+        })?;
+
+        let key = (collection.to_string(), shard_key.to_string());
+        if context.ready_shard_keys.contains(&key) {
+            return Ok(());
+        }
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("Qdrant client not initialized"))?;
+
+        let request = CreateShardKeyRequestBuilder::new(collection).request(
+            CreateShardKeyBuilder::default().shard_key(ShardKey::from(shard_key.to_string())),
         );
+        match client.create_shard_key(request).await {
+            Ok(_) => info!(
+                "Created shard key '{}' on collection '{}'",
+                shard_key, collection
+            ),
+            // Created earlier (e.g., by a previous run)
+            Err(e) if e.to_string().contains("already exists") => {
+                debug!(
+                    "Shard key '{}' already exists on '{}'",
+                    shard_key, collection
+                )
+            }
+            Err(e) => {
+                return Err(ConnectorError::retryable(format!(
+                    "Failed to create shard key '{}' on collection '{}': {}",
+                    shard_key, collection, e
+                )))
+            }
+        }
+
+        if let Some(context) = self.collections.get_mut(topic) {
+            context.ready_shard_keys.insert(key);
+        }
 
         Ok(())
     }
 
-    /// Ensure collection exists for a specific mapping, create if needed
-    async fn ensure_collection(&self, mapping: &TopicMapping) -> ConnectorResult<()> {
+    /// Ensure a mapping's collection exists, create if needed
+    async fn ensure_collection(
+        &self,
+        mapping: &TopicMapping,
+        collection: &str,
+    ) -> ConnectorResult<()> {
         let client = self
             .client
             .as_ref()
@@ -126,51 +345,205 @@ impl QdrantSinkConnector {
             .await
             .map_err(|e| ConnectorError::fatal(format!("Failed to list collections: {}", e)))?;
 
-        let collection_exists = collections.collections.iter().any(|c| c.name == mapping.to);
+        let collection_exists = collections.collections.iter().any(|c| c.name == collection);
 
         if collection_exists {
             info!(
                 "Collection '{}' already exists (topic: {})",
-                mapping.to, mapping.from
+                collection, mapping.from
             );
-            return Ok(());
-        }
-
-        if !mapping.auto_create_collection {
+        } else if !mapping.auto_create_collection {
             return Err(ConnectorError::fatal(format!(
                 "Collection '{}' does not exist and auto_create_collection is disabled",
-                mapping.to
+                collection
             )));
+        } else {
+            Self::create_collection(client, mapping, collection).await?;
         }
 
-        // Create collection
-        info!(
-            "Creating collection '{}' with dimension {} and distance metric {:?} (topic: {})",
-            mapping.to, mapping.vector_dimension, mapping.distance, mapping.from
-        );
+        Self::ensure_payload_indexes(client, mapping, collection).await
+    }
 
-        let vectors_config = qdrant_client::qdrant::VectorParamsBuilder::new(
-            mapping.vector_dimension as u64,
-            mapping.distance.to_qdrant(),
-        )
-        .build();
+    /// Create the collection for a mapping
+    async fn create_collection(
+        client: &QdrantClient,
+        mapping: &TopicMapping,
+        collection: &str,
+    ) -> ConnectorResult<()> {
+        // Create collection
+        let vectors_config: VectorsConfig = if mapping.vectors.is_empty() {
+            info!(
+                "Creating collection '{}' with dimension {} and distance metric {:?} (topic: {})",
+                collection, mapping.vector_dimension, mapping.distance, mapping.from
+            );
 
-        client
-            .create_collection(
-                CreateCollectionBuilder::new(&mapping.to).vectors_config(vectors_config),
+            VectorParamsBuilder::new(
+                mapping.vector_dimension as u64,
+                mapping.distance.to_qdrant(),
             )
-            .await
-            .map_err(|e| {
-                ConnectorError::fatal(format!(
-                    "Failed to create collection '{}': {}",
-                    mapping.to, e
-                ))
-            })?;
+            .on_disk(mapping.on_disk)
+            .build()
+            .into()
+        } else {
+            let mut vectors_config = VectorsConfigBuilder::default();
+            for vector in &mapping.vectors {
+                info!(
+                    "Creating collection '{}' with vector '{}' (dimension {}, distance metric {:?}, topic: {})",
+                    collection, vector.name, vector.dimension, vector.distance, mapping.from
+                );
+                vectors_config.add_named_vector_params(
+                    &vector.name,
+                    VectorParamsBuilder::new(vector.dimension as u64, vector.distance.to_qdrant())
+                        .on_disk(mapping.on_disk),
+                );
+            }
+            vectors_config.into()
+        };
+
+        let mut builder = CreateCollectionBuilder::new(collection)
+            .vectors_config(vectors_config)
+            .on_disk_payload(mapping.on_disk_payload);
+        if !mapping.sparse_vectors.is_empty() {
+            let mut sparse_config = SparseVectorsConfigBuilder::default();
+            for sparse in &mapping.sparse_vectors {
+                info!(
+                    "Creating collection '{}' with sparse vector '{}' (idf: {}, topic: {})",
+                    collection, sparse.name, sparse.idf, mapping.from
+                );
+                let mut params = SparseVectorParamsBuilder::default()
+                    .index(SparseIndexConfigBuilder::default().on_disk(mapping.on_disk));
+                if sparse.idf {
+                    params = params.modifier(Modifier::Idf);
+                }
+                sparse_config.add_named_vector_params(&sparse.name, params);
+            }
+            builder = builder.sparse_vectors_config(sparse_config);
+        }
+        if let Some(hnsw) = &mapping.hnsw {
+            builder = builder.hnsw_config(hnsw.to_qdrant());
+        }
+        if let Some(memmap_threshold_kb) = mapping.memmap_threshold_kb {
+            builder = builder.optimizers_config(
+                OptimizersConfigDiffBuilder::default().memmap_threshold(memmap_threshold_kb),
+            );
+        }
+        if let Some(quantization) = &mapping.quantization {
+            info!(
+                "Collection '{}' uses {:?} quantization",
+                collection, quantization
+            );
+            builder = builder.quantization_config(quantization.to_qdrant());
+        }
+        if mapping
+            .tenant
+            .as_ref()
+            .is_some_and(|tenant| tenant.shard_key)
+        {
+            info!("Collection '{}' uses custom sharding by tenant", collection);
+            builder = builder.sharding_method(ShardingMethod::Custom.into());
+        }
+        if let Some(shard_number) = mapping.shard_number {
+            builder = builder.shard_number(shard_number);
+        }
+        if let Some(replication_factor) = mapping.replication_factor {
+            builder = builder.replication_factor(replication_factor);
+        }
+        if let Some(write_consistency_factor) = mapping.write_consistency_factor {
+            builder = builder.write_consistency_factor(write_consistency_factor);
+        }
+
+        client.create_collection(builder).await.map_err(|e| {
+            ConnectorError::fatal(format!(
+                "Failed to create collection '{}': {}",
+                collection, e
+            ))
+        })?;
 
-        info!("Collection '{}' created successfully", mapping.to);
+        info!("Collection '{}' created successfully", collection);
 
         Ok(())
     }
+
+    /// Create the mapping's payload indexes (existing indexes are left as they are)
+    async fn ensure_payload_indexes(
+        client: &QdrantClient,
+        mapping: &TopicMapping,
+        collection: &str,
+    ) -> ConnectorResult<()> {
+        for index in &mapping.payload_indexes {
+            client
+                .create_field_index(
+                    CreateFieldIndexCollectionBuilder::new(
+                        collection,
+                        &index.field,
+                        index.index_type.to_qdrant(),
+                    )
+                    .wait(true),
+                )
+                .await
+                .map_err(|e| {
+                    ConnectorError::fatal(format!(
+                        "Failed to create {:?} payload index on '{}.{}': {}",
+                        index.index_type, collection, index.field, e
+                    ))
+                })?;
+
+            info!(
+                "Payload index on '{}.{}' ({:?}) ready",
+                collection, index.field, index.index_type
+            );
+        }
+
+        // Tenant-aware keyword index, so Qdrant co-locates each tenant's points
+        if let Some(tenant) = &mapping.tenant {
+            if !mapping
+                .payload_indexes
+                .iter()
+                .any(|index| index.field == tenant.payload_key)
+            {
+                let params = PayloadIndexParams {
+                    index_params: Some(payload_index_params::IndexParams::KeywordIndexParams(
+                        KeywordIndexParams {
+                            is_tenant: Some(true),
+                            ..Default::default()
+                        },
+                    )),
+                };
+                client
+                    .create_field_index(
+                        CreateFieldIndexCollectionBuilder::new(
+                            collection,
+                            &tenant.payload_key,
+                            FieldType::Keyword,
+                        )
+                        .field_index_params(params)
+                        .wait(true),
+                    )
+                    .await
+                    .map_err(|e| {
+                        ConnectorError::fatal(format!(
+                            "Failed to create tenant index on '{}.{}': {}",
+                            collection, tenant.payload_key, e
+                        ))
+                    })?;
+
+                info!(
+                    "Tenant index on '{}.{}' ready",
+                    collection, tenant.payload_key
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Route a write to a single tenant shard
+fn shard_key_selector(shard_key: &str) -> ShardKeySelector {
+    ShardKeySelector {
+        shard_keys: vec![ShardKey::from(shard_key.to_string())],
+        ..Default::default()
+    }
 }
 
 impl Default for QdrantSinkConnector {
@@ -194,9 +567,10 @@ impl SinkConnector for QdrantSinkConnector {
         );
 
         // Create Qdrant client
-        let client_config = self.config.qdrant_client_config();
-        let client = Qdrant::new(client_config)
-            .map_err(|e| ConnectorError::fatal(format!("Failed to create Qdrant client: {}", e)))?;
+        let client = QdrantClient::new(&self.config)?;
+        if let Some(ca_path) = &self.config.tls_ca_path {
+            info!("Trusting CA certificates from {} for Qdrant TLS", ca_path);
+        }
 
         // Test connection by listing collections
         client
@@ -215,11 +589,26 @@ impl SinkConnector for QdrantSinkConnector {
                 mapping.to, mapping.from, mapping.vector_dimension, mapping.distance
             );
 
-            // Ensure collection exists
-            self.ensure_collection(mapping).await?;
-
             // Create collection context
-            let context = CollectionContext::new(mapping.clone());
+            let mut context = CollectionContext::new(mapping.clone())?;
+
+            // Ensure collection exists; templated collections are created on first use
+            if mapping.is_dynamic() {
+                info!(
+                    "Route '{}' resolves collection '{}' per message",
+                    mapping.from, mapping.to
+                );
+            } else {
+                self.ensure_collection(mapping, &mapping.to).await?;
+                context.ready_collections.insert(mapping.to.clone());
+            }
+
+            if let Some(embedding) = &mapping.embedding {
+                info!(
+                    "Route '{}' embeds field '{}' with {:?} model '{}'",
+                    mapping.from, embedding.text_field, embedding.provider, embedding.model
+                );
+            }
 
             self.collections.insert(mapping.from.clone(), context);
         }
@@ -252,7 +641,13 @@ impl SinkConnector for QdrantSinkConnector {
     }
 
     async fn process_batch(&mut self, records: Vec<SinkRecord>) -> ConnectorResult<()> {
-        let mut batches: HashMap<String, Vec<PointStruct>> = HashMap::new();
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        // Records grouped by topic, collection, and tenant shard key (if sharded by tenant)
+        let mut batches: HashMap<(String, String, Option<String>), Vec<SinkRecord>> =
+            HashMap::new();
 
         for record in records {
             let topic = record.topic().to_string();
@@ -263,24 +658,66 @@ impl SinkConnector for QdrantSinkConnector {
                     vec![],
                 )
             })?;
-
-            let point = transform_to_point(
-                &record,
-                context.mapping.vector_dimension,
-                context.mapping.include_danube_metadata,
-            )?;
-
-            debug!(
-                "Transformed message from topic {} into Qdrant point for collection '{}'",
-                record.topic(),
-                context.mapping.to
-            );
-
-            batches.entry(topic).or_default().push(point);
+            let collection = resolve_collection(&record, &context.mapping)?;
+            let shard_key = match &context.mapping.tenant {
+                Some(tenant) if tenant.shard_key => resolve_tenant(&record, &context.mapping)?,
+                _ => None,
+            };
+
+            batches
+                .entry((topic, collection, shard_key))
+                .or_default()
+                .push(record);
         }
 
-        for (topic, points) in batches {
-            self.flush_batch(&topic, points).await?;
+        for ((topic, collection, shard_key), records) in batches {
+            self.prepare_collection(&topic, &collection).await?;
+            if let Some(shard_key) = &shard_key {
+                self.prepare_shard_key(&topic, &collection, shard_key)
+                    .await?;
+            }
+            let shard_key = shard_key.as_deref();
+
+            // Apply upserts and deletes in message order, one request per consecutive run
+            let mut upserts = Vec::new();
+            let mut deletes = Vec::new();
+
+            for record in records {
+                let context = self.collections.get(&topic).ok_or_else(|| {
+                    ConnectorError::fatal(format!(
+                        "No collection context found for topic: {}",
+                        topic
+                    ))
+                })?;
+
+                match delete_point_id(&record, &context.mapping)? {
+                    Some(point_id) => {
+                        self.upsert_records(
+                            &topic,
+                            &collection,
+                            shard_key,
+                            std::mem::take(&mut upserts),
+                        )
+                        .await?;
+                        deletes.push(point_id);
+                    }
+                    None => {
+                        self.delete_points(
+                            &topic,
+                            &collection,
+                            shard_key,
+                            std::mem::take(&mut deletes),
+                        )
+                        .await?;
+                        upserts.push(record);
+                    }
+                }
+            }
+
+            self.upsert_records(&topic, &collection, shard_key, upserts)
+                .await?;
+            self.delete_points(&topic, &collection, shard_key, deletes)
+                .await?;
         }
 
         Ok(())
@@ -295,8 +732,12 @@ impl SinkConnector for QdrantSinkConnector {
 
         for (topic, context) in &self.collections {
             info!(
-                "Collection '{}' (topic: {}): {} points inserted, {} batches flushed",
-                context.mapping.to, topic, context.points_inserted, context.batches_flushed
+                "Collection '{}' (topic: {}): {} points inserted, {} deleted, {} batches flushed",
+                context.mapping.to,
+                topic,
+                context.points_inserted,
+                context.points_deleted,
+                context.batches_flushed
             );
             total_points += context.points_inserted;
             total_batches += context.batches_flushed;
@@ -347,17 +788,38 @@ mod tests {
             subscription_type: SubscriptionType::Exclusive,
             to: "test_collection".to_string(),
             vector_dimension: 384,
+            vectors: vec![],
+            sparse_vectors: vec![],
             distance: Distance::Cosine,
+            on_disk: false,
+            on_disk_payload: false,
+            memmap_threshold_kb: None,
+            hnsw: None,
+            quantization: None,
+            shard_number: None,
+            replication_factor: None,
+            write_consistency_factor: None,
+            payload_indexes: vec![],
+            tenant: None,
+            delete: None,
             auto_create_collection: true,
             include_danube_metadata: true,
+            wait: false,
+            write_ordering: None,
+            vector_field: "vector".to_string(),
+            id_field: "id".to_string(),
+            payload_field: "payload".to_string(),
             expected_schema_subject: None,
+            embedding: None,
         };
 
-        let context = CollectionContext::new(mapping.clone());
+        let context = CollectionContext::new(mapping.clone()).unwrap();
 
         assert_eq!(context.mapping.from, mapping.from);
         assert_eq!(context.mapping.to, mapping.to);
         assert_eq!(context.points_inserted, 0);
         assert_eq!(context.batches_flushed, 0);
+        assert_eq!(context.points_deleted, 0);
+        assert!(context.embedder.is_none());
     }
 }
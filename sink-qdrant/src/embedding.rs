@@ -0,0 +1,307 @@
+//! Embedding generation for routes consuming plain text documents
+//!
+//! Converts a batch of texts into vectors with a single request to the route's
+//! embedding provider (OpenAI, a generic HTTP endpoint, or a locally hosted
+//! Ollama-compatible server), or in-process with ONNX Runtime (`onnx` feature).
+
+use crate::config::{EmbeddingConfig, EmbeddingProvider};
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use serde::Deserialize;
+use serde_json::json;
+#[cfg(feature = "onnx")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::debug;
+
+const OPENAI_URL: &str = "https://api.openai.com/v1/embeddings";
+const LOCAL_URL: &str = "http://localhost:11434";
+
+/// Client for a route's embedding provider
+pub struct Embedder {
+    config: EmbeddingConfig,
+    client: reqwest::Client,
+    /// In-process model of the onnx provider
+    #[cfg(feature = "onnx")]
+    model: Option<Arc<Mutex<fastembed::TextEmbedding>>>,
+}
+
+/// OpenAI response: `{"data": [{"index": 0, "embedding": [...]}]}`
+#[derive(Deserialize)]
+struct OpenAIResponse {
+    data: Vec<OpenAIEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAIEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Generic HTTP and Ollama response: `{"embeddings": [[...]]}`
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl Embedder {
+    /// Create an embedder for a route's embedding configuration
+    pub fn new(config: EmbeddingConfig) -> ConnectorResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| {
+                ConnectorError::fatal(format!("Failed to create embedding client: {}", e))
+            })?;
+
+        #[cfg(feature = "onnx")]
+        let model = match config.provider {
+            EmbeddingProvider::Onnx => Some(Arc::new(Mutex::new(load_onnx_model(&config)?))),
+            _ => None,
+        };
+        #[cfg(not(feature = "onnx"))]
+        if config.provider == EmbeddingProvider::Onnx {
+            return Err(ConnectorError::config(
+                "Embedding provider 'onnx' requires the connector built with the 'onnx' feature",
+            ));
+        }
+
+        Ok(Self {
+            config,
+            client,
+            #[cfg(feature = "onnx")]
+            model,
+        })
+    }
+
+    /// Document field holding the text to embed
+    pub fn text_field(&self) -> &str {
+        &self.config.text_field
+    }
+
+    /// Embed a batch of texts, returning one vector per text in order
+    pub async fn embed(&self, texts: &[String]) -> ConnectorResult<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let embeddings = match self.config.provider {
+            EmbeddingProvider::Onnx => self.embed_onnx(texts).await?,
+            _ => self.embed_http(texts).await?,
+        };
+
+        if embeddings.len() != texts.len() {
+            return Err(ConnectorError::retryable(format!(
+                "Embedding provider returned {} vectors for {} texts",
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+
+        Ok(embeddings)
+    }
+
+    /// Embed a batch of texts with one request to the provider's HTTP API
+    async fn embed_http(&self, texts: &[String]) -> ConnectorResult<Vec<Vec<f32>>> {
+        let mut request = self
+            .client
+            .post(self.endpoint())
+            .json(&json!({ "model": self.config.model, "input": texts }));
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        debug!(
+            "Requesting {} embeddings from {:?} provider (model: {})",
+            texts.len(),
+            self.config.provider,
+            self.config.model
+        );
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ConnectorError::retryable(format!("Embedding request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("Embedding provider returned {}: {}", status, body);
+            return Err(
+                if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    ConnectorError::retryable(message)
+                } else {
+                    ConnectorError::fatal(message)
+                },
+            );
+        }
+
+        let embeddings = match self.config.provider {
+            EmbeddingProvider::OpenAI => {
+                let mut response: OpenAIResponse =
+                    response.json().await.map_err(invalid_response)?;
+                response.data.sort_by_key(|embedding| embedding.index);
+                response
+                    .data
+                    .into_iter()
+                    .map(|embedding| embedding.embedding)
+                    .collect()
+            }
+            _ => {
+                let response: EmbeddingsResponse =
+                    response.json().await.map_err(invalid_response)?;
+                response.embeddings
+            }
+        };
+
+        Ok(embeddings)
+    }
+
+    /// Embed a batch of texts in-process (CPU-bound, so off the async runtime)
+    #[cfg(feature = "onnx")]
+    async fn embed_onnx(&self, texts: &[String]) -> ConnectorResult<Vec<Vec<f32>>> {
+        let model = self
+            .model
+            .clone()
+            .ok_or_else(|| ConnectorError::fatal("ONNX embedding model not loaded"))?;
+        let texts = texts.to_vec();
+
+        debug!(
+            "Embedding {} texts with ONNX model '{}'",
+            texts.len(),
+            self.config.model
+        );
+
+        tokio::task::spawn_blocking(move || {
+            let mut model = model
+                .lock()
+                .map_err(|_| ConnectorError::fatal("ONNX embedding model lock poisoned"))?;
+            model
+                .embed(texts, None)
+                .map_err(|e| ConnectorError::fatal(format!("ONNX embedding failed: {}", e)))
+        })
+        .await
+        .map_err(|e| ConnectorError::fatal(format!("ONNX embedding task failed: {}", e)))?
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    async fn embed_onnx(&self, _texts: &[String]) -> ConnectorResult<Vec<Vec<f32>>> {
+        Err(ConnectorError::fatal(
+            "Embedding provider 'onnx' requires the 'onnx' feature",
+        ))
+    }
+
+    /// Request URL for the configured provider
+    fn endpoint(&self) -> String {
+        match self.config.provider {
+            EmbeddingProvider::OpenAI => self
+                .config
+                .url
+                .clone()
+                .unwrap_or_else(|| OPENAI_URL.to_string()),
+            // Required by config validation (onnx has no endpoint)
+            EmbeddingProvider::Http | EmbeddingProvider::Onnx => {
+                self.config.url.clone().unwrap_or_default()
+            }
+            EmbeddingProvider::Local => format!(
+                "{}/api/embed",
+                self.config
+                    .url
+                    .as_deref()
+                    .unwrap_or(LOCAL_URL)
+                    .trim_end_matches('/')
+            ),
+        }
+    }
+}
+
+/// Load a supported sentence-transformer model (downloaded into `cache_dir` if missing)
+#[cfg(feature = "onnx")]
+fn load_onnx_model(config: &EmbeddingConfig) -> ConnectorResult<fastembed::TextEmbedding> {
+    let model = fastembed::TextEmbedding::list_supported_models()
+        .into_iter()
+        .find(|info| info.model_code.eq_ignore_ascii_case(&config.model))
+        .ok_or_else(|| {
+            ConnectorError::config(format!(
+                "Unsupported ONNX embedding model '{}'",
+                config.model
+            ))
+        })?
+        .model;
+
+    let mut options = fastembed::InitOptions::new(model).with_show_download_progress(false);
+    if let Some(cache_dir) = &config.cache_dir {
+        options = options.with_cache_dir(cache_dir.into());
+    }
+
+    fastembed::TextEmbedding::try_new(options).map_err(|e| {
+        ConnectorError::fatal(format!(
+            "Failed to load ONNX embedding model '{}': {}",
+            config.model, e
+        ))
+    })
+}
+
+fn invalid_response(e: reqwest::Error) -> ConnectorError {
+    ConnectorError::retryable(format!("Invalid embedding response: {}", e))
+}
+
+/// Read the text to embed from a document field (dotted path)
+pub fn extract_text(document: &serde_json::Value, field: &str) -> Option<String> {
+    field
+        .split('.')
+        .try_fold(document, |current, key| current.get(key))?
+        .as_str()
+        .filter(|text| !text.is_empty())
+        .map(|text| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedder(provider: EmbeddingProvider, url: Option<&str>) -> Embedder {
+        Embedder::new(EmbeddingConfig {
+            provider,
+            model: "test-model".to_string(),
+            text_field: "text".to_string(),
+            url: url.map(|url| url.to_string()),
+            api_key: None,
+            timeout_secs: 30,
+            cache_dir: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_endpoint() {
+        assert_eq!(
+            embedder(EmbeddingProvider::OpenAI, None).endpoint(),
+            "https://api.openai.com/v1/embeddings"
+        );
+        assert_eq!(
+            embedder(EmbeddingProvider::Local, None).endpoint(),
+            "http://localhost:11434/api/embed"
+        );
+        assert_eq!(
+            embedder(EmbeddingProvider::Local, Some("http://ollama:11434/")).endpoint(),
+            "http://ollama:11434/api/embed"
+        );
+        assert_eq!(
+            embedder(EmbeddingProvider::Http, Some("http://embedder:8080/embed")).endpoint(),
+            "http://embedder:8080/embed"
+        );
+    }
+
+    #[test]
+    fn test_extract_text() {
+        let document = serde_json::json!({"text": "hello", "doc": {"body": "nested"}, "n": 1});
+
+        assert_eq!(extract_text(&document, "text"), Some("hello".to_string()));
+        assert_eq!(
+            extract_text(&document, "doc.body"),
+            Some("nested".to_string())
+        );
+        assert_eq!(extract_text(&document, "n"), None);
+        assert_eq!(extract_text(&document, "missing"), None);
+    }
+}
@@ -3,8 +3,10 @@
 //! This connector consumes messages from Danube topics and upserts vector embeddings to Qdrant.
 //! Perfect for building RAG (Retrieval Augmented Generation) pipelines and AI applications.
 
+mod client;
 mod config;
 mod connector;
+mod embedding;
 mod record;
 
 use config::QdrantSinkConfig;
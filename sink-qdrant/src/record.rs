@@ -1,7 +1,8 @@
 //! Message transformation logic for converting Danube messages to Qdrant points
 
+use crate::config::{DeleteConfig, TopicMapping};
 use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
-use qdrant_client::qdrant::{PointStruct, Value};
+use qdrant_client::qdrant::{NamedVectors, PointStruct, Value, Vector, Vectors};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -13,51 +14,422 @@ pub struct VectorMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
-    /// Vector embedding (required)
-    pub vector: Vec<f32>,
+    /// Vector embedding (required unless the collection uses named vectors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+
+    /// Named vectors (required for collections with named vectors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vectors: Option<HashMap<String, Vec<f32>>>,
+
+    /// Sparse vector, for collections declaring a single sparse vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vector: Option<SparseValues>,
+
+    /// Named sparse vectors (for hybrid dense+sparse collections)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<HashMap<String, SparseValues>>,
 
     /// Optional payload/metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
 }
 
+/// Sparse vector as non-zero dimension indices and their values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseValues {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
 /// Transform a Danube SinkRecord into a Qdrant PointStruct
 pub fn transform_to_point(
     record: &SinkRecord,
-    expected_dimension: usize,
-    include_danube_metadata: bool,
+    mapping: &TopicMapping,
 ) -> ConnectorResult<PointStruct> {
     // Parse message from typed payload (already serde_json::Value)
-    let message: VectorMessage = serde_json::from_value(record.payload().clone()).map_err(|e| {
-        ConnectorError::invalid_data(format!("Failed to deserialize message: {}", e), vec![])
+    let message: VectorMessage = serde_json::from_value(standard_layout(record.payload(), mapping))
+        .map_err(|e| {
+            ConnectorError::invalid_data(format!("Failed to deserialize message: {}", e), vec![])
+        })?;
+
+    let sparse = sparse_vectors(message.sparse_vector, message.sparse_vectors, mapping)?;
+    let vectors = point_vectors(message.vector, message.vectors, sparse, mapping)?;
+
+    // Generate point ID
+    let point_id = generate_point_id(message.id.as_deref(), record);
+
+    // Build payload
+    let mut payload = build_payload(message.payload, record, mapping.include_danube_metadata)?;
+    add_tenant(&mut payload, record, mapping)?;
+
+    // Create Qdrant point
+    Ok(PointStruct::new(point_id, vectors, payload))
+}
+
+/// Move the route's configured message fields to the standard layout (`id`, `vector`, `payload`)
+///
+/// IDs are converted to strings, so numeric IDs are accepted too.
+fn standard_layout(message: &serde_json::Value, mapping: &TopicMapping) -> serde_json::Value {
+    let mut standard = message.clone();
+    let Some(fields) = standard.as_object_mut() else {
+        return standard;
+    };
+
+    for (path, key) in [
+        (&mapping.vector_field, "vector"),
+        (&mapping.payload_field, "payload"),
+    ] {
+        if path != key {
+            fields.remove(key);
+            if let Some(value) = field(message, path) {
+                fields.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+
+    fields.remove("id");
+    if let Some(id) = message_id(message, &mapping.id_field) {
+        fields.insert("id".to_string(), serde_json::Value::String(id));
+    }
+
+    standard
+}
+
+/// Validate a message's vector data against the collection's vector configuration
+///
+/// Collections with named vectors take a `vectors` map; each vector must be
+/// declared, but points may omit some of them. Sparse vectors are added next
+/// to the dense ones (the unnamed dense vector is stored under the default name "").
+fn point_vectors(
+    vector: Option<Vec<f32>>,
+    vectors: Option<HashMap<String, Vec<f32>>>,
+    sparse: HashMap<String, SparseValues>,
+    mapping: &TopicMapping,
+) -> ConnectorResult<Vectors> {
+    let mut named = NamedVectors::default();
+
+    if mapping.vectors.is_empty() {
+        let vector = vector
+            .ok_or_else(|| ConnectorError::invalid_data("Message has no 'vector' field", vec![]))?;
+        validate_dimension(&vector, mapping.vector_dimension)?;
+        if mapping.sparse_vectors.is_empty() {
+            return Ok(vector.into());
+        }
+        named = named.add_vector("", vector);
+    } else {
+        let vectors = vectors
+            .filter(|vectors| !vectors.is_empty())
+            .ok_or_else(|| {
+                ConnectorError::invalid_data(
+                    format!(
+                        "Collection '{}' uses named vectors, message has no 'vectors' map",
+                        mapping.to
+                    ),
+                    vec![],
+                )
+            })?;
+
+        for (name, vector) in vectors {
+            let declared = mapping
+                .vectors
+                .iter()
+                .find(|declared| declared.name == name)
+                .ok_or_else(|| {
+                    ConnectorError::invalid_data(
+                        format!("Collection '{}' has no vector named '{}'", mapping.to, name),
+                        vec![],
+                    )
+                })?;
+            validate_dimension(&vector, declared.dimension)?;
+            named = named.add_vector(name, vector);
+        }
+    }
+
+    for (name, values) in sparse {
+        named = named.add_vector(name, Vector::new_sparse(values.indices, values.values));
+    }
+
+    Ok(named.into())
+}
+
+/// Validate a message's sparse vectors against the collection's sparse vectors
+///
+/// The `sparse_vector` shorthand is accepted when a single sparse vector is declared.
+fn sparse_vectors(
+    sparse_vector: Option<SparseValues>,
+    sparse_vectors: Option<HashMap<String, SparseValues>>,
+    mapping: &TopicMapping,
+) -> ConnectorResult<HashMap<String, SparseValues>> {
+    let mut sparse = sparse_vectors.unwrap_or_default();
+
+    if let Some(values) = sparse_vector {
+        let [declared] = mapping.sparse_vectors.as_slice() else {
+            return Err(ConnectorError::invalid_data(
+                format!(
+                    "Collection '{}' declares {} sparse vectors, 'sparse_vector' needs exactly one",
+                    mapping.to,
+                    mapping.sparse_vectors.len()
+                ),
+                vec![],
+            ));
+        };
+        sparse.insert(declared.name.clone(), values);
+    }
+
+    for (name, values) in &sparse {
+        if !mapping
+            .sparse_vectors
+            .iter()
+            .any(|declared| &declared.name == name)
+        {
+            return Err(ConnectorError::invalid_data(
+                format!(
+                    "Collection '{}' has no sparse vector named '{}'",
+                    mapping.to, name
+                ),
+                vec![],
+            ));
+        }
+        if values.indices.len() != values.values.len() {
+            return Err(ConnectorError::invalid_data(
+                format!(
+                    "Sparse vector '{}' has {} indices but {} values",
+                    name,
+                    values.indices.len(),
+                    values.values.len()
+                ),
+                vec![],
+            ));
+        }
+    }
+
+    Ok(sparse)
+}
+
+/// Transform a plain document and its generated embedding into a Qdrant PointStruct
+///
+/// The whole document becomes the point payload; its optional ID field
+/// (`id_field`) is used as point ID.
+pub fn document_to_point(
+    record: &SinkRecord,
+    vector: Vec<f32>,
+    mapping: &TopicMapping,
+) -> ConnectorResult<PointStruct> {
+    validate_dimension(&vector, mapping.vector_dimension)?;
+
+    let document = record.payload();
+    let point_id = generate_point_id(message_id(document, &mapping.id_field).as_deref(), record);
+
+    let mut payload = build_payload(
+        Some(document.clone()),
+        record,
+        mapping.include_danube_metadata,
+    )?;
+    add_tenant(&mut payload, record, mapping)?;
+
+    Ok(PointStruct::new(point_id, vector, payload))
+}
+
+/// Resolve the collection a record is written to
+///
+/// Static routes always use `to`; templated routes (e.g., `docs_{tenant}`) are
+/// rendered from the record, and unresolvable records are reported as invalid data.
+pub fn resolve_collection(record: &SinkRecord, mapping: &TopicMapping) -> ConnectorResult<String> {
+    if !mapping.is_dynamic() {
+        return Ok(mapping.to.clone());
+    }
+
+    render_collection(&mapping.to, record.payload(), |name| {
+        record.get_attribute(name).map(|value| value.to_string())
+    })
+    .map_err(|e| {
+        ConnectorError::invalid_data(
+            format!(
+                "Failed to resolve collection '{}' for message from topic {}: {}",
+                mapping.to,
+                record.topic(),
+                e
+            ),
+            vec![],
+        )
+    })
+}
+
+/// Render a collection name template, replacing each `{name}` with a payload field
+///
+/// Placeholders not found in the payload fall back to the message attribute of
+/// the same name.
+fn render_collection(
+    template: &str,
+    payload: &serde_json::Value,
+    attribute: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut collection = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed placeholder in '{}'", template))?;
+        let name = &rest[start + 1..start + end];
+        let value = lookup_value(payload, name, &attribute)
+            .ok_or_else(|| format!("no payload field or attribute '{}'", name))?;
+        collection.push_str(&rest[..start]);
+        collection.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    collection.push_str(rest);
+
+    if !collection
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(format!(
+            "'{}' is not a valid collection name (letters, digits, '_', '-', '.')",
+            collection
+        ));
+    }
+
+    Ok(collection)
+}
+
+/// Read a string or number from a payload field (dotted path), falling back to
+/// the message attribute of the same name
+fn lookup_value(
+    payload: &serde_json::Value,
+    name: &str,
+    attribute: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    field(payload, name)
+        .and_then(|value| match value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .or_else(|| attribute(name))
+        .filter(|value| !value.is_empty())
+}
+
+/// Resolve the tenant of a record on a multi-tenant route
+///
+/// Records without a tenant can't be isolated, so they are reported as invalid data.
+pub fn resolve_tenant(
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+) -> ConnectorResult<Option<String>> {
+    let Some(tenant) = &mapping.tenant else {
+        return Ok(None);
+    };
+
+    lookup_value(record.payload(), &tenant.field, |name| {
+        record.get_attribute(name).map(|value| value.to_string())
+    })
+    .map(Some)
+    .ok_or_else(|| {
+        ConnectorError::invalid_data(
+            format!(
+                "Message from topic {} has no tenant field or attribute '{}'",
+                record.topic(),
+                tenant.field
+            ),
+            vec![],
+        )
+    })
+}
+
+/// Copy the record's tenant into the point payload
+fn add_tenant(
+    payload: &mut HashMap<String, Value>,
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+) -> ConnectorResult<()> {
+    if let (Some(tenant), Some(value)) = (&mapping.tenant, resolve_tenant(record, mapping)?) {
+        payload.insert(tenant.payload_key.clone(), Value::from(value));
+    }
+    Ok(())
+}
+
+/// Point ID deleted by a message, if the route's delete convention marks it as a delete
+///
+/// A delete without an `id` can't be applied, so it is reported as invalid data.
+pub fn delete_point_id(
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+) -> ConnectorResult<Option<u64>> {
+    let Some(delete) = &mapping.delete else {
+        return Ok(None);
+    };
+
+    let message = record.payload();
+    if !is_delete(message, delete, mapping) {
+        return Ok(None);
+    }
+
+    let id = message_id(message, &mapping.id_field).ok_or_else(|| {
+        ConnectorError::invalid_data(
+            format!(
+                "Delete message from topic {} has no '{}' field",
+                record.topic(),
+                mapping.id_field
+            ),
+            vec![],
+        )
     })?;
 
-    // Validate vector dimension
-    if message.vector.len() != expected_dimension {
+    Ok(Some(generate_point_id(Some(&id), record)))
+}
+
+/// Check whether a message is a delete under the route's convention
+fn is_delete(message: &serde_json::Value, delete: &DeleteConfig, mapping: &TopicMapping) -> bool {
+    if message.get(&delete.op_field).and_then(|op| op.as_str()) == Some(delete.op_value.as_str()) {
+        return true;
+    }
+
+    let vector_field = if mapping.vectors.is_empty() {
+        mapping.vector_field.as_str()
+    } else {
+        "vectors"
+    };
+    delete.on_null_vector
+        && message_id(message, &mapping.id_field).is_some()
+        && field(message, vector_field).map_or(true, |vector| vector.is_null())
+}
+
+/// Read a message's ID field (string or number)
+fn message_id(message: &serde_json::Value, id_field: &str) -> Option<String> {
+    match field(message, id_field) {
+        Some(serde_json::Value::String(id)) => Some(id.clone()),
+        Some(serde_json::Value::Number(id)) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Read a message field (dotted path)
+fn field<'a>(message: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .try_fold(message, |current, key| current.get(key))
+}
+
+/// Check a vector against the collection's dimension
+fn validate_dimension(vector: &[f32], expected_dimension: usize) -> ConnectorResult<()> {
+    if vector.len() != expected_dimension {
         return Err(ConnectorError::invalid_data(
             format!(
                 "Vector dimension mismatch: expected {}, got {}",
                 expected_dimension,
-                message.vector.len()
+                vector.len()
             ),
             vec![],
         ));
     }
-
-    // Generate point ID
-    let point_id = generate_point_id(&message, record);
-
-    // Build payload
-    let payload = build_payload(message.payload, record, include_danube_metadata)?;
-
-    // Create Qdrant point
-    Ok(PointStruct::new(point_id, message.vector, payload))
+    Ok(())
 }
 
 /// Generate a unique point ID
 /// Priority: 1) Use message.id if provided, 2) Hash of (topic + offset)
-fn generate_point_id(message: &VectorMessage, record: &SinkRecord) -> u64 {
-    if let Some(ref id) = message.id {
+fn generate_point_id(id: Option<&str>, record: &SinkRecord) -> u64 {
+    if let Some(id) = id {
         // Try to parse as u64
         if let Ok(num_id) = id.parse::<u64>() {
             return num_id;
@@ -221,7 +593,7 @@ mod tests {
         let message: VectorMessage = serde_json::from_value(json).unwrap();
 
         assert_eq!(message.id, Some("test-123".to_string()));
-        assert_eq!(message.vector.len(), 3);
+        assert_eq!(message.vector.map(|v| v.len()), Some(3));
         assert!(message.payload.is_some());
     }
 
@@ -234,10 +606,241 @@ mod tests {
         let message: VectorMessage = serde_json::from_value(json).unwrap();
 
         assert!(message.id.is_none());
-        assert_eq!(message.vector.len(), 3);
+        assert_eq!(message.vector.map(|v| v.len()), Some(3));
         assert!(message.payload.is_none());
     }
 
+    #[test]
+    fn test_point_vectors() {
+        let single: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/vectors",
+            "subscription": "sub",
+            "to": "vectors",
+            "vector_dimension": 3
+        }))
+        .unwrap();
+        assert!(point_vectors(Some(vec![0.1, 0.2, 0.3]), None, HashMap::new(), &single).is_ok());
+        assert!(point_vectors(Some(vec![0.1, 0.2]), None, HashMap::new(), &single).is_err());
+        assert!(point_vectors(None, None, HashMap::new(), &single).is_err());
+
+        let named: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/docs",
+            "subscription": "sub",
+            "to": "docs",
+            "vectors": [
+                {"name": "title_vec", "dimension": 2},
+                {"name": "body_vec", "dimension": 3}
+            ]
+        }))
+        .unwrap();
+        let vectors = |entries: &[(&str, Vec<f32>)]| {
+            Some(
+                entries
+                    .iter()
+                    .map(|(name, vector)| (name.to_string(), vector.clone()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        };
+
+        assert!(point_vectors(
+            None,
+            vectors(&[
+                ("title_vec", vec![0.1, 0.2]),
+                ("body_vec", vec![0.1, 0.2, 0.3])
+            ]),
+            HashMap::new(),
+            &named
+        )
+        .is_ok());
+        // Points may omit declared vectors
+        assert!(point_vectors(
+            None,
+            vectors(&[("title_vec", vec![0.1, 0.2])]),
+            HashMap::new(),
+            &named
+        )
+        .is_ok());
+        assert!(point_vectors(
+            None,
+            vectors(&[("title_vec", vec![0.1])]),
+            HashMap::new(),
+            &named
+        )
+        .is_err());
+        assert!(point_vectors(
+            None,
+            vectors(&[("other", vec![0.1, 0.2])]),
+            HashMap::new(),
+            &named
+        )
+        .is_err());
+        assert!(point_vectors(Some(vec![0.1, 0.2]), None, HashMap::new(), &named).is_err());
+    }
+
+    #[test]
+    fn test_sparse_vectors() {
+        let hybrid: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/docs",
+            "subscription": "sub",
+            "to": "docs",
+            "vector_dimension": 3,
+            "sparse_vectors": [{"name": "text_sparse", "idf": true}]
+        }))
+        .unwrap();
+        let values = |indices: Vec<u32>, values: Vec<f32>| SparseValues { indices, values };
+
+        // Shorthand maps to the only declared sparse vector
+        let sparse =
+            sparse_vectors(Some(values(vec![1, 7], vec![0.5, 0.2])), None, &hybrid).unwrap();
+        assert!(sparse.contains_key("text_sparse"));
+        assert!(point_vectors(Some(vec![0.1, 0.2, 0.3]), None, sparse, &hybrid).is_ok());
+
+        let unknown = HashMap::from([("other".to_string(), values(vec![1], vec![0.5]))]);
+        assert!(sparse_vectors(None, Some(unknown), &hybrid).is_err());
+        assert!(sparse_vectors(Some(values(vec![1, 2], vec![0.5])), None, &hybrid).is_err());
+    }
+
+    #[test]
+    fn test_is_delete() {
+        let mut mapping: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/vectors",
+            "subscription": "sub",
+            "to": "vectors",
+            "vector_dimension": 3,
+            "delete": {}
+        }))
+        .unwrap();
+        let delete = mapping.delete.clone().unwrap();
+
+        assert!(is_delete(
+            &serde_json::json!({"op": "delete", "id": "42"}),
+            &delete,
+            &mapping
+        ));
+        assert!(!is_delete(
+            &serde_json::json!({"op": "upsert", "id": "42", "vector": [0.1, 0.2, 0.3]}),
+            &delete,
+            &mapping
+        ));
+        // Null vectors are only deletes when enabled
+        assert!(!is_delete(
+            &serde_json::json!({"id": "42", "vector": null}),
+            &delete,
+            &mapping
+        ));
+
+        mapping.delete.as_mut().unwrap().on_null_vector = true;
+        let delete = mapping.delete.clone().unwrap();
+        assert!(is_delete(
+            &serde_json::json!({"id": "42", "vector": null}),
+            &delete,
+            &mapping
+        ));
+        assert!(is_delete(&serde_json::json!({"id": 42}), &delete, &mapping));
+        assert!(!is_delete(
+            &serde_json::json!({"vector": null}),
+            &delete,
+            &mapping
+        ));
+    }
+
+    #[test]
+    fn test_message_id() {
+        assert_eq!(
+            message_id(&serde_json::json!({"id": "doc-1"}), "id"),
+            Some("doc-1".to_string())
+        );
+        assert_eq!(
+            message_id(&serde_json::json!({"id": 42}), "id"),
+            Some("42".to_string())
+        );
+        assert_eq!(
+            message_id(
+                &serde_json::json!({"meta": {"doc_id": "d7"}}),
+                "meta.doc_id"
+            ),
+            Some("d7".to_string())
+        );
+        assert_eq!(message_id(&serde_json::json!({"id": null}), "id"), None);
+    }
+
+    #[test]
+    fn test_standard_layout() {
+        let mapping: TopicMapping = serde_json::from_value(serde_json::json!({
+            "from": "/default/vectors",
+            "subscription": "sub",
+            "to": "vectors",
+            "vector_dimension": 3,
+            "vector_field": "embedding",
+            "id_field": "doc_id",
+            "payload_field": "meta"
+        }))
+        .unwrap();
+
+        let message = standard_layout(
+            &serde_json::json!({
+                "doc_id": 7,
+                "embedding": [0.1, 0.2, 0.3],
+                "meta": {"source": "wiki"}
+            }),
+            &mapping,
+        );
+        let message: VectorMessage = serde_json::from_value(message).unwrap();
+
+        assert_eq!(message.id, Some("7".to_string()));
+        assert_eq!(message.vector.map(|v| v.len()), Some(3));
+        assert_eq!(message.payload, Some(serde_json::json!({"source": "wiki"})));
+    }
+
+    #[test]
+    fn test_render_collection() {
+        let payload = serde_json::json!({"tenant": "acme", "meta": {"region": "eu"}, "n": 7});
+        let attributes = |name: &str| (name == "team").then(|| "search".to_string());
+
+        assert_eq!(
+            render_collection("docs_{tenant}", &payload, attributes),
+            Ok("docs_acme".to_string())
+        );
+        assert_eq!(
+            render_collection("{meta.region}-{n}", &payload, attributes),
+            Ok("eu-7".to_string())
+        );
+        // Falls back to message attributes
+        assert_eq!(
+            render_collection("docs_{team}", &payload, attributes),
+            Ok("docs_search".to_string())
+        );
+
+        assert!(render_collection("docs_{missing}", &payload, attributes).is_err());
+        assert!(render_collection("docs_{tenant", &payload, attributes).is_err());
+        assert!(render_collection(
+            "docs_{tenant}",
+            &serde_json::json!({"tenant": "a/b"}),
+            attributes
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_lookup_value() {
+        let payload = serde_json::json!({"payload": {"tenant": "acme"}, "org": 42, "empty": ""});
+        let attributes = |name: &str| (name == "tenant").then(|| "from-attr".to_string());
+
+        assert_eq!(
+            lookup_value(&payload, "payload.tenant", attributes),
+            Some("acme".to_string())
+        );
+        assert_eq!(
+            lookup_value(&payload, "org", attributes),
+            Some("42".to_string())
+        );
+        assert_eq!(
+            lookup_value(&payload, "tenant", attributes),
+            Some("from-attr".to_string())
+        );
+        assert_eq!(lookup_value(&payload, "empty", attributes), None);
+    }
+
     #[test]
     fn test_add_json_to_payload() {
         let mut payload = HashMap::new();
@@ -0,0 +1,520 @@
+//! Configuration module for the object storage Sink Connector
+//!
+//! This module handles all configuration aspects including:
+//! - Storage backend selection (S3, Azure, or GCS) and connection settings
+//! - Topic-to-file routes with key templates, formats and file size limits
+//! - Optional Parquet schemas (inferred per file otherwise)
+//! - Environment variable overrides
+
+use crate::template::KeyTemplate;
+use danube_connect_core::{
+    ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorError,
+    ConnectorResult, SubscriptionType,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+/// Complete configuration for the object storage Sink Connector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3SinkConfig {
+    /// Core connector configuration (Danube connection, etc.)
+    #[serde(flatten)]
+    pub core: ConnectorConfig,
+
+    /// Object storage configuration
+    pub s3: S3Config,
+}
+
+/// Object storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Config {
+    /// Storage backend (s3, azure, or gcs)
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+
+    /// Bucket to write to (the container for Azure)
+    pub bucket: String,
+
+    /// AWS region
+    /// Credentials from environment: AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// S3 endpoint URL (optional, for MinIO or custom S3-compatible storage)
+    /// Example: "http://localhost:9000"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Allow plain HTTP connections (useful for MinIO local testing)
+    #[serde(default)]
+    pub allow_http: bool,
+
+    /// Use path-style addressing (bucket in path instead of hostname)
+    /// Required for MinIO, Ceph, and other S3-compatible stores without virtual-host DNS
+    #[serde(default)]
+    pub path_style: bool,
+
+    /// Azure storage account name (required if storage_backend = "azure")
+    /// Credentials from environment: AZURE_STORAGE_ACCOUNT_KEY or AZURE_STORAGE_SAS_KEY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_storage_account: Option<String>,
+
+    /// Path to a GCS service account JSON key file
+    /// Otherwise GOOGLE_APPLICATION_CREDENTIALS or GOOGLE_SERVICE_ACCOUNT is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcs_service_account_path: Option<String>,
+
+    /// Additional object store options passed through as-is
+    /// Applied last, so they take precedence over the typed settings above
+    /// Example: { "aws_server_side_encryption" = "aws:kms", "timeout" = "60s" }
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub storage_options: HashMap<String, String>,
+
+    /// Maximum number of files uploaded concurrently
+    #[serde(default = "default_max_parallel_uploads")]
+    pub max_parallel_uploads: usize,
+
+    /// Routes: Danube topics → object keys
+    #[serde(default)]
+    pub routes: Vec<TopicMapping>,
+}
+
+/// Cloud storage backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// Amazon S3 (or S3-compatible like MinIO)
+    S3,
+    /// Azure Blob Storage
+    Azure,
+    /// Google Cloud Storage
+    GCS,
+}
+
+impl Default for StorageBackend {
+    fn default() -> Self {
+        StorageBackend::S3
+    }
+}
+
+/// File format written by a route
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum FileFormat {
+    /// One JSON document per line
+    Jsonl,
+    /// Apache Parquet
+    Parquet,
+}
+
+/// Compression of written files
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum FileCompression {
+    /// Uncompressed
+    None,
+    /// Gzip (JSONL files, or Parquet pages)
+    Gzip,
+    /// Snappy (Parquet pages)
+    Snappy,
+    /// Zstandard (Parquet pages)
+    Zstd,
+}
+
+/// Time used to render the date and time placeholders of the key template
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum PartitionTime {
+    /// When the message was published to Danube
+    PublishTime,
+    /// When the connector writes the file
+    ProcessingTime,
+}
+
+impl Default for PartitionTime {
+    fn default() -> Self {
+        PartitionTime::PublishTime
+    }
+}
+
+/// Parquet column definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaField {
+    /// Field name
+    pub name: String,
+
+    /// Arrow data type (e.g., "Utf8", "Int64", "Float64", "Boolean", "Timestamp")
+    pub data_type: String,
+
+    /// Whether the field is nullable
+    #[serde(default = "default_true")]
+    pub nullable: bool,
+}
+
+/// Mapping from a Danube topic to object keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicMapping {
+    /// Danube topic to consume from
+    pub from: String,
+
+    /// Danube subscription name
+    pub subscription: String,
+
+    /// Subscription type: Exclusive, Shared, FailOver
+    #[serde(default = "default_subscription_type")]
+    pub subscription_type: SubscriptionType,
+
+    /// Expected schema subject for validation (optional)
+    /// If set, the runtime validates and deserializes messages automatically
+    /// Schema must be registered in Danube Schema Registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_schema_subject: Option<String>,
+
+    /// Object key template, e.g. "events/dt={date}/hour={hour}/part-{part}.{ext}"
+    #[serde(default = "default_key_template")]
+    pub key_template: String,
+
+    /// File format
+    pub format: FileFormat,
+
+    /// Compression (default: None for Jsonl, Snappy for Parquet)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<FileCompression>,
+
+    /// Time rendered into the key template (default: PublishTime)
+    #[serde(default)]
+    pub partition_time: PartitionTime,
+
+    /// Maximum records per file; larger groups are split into more parts
+    #[serde(default = "default_max_file_records")]
+    pub max_file_records: usize,
+
+    /// Maximum estimated payload bytes per file (default: 64 MiB)
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: usize,
+
+    /// Parquet columns (inferred from each file's records when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema: Vec<SchemaField>,
+
+    /// Add Danube metadata to each record as a `_danube_metadata` object
+    #[serde(default)]
+    pub include_danube_metadata: bool,
+
+    /// Drop records that can't be written (e.g., not matching the schema)
+    /// instead of failing the batch
+    #[serde(default)]
+    pub skip_invalid_records: bool,
+}
+
+impl TopicMapping {
+    /// Effective compression of the route's files
+    pub fn compression(&self) -> FileCompression {
+        self.compression.unwrap_or(match self.format {
+            FileFormat::Jsonl => FileCompression::None,
+            FileFormat::Parquet => FileCompression::Snappy,
+        })
+    }
+
+    /// File extension rendered for `{ext}`
+    pub fn extension(&self) -> &'static str {
+        match (self.format, self.compression()) {
+            (FileFormat::Jsonl, FileCompression::Gzip) => "jsonl.gz",
+            (FileFormat::Jsonl, _) => "jsonl",
+            (FileFormat::Parquet, _) => "parquet",
+        }
+    }
+}
+
+// Default value functions
+fn default_true() -> bool {
+    true
+}
+
+fn default_subscription_type() -> SubscriptionType {
+    SubscriptionType::Shared
+}
+
+fn default_max_parallel_uploads() -> usize {
+    4
+}
+
+fn default_key_template() -> String {
+    "{topic}/dt={date}/hour={hour}/part-{part}.{ext}".to_string()
+}
+
+fn default_max_file_records() -> usize {
+    100_000
+}
+
+fn default_max_file_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+impl S3SinkConfig {
+    /// Load configuration from TOML file
+    ///
+    /// The config file path must be specified via CONNECTOR_CONFIG_PATH environment variable.
+    /// Environment variables can override the bucket and endpoints.
+    pub fn load() -> ConnectorResult<Self> {
+        ConnectorConfigLoader::new().load()
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> ConnectorResult<()> {
+        self.validate_config()
+    }
+}
+
+impl ConfigEnvOverrides for S3SinkConfig {
+    fn apply_env_overrides(&mut self) -> ConnectorResult<()> {
+        if let Ok(danube_url) = env::var("DANUBE_SERVICE_URL") {
+            self.core.danube_service_url = danube_url;
+        }
+
+        if let Ok(connector_name) = env::var("CONNECTOR_NAME") {
+            self.core.connector_name = connector_name;
+        }
+
+        if let Ok(bucket) = env::var("S3_BUCKET") {
+            self.s3.bucket = bucket;
+        }
+
+        if let Ok(region) = env::var("AWS_REGION") {
+            self.s3.region = Some(region);
+        }
+
+        if let Ok(endpoint) = env::var("S3_ENDPOINT") {
+            self.s3.endpoint = Some(endpoint);
+        }
+
+        if let Ok(account) = env::var("AZURE_STORAGE_ACCOUNT") {
+            self.s3.azure_storage_account = Some(account);
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigValidate for S3SinkConfig {
+    fn validate_config(&self) -> ConnectorResult<()> {
+        let s3 = &self.s3;
+
+        if s3.bucket.is_empty() {
+            return Err(ConnectorError::config("S3_BUCKET cannot be empty"));
+        }
+
+        if s3.storage_backend == StorageBackend::Azure && s3.azure_storage_account.is_none() {
+            return Err(ConnectorError::config(
+                "azure_storage_account is required for the azure storage backend",
+            ));
+        }
+
+        if s3.max_parallel_uploads == 0 {
+            return Err(ConnectorError::config(
+                "max_parallel_uploads must be at least 1",
+            ));
+        }
+
+        if s3.routes.is_empty() {
+            return Err(ConnectorError::config("At least one route is required"));
+        }
+
+        let mut topics = HashSet::new();
+        for mapping in &s3.routes {
+            if mapping.from.is_empty() || mapping.subscription.is_empty() {
+                return Err(ConnectorError::config(
+                    "Route 'from' and 'subscription' cannot be empty",
+                ));
+            }
+            if !topics.insert(&mapping.from) {
+                return Err(ConnectorError::config(format!(
+                    "Topic '{}' is routed more than once",
+                    mapping.from
+                )));
+            }
+
+            KeyTemplate::parse(&mapping.key_template).map_err(|e| {
+                ConnectorError::config(format!(
+                    "Route '{}': invalid key_template '{}': {}",
+                    mapping.from, mapping.key_template, e
+                ))
+            })?;
+
+            if mapping.max_file_records == 0 || mapping.max_file_bytes == 0 {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': max_file_records and max_file_bytes must be at least 1",
+                    mapping.from
+                )));
+            }
+
+            match mapping.format {
+                FileFormat::Jsonl => {
+                    if !matches!(
+                        mapping.compression(),
+                        FileCompression::None | FileCompression::Gzip
+                    ) {
+                        return Err(ConnectorError::config(format!(
+                            "Route '{}': JSONL files support None or Gzip compression",
+                            mapping.from
+                        )));
+                    }
+                    if !mapping.schema.is_empty() {
+                        return Err(ConnectorError::config(format!(
+                            "Route '{}': schema only applies to Parquet files",
+                            mapping.from
+                        )));
+                    }
+                }
+                FileFormat::Parquet => {
+                    crate::encode::parquet_schema(&mapping.schema).map_err(|e| {
+                        ConnectorError::config(format!("Route '{}': {}", mapping.from, e))
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_mapping() -> TopicMapping {
+        TopicMapping {
+            from: "/default/events".to_string(),
+            subscription: "s3-sink".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            expected_schema_subject: None,
+            key_template: default_key_template(),
+            format: FileFormat::Jsonl,
+            compression: None,
+            partition_time: PartitionTime::PublishTime,
+            max_file_records: 1000,
+            max_file_bytes: 1024 * 1024,
+            schema: Vec::new(),
+            include_danube_metadata: false,
+            skip_invalid_records: false,
+        }
+    }
+
+    fn test_config() -> S3SinkConfig {
+        S3SinkConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(),
+            },
+            s3: S3Config {
+                storage_backend: StorageBackend::S3,
+                bucket: "lake".to_string(),
+                region: Some("us-east-1".to_string()),
+                endpoint: None,
+                allow_http: false,
+                path_style: false,
+                azure_storage_account: None,
+                gcs_service_account_path: None,
+                storage_options: HashMap::new(),
+                max_parallel_uploads: 4,
+                routes: vec![test_mapping()],
+            },
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = test_config();
+        assert!(config.validate().is_ok());
+
+        config.s3.bucket.clear();
+        assert!(config.validate().is_err());
+        config.s3.bucket = "lake".to_string();
+
+        // Files need a part number to get unique keys
+        config.s3.routes[0].key_template = "events/{date}.{ext}".to_string();
+        assert!(config.validate().is_err());
+        config.s3.routes[0].key_template = default_key_template();
+
+        config.s3.routes[0].compression = Some(FileCompression::Snappy);
+        assert!(config.validate().is_err());
+        config.s3.routes[0].compression = Some(FileCompression::Gzip);
+        assert!(config.validate().is_ok());
+
+        config.s3.routes[0].max_file_records = 0;
+        assert!(config.validate().is_err());
+        config.s3.routes[0].max_file_records = 1000;
+
+        config.s3.routes.push(test_mapping());
+        assert!(config.validate().is_err());
+        config.s3.routes.pop();
+
+        config.s3.routes.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parquet_validation() {
+        let mut config = test_config();
+        config.s3.routes[0].format = FileFormat::Parquet;
+        assert!(config.validate().is_ok());
+
+        config.s3.routes[0].schema = vec![SchemaField {
+            name: "amount".to_string(),
+            data_type: "Decimal".to_string(),
+            nullable: true,
+        }];
+        assert!(config.validate().is_err());
+
+        config.s3.routes[0].schema[0].data_type = "Float64".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_extension() {
+        let mut mapping = test_mapping();
+        assert_eq!(mapping.extension(), "jsonl");
+
+        mapping.compression = Some(FileCompression::Gzip);
+        assert_eq!(mapping.extension(), "jsonl.gz");
+
+        mapping.format = FileFormat::Parquet;
+        mapping.compression = None;
+        assert_eq!(mapping.compression(), FileCompression::Snappy);
+        assert_eq!(mapping.extension(), "parquet");
+    }
+
+    #[test]
+    fn test_default_values() {
+        let config: S3Config = toml::from_str(
+            r#"
+            bucket = "lake"
+
+            [[routes]]
+            from = "/default/events"
+            subscription = "s3-sink"
+            format = "Parquet"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.storage_backend, StorageBackend::S3);
+        assert_eq!(config.max_parallel_uploads, 4);
+
+        let mapping = &config.routes[0];
+        assert!(matches!(
+            mapping.subscription_type,
+            SubscriptionType::Shared
+        ));
+        assert_eq!(mapping.key_template, default_key_template());
+        assert_eq!(mapping.partition_time, PartitionTime::PublishTime);
+        assert_eq!(mapping.max_file_records, 100_000);
+        assert_eq!(mapping.max_file_bytes, 64 * 1024 * 1024);
+        assert!(!mapping.include_danube_metadata);
+        assert!(!mapping.skip_invalid_records);
+    }
+}
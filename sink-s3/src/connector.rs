@@ -0,0 +1,689 @@
+//! Object storage Sink Connector implementation
+//!
+//! This module implements the core connector logic for writing messages from
+//! Danube topics to files in S3, GCS or Azure Blob Storage:
+//! - Each batch is grouped by route and rendered key (e.g. by hour)
+//! - Groups are split into files by `max_file_records` and `max_file_bytes`
+//! - Files are encoded as JSONL or Parquet and uploaded concurrently
+//! - Part numbers continue after the existing files of a partition, and
+//!   uploads never overwrite an existing object when the store supports it
+//!
+//! Files are written once per batch and the batch is acknowledged after all its
+//! files are uploaded, so a failed batch is retried as a whole and files that
+//! were already uploaded are written again (at-least-once delivery).
+
+use crate::config::{S3SinkConfig, TopicMapping};
+use crate::encode::{check_record, encode_file, parquet_schema};
+use crate::record::{partition_time, to_file_record};
+use crate::storage::build_store;
+use crate::template::{KeyTemplate, PartKey};
+use arrow_schema::SchemaRef;
+use async_trait::async_trait;
+use danube_connect_core::{
+    ConnectorConfig, ConnectorError, ConnectorResult, ConsumerConfig, SinkConnector, SinkRecord,
+};
+use futures::stream::{self, StreamExt};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutMode, PutPayload};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+/// Part numbers tried for a file before giving up, when concurrent writers
+/// keep taking the next part of a partition
+const MAX_PART_ATTEMPTS: u32 = 10;
+
+/// Partitions whose next part number is remembered; past this, the numbers
+/// are forgotten and read again from the store on next use
+const MAX_TRACKED_PARTITIONS: usize = 10_000;
+
+/// Context for writing the files of a single route (per topic mapping)
+#[derive(Debug)]
+struct RouteContext {
+    /// Topic mapping configuration
+    mapping: TopicMapping,
+
+    /// Parsed key template
+    template: KeyTemplate,
+
+    /// Configured Parquet schema (inferred per file when None)
+    schema: Option<SchemaRef>,
+
+    /// Statistics
+    records_written: u64,
+    records_skipped: u64,
+    files_written: u64,
+    bytes_written: u64,
+    last_error: Option<String>,
+}
+
+impl RouteContext {
+    fn new(mapping: TopicMapping) -> ConnectorResult<Self> {
+        let template = KeyTemplate::parse(&mapping.key_template).map_err(|e| {
+            ConnectorError::config(format!(
+                "Route '{}': invalid key_template '{}': {}",
+                mapping.from, mapping.key_template, e
+            ))
+        })?;
+        let schema = parquet_schema(&mapping.schema)
+            .map_err(|e| ConnectorError::config(format!("Route '{}': {}", mapping.from, e)))?;
+
+        Ok(Self {
+            mapping,
+            template,
+            schema,
+            records_written: 0,
+            records_skipped: 0,
+            files_written: 0,
+            bytes_written: 0,
+            last_error: None,
+        })
+    }
+}
+
+/// A file to upload
+struct PendingFile {
+    /// Topic of the route
+    topic: String,
+    /// Key of the file, except for its part number
+    key: PartKey,
+    /// Records of the file
+    records: Vec<Value>,
+}
+
+/// Object storage Sink Connector
+pub struct S3SinkConnector {
+    /// Configuration
+    config: S3SinkConfig,
+
+    /// Object store client
+    store: Option<Arc<dyn ObjectStore>>,
+
+    /// Route contexts (one per topic mapping)
+    routes: HashMap<String, RouteContext>,
+
+    /// Next part number of each partition
+    parts: PartCounter,
+}
+
+impl S3SinkConnector {
+    /// Create a new connector with the given configuration
+    ///
+    /// The configuration must be valid (see `S3SinkConfig::validate`).
+    pub fn with_config(config: S3SinkConfig) -> ConnectorResult<Self> {
+        let routes = config
+            .s3
+            .routes
+            .iter()
+            .map(|mapping| Ok((mapping.from.clone(), RouteContext::new(mapping.clone())?)))
+            .collect::<ConnectorResult<_>>()?;
+
+        Ok(Self {
+            config,
+            store: None,
+            routes,
+            parts: PartCounter::default(),
+        })
+    }
+
+    /// Create a new connector (loads config automatically)
+    pub fn new() -> ConnectorResult<Self> {
+        let config = S3SinkConfig::load()?;
+        config.validate()?;
+        Self::with_config(config)
+    }
+
+    /// Encode a file's records, dropping invalid records if the route allows it
+    ///
+    /// Returns None when all the records were dropped.
+    fn encode(
+        context: &mut RouteContext,
+        mut records: Vec<Value>,
+    ) -> ConnectorResult<Option<(Vec<u8>, usize)>> {
+        let mapping = &context.mapping;
+        let error = match encode_file(
+            &records,
+            mapping.format,
+            mapping.compression(),
+            context.schema.as_ref(),
+        ) {
+            Ok(content) => return Ok(Some((content, records.len()))),
+            Err(e) => e,
+        };
+
+        // Find the records that can't be written on their own
+        let schema = context.schema.as_ref();
+        let invalid: Vec<usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| check_record(record, schema).is_err())
+            .map(|(index, _)| index)
+            .collect();
+
+        if !mapping.skip_invalid_records || invalid.is_empty() {
+            let payload = invalid
+                .first()
+                .map(|index| serde_json::to_vec(&records[*index]).unwrap_or_default())
+                .unwrap_or_default();
+            return Err(ConnectorError::invalid_data(
+                format!(
+                    "Failed to encode {:?} file for topic {}: {}",
+                    mapping.format, mapping.from, error
+                ),
+                payload,
+            ));
+        }
+
+        for index in invalid.iter().rev() {
+            let record = records.remove(*index);
+            let reason = check_record(&record, schema).err().unwrap_or_default();
+            warn!(
+                "Skipping invalid record on topic {}: {} ({})",
+                mapping.from, reason, record
+            );
+        }
+        context.records_skipped += invalid.len() as u64;
+        if records.is_empty() {
+            return Ok(None);
+        }
+
+        let content = encode_file(
+            &records,
+            mapping.format,
+            mapping.compression(),
+            context.schema.as_ref(),
+        )
+        .map_err(|e| {
+            ConnectorError::invalid_data(
+                format!(
+                    "Failed to encode {:?} file for topic {}: {}",
+                    mapping.format, mapping.from, e
+                ),
+                Vec::new(),
+            )
+        })?;
+        Ok(Some((content, records.len())))
+    }
+}
+
+/// Next part numbers of the partitions written by the connector
+///
+/// The first file of a partition continues after the highest part already in
+/// its folder, so restarts and other connector instances don't overwrite
+/// earlier files.
+#[derive(Default)]
+struct PartCounter {
+    next: Mutex<HashMap<PartKey, u64>>,
+}
+
+impl PartCounter {
+    /// Take the next part number of a partition
+    async fn take(
+        &self,
+        store: &dyn ObjectStore,
+        key: &PartKey,
+    ) -> Result<u64, object_store::Error> {
+        let mut next = self.next.lock().await;
+        let part = match next.get(key) {
+            Some(part) => *part,
+            None => {
+                if next.len() >= MAX_TRACKED_PARTITIONS {
+                    next.clear();
+                }
+                first_part(store, key).await?
+            }
+        };
+        next.insert(key.clone(), part + 1);
+        Ok(part)
+    }
+}
+
+/// First free part number of a partition, after the parts in its folder
+async fn first_part(store: &dyn ObjectStore, key: &PartKey) -> Result<u64, object_store::Error> {
+    let folder = key.folder();
+    let folder_path = (!folder.is_empty()).then(|| ObjectPath::from(folder));
+
+    let mut listing = store.list(folder_path.as_ref());
+    let mut last = 0;
+    while let Some(meta) = listing.next().await {
+        if let Some(part) = key.part_of(meta?.location.as_ref()) {
+            last = last.max(part);
+        }
+    }
+    Ok(last + 1)
+}
+
+/// Upload a file under the next free part number of its partition
+///
+/// Returns the object key of the file.
+async fn upload(
+    store: &dyn ObjectStore,
+    parts: &PartCounter,
+    key: &PartKey,
+    content: PutPayload,
+) -> Result<String, object_store::Error> {
+    let mut attempts = 0;
+    loop {
+        let part = parts.take(store, key).await?;
+        let path = ObjectPath::from(key.key(part));
+
+        match store
+            .put_opts(&path, content.clone(), PutMode::Create.into())
+            .await
+        {
+            Ok(_) => return Ok(path.to_string()),
+            // Another writer took this part
+            Err(object_store::Error::AlreadyExists { .. }) if attempts < MAX_PART_ATTEMPTS => {
+                debug!("Object '{}' already exists, trying the next part", path);
+                attempts += 1;
+            }
+            // Stores without conditional writes rely on the part numbering alone
+            Err(object_store::Error::NotImplemented { .. }) => {
+                store.put(&path, content).await?;
+                return Ok(path.to_string());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Check that the bucket is accessible
+async fn check_access(store: &dyn ObjectStore) -> ConnectorResult<()> {
+    match store.list(None).next().await {
+        Some(Err(e)) => Err(ConnectorError::retryable(format!(
+            "Failed to list the bucket: {}",
+            e
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Split a partition's records into files by record count and estimated size
+fn split_files(records: Vec<Value>, mapping: &TopicMapping) -> Vec<Vec<Value>> {
+    let mut files = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes = 0;
+
+    for record in records {
+        // JSON length, a close estimate for JSONL and an upper bound for Parquet
+        let size = record.to_string().len() + 1;
+        if !current.is_empty()
+            && (current.len() >= mapping.max_file_records
+                || current_bytes + size > mapping.max_file_bytes)
+        {
+            files.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        current_bytes += size;
+        current.push(record);
+    }
+    if !current.is_empty() {
+        files.push(current);
+    }
+
+    files
+}
+
+#[async_trait]
+impl SinkConnector for S3SinkConnector {
+    async fn initialize(&mut self, _config: ConnectorConfig) -> ConnectorResult<()> {
+        info!("Initializing object storage Sink Connector");
+        info!(
+            "Writing to {:?} bucket '{}'",
+            self.config.s3.storage_backend, self.config.s3.bucket
+        );
+
+        let store = build_store(&self.config.s3)?;
+        check_access(store.as_ref()).await?;
+        self.store = Some(store);
+
+        info!("Object store client initialized successfully");
+        info!("Configured {} routes", self.config.s3.routes.len());
+
+        Ok(())
+    }
+
+    async fn consumer_configs(&self) -> ConnectorResult<Vec<ConsumerConfig>> {
+        let configs = self
+            .config
+            .s3
+            .routes
+            .iter()
+            .map(|mapping| ConsumerConfig {
+                topic: mapping.from.clone(),
+                consumer_name: format!(
+                    "{}-{}",
+                    self.config.core.connector_name, mapping.subscription
+                ),
+                subscription: mapping.subscription.clone(),
+                subscription_type: mapping.subscription_type.clone(),
+                expected_schema_subject: mapping.expected_schema_subject.clone(),
+            })
+            .collect();
+
+        Ok(configs)
+    }
+
+    async fn process_batch(&mut self, records: Vec<SinkRecord>) -> ConnectorResult<()> {
+        // Group records by topic and partition, keeping their order
+        let mut groups: Vec<(String, PartKey, Vec<Value>)> = Vec::new();
+        let mut group_index: HashMap<(String, PartKey), usize> = HashMap::new();
+
+        for record in records {
+            let topic = record.topic().to_string();
+
+            let context = self.routes.get_mut(&topic).ok_or_else(|| {
+                ConnectorError::fatal(format!("No mapping configured for topic: {}", topic))
+            })?;
+
+            let file_record = match to_file_record(&record, &context.mapping) {
+                Ok(file_record) => file_record,
+                Err(e) if context.mapping.skip_invalid_records => {
+                    warn!("Skipping invalid record: {}", e);
+                    context.records_skipped += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let key = context.template.render(
+                &topic,
+                partition_time(&record, &context.mapping),
+                context.mapping.extension(),
+            );
+            let index = *group_index
+                .entry((topic.clone(), key.clone()))
+                .or_insert_with(|| {
+                    groups.push((topic, key, Vec::new()));
+                    groups.len() - 1
+                });
+            groups[index].2.push(file_record);
+        }
+
+        // Split and encode the files
+        let mut files = Vec::new();
+        for (topic, key, records) in groups {
+            let context = self
+                .routes
+                .get_mut(&topic)
+                .expect("groups only hold routed topics");
+            for records in split_files(records, &context.mapping) {
+                files.push(PendingFile {
+                    topic: topic.clone(),
+                    key: key.clone(),
+                    records,
+                });
+            }
+        }
+
+        let mut encoded = Vec::with_capacity(files.len());
+        for file in files {
+            let context = self
+                .routes
+                .get_mut(&file.topic)
+                .expect("files only hold routed topics");
+            if let Some((content, count)) = Self::encode(context, file.records)? {
+                encoded.push((file.topic, file.key, content, count));
+            }
+        }
+
+        if encoded.is_empty() {
+            return Ok(());
+        }
+
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("Object store not initialized"))?;
+
+        // Files are independent, so they're uploaded concurrently; every upload
+        // runs to completion before the first error is reported
+        let parts = &self.parts;
+        let results: Vec<_> = stream::iter(encoded)
+            .map(|(topic, key, content, count)| async move {
+                let size = content.len();
+                let result = upload(store.as_ref(), parts, &key, PutPayload::from(content)).await;
+                (topic, key, size, count, result)
+            })
+            .buffer_unordered(self.config.s3.max_parallel_uploads)
+            .collect()
+            .await;
+
+        let mut first_error = None;
+        for (topic, key, size, count, result) in results {
+            let context = self
+                .routes
+                .get_mut(&topic)
+                .expect("files only hold routed topics");
+            match result {
+                Ok(path) => {
+                    debug!("Wrote {} records ({} bytes) to '{}'", count, size, path);
+                    context.records_written += count as u64;
+                    context.files_written += 1;
+                    context.bytes_written += size as u64;
+                    context.last_error = None;
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to upload file of {} records to '{}{{part}}{}': {}",
+                        count, key.before, key.after, e
+                    );
+                    context.last_error = Some(format!("Upload error: {}", e));
+                    first_error.get_or_insert_with(|| {
+                        ConnectorError::retryable(format!(
+                            "Failed to upload file for topic {}: {}",
+                            topic, e
+                        ))
+                    });
+                }
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    async fn shutdown(&mut self) -> ConnectorResult<()> {
+        info!("Shutting down object storage Sink Connector");
+
+        // Print final statistics
+        info!("Final statistics:");
+        for (topic, context) in &self.routes {
+            info!(
+                "  Topic '{}' → '{}': {} records, {} skipped ({} files, {} bytes)",
+                topic,
+                context.mapping.key_template,
+                context.records_written,
+                context.records_skipped,
+                context.files_written,
+                context.bytes_written
+            );
+        }
+
+        info!("Object storage Sink Connector shutdown complete");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> ConnectorResult<()> {
+        let store = self.store.as_ref().ok_or_else(|| {
+            ConnectorError::fatal("Object store not initialized. Call initialize() first.")
+        })?;
+
+        check_access(store.as_ref()).await?;
+
+        // Check for recent errors
+        for (topic, context) in &self.routes {
+            if let Some(error) = &context.last_error {
+                warn!("Topic '{}' has recent error: {}", topic, error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for S3SinkConnector {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default connector")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FileFormat, PartitionTime, S3Config, SchemaField, StorageBackend};
+    use chrono::{TimeZone, Utc};
+    use danube_connect_core::SubscriptionType;
+    use object_store::memory::InMemory;
+    use serde_json::json;
+
+    fn test_mapping() -> TopicMapping {
+        TopicMapping {
+            from: "/default/events".to_string(),
+            subscription: "s3-sink".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            expected_schema_subject: None,
+            key_template: "events/dt={date}/part-{part}.{ext}".to_string(),
+            format: FileFormat::Jsonl,
+            compression: None,
+            partition_time: PartitionTime::PublishTime,
+            max_file_records: 2,
+            max_file_bytes: 1024,
+            schema: Vec::new(),
+            include_danube_metadata: false,
+            skip_invalid_records: false,
+        }
+    }
+
+    fn test_config() -> S3SinkConfig {
+        S3SinkConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(), // No schemas for sink connector test
+            },
+            s3: S3Config {
+                storage_backend: StorageBackend::S3,
+                bucket: "lake".to_string(),
+                region: Some("us-east-1".to_string()),
+                endpoint: None,
+                allow_http: false,
+                path_style: false,
+                azure_storage_account: None,
+                gcs_service_account_path: None,
+                storage_options: HashMap::new(),
+                max_parallel_uploads: 4,
+                routes: vec![test_mapping()],
+            },
+        }
+    }
+
+    fn part_key() -> PartKey {
+        KeyTemplate::parse(&test_mapping().key_template)
+            .unwrap()
+            .render(
+                "/default/events",
+                Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+                "jsonl",
+            )
+    }
+
+    #[test]
+    fn test_connector_creation() {
+        let connector = S3SinkConnector::with_config(test_config()).unwrap();
+        assert_eq!(connector.routes.len(), 1);
+        assert!(connector.store.is_none());
+    }
+
+    #[test]
+    fn test_split_files() {
+        let mut mapping = test_mapping();
+        let records: Vec<Value> = (0..5).map(|id| json!({"id": id})).collect();
+
+        let files = split_files(records.clone(), &mapping);
+        assert_eq!(
+            files.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+
+        // Records of 9 bytes (with the newline), 20 bytes per file
+        mapping.max_file_records = 100;
+        mapping.max_file_bytes = 20;
+        let files = split_files(records, &mapping);
+        assert_eq!(
+            files.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+
+        // A record larger than the limit gets its own file
+        mapping.max_file_bytes = 1;
+        let files = split_files(vec![json!({"id": 1}), json!({"id": 2})], &mapping);
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_encode_skips_invalid_records() {
+        let mut mapping = test_mapping();
+        mapping.format = FileFormat::Parquet;
+        mapping.schema = vec![SchemaField {
+            name: "id".to_string(),
+            data_type: "Int64".to_string(),
+            nullable: false,
+        }];
+        let records = vec![json!({"id": 1}), json!({"id": "one"}), json!({"id": 3})];
+
+        let mut context = RouteContext::new(mapping.clone()).unwrap();
+        assert!(S3SinkConnector::encode(&mut context, records.clone()).is_err());
+
+        mapping.skip_invalid_records = true;
+        let mut context = RouteContext::new(mapping).unwrap();
+        let (_, count) = S3SinkConnector::encode(&mut context, records)
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(context.records_skipped, 1);
+
+        let encoded = S3SinkConnector::encode(&mut context, vec![json!({"id": "two"})]).unwrap();
+        assert!(encoded.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_part_numbering() {
+        let store = InMemory::new();
+        let key = part_key();
+
+        // Continues after the existing parts of the folder
+        store
+            .put(
+                &ObjectPath::from("events/dt=2024-06-01/part-0007.jsonl"),
+                PutPayload::from("{}\n"),
+            )
+            .await
+            .unwrap();
+
+        let parts = PartCounter::default();
+        let path = upload(&store, &parts, &key, PutPayload::from("{}\n"))
+            .await
+            .unwrap();
+        assert_eq!(path, "events/dt=2024-06-01/part-0008.jsonl");
+
+        // Parts taken by another writer are skipped
+        store
+            .put(
+                &ObjectPath::from("events/dt=2024-06-01/part-0009.jsonl"),
+                PutPayload::from("{}\n"),
+            )
+            .await
+            .unwrap();
+        let path = upload(&store, &parts, &key, PutPayload::from("{}\n"))
+            .await
+            .unwrap();
+        assert_eq!(path, "events/dt=2024-06-01/part-0010.jsonl");
+    }
+}
@@ -0,0 +1,279 @@
+//! File encoding
+//!
+//! Encodes a file's records as JSONL (optionally gzipped) or Parquet. Parquet
+//! files use the route's schema when configured; otherwise the schema is
+//! inferred from the file's records, so files of a route may differ when the
+//! payloads change.
+
+use crate::config::{FileCompression, FileFormat, SchemaField};
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use flate2::write::GzEncoder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Records decoded into Arrow at once when writing Parquet
+const PARQUET_BATCH_ROWS: usize = 8192;
+
+/// Build the Arrow schema of configured Parquet columns (None when inferred)
+pub fn parquet_schema(fields: &[SchemaField]) -> Result<Option<SchemaRef>, String> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let data_type = match field.data_type.as_str() {
+                "Utf8" => DataType::Utf8,
+                "Boolean" => DataType::Boolean,
+                "Int32" => DataType::Int32,
+                "Int64" => DataType::Int64,
+                "Float32" => DataType::Float32,
+                "Float64" => DataType::Float64,
+                "Date32" => DataType::Date32,
+                "Timestamp" => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                other => {
+                    return Err(format!(
+                        "unsupported data_type '{}' for field '{}' (Utf8, Boolean, Int32, \
+                         Int64, Float32, Float64, Date32, Timestamp)",
+                        other, field.name
+                    ))
+                }
+            };
+            Ok(Field::new(&field.name, data_type, field.nullable))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Some(Arc::new(Schema::new(fields))))
+}
+
+/// Encode records as a file
+pub fn encode_file(
+    records: &[Value],
+    format: FileFormat,
+    compression: FileCompression,
+    schema: Option<&SchemaRef>,
+) -> Result<Vec<u8>, String> {
+    match format {
+        FileFormat::Jsonl => encode_jsonl(records, compression),
+        FileFormat::Parquet => encode_parquet(records, compression, schema),
+    }
+}
+
+/// Check that a record can be written to a Parquet file with the schema
+///
+/// Used to find the invalid records of a file that failed to encode.
+pub fn check_record(record: &Value, schema: Option<&SchemaRef>) -> Result<(), String> {
+    let schema = match schema {
+        Some(schema) => schema.clone(),
+        None => Arc::new(infer_schema(std::slice::from_ref(record))?),
+    };
+    record_batch(std::slice::from_ref(record), &schema).map(|_| ())
+}
+
+fn encode_jsonl(records: &[Value], compression: FileCompression) -> Result<Vec<u8>, String> {
+    let mut content = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut content, record).map_err(|e| e.to_string())?;
+        content.push(b'\n');
+    }
+
+    if compression != FileCompression::Gzip {
+        return Ok(content);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&content)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| format!("Failed to compress file: {}", e))
+}
+
+fn encode_parquet(
+    records: &[Value],
+    compression: FileCompression,
+    schema: Option<&SchemaRef>,
+) -> Result<Vec<u8>, String> {
+    let schema = match schema {
+        Some(schema) => schema.clone(),
+        None => Arc::new(infer_schema(records)?),
+    };
+
+    let compression = match compression {
+        FileCompression::None => Compression::UNCOMPRESSED,
+        FileCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+        FileCompression::Snappy => Compression::SNAPPY,
+        FileCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+    };
+    let properties = WriterProperties::builder()
+        .set_compression(compression)
+        .build();
+
+    let mut content = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut content, schema.clone(), Some(properties))
+        .map_err(|e| format!("Failed to create Parquet writer: {}", e))?;
+    for chunk in records.chunks(PARQUET_BATCH_ROWS) {
+        let batch = record_batch(chunk, &schema)?;
+        writer
+            .write(&batch)
+            .map_err(|e| format!("Failed to write Parquet file: {}", e))?;
+    }
+    writer
+        .close()
+        .map_err(|e| format!("Failed to write Parquet file: {}", e))?;
+
+    Ok(content)
+}
+
+fn infer_schema(records: &[Value]) -> Result<Schema, String> {
+    if let Some(record) = records.iter().find(|record| !record.is_object()) {
+        return Err(format!(
+            "Parquet records must be JSON objects, got {}",
+            record
+        ));
+    }
+    infer_json_schema_from_iterator(records.iter().map(Ok))
+        .map_err(|e| format!("Failed to infer Parquet schema: {}", e))
+}
+
+fn record_batch(
+    records: &[Value],
+    schema: &SchemaRef,
+) -> Result<arrow_json::reader::RecordBatch, String> {
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(records.len().max(1))
+        .build_decoder()
+        .map_err(|e| e.to_string())?;
+    decoder
+        .serialize(records)
+        .map_err(|e| format!("Records don't match the Parquet schema: {}", e))?;
+    decoder
+        .flush()
+        .map_err(|e| format!("Records don't match the Parquet schema: {}", e))?
+        .ok_or_else(|| "No records to write".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use flate2::read::GzDecoder;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use std::io::Read;
+
+    fn records() -> Vec<Value> {
+        vec![
+            json!({"id": 1, "type": "click", "data": {"x": 1.5}}),
+            json!({"id": 2, "type": "view", "data": {"x": 2.5}}),
+        ]
+    }
+
+    #[test]
+    fn test_encode_jsonl() {
+        let content =
+            encode_file(&records(), FileFormat::Jsonl, FileCompression::None, None).unwrap();
+        let text = String::from_utf8(content).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(
+            serde_json::from_str::<Value>(text.lines().next().unwrap()).unwrap(),
+            records()[0]
+        );
+
+        let content =
+            encode_file(&records(), FileFormat::Jsonl, FileCompression::Gzip, None).unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(content.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+    }
+
+    #[test]
+    fn test_encode_parquet_inferred() {
+        let content = encode_file(
+            &records(),
+            FileFormat::Parquet,
+            FileCompression::Snappy,
+            None,
+        )
+        .unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(content)).unwrap();
+        let schema = reader.schema().clone();
+        assert!(schema.field_with_name("id").is_ok());
+        assert!(schema.field_with_name("data").is_ok());
+
+        let rows: usize = reader
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 2);
+
+        // Parquet rows are objects
+        assert!(encode_file(
+            &[json!(1)],
+            FileFormat::Parquet,
+            FileCompression::None,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_encode_parquet_schema() {
+        let schema = parquet_schema(&[
+            SchemaField {
+                name: "id".to_string(),
+                data_type: "Int64".to_string(),
+                nullable: false,
+            },
+            SchemaField {
+                name: "type".to_string(),
+                data_type: "Utf8".to_string(),
+                nullable: true,
+            },
+        ])
+        .unwrap()
+        .unwrap();
+
+        // Fields outside the schema are dropped
+        let content = encode_file(
+            &records(),
+            FileFormat::Parquet,
+            FileCompression::Zstd,
+            Some(&schema),
+        )
+        .unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(content)).unwrap();
+        assert_eq!(reader.schema().fields().len(), 2);
+
+        let invalid = json!({"id": "not a number", "type": "click"});
+        assert!(check_record(&records()[0], Some(&schema)).is_ok());
+        assert!(check_record(&invalid, Some(&schema)).is_err());
+        assert!(encode_file(
+            &[records()[0].clone(), invalid],
+            FileFormat::Parquet,
+            FileCompression::None,
+            Some(&schema)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parquet_schema_types() {
+        assert!(parquet_schema(&[]).unwrap().is_none());
+        assert!(parquet_schema(&[SchemaField {
+            name: "amount".to_string(),
+            data_type: "Decimal".to_string(),
+            nullable: true,
+        }])
+        .is_err());
+    }
+}
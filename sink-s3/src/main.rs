@@ -0,0 +1,72 @@
+//! Object Storage Sink Connector for Danube Connect
+//!
+//! This connector consumes messages from Danube topics and writes them as JSONL or Parquet
+//! files to S3, GCS or Azure Blob Storage, partitioned by time under a key template.
+
+mod config;
+mod connector;
+mod encode;
+mod record;
+mod storage;
+mod template;
+
+use config::S3SinkConfig;
+use connector::S3SinkConnector;
+use danube_connect_core::{ConnectorResult, SinkRuntime};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> ConnectorResult<()> {
+    // Initialize logging first
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info,danube_sink_s3=debug"));
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_target(true))
+        .try_init()
+        .ok(); // Ignore error if already initialized
+
+    tracing::info!("Starting Object Storage Sink Connector");
+    tracing::info!("Version: {}", env!("CARGO_PKG_VERSION"));
+
+    // Load unified configuration from single file (TOML + ENV overrides)
+    let config = S3SinkConfig::load().map_err(|e| {
+        tracing::error!("Failed to load configuration: {}", e);
+        e
+    })?;
+
+    // Validate configuration
+    config.validate()?;
+
+    tracing::info!("Configuration loaded and validated successfully");
+    tracing::info!("Connector: {}", config.core.connector_name);
+    tracing::info!("Danube URL: {}", config.core.danube_service_url);
+    tracing::info!("Storage Backend: {:?}", config.s3.storage_backend);
+    tracing::info!("Bucket: {}", config.s3.bucket);
+    tracing::info!("Routes: {} configured", config.s3.routes.len());
+
+    for (idx, mapping) in config.s3.routes.iter().enumerate() {
+        tracing::info!(
+            "  Route {}: Topic '{}' → '{}' ({:?}, {:?})",
+            idx + 1,
+            mapping.from,
+            mapping.key_template,
+            mapping.format,
+            mapping.compression()
+        );
+    }
+
+    // Create connector instance with object storage configuration
+    let connector = S3SinkConnector::with_config(config.clone())?;
+
+    // Create and run the sink runtime
+    tracing::info!("Initializing connector runtime...");
+    let mut runtime = SinkRuntime::new(connector, config.core).await?;
+
+    // Run until shutdown signal
+    runtime.run().await?;
+
+    tracing::info!("Object Storage Sink Connector terminated");
+    Ok(())
+}
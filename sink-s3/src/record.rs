@@ -0,0 +1,91 @@
+//! Record processing module for the object storage Sink Connector
+//!
+//! This module turns Danube messages into file records and picks the time
+//! each record is partitioned by. Payloads are already deserialized as
+//! serde_json::Value by the runtime.
+
+use crate::config::{PartitionTime, TopicMapping};
+use chrono::{DateTime, Utc};
+use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
+use serde_json::{json, Value};
+
+/// Field holding Danube metadata when `include_danube_metadata` is set
+pub const METADATA_FIELD: &str = "_danube_metadata";
+
+/// Convert a Danube SinkRecord into the record written to files
+///
+/// With `include_danube_metadata`, the payload must be a JSON object.
+pub fn to_file_record(record: &SinkRecord, mapping: &TopicMapping) -> ConnectorResult<Value> {
+    let metadata = mapping
+        .include_danube_metadata
+        .then(|| danube_metadata(record));
+
+    with_metadata(record.payload().clone(), metadata).map_err(|e| {
+        ConnectorError::invalid_data(
+            format!(
+                "Failed to convert message on topic {}: {}",
+                record.topic(),
+                e
+            ),
+            serde_json::to_vec(record.payload()).unwrap_or_default(),
+        )
+    })
+}
+
+/// Time rendered into the key template for a record
+pub fn partition_time(record: &SinkRecord, mapping: &TopicMapping) -> DateTime<Utc> {
+    match mapping.partition_time {
+        PartitionTime::PublishTime => publish_time(record),
+        PartitionTime::ProcessingTime => Utc::now(),
+    }
+}
+
+/// Add the metadata object to a payload
+fn with_metadata(mut data: Value, metadata: Option<Value>) -> Result<Value, String> {
+    let Some(metadata) = metadata else {
+        return Ok(data);
+    };
+    let Value::Object(fields) = &mut data else {
+        return Err("payload must be a JSON object to include Danube metadata".to_string());
+    };
+    fields.insert(METADATA_FIELD.to_string(), metadata);
+    Ok(data)
+}
+
+/// Publish time of a record (microseconds since epoch)
+fn publish_time(record: &SinkRecord) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(record.publish_time() as i64).unwrap_or_else(Utc::now)
+}
+
+/// Build the Danube metadata object of a record
+fn danube_metadata(record: &SinkRecord) -> Value {
+    json!({
+        "danube_topic": record.topic(),
+        "danube_timestamp": publish_time(record).to_rfc3339(),
+        "danube_producer": record.producer_name(),
+        "danube_offset": record.offset(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // SinkRecord can't be constructed outside danube-connect-core, so the
+    // conversion is tested on payloads.
+
+    use super::*;
+
+    #[test]
+    fn test_with_metadata() {
+        let payload = json!({"id": 1});
+        assert_eq!(with_metadata(payload.clone(), None).unwrap(), payload);
+
+        let metadata = json!({"danube_topic": "/default/events"});
+        let record = with_metadata(payload, Some(metadata.clone())).unwrap();
+        assert_eq!(record["id"], 1);
+        assert_eq!(record[METADATA_FIELD], metadata);
+
+        // Non-object payloads are written as-is, but can't carry metadata
+        assert_eq!(with_metadata(json!([1, 2]), None).unwrap(), json!([1, 2]));
+        assert!(with_metadata(json!([1, 2]), Some(metadata)).is_err());
+    }
+}
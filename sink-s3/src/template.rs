@@ -0,0 +1,196 @@
+//! Object key templates
+//!
+//! A route's `key_template` decides where its files go, e.g.
+//! `events/dt={date}/hour={hour}/part-{part}.{ext}`. The date and time
+//! placeholders partition files by time, and `{part}` numbers the files of a
+//! partition (`part-0001`, `part-0002`, ...) so every file gets a new key.
+//!
+//! Placeholders: `{topic}` (without the leading '/'), `{date}` (YYYY-MM-DD),
+//! `{year}`, `{month}`, `{day}`, `{hour}`, `{minute}`, `{part}`, and `{ext}`
+//! (the format's file extension).
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+/// A parsed key template
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Topic,
+    Date,
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Part,
+    Ext,
+}
+
+impl KeyTemplate {
+    /// Parse a template, checking its placeholders
+    ///
+    /// The template must contain `{part}` once, in its last path segment, so
+    /// the files of a partition can be numbered by listing their folder.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        if template.is_empty() || template.starts_with('/') {
+            return Err("must be a relative key, e.g. \"{topic}/part-{part}.{ext}\"".to_string());
+        }
+
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or("unclosed '{'")?;
+            segments.push(match &rest[start + 1..end] {
+                "topic" => Segment::Topic,
+                "date" => Segment::Date,
+                "year" => Segment::Year,
+                "month" => Segment::Month,
+                "day" => Segment::Day,
+                "hour" => Segment::Hour,
+                "minute" => Segment::Minute,
+                "part" => Segment::Part,
+                "ext" => Segment::Ext,
+                name => return Err(format!("unknown placeholder '{{{}}}'", name)),
+            });
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+
+        let parts = segments.iter().filter(|s| **s == Segment::Part).count();
+        if parts != 1 {
+            return Err("must contain '{part}' exactly once".to_string());
+        }
+        let after_part = segments
+            .iter()
+            .skip_while(|s| **s != Segment::Part)
+            .any(|s| {
+                matches!(s, Segment::Text(text) if text.contains('/')) || *s == Segment::Topic
+            });
+        if after_part {
+            return Err("'{part}' must be in the last path segment".to_string());
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Render the template for a topic and time, except for the part number
+    pub fn render(&self, topic: &str, time: DateTime<Utc>, ext: &str) -> PartKey {
+        let mut before = String::new();
+        let mut after = String::new();
+        let mut output = &mut before;
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => output.push_str(text),
+                Segment::Topic => output.push_str(topic.trim_start_matches('/')),
+                Segment::Date => output.push_str(&time.format("%Y-%m-%d").to_string()),
+                Segment::Year => output.push_str(&format!("{:04}", time.year())),
+                Segment::Month => output.push_str(&format!("{:02}", time.month())),
+                Segment::Day => output.push_str(&format!("{:02}", time.day())),
+                Segment::Hour => output.push_str(&format!("{:02}", time.hour())),
+                Segment::Minute => output.push_str(&format!("{:02}", time.minute())),
+                Segment::Ext => output.push_str(ext),
+                Segment::Part => output = &mut after,
+            }
+        }
+
+        PartKey { before, after }
+    }
+}
+
+/// A rendered key, around its part number
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartKey {
+    /// Key text before the part number
+    pub before: String,
+    /// Key text after the part number
+    pub after: String,
+}
+
+impl PartKey {
+    /// The object key of a part (numbers have at least four digits)
+    pub fn key(&self, part: u64) -> String {
+        format!("{}{:04}{}", self.before, part, self.after)
+    }
+
+    /// Folder holding the parts (empty for the bucket root)
+    pub fn folder(&self) -> &str {
+        self.before
+            .rfind('/')
+            .map_or("", |index| &self.before[..index])
+    }
+
+    /// Read the part number of an existing key, if it is a part of this key
+    pub fn part_of(&self, key: &str) -> Option<u64> {
+        let digits = key.strip_prefix(&self.before)?.strip_suffix(&self.after)?;
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 12, 5, 0).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let template =
+            KeyTemplate::parse("events/dt={date}/hour={hour}/part-{part}.{ext}").unwrap();
+        let key = template.render("/default/events", time(), "parquet");
+        assert_eq!(key.key(1), "events/dt=2024-06-01/hour=12/part-0001.parquet");
+        assert_eq!(
+            key.key(12345),
+            "events/dt=2024-06-01/hour=12/part-12345.parquet"
+        );
+        assert_eq!(key.folder(), "events/dt=2024-06-01/hour=12");
+
+        let template =
+            KeyTemplate::parse("{topic}/{year}/{month}/{day}/{hour}{minute}-{part}.{ext}").unwrap();
+        let key = template.render("/default/events", time(), "jsonl.gz");
+        assert_eq!(key.key(7), "default/events/2024/06/01/1205-0007.jsonl.gz");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(KeyTemplate::parse("").is_err());
+        assert!(KeyTemplate::parse("/events/{part}.jsonl").is_err());
+        assert!(KeyTemplate::parse("events/{date}.jsonl").is_err());
+        assert!(KeyTemplate::parse("events/{part}-{part}.jsonl").is_err());
+        assert!(KeyTemplate::parse("events/{uuid}-{part}.jsonl").is_err());
+        assert!(KeyTemplate::parse("events/{part.jsonl").is_err());
+        assert!(KeyTemplate::parse("events/{part}/{date}.jsonl").is_err());
+    }
+
+    #[test]
+    fn test_part_of() {
+        let template = KeyTemplate::parse("events/part-{part}.{ext}").unwrap();
+        let key = template.render("/default/events", time(), "jsonl");
+
+        assert_eq!(key.part_of("events/part-0042.jsonl"), Some(42));
+        assert_eq!(key.part_of("events/part-10000.jsonl"), Some(10000));
+        assert_eq!(key.part_of("events/part-0042.parquet"), None);
+        assert_eq!(key.part_of("events/part-.jsonl"), None);
+        assert_eq!(key.part_of("events/part-abc.jsonl"), None);
+        assert_eq!(key.part_of("other/part-0001.jsonl"), None);
+    }
+}
@@ -11,6 +11,7 @@ use danube_connect_core::{
     ConnectorResult, SubscriptionType,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 
 /// Storage mode for SurrealDB records
@@ -21,6 +22,180 @@ pub enum StorageMode {
     Document,
     /// Store as time-series data with timestamp optimization
     TimeSeries,
+    /// Store as graph edges (RELATE) between two records, using the `graph` settings
+    Graph,
+}
+
+/// Authentication level used to sign in to SurrealDB
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum AuthLevel {
+    /// Root user (default); skipped if no credentials are set
+    Root,
+    /// Namespace user, scoped to `surrealdb.namespace`
+    Namespace,
+    /// Database user, scoped to `surrealdb.namespace` / `surrealdb.database`
+    Database,
+    /// Record user, signed in through a `DEFINE ACCESS ... TYPE RECORD` method
+    Record,
+}
+
+/// Graph edge settings for the Graph storage mode
+///
+/// Each message becomes an edge `from_table:<from_field> -> <route.to> -> to_table:<to_field>`,
+/// e.g. `user:u1 -> purchased -> product:p9`, with the payload as edge content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphConfig {
+    /// Table of the edge's source record (e.g., "user")
+    pub from_table: String,
+
+    /// Payload field holding the source record ID (dotted paths supported)
+    pub from_field: String,
+
+    /// Table of the edge's target record (e.g., "product")
+    pub to_table: String,
+
+    /// Payload field holding the target record ID (dotted paths supported)
+    pub to_field: String,
+}
+
+/// Delete (tombstone) convention for CDC-style replication
+///
+/// Matching messages issue `DELETE table:id` instead of writing a record.
+/// The record ID comes from `id_field` / `id_template` or the `record_id` attribute;
+/// null payloads can only use the `record_id` attribute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteConfig {
+    /// Treat null payloads (tombstones) as deletes (default: true)
+    #[serde(default = "default_delete_on_null")]
+    pub on_null_payload: bool,
+
+    /// Payload field marking the operation (e.g., "op"); disabled if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op_field: Option<String>,
+
+    /// Value of `op_field` that marks a delete (default: "delete")
+    #[serde(default = "default_delete_op_value")]
+    pub op_value: String,
+}
+
+/// How a custom statement template is executed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum StatementMode {
+    /// Once per record, with `$data` (record content) and `$id` (record ID or NONE)
+    PerRecord,
+    /// Once per batch, with `$records` (all rows, `id` included when known)
+    PerBatch,
+}
+
+/// Custom SurrealQL statement replacing the default write for a route
+///
+/// Example: `UPDATE type::thing('user', $data.id) MERGE $data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementTemplate {
+    /// Parameterized SurrealQL statement
+    pub query: String,
+
+    /// Execution mode: PerRecord (default) or PerBatch
+    #[serde(default)]
+    pub mode: StatementMode,
+}
+
+/// Per-route deduplication of redelivered records by record ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// How long a written record ID is remembered, in seconds (default: 300)
+    #[serde(default = "default_dedup_window_secs")]
+    pub window_secs: u64,
+
+    /// Maximum number of record IDs remembered (default: 100000)
+    #[serde(default = "default_dedup_max_entries")]
+    pub max_entries: usize,
+}
+
+/// What to do with records that can't be written
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum ErrorPolicy {
+    /// Fail the batch (retryable) until it succeeds (default)
+    Fail,
+    /// Log and drop the failing records
+    Skip,
+    /// Publish the failing records to `dlq_topic`
+    Dlq,
+}
+
+/// Per-route handling of records that can't be written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorHandling {
+    /// Error policy: Fail, Skip, or Dlq
+    #[serde(default)]
+    pub policy: ErrorPolicy,
+
+    /// Failed batch attempts before failing records are skipped or dead-lettered
+    #[serde(default = "default_error_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Danube topic receiving failed records (required for the Dlq policy)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dlq_topic: Option<String>,
+}
+
+impl ErrorHandling {
+    /// Whether failing records are skipped or dead-lettered instead of failing the batch
+    pub fn isolates_records(&self) -> bool {
+        self.policy != ErrorPolicy::Fail
+    }
+}
+
+/// Table schema applied with DEFINE statements at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableSchema {
+    /// SCHEMAFULL (only defined fields are stored) or SCHEMALESS (default: true)
+    #[serde(default = "default_schemafull")]
+    pub schemafull: bool,
+
+    /// Field definitions
+    #[serde(default)]
+    pub fields: Vec<FieldDefinition>,
+
+    /// Index definitions
+    #[serde(default)]
+    pub indexes: Vec<IndexDefinition>,
+}
+
+/// Field definition (DEFINE FIELD)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDefinition {
+    /// Field name (nested fields use dots, e.g., "address.city")
+    pub name: String,
+
+    /// SurrealQL type (e.g., "string", "number", "datetime", "option<string>")
+    #[serde(rename = "type")]
+    pub field_type: String,
+
+    /// Optional default value expression (e.g., "0", "time::now()")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Optional assertion (e.g., "$value > 0")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assert: Option<String>,
+}
+
+/// Index definition (DEFINE INDEX)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    /// Index name
+    pub name: String,
+
+    /// Indexed fields
+    pub fields: Vec<String>,
+
+    /// Enforce uniqueness
+    #[serde(default)]
+    pub unique: bool,
 }
 
 /// Complete configuration for the SurrealDB Sink Connector
@@ -40,6 +215,10 @@ pub struct SurrealDBConfig {
     /// SurrealDB connection URL (e.g., "ws://localhost:8000", "http://localhost:8000")
     pub url: String,
 
+    /// Connection transport: ws (WebSocket) or http
+    #[serde(default)]
+    pub engine: Engine,
+
     /// SurrealDB namespace
     pub namespace: String,
 
@@ -54,6 +233,18 @@ pub struct SurrealDBConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
 
+    /// Authentication level: Root, Namespace, Database, or Record
+    #[serde(default)]
+    pub auth_level: AuthLevel,
+
+    /// Record access method name (required for the Record auth level)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access: Option<String>,
+
+    /// Sign-in parameters passed to the record access method (e.g., email, pass)
+    #[serde(default)]
+    pub access_params: HashMap<String, String>,
+
     /// Connection timeout in seconds
     #[serde(default = "default_connection_timeout")]
     pub connection_timeout_secs: u64,
@@ -62,11 +253,59 @@ pub struct SurrealDBConfig {
     #[serde(default = "default_request_timeout")]
     pub request_timeout_secs: u64,
 
+    /// Reconnect attempts after a lost connection before giving up on a flush
+    #[serde(default = "default_reconnect_max_attempts")]
+    pub reconnect_max_attempts: u32,
+
+    /// Initial reconnect backoff in milliseconds (doubles per attempt)
+    #[serde(default = "default_reconnect_backoff_ms")]
+    pub reconnect_backoff_ms: u64,
+
+    /// Maximum reconnect backoff in milliseconds
+    #[serde(default = "default_reconnect_max_backoff_ms")]
+    pub reconnect_max_backoff_ms: u64,
+
+    /// Maximum number of tables flushed concurrently per batch
+    #[serde(default = "default_max_parallel_flushes")]
+    pub max_parallel_flushes: usize,
+
+    /// TLS settings for `wss://` / `https://` URLs (optional, defaults apply if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+
     /// Routes: Danube topics → SurrealDB tables
     #[serde(default)]
     pub routes: Vec<TopicMapping>,
 }
 
+/// Connection transport to SurrealDB
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    /// WebSocket: one persistent connection (recommended)
+    Ws,
+    /// HTTP: one request per query, for deployments exposing only the HTTP API
+    Http,
+}
+
+/// TLS settings for secure (`wss://` / `https://`) connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Path to a PEM bundle of additional CA certificates (e.g., a private CA)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<String>,
+
+    /// Trust the public web PKI roots (default: true)
+    /// Disable to trust only `ca_cert_path`
+    #[serde(default = "default_true")]
+    pub trust_public_roots: bool,
+
+    /// Verify the server certificate (default: true)
+    /// Disabling this is insecure and intended for testing only
+    #[serde(default = "default_true")]
+    pub verify_certificates: bool,
+}
+
 /// Mapping from a Danube topic to a SurrealDB table
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopicMapping {
@@ -83,6 +322,14 @@ pub struct TopicMapping {
     /// SurrealDB table name to insert into
     pub to: String,
 
+    /// Namespace override for this route (defaults to `surrealdb.namespace`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Database override for this route (defaults to `surrealdb.database`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+
     /// Include Danube metadata in records (topic, offset, timestamp)
     #[serde(default = "default_include_metadata")]
     pub include_danube_metadata: bool,
@@ -96,6 +343,59 @@ pub struct TopicMapping {
     /// Storage mode: Document or TimeSeries
     #[serde(default)]
     pub storage_mode: StorageMode,
+
+    /// Payload field holding the event time for TimeSeries mode (dotted paths supported)
+    /// Falls back to the Danube publish time when missing or unparseable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_field: Option<String>,
+
+    /// Format of `timestamp_field`: "rfc3339" (default), "unix_s", "unix_ms", "unix_us",
+    /// or a chrono pattern (e.g., "%Y-%m-%d %H:%M:%S")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+
+    /// Graph edge settings (required when storage_mode = "Graph")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graph: Option<GraphConfig>,
+
+    /// Delete (tombstone) handling (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<DeleteConfig>,
+
+    /// Table schema applied via DEFINE statements at startup (optional)
+    /// Without it, tables are created implicitly and schemaless
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<TableSchema>,
+
+    /// Custom SurrealQL statement used instead of the default INSERT IGNORE/RELATE (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement: Option<StatementTemplate>,
+
+    /// Error handling for records that can't be written (default: fail the batch)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<ErrorHandling>,
+
+    /// Drop records whose ID was written within a recent window (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup: Option<DedupConfig>,
+
+    /// Payload field used as the record ID (supports dotted paths, e.g., "user.id")
+    /// Takes precedence over the `record_id` message attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_field: Option<String>,
+
+    /// Record ID template built from payload fields (e.g., "{user_id}:{ts}")
+    /// Takes precedence over the `record_id` message attribute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_template: Option<String>,
+
+    /// Rename payload fields: source → target (dotted paths supported)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub field_map: HashMap<String, String>,
+
+    /// Payload fields to drop before writing (dotted paths supported)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_fields: Vec<String>,
 }
 
 // Default value functions
@@ -107,20 +407,125 @@ fn default_request_timeout() -> u64 {
     30
 }
 
+fn default_reconnect_max_attempts() -> u32 {
+    10
+}
+
+fn default_reconnect_backoff_ms() -> u64 {
+    500
+}
+
+fn default_reconnect_max_backoff_ms() -> u64 {
+    30_000
+}
+
+fn default_max_parallel_flushes() -> usize {
+    4
+}
+
+fn default_dedup_window_secs() -> u64 {
+    300
+}
+
+fn default_dedup_max_entries() -> usize {
+    100_000
+}
+
+fn default_error_max_attempts() -> u32 {
+    3
+}
+
+fn default_true() -> bool {
+    true
+}
+
 fn default_include_metadata() -> bool {
     true
 }
 
+fn default_delete_on_null() -> bool {
+    true
+}
+
+fn default_delete_op_value() -> String {
+    "delete".to_string()
+}
+
+fn default_schemafull() -> bool {
+    true
+}
+
 fn default_subscription_type() -> SubscriptionType {
     SubscriptionType::Shared
 }
 
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::Ws
+    }
+}
+
+impl Default for AuthLevel {
+    fn default() -> Self {
+        AuthLevel::Root
+    }
+}
+
+impl Default for ErrorPolicy {
+    fn default() -> Self {
+        ErrorPolicy::Fail
+    }
+}
+
+impl Default for StatementMode {
+    fn default() -> Self {
+        StatementMode::PerRecord
+    }
+}
+
 impl Default for StorageMode {
     fn default() -> Self {
         StorageMode::Document
     }
 }
 
+impl SurrealDBConfig {
+    /// Connection endpoint for the configured engine
+    ///
+    /// The URL scheme is optional; `wss://` and `https://` select TLS.
+    pub fn endpoint(&self) -> String {
+        let (scheme, address) = self.url.split_once("://").unwrap_or(("", &self.url));
+        let secure = matches!(scheme, "wss" | "https");
+        let scheme = match (self.engine, secure) {
+            (Engine::Ws, false) => "ws",
+            (Engine::Ws, true) => "wss",
+            (Engine::Http, false) => "http",
+            (Engine::Http, true) => "https",
+        };
+        format!("{}://{}", scheme, address)
+    }
+
+    /// Whether the connection uses TLS
+    pub fn is_secure(&self) -> bool {
+        self.url.starts_with("wss://") || self.url.starts_with("https://")
+    }
+
+    /// Whether any route writes outside the default namespace/database
+    pub fn has_scoped_routes(&self) -> bool {
+        self.routes
+            .iter()
+            .any(|mapping| mapping.namespace.is_some() || mapping.database.is_some())
+    }
+
+    /// Namespace and database a route writes to
+    pub fn route_scope<'a>(&'a self, mapping: &'a TopicMapping) -> (&'a str, &'a str) {
+        (
+            mapping.namespace.as_deref().unwrap_or(&self.namespace),
+            mapping.database.as_deref().unwrap_or(&self.database),
+        )
+    }
+}
+
 impl SurrealDBSinkConfig {
     /// Load configuration from TOML file
     ///
@@ -142,6 +547,51 @@ impl SurrealDBSinkConfig {
     }
 }
 
+impl SurrealDBSinkConfig {
+    /// Check that the auth level has its credentials and covers all routes
+    fn validate_auth(&self) -> ConnectorResult<()> {
+        let surrealdb = &self.surrealdb;
+        let has_credentials = surrealdb.username.is_some() && surrealdb.password.is_some();
+
+        match surrealdb.auth_level {
+            AuthLevel::Root => {}
+            AuthLevel::Namespace | AuthLevel::Database if !has_credentials => {
+                return Err(ConnectorError::config(format!(
+                    "auth_level '{:?}' requires username and password",
+                    surrealdb.auth_level
+                )));
+            }
+            AuthLevel::Namespace | AuthLevel::Database => {}
+            AuthLevel::Record => {
+                if surrealdb.access.as_deref().map_or(true, str::is_empty) {
+                    return Err(ConnectorError::config(
+                        "auth_level 'Record' requires an access method name",
+                    ));
+                }
+            }
+        }
+
+        // Scoped users can't switch to other namespaces/databases
+        for mapping in &surrealdb.routes {
+            let leaves_scope = match surrealdb.auth_level {
+                AuthLevel::Root => false,
+                AuthLevel::Namespace => mapping.namespace.is_some(),
+                AuthLevel::Database | AuthLevel::Record => {
+                    mapping.namespace.is_some() || mapping.database.is_some()
+                }
+            };
+            if leaves_scope {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': namespace/database overrides are outside the scope of auth_level '{:?}'",
+                    mapping.from, surrealdb.auth_level
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl ConfigEnvOverrides for SurrealDBSinkConfig {
     fn apply_env_overrides(&mut self) -> ConnectorResult<()> {
         if let Ok(danube_url) = env::var("DANUBE_SERVICE_URL") {
@@ -183,6 +633,64 @@ impl ConfigValidate for SurrealDBSinkConfig {
         if self.surrealdb.database.is_empty() {
             return Err(ConnectorError::config("SURREALDB_DATABASE cannot be empty"));
         }
+        if self.surrealdb.has_scoped_routes()
+            && (self.surrealdb.namespace.contains('`') || self.surrealdb.database.contains('`'))
+        {
+            return Err(ConnectorError::config(
+                "SurrealDB namespace/database names cannot contain '`'",
+            ));
+        }
+
+        self.validate_auth()?;
+
+        if let Some((scheme, _)) = self.surrealdb.url.split_once("://") {
+            let engine = match scheme {
+                "ws" | "wss" => Engine::Ws,
+                "http" | "https" => Engine::Http,
+                _ => {
+                    return Err(ConnectorError::config(format!(
+                        "Unsupported SurrealDB URL scheme '{}://'",
+                        scheme
+                    )))
+                }
+            };
+            if engine != self.surrealdb.engine {
+                return Err(ConnectorError::config(format!(
+                    "SurrealDB URL '{}' doesn't match engine '{:?}'",
+                    self.surrealdb.url, self.surrealdb.engine
+                )));
+            }
+        }
+
+        if let Some(tls) = &self.surrealdb.tls {
+            if !self.surrealdb.is_secure() {
+                return Err(ConnectorError::config(
+                    "[surrealdb.tls] settings require a wss:// or https:// URL",
+                ));
+            }
+            if tls.verify_certificates && !tls.trust_public_roots && tls.ca_cert_path.is_none() {
+                return Err(ConnectorError::config(
+                    "TLS trust_public_roots = false requires ca_cert_path",
+                ));
+            }
+        }
+
+        if self.surrealdb.reconnect_max_attempts == 0 {
+            return Err(ConnectorError::config(
+                "reconnect_max_attempts must be at least 1",
+            ));
+        }
+        if self.surrealdb.reconnect_backoff_ms > self.surrealdb.reconnect_max_backoff_ms {
+            return Err(ConnectorError::config(
+                "reconnect_backoff_ms cannot exceed reconnect_max_backoff_ms",
+            ));
+        }
+
+        if self.surrealdb.max_parallel_flushes == 0 {
+            return Err(ConnectorError::config(
+                "max_parallel_flushes must be at least 1",
+            ));
+        }
 
         // Validate topic mappings
         if self.surrealdb.routes.is_empty() {
@@ -199,10 +707,187 @@ impl ConfigValidate for SurrealDBSinkConfig {
             if mapping.to.is_empty() {
                 return Err(ConnectorError::config("Route 'to' cannot be empty"));
             }
-            // storage_mode is an enum with default, so it's always valid
-            // Just verify it's one of the expected values (Document or TimeSeries)
-            match mapping.storage_mode {
-                StorageMode::Document | StorageMode::TimeSeries => {}
+            for name in [&mapping.namespace, &mapping.database]
+                .into_iter()
+                .flatten()
+            {
+                if name.is_empty() || name.contains('`') {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': invalid namespace/database name '{}'",
+                        mapping.from, name
+                    )));
+                }
+            }
+            if mapping.id_field.is_some() && mapping.id_template.is_some() {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': id_field and id_template are mutually exclusive",
+                    mapping.from
+                )));
+            }
+            if let Some(template) = &mapping.id_template {
+                validate_id_template(template).map_err(|e| {
+                    ConnectorError::config(format!(
+                        "Route '{}': invalid id_template '{}': {}",
+                        mapping.from, template, e
+                    ))
+                })?;
+            }
+
+            if let Some(delete) = &mapping.delete {
+                if delete.op_field.as_deref() == Some("") {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': delete op_field cannot be empty",
+                        mapping.from
+                    )));
+                }
+                if !delete.on_null_payload && delete.op_field.is_none() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': [delete] needs on_null_payload or op_field",
+                        mapping.from
+                    )));
+                }
+            }
+
+            let paths = mapping
+                .field_map
+                .iter()
+                .flat_map(|(source, target)| [source, target])
+                .chain(&mapping.exclude_fields);
+            for path in paths {
+                if path.is_empty() || path.split('.').any(str::is_empty) {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': invalid field path '{}' in field_map/exclude_fields",
+                        mapping.from, path
+                    )));
+                }
+            }
+            if let Some(target) = mapping
+                .field_map
+                .values()
+                .find(|target| mapping.field_map.contains_key(*target))
+            {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': field_map target '{}' is also a source",
+                    mapping.from, target
+                )));
+            }
+
+            if mapping.timestamp_field.is_some() && mapping.storage_mode != StorageMode::TimeSeries
+            {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': timestamp_field requires storage_mode 'TimeSeries'",
+                    mapping.from
+                )));
+            }
+            if let Some(format) = &mapping.timestamp_format {
+                if mapping.timestamp_field.is_none() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': timestamp_format requires timestamp_field",
+                        mapping.from
+                    )));
+                }
+                if !is_valid_timestamp_format(format) {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': invalid timestamp_format '{}' (use rfc3339, unix_s, unix_ms, unix_us, or a chrono pattern)",
+                        mapping.from, format
+                    )));
+                }
+            }
+
+            if let Some(on_error) = &mapping.on_error {
+                if on_error.max_attempts == 0 {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': on_error max_attempts must be at least 1",
+                        mapping.from
+                    )));
+                }
+                let has_dlq_topic = on_error
+                    .dlq_topic
+                    .as_deref()
+                    .map_or(false, |t| !t.is_empty());
+                if (on_error.policy == ErrorPolicy::Dlq) != has_dlq_topic {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': dlq_topic is required for, and only used by, policy 'Dlq'",
+                        mapping.from
+                    )));
+                }
+            }
+
+            if let Some(dedup) = &mapping.dedup {
+                if dedup.window_secs == 0 || dedup.max_entries == 0 {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': dedup window_secs and max_entries must be at least 1",
+                        mapping.from
+                    )));
+                }
+                if mapping.id_field.is_none() && mapping.id_template.is_none() {
+                    tracing::warn!(
+                        "Route '{}': dedup only applies to records with a record_id attribute",
+                        mapping.from
+                    );
+                }
+            }
+
+            if let Some(statement) = &mapping.statement {
+                if statement.query.trim().is_empty() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': statement query cannot be empty",
+                        mapping.from
+                    )));
+                }
+                if mapping.storage_mode == StorageMode::Graph {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': a custom statement replaces RELATE, use storage_mode 'Document' instead of 'Graph'",
+                        mapping.from
+                    )));
+                }
+            }
+
+            if let Some(schema) = &mapping.schema {
+                validate_table_schema(schema).map_err(|e| {
+                    ConnectorError::config(format!(
+                        "Route '{}': invalid schema: {}",
+                        mapping.from, e
+                    ))
+                })?;
+                if !is_valid_identifier(&mapping.to) {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': schema requires a plain table name, got '{}'",
+                        mapping.from, mapping.to
+                    )));
+                }
+            }
+
+            match (mapping.storage_mode, &mapping.graph) {
+                (StorageMode::Graph, Some(graph)) => {
+                    if graph.from_field.is_empty() || graph.to_field.is_empty() {
+                        return Err(ConnectorError::config(format!(
+                            "Route '{}': graph from_field and to_field cannot be empty",
+                            mapping.from
+                        )));
+                    }
+                    for table in [&mapping.to, &graph.from_table, &graph.to_table] {
+                        if !is_valid_identifier(table) {
+                            return Err(ConnectorError::config(format!(
+                                "Route '{}': invalid graph table name '{}' (use letters, digits, '_')",
+                                mapping.from, table
+                            )));
+                        }
+                    }
+                }
+                (StorageMode::Graph, None) => {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': storage_mode 'Graph' requires a [graph] section",
+                        mapping.from
+                    )));
+                }
+                (_, Some(_)) => {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': [graph] settings require storage_mode 'Graph'",
+                        mapping.from
+                    )));
+                }
+                (StorageMode::Document | StorageMode::TimeSeries, None) => {}
             }
         }
 
@@ -210,6 +895,85 @@ impl ConfigValidate for SurrealDBSinkConfig {
     }
 }
 
+/// Check that a name is a plain SurrealQL identifier (letters, digits, '_')
+pub fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check that a timestamp format is a known name or a chrono pattern
+fn is_valid_timestamp_format(format: &str) -> bool {
+    matches!(format, "rfc3339" | "unix_s" | "unix_ms" | "unix_us") || format.contains('%')
+}
+
+/// Validate DEFINE schema settings before they are interpolated into SurrealQL
+fn validate_table_schema(schema: &TableSchema) -> Result<(), String> {
+    let is_field_path = |name: &str| {
+        name.split('.')
+            .all(|part| part == "*" || is_valid_identifier(part))
+    };
+    let is_clause = |clause: &str| !clause.trim().is_empty() && !clause.contains(';');
+
+    for field in &schema.fields {
+        if !is_field_path(&field.name) {
+            return Err(format!("invalid field name '{}'", field.name));
+        }
+        if !is_clause(&field.field_type) {
+            return Err(format!("invalid type for field '{}'", field.name));
+        }
+        let clauses = [&field.default, &field.assert];
+        if clauses
+            .into_iter()
+            .flatten()
+            .any(|clause| !is_clause(clause))
+        {
+            return Err(format!(
+                "invalid default or assert for field '{}'",
+                field.name
+            ));
+        }
+    }
+
+    for index in &schema.indexes {
+        if !is_valid_identifier(&index.name) {
+            return Err(format!("invalid index name '{}'", index.name));
+        }
+        if index.fields.is_empty() || !index.fields.iter().all(|f| is_field_path(f)) {
+            return Err(format!("invalid fields for index '{}'", index.name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that an ID template has balanced, non-empty `{field}` placeholders
+fn validate_id_template(template: &str) -> Result<(), String> {
+    let mut placeholders = 0;
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err("unmatched '}'".to_string());
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| "unclosed '{'".to_string())?;
+        let field = &rest[start + 1..start + end];
+        if field.is_empty() || field.contains('{') {
+            return Err("placeholders must name a field, e.g. {user_id}".to_string());
+        }
+        placeholders += 1;
+        rest = &rest[start + end + 1..];
+    }
+
+    if placeholders == 0 {
+        return Err("template must contain at least one {field} placeholder".to_string());
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,20 +990,43 @@ mod tests {
             },
             surrealdb: SurrealDBConfig {
                 url: "ws://localhost:8000".to_string(),
+                engine: Engine::Ws,
                 namespace: "test".to_string(),
                 database: "test".to_string(),
                 username: None,
                 password: None,
+                auth_level: AuthLevel::Root,
+                access: None,
+                access_params: HashMap::new(),
                 connection_timeout_secs: 30,
                 request_timeout_secs: 30,
+                reconnect_max_attempts: 10,
+                reconnect_backoff_ms: 500,
+                reconnect_max_backoff_ms: 30_000,
+                max_parallel_flushes: 4,
+                tls: None,
                 routes: vec![TopicMapping {
                     from: "/test/topic".to_string(),
                     subscription: "test-sub".to_string(),
                     subscription_type: SubscriptionType::Shared,
                     to: "events".to_string(),
+                    namespace: None,
+                    database: None,
                     include_danube_metadata: true,
                     expected_schema_subject: None,
                     storage_mode: StorageMode::Document,
+                    timestamp_field: None,
+                    timestamp_format: None,
+                    graph: None,
+                    delete: None,
+                    id_field: None,
+                    id_template: None,
+                    field_map: HashMap::new(),
+                    exclude_fields: Vec::new(),
+                    schema: None,
+                    statement: None,
+                    on_error: None,
+                    dedup: None,
                 }],
             },
         };
@@ -249,6 +1036,13 @@ mod tests {
         // Test empty URL
         config.surrealdb.url = "".to_string();
         assert!(config.validate().is_err());
+
+        // Test URL scheme not matching the engine
+        config.surrealdb.url = "http://localhost:8000".to_string();
+        assert!(config.validate().is_err());
+        config.surrealdb.engine = Engine::Http;
+        assert!(config.validate().is_ok());
+        config.surrealdb.engine = Engine::Ws;
         config.surrealdb.url = "ws://localhost:8000".to_string();
 
         // Test empty topic mappings
@@ -268,30 +1062,67 @@ mod tests {
             },
             surrealdb: SurrealDBConfig {
                 url: "ws://localhost:8000".to_string(),
+                engine: Engine::Ws,
                 namespace: "test".to_string(),
                 database: "test".to_string(),
                 username: None,
                 password: None,
+                auth_level: AuthLevel::Root,
+                access: None,
+                access_params: HashMap::new(),
                 connection_timeout_secs: 30,
                 request_timeout_secs: 30,
+                reconnect_max_attempts: 10,
+                reconnect_backoff_ms: 500,
+                reconnect_max_backoff_ms: 30_000,
+                max_parallel_flushes: 4,
+                tls: None,
                 routes: vec![
                     TopicMapping {
                         from: "/test/document".to_string(),
                         subscription: "test-doc".to_string(),
                         subscription_type: SubscriptionType::Shared,
                         to: "documents".to_string(),
+                        namespace: None,
+                        database: None,
                         include_danube_metadata: true,
                         expected_schema_subject: None,
                         storage_mode: StorageMode::Document,
+                        timestamp_field: None,
+                        timestamp_format: None,
+                        graph: None,
+                        delete: None,
+                        id_field: None,
+                        id_template: None,
+                        field_map: HashMap::new(),
+                        exclude_fields: Vec::new(),
+                        schema: None,
+                        statement: None,
+                        on_error: None,
+                        dedup: None,
                     },
                     TopicMapping {
                         from: "/test/timeseries".to_string(),
                         subscription: "test-ts".to_string(),
                         subscription_type: SubscriptionType::Shared,
                         to: "timeseries".to_string(),
+                        namespace: None,
+                        database: None,
                         include_danube_metadata: true,
                         expected_schema_subject: None,
                         storage_mode: StorageMode::TimeSeries,
+                        timestamp_field: None,
+                        timestamp_format: None,
+                        graph: None,
+                        delete: None,
+                        id_field: None,
+                        id_template: None,
+                        field_map: HashMap::new(),
+                        exclude_fields: Vec::new(),
+                        schema: None,
+                        statement: None,
+                        on_error: None,
+                        dedup: None,
                     },
                 ],
             },
@@ -301,11 +1132,200 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_validate_id_template() {
+        assert!(validate_id_template("{user_id}").is_ok());
+        assert!(validate_id_template("{user_id}:{ts}").is_ok());
+        assert!(validate_id_template("order-{order.id}").is_ok());
+        assert!(validate_id_template("static").is_err());
+        assert!(validate_id_template("{}").is_err());
+        assert!(validate_id_template("{user_id").is_err());
+        assert!(validate_id_template("user_id}").is_err());
+    }
+
+    #[test]
+    fn test_is_valid_timestamp_format() {
+        assert!(is_valid_timestamp_format("rfc3339"));
+        assert!(is_valid_timestamp_format("unix_ms"));
+        assert!(is_valid_timestamp_format("%Y-%m-%d %H:%M:%S"));
+        assert!(!is_valid_timestamp_format("epoch"));
+    }
+
+    #[test]
+    fn test_is_valid_identifier() {
+        assert!(is_valid_identifier("purchased"));
+        assert!(is_valid_identifier("user_events2"));
+        assert!(!is_valid_identifier(""));
+        assert!(!is_valid_identifier("2users"));
+        assert!(!is_valid_identifier("user; DELETE user"));
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let mut surrealdb: SurrealDBConfig = toml::from_str(
+            r#"
+            url = "ws://localhost:8000"
+            namespace = "test"
+            database = "test"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(surrealdb.engine, Engine::Ws);
+        assert_eq!(surrealdb.endpoint(), "ws://localhost:8000");
+
+        surrealdb.engine = Engine::Http;
+        surrealdb.url = "http://localhost:8000".to_string();
+        assert_eq!(surrealdb.endpoint(), "http://localhost:8000");
+
+        surrealdb.url = "https://db.example.com".to_string();
+        assert_eq!(surrealdb.endpoint(), "https://db.example.com");
+        assert!(surrealdb.is_secure());
+
+        // The scheme is optional, the engine selects the transport
+        surrealdb.url = "localhost:8000".to_string();
+        assert_eq!(surrealdb.endpoint(), "http://localhost:8000");
+    }
+
+    #[test]
+    fn test_auth_validation() {
+        let mut config = SurrealDBSinkConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(),
+            },
+            surrealdb: SurrealDBConfig {
+                url: "ws://localhost:8000".to_string(),
+                engine: Engine::Ws,
+                namespace: "test".to_string(),
+                database: "test".to_string(),
+                username: None,
+                password: None,
+                auth_level: AuthLevel::Database,
+                access: None,
+                access_params: HashMap::new(),
+                connection_timeout_secs: 30,
+                request_timeout_secs: 30,
+                reconnect_max_attempts: 10,
+                reconnect_backoff_ms: 500,
+                reconnect_max_backoff_ms: 30_000,
+                max_parallel_flushes: 4,
+                tls: None,
+                routes: vec![TopicMapping {
+                    from: "/test/topic".to_string(),
+                    subscription: "test-sub".to_string(),
+                    subscription_type: SubscriptionType::Shared,
+                    to: "events".to_string(),
+                    namespace: None,
+                    database: None,
+                    include_danube_metadata: true,
+                    expected_schema_subject: None,
+                    storage_mode: StorageMode::Document,
+                    timestamp_field: None,
+                    timestamp_format: None,
+                    graph: None,
+                    delete: None,
+                    id_field: None,
+                    id_template: None,
+                    field_map: HashMap::new(),
+                    exclude_fields: Vec::new(),
+                    schema: None,
+                    statement: None,
+                    on_error: None,
+                    dedup: None,
+                }],
+            },
+        };
+
+        // Database users need credentials
+        assert!(config.validate().is_err());
+        config.surrealdb.username = Some("writer".to_string());
+        config.surrealdb.password = Some("secret".to_string());
+        assert!(config.validate().is_ok());
+
+        // ...and can't write to other databases
+        config.surrealdb.routes[0].database = Some("other".to_string());
+        assert!(config.validate().is_err());
+        config.surrealdb.routes[0].database = None;
+
+        // Record users need an access method
+        config.surrealdb.auth_level = AuthLevel::Record;
+        assert!(config.validate().is_err());
+        config.surrealdb.access = Some("connector".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_route_scope() {
+        let mut surrealdb = SurrealDBConfig {
+            url: "ws://localhost:8000".to_string(),
+            engine: Engine::Ws,
+            namespace: "prod".to_string(),
+            database: "main".to_string(),
+            username: None,
+            password: None,
+            auth_level: AuthLevel::Root,
+            access: None,
+            access_params: HashMap::new(),
+            connection_timeout_secs: 30,
+            request_timeout_secs: 30,
+            reconnect_max_attempts: 10,
+            reconnect_backoff_ms: 500,
+            reconnect_max_backoff_ms: 30_000,
+            max_parallel_flushes: 4,
+            tls: None,
+            routes: vec![TopicMapping {
+                from: "/tenants/acme/events".to_string(),
+                subscription: "test-sub".to_string(),
+                subscription_type: SubscriptionType::Shared,
+                to: "events".to_string(),
+                namespace: None,
+                database: None,
+                include_danube_metadata: true,
+                expected_schema_subject: None,
+                storage_mode: StorageMode::Document,
+                timestamp_field: None,
+                timestamp_format: None,
+                graph: None,
+                delete: None,
+                id_field: None,
+                id_template: None,
+                field_map: HashMap::new(),
+                exclude_fields: Vec::new(),
+                schema: None,
+                statement: None,
+                on_error: None,
+                dedup: None,
+            }],
+        };
+        assert!(!surrealdb.has_scoped_routes());
+        assert_eq!(
+            surrealdb.route_scope(&surrealdb.routes[0]),
+            ("prod", "main")
+        );
+
+        surrealdb.routes[0].database = Some("acme".to_string());
+        assert!(surrealdb.has_scoped_routes());
+        assert_eq!(
+            surrealdb.route_scope(&surrealdb.routes[0]),
+            ("prod", "acme")
+        );
+    }
+
     #[test]
     fn test_default_values() {
         assert_eq!(default_connection_timeout(), 30);
         assert_eq!(default_request_timeout(), 30);
+        assert_eq!(default_reconnect_max_attempts(), 10);
+        assert_eq!(default_reconnect_backoff_ms(), 500);
+        assert_eq!(default_max_parallel_flushes(), 4);
         assert!(default_include_metadata());
         assert_eq!(StorageMode::default(), StorageMode::Document);
+        assert_eq!(AuthLevel::default(), AuthLevel::Root);
+        assert_eq!(ErrorPolicy::default(), ErrorPolicy::Fail);
+        assert_eq!(default_error_max_attempts(), 3);
+        assert_eq!(default_dedup_window_secs(), 300);
     }
 }
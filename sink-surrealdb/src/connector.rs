@@ -2,20 +2,33 @@
 //!
 //! This module implements the core connector logic for streaming messages
 //! from Danube topics to SurrealDB tables with:
-//! - Multi-topic support with per-table batching
+//! - Multi-topic support with per-table batching, flushed concurrently
 //! - Configurable batch sizes and flush intervals
-//! - Automatic retry and error handling
+//! - Automatic retry and error handling (fail, skip, or dead-letter bad records)
 //! - Performance metrics and health checks
+//! - Automatic reconnection with backoff after a lost connection
 
-use crate::config::{SurrealDBSinkConfig, TopicMapping};
+use crate::config::{
+    AuthLevel, ErrorHandling, ErrorPolicy, StatementMode, StorageMode, SurrealDBConfig,
+    SurrealDBSinkConfig, TopicMapping,
+};
+use crate::dedup::DedupWindow;
+use crate::dlq::DeadLetterQueue;
 use crate::record::{to_surrealdb_record, SurrealDBRecord};
+use crate::schema::define_statements;
+use crate::tls::client_config;
 use async_trait::async_trait;
 use danube_connect_core::{
     ConnectorConfig, ConnectorError, ConnectorResult, ConsumerConfig, SinkConnector, SinkRecord,
 };
-use std::collections::HashMap;
-use surrealdb::engine::remote::ws::{Client, Ws};
-use surrealdb::opt::auth::Root;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use surrealdb::engine::any::{self, Any};
+use surrealdb::opt::auth::{Database, Namespace, Record, Root};
+use surrealdb::opt::Config;
 use surrealdb::Surreal;
 use tracing::{debug, error, info, warn};
 
@@ -27,17 +40,62 @@ struct TableContext {
 
     /// Statistics
     records_inserted: u64,
+    records_deleted: u64,
+    records_failed: u64,
+    records_deduplicated: u64,
     batches_flushed: u64,
     last_error: Option<String>,
+
+    /// Consecutive failed attempts of the current batch (error policy)
+    failed_attempts: u32,
+
+    /// Recently written record IDs (if the route deduplicates)
+    dedup: Option<DedupWindow>,
 }
 
 impl TableContext {
     fn new(mapping: TopicMapping) -> Self {
+        let dedup = mapping.dedup.as_ref().map(|dedup| {
+            DedupWindow::new(Duration::from_secs(dedup.window_secs), dedup.max_entries)
+        });
+
         Self {
             mapping,
             records_inserted: 0,
+            records_deleted: 0,
+            records_failed: 0,
+            records_deduplicated: 0,
             batches_flushed: 0,
             last_error: None,
+            failed_attempts: 0,
+            dedup,
+        }
+    }
+
+    /// Whether a record redelivers an ID written within the dedup window, or
+    /// earlier in the current batch (`batch_ids`)
+    fn is_duplicate(&self, record: &SurrealDBRecord, batch_ids: &mut HashSet<String>) -> bool {
+        let (Some(dedup), Some(id)) = (&self.dedup, &record.id) else {
+            return false;
+        };
+        if record.delete {
+            batch_ids.remove(id);
+            return false;
+        }
+        dedup.contains(id) || !batch_ids.insert(id.clone())
+    }
+
+    /// Update the dedup window with a successfully flushed batch
+    fn remember_written(&mut self, written: Vec<(String, bool)>) {
+        let Some(dedup) = &mut self.dedup else {
+            return;
+        };
+        for (id, delete) in written {
+            if delete {
+                dedup.remove(&id);
+            } else {
+                dedup.insert(id);
+            }
         }
     }
 }
@@ -48,7 +106,13 @@ pub struct SurrealDBSinkConnector {
     config: SurrealDBSinkConfig,
 
     /// SurrealDB client connection
-    client: Option<Surreal<Client>>,
+    client: Option<Surreal<Any>>,
+
+    /// Set when the connection was lost; the next batch reconnects first
+    reconnect_needed: bool,
+
+    /// Dead-letter queue (connected if any route uses the Dlq error policy)
+    dlq: Option<DeadLetterQueue>,
 
     /// Table contexts (one per topic mapping)
     tables: HashMap<String, TableContext>,
@@ -70,6 +134,8 @@ impl SurrealDBSinkConnector {
         Self {
             config,
             client: None,
+            reconnect_needed: false,
+            dlq: None,
             tables,
         }
     }
@@ -80,80 +146,182 @@ impl SurrealDBSinkConnector {
         Ok(Self::with_config(config))
     }
 
+    /// Re-establish the connection with exponential backoff
+    ///
+    /// Each attempt opens a new connection, signs in again, and re-selects the
+    /// namespace and database, since session state doesn't survive a reconnect.
+    async fn reconnect(&mut self) -> ConnectorResult<()> {
+        let surrealdb = &self.config.surrealdb;
+        let mut backoff = Duration::from_millis(surrealdb.reconnect_backoff_ms);
+        let max_backoff = Duration::from_millis(surrealdb.reconnect_max_backoff_ms);
+
+        for attempt in 1..=surrealdb.reconnect_max_attempts {
+            match connect(surrealdb).await {
+                Ok(client) => {
+                    info!("Reconnected to SurrealDB after {} attempt(s)", attempt);
+                    self.client = Some(client);
+                    self.reconnect_needed = false;
+                    return Ok(());
+                }
+                Err(e) if attempt < surrealdb.reconnect_max_attempts => {
+                    warn!(
+                        "SurrealDB reconnect attempt {}/{} failed: {}, retrying in {:?}",
+                        attempt, surrealdb.reconnect_max_attempts, e, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+                Err(e) => {
+                    error!(
+                        "Giving up on SurrealDB reconnect after {} attempts",
+                        attempt
+                    );
+                    return Err(ConnectorError::retryable(format!(
+                        "Failed to reconnect to SurrealDB: {}",
+                        e
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Shared state for flushing table batches, possibly concurrently
+///
+/// Each flush gets exclusive access to its own table context, while the
+/// client connection and DLQ are shared.
+struct TableFlusher<'a> {
+    client: &'a Surreal<Any>,
+    surrealdb: &'a SurrealDBConfig,
+    dlq: Option<&'a DeadLetterQueue>,
+
+    /// Set when a failed flush finds the connection lost
+    connection_lost: AtomicBool,
+}
+
+impl TableFlusher<'_> {
+    /// Flush a route's batch, applying the route's error policy on failure
+    ///
+    /// With the Skip or Dlq policy, a batch still failing after `max_attempts` is
+    /// written record by record, so only the records SurrealDB rejects are skipped
+    /// or dead-lettered. A lost connection always fails the batch.
+    async fn flush_route(
+        &self,
+        context: &mut TableContext,
+        batch: Vec<(SinkRecord, SurrealDBRecord)>,
+    ) -> ConnectorResult<()> {
+        let on_error = context
+            .mapping
+            .on_error
+            .clone()
+            .filter(ErrorHandling::isolates_records);
+        let Some(on_error) = on_error else {
+            let records = batch.into_iter().map(|(_, record)| record).collect();
+            return self.flush_table(context, records).await;
+        };
+
+        let records = batch.iter().map(|(_, record)| record.clone()).collect();
+        let error = match self.flush_table(context, records).await {
+            Ok(()) => {
+                context.failed_attempts = 0;
+                return Ok(());
+            }
+            Err(e) if self.connection_lost.load(Ordering::Relaxed) => return Err(e),
+            Err(e) => e,
+        };
+
+        context.failed_attempts += 1;
+        if context.failed_attempts < on_error.max_attempts {
+            return Err(error);
+        }
+        context.failed_attempts = 0;
+
+        warn!(
+            "Batch for table '{}' failed {} times, writing its {} records individually",
+            context.mapping.to,
+            on_error.max_attempts,
+            batch.len()
+        );
+        for (sink_record, record) in batch {
+            match self.flush_table(context, vec![record]).await {
+                Ok(()) => {}
+                Err(e) if self.connection_lost.load(Ordering::Relaxed) => return Err(e),
+                Err(e) => {
+                    handle_failed_record(self.dlq, context, &on_error, &sink_record, &e).await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Flush a specific table's batch to SurrealDB
     async fn flush_table(
-        &mut self,
-        topic: &str,
+        &self,
+        context: &mut TableContext,
         records: Vec<SurrealDBRecord>,
     ) -> ConnectorResult<()> {
-        let context = self
-            .tables
-            .get_mut(topic)
-            .ok_or_else(|| ConnectorError::fatal(format!("Unknown topic: {}", topic)))?;
-
         if records.is_empty() {
             return Ok(());
         }
 
         let table_name = &context.mapping.to;
         let batch_size = records.len();
+        let deleted = records.iter().filter(|record| record.delete).count();
+        let written: Vec<_> = match context.dedup {
+            Some(_) => records
+                .iter()
+                .filter_map(|record| Some((record.id.clone()?, record.delete)))
+                .collect(),
+            None => Vec::new(),
+        };
 
         debug!(
             "Flushing {} records to SurrealDB table '{}'",
             batch_size, table_name
         );
 
-        let client = self
-            .client
-            .as_ref()
-            .ok_or_else(|| ConnectorError::fatal("SurrealDB client not initialized"))?;
+        // Write the whole batch in a single transaction (one round trip), so a
+        // failure leaves nothing behind and the retried batch applies atomically
+        // SurrealDB 2.x has serialization issues with serde_json::Value enums
+        // Workaround: Bind the rows as query parameters - SurrealDB handles the serialization
+        let (query, bindings) = transaction_query(
+            &scope_statement(self.surrealdb, &context.mapping),
+            flush_statements(&context.mapping, records),
+        );
+        let mut request = self.client.query(query);
+        for binding in bindings {
+            request = request.bind(binding);
+        }
+        let result = request.await.and_then(|response| response.check());
 
-        for record in records {
-            // SurrealDB 2.x has serialization issues with serde_json::Value enums
-            // Workaround: Use query parameters with cloned data
-            // Clone is necessary because .bind() requires 'static lifetime
-            let data = record.data.clone();
-
-            let result = match &record.id {
-                Some(id) => {
-                    // Insert with specific record ID using query parameters
-                    let thing = format!("{}:{}", table_name, id);
-                    let query = format!("CREATE {} CONTENT $data", thing);
-                    // Bind the data as a parameter - SurrealDB handles the serialization
-                    client
-                        .query(query)
-                        .bind(("data", data))
-                        .await
-                        .map(|_| ())
-                        .map_err(|e| (id.clone(), e))
-                }
-                None => {
-                    // Auto-generate ID using query parameters
-                    let query = format!("CREATE {} CONTENT $data", table_name);
-                    // Bind the data as a parameter - SurrealDB handles the serialization
-                    client
-                        .query(query)
-                        .bind(("data", data))
-                        .await
-                        .map(|_| ())
-                        .map_err(|e| (String::from("auto"), e))
-                }
-            };
+        if let Err(e) = result {
+            error!(
+                "Failed to insert batch of {} records into '{}': {}",
+                batch_size, table_name, e
+            );
+            context.last_error = Some(format!("Insert error: {}", e));
 
-            if let Err((id, e)) = result {
-                error!("Failed to insert record with ID '{}': {}", id, e);
-                context.last_error = Some(format!("Insert error: {}", e));
-                return Err(ConnectorError::retryable(format!(
-                    "Failed to insert record: {}",
-                    e
-                )));
+            // Tell a lost connection apart from a rejected query
+            if self.client.health().await.is_err() {
+                warn!("SurrealDB connection lost, reconnecting before the next batch");
+                self.connection_lost.store(true, Ordering::Relaxed);
             }
+
+            return Err(ConnectorError::retryable(format!(
+                "Failed to insert records: {}",
+                e
+            )));
         }
 
         // Update statistics
-        context.records_inserted += batch_size as u64;
+        context.records_inserted += (batch_size - deleted) as u64;
+        context.records_deleted += deleted as u64;
         context.batches_flushed += 1;
         context.last_error = None;
+        context.remember_written(written);
 
         info!(
             "Successfully flushed {} records to table '{}' (total: {}, batches: {})",
@@ -164,50 +332,340 @@ impl SurrealDBSinkConnector {
     }
 }
 
+/// Skip or dead-letter a record that can't be written
+async fn handle_failed_record(
+    dlq: Option<&DeadLetterQueue>,
+    context: &mut TableContext,
+    on_error: &ErrorHandling,
+    record: &SinkRecord,
+    error: &ConnectorError,
+) -> ConnectorResult<()> {
+    match (on_error.policy, &on_error.dlq_topic) {
+        (ErrorPolicy::Dlq, Some(dlq_topic)) => {
+            let dlq = dlq.ok_or_else(|| ConnectorError::fatal("DLQ client not initialized"))?;
+            dlq.publish(dlq_topic, &context.mapping.to, record, error)
+                .await?;
+        }
+        _ => warn!(
+            "Skipping record from {} for table '{}': {}",
+            context.mapping.from, context.mapping.to, error
+        ),
+    }
+
+    context.records_failed += 1;
+    Ok(())
+}
+
+/// Open a SurrealDB connection: connect, sign in, and select namespace/database
+async fn connect(surrealdb: &SurrealDBConfig) -> ConnectorResult<Surreal<Any>> {
+    // Connect to SurrealDB over the configured engine (wss:// and https:// use TLS)
+    let endpoint = surrealdb.endpoint();
+    let connecting = match &surrealdb.tls {
+        Some(tls) => any::connect((endpoint, Config::new().rustls(client_config(tls)?))),
+        None => any::connect(endpoint),
+    };
+    let client = tokio::time::timeout(
+        Duration::from_secs(surrealdb.connection_timeout_secs),
+        connecting,
+    )
+    .await
+    .map_err(|_| {
+        ConnectorError::retryable(format!(
+            "Timed out connecting to SurrealDB after {}s",
+            surrealdb.connection_timeout_secs
+        ))
+    })?
+    .map_err(|e| ConnectorError::retryable(format!("Failed to connect to SurrealDB: {}", e)))?;
+
+    sign_in(&client, surrealdb).await?;
+
+    // Use namespace and database
+    client
+        .use_ns(&surrealdb.namespace)
+        .use_db(&surrealdb.database)
+        .await
+        .map_err(|e| {
+            ConnectorError::retryable(format!(
+                "Failed to use namespace '{}' and database '{}': {}",
+                surrealdb.namespace, surrealdb.database, e
+            ))
+        })?;
+
+    info!(
+        "Using namespace '{}' and database '{}'",
+        surrealdb.namespace, surrealdb.database
+    );
+
+    Ok(client)
+}
+
+/// Sign in at the configured auth level
+///
+/// Root sign-in is skipped when no credentials are set (unauthenticated server).
+async fn sign_in(client: &Surreal<Any>, surrealdb: &SurrealDBConfig) -> ConnectorResult<()> {
+    let namespace = surrealdb.namespace.as_str();
+    let database = surrealdb.database.as_str();
+    let credentials = surrealdb
+        .username
+        .as_deref()
+        .zip(surrealdb.password.as_deref());
+
+    let result = match (surrealdb.auth_level, credentials) {
+        (AuthLevel::Root, None) => return Ok(()),
+        (AuthLevel::Root, Some((username, password))) => {
+            client.signin(Root { username, password }).await
+        }
+        (AuthLevel::Namespace, Some((username, password))) => {
+            client
+                .signin(Namespace {
+                    namespace,
+                    username,
+                    password,
+                })
+                .await
+        }
+        (AuthLevel::Database, Some((username, password))) => {
+            client
+                .signin(Database {
+                    namespace,
+                    database,
+                    username,
+                    password,
+                })
+                .await
+        }
+        (AuthLevel::Record, _) => {
+            client
+                .signin(Record {
+                    namespace,
+                    database,
+                    access: surrealdb.access.as_deref().unwrap_or_default(),
+                    params: &surrealdb.access_params,
+                })
+                .await
+        }
+        (AuthLevel::Namespace | AuthLevel::Database, None) => {
+            return Err(ConnectorError::config(format!(
+                "auth_level '{:?}' requires username and password",
+                surrealdb.auth_level
+            )));
+        }
+    };
+
+    result.map_err(|e| ConnectorError::fatal(format!("SurrealDB authentication failed: {}", e)))?;
+    info!(
+        "Authenticated with SurrealDB at {:?} level",
+        surrealdb.auth_level
+    );
+
+    Ok(())
+}
+
+/// Build the `USE` prefix selecting a route's namespace and database
+///
+/// All routes share one session, so once any route overrides its namespace or
+/// database every query selects its own. Otherwise the session keeps the
+/// defaults selected at initialization and no prefix is needed.
+fn scope_statement(surrealdb: &SurrealDBConfig, mapping: &TopicMapping) -> String {
+    if !surrealdb.has_scoped_routes() {
+        return String::new();
+    }
+
+    let (namespace, database) = surrealdb.route_scope(mapping);
+    format!("USE NS `{}` DB `{}`; ", namespace, database)
+}
+
+/// Wrap a batch's statements in one transaction
+///
+/// Each statement reads its rows from `$records`, so the rows are bound as
+/// `$batch0`, `$batch1`, ... and assigned to `$records` before each statement.
+fn transaction_query(
+    scope: &str,
+    statements: Vec<(String, Value)>,
+) -> (String, Vec<(String, Value)>) {
+    let mut query = format!("{}BEGIN TRANSACTION;\n", scope);
+    let mut bindings = Vec::with_capacity(statements.len());
+
+    for (index, (statement, rows)) in statements.into_iter().enumerate() {
+        let name = format!("batch{}", index);
+        query.push_str(&format!("LET $records = ${};\n{};\n", name, statement));
+        bindings.push((name, rows));
+    }
+    query.push_str("COMMIT TRANSACTION;");
+
+    (query, bindings)
+}
+
+/// Build the SurrealQL statements for a batch, splitting it into runs of writes
+/// and deletes so a tombstone never overtakes an earlier write of the same record
+fn flush_statements(mapping: &TopicMapping, records: Vec<SurrealDBRecord>) -> Vec<(String, Value)> {
+    let mut statements = Vec::new();
+    let mut run: Vec<SurrealDBRecord> = Vec::new();
+
+    for record in records {
+        if run
+            .last()
+            .map_or(false, |last| last.delete != record.delete)
+        {
+            statements.push(run_statement(mapping, std::mem::take(&mut run)));
+        }
+        run.push(record);
+    }
+    if !run.is_empty() {
+        statements.push(run_statement(mapping, run));
+    }
+
+    statements
+}
+
+/// Build the statement for a run of records that are all writes or all deletes
+fn run_statement(mapping: &TopicMapping, records: Vec<SurrealDBRecord>) -> (String, Value) {
+    if records.first().map_or(false, |record| record.delete) {
+        let ids = records
+            .into_iter()
+            .filter_map(|record| record.id.map(Value::String))
+            .collect();
+        let query = format!(
+            "FOR $id IN $records {{ DELETE type::thing('{}', $id); }}",
+            mapping.to
+        );
+        return (query, Value::Array(ids));
+    }
+
+    match &mapping.statement {
+        Some(template) => template_statement(template.mode, &template.query, records),
+        None => flush_statement(mapping, records),
+    }
+}
+
+/// Build a custom statement template for a run of writes
+///
+/// Per-record templates run in a loop with `$data` and `$id` set for each record.
+fn template_statement(
+    mode: StatementMode,
+    query: &str,
+    records: Vec<SurrealDBRecord>,
+) -> (String, Value) {
+    let query = query.trim().trim_end_matches(';');
+
+    match mode {
+        StatementMode::PerRecord => {
+            let rows = records
+                .into_iter()
+                .map(|record| json!({"id": record.id, "data": record.data}))
+                .collect();
+            let query = format!(
+                "FOR $record IN $records {{ \
+                 LET $id = $record.id; \
+                 LET $data = $record.data; \
+                 {}; \
+                 }}",
+                query
+            );
+            (query, Value::Array(rows))
+        }
+        StatementMode::PerBatch => {
+            let rows = records.into_iter().map(SurrealDBRecord::into_row).collect();
+            (query.to_string(), Value::Array(rows))
+        }
+    }
+}
+
+/// Build the SurrealQL statement and `$records` binding for a batch of writes
+///
+/// Document and TimeSeries records are bulk-inserted with `INSERT IGNORE`: a
+/// redelivered record whose ID already exists is skipped instead of failing the
+/// whole batch, which a plain `INSERT` would. Graph records are related in a
+/// loop, one RELATE per edge, within the same query.
+fn flush_statement(mapping: &TopicMapping, records: Vec<SurrealDBRecord>) -> (String, Value) {
+    let table_name = &mapping.to;
+
+    match (&mapping.storage_mode, &mapping.graph) {
+        (StorageMode::Graph, Some(graph)) => {
+            let edges = records
+                .into_iter()
+                .map(|mut record| {
+                    let (from, to) = record.edge.take().unwrap_or_default();
+                    json!({"from": from, "to": to, "data": record.into_row()})
+                })
+                .collect();
+            let query = format!(
+                "FOR $edge IN $records {{ \
+                 LET $from = type::thing('{}', $edge.from); \
+                 LET $to = type::thing('{}', $edge.to); \
+                 RELATE $from->{}->$to CONTENT $edge.data; \
+                 }}",
+                graph.from_table, graph.to_table, table_name
+            );
+            (query, Value::Array(edges))
+        }
+        _ => {
+            let rows = records.into_iter().map(SurrealDBRecord::into_row).collect();
+            (
+                format!("INSERT IGNORE INTO {} $records", table_name),
+                Value::Array(rows),
+            )
+        }
+    }
+}
+
 #[async_trait]
 impl SinkConnector for SurrealDBSinkConnector {
     async fn initialize(&mut self, _config: ConnectorConfig) -> ConnectorResult<()> {
         info!("Initializing SurrealDB Sink Connector");
-        info!("Connecting to SurrealDB at: {}", self.config.surrealdb.url);
+        info!(
+            "Connecting to SurrealDB at: {} ({:?} engine)",
+            self.config.surrealdb.url, self.config.surrealdb.engine
+        );
 
-        // Connect to SurrealDB
-        let client = Surreal::new::<Ws>(&self.config.surrealdb.url)
-            .await
-            .map_err(|e| {
-                ConnectorError::retryable(format!("Failed to connect to SurrealDB: {}", e))
-            })?;
+        let client = connect(&self.config.surrealdb).await?;
+
+        // Connect the DLQ if any route dead-letters failed records
+        let uses_dlq = self.config.surrealdb.routes.iter().any(|mapping| {
+            mapping
+                .on_error
+                .as_ref()
+                .map_or(false, |on_error| on_error.policy == ErrorPolicy::Dlq)
+        });
+        if uses_dlq {
+            self.dlq = Some(
+                DeadLetterQueue::connect(
+                    &self.config.core.danube_service_url,
+                    &self.config.core.connector_name,
+                )
+                .await?,
+            );
+            info!("Connected DLQ client");
+        }
 
-        // Authenticate if credentials provided
-        if let (Some(username), Some(password)) = (
-            &self.config.surrealdb.username,
-            &self.config.surrealdb.password,
-        ) {
+        // Apply DEFINE statements for routes with a schema definition
+        for mapping in &self.config.surrealdb.routes {
+            let Some(schema) = &mapping.schema else {
+                continue;
+            };
+
+            let statements = define_statements(mapping, schema);
             client
-                .signin(Root { username, password })
+                .query(format!(
+                    "{}{}",
+                    scope_statement(&self.config.surrealdb, mapping),
+                    statements.join(";\n")
+                ))
                 .await
+                .and_then(|response| response.check())
                 .map_err(|e| {
-                    ConnectorError::fatal(format!("SurrealDB authentication failed: {}", e))
+                    ConnectorError::fatal(format!(
+                        "Failed to define schema for table '{}': {}",
+                        mapping.to, e
+                    ))
                 })?;
-            info!("Authenticated with SurrealDB as user '{}'", username);
+            info!(
+                "Applied schema for table '{}' ({} statements)",
+                mapping.to,
+                statements.len()
+            );
         }
 
-        // Use namespace and database
-        client
-            .use_ns(&self.config.surrealdb.namespace)
-            .use_db(&self.config.surrealdb.database)
-            .await
-            .map_err(|e| {
-                ConnectorError::retryable(format!(
-                    "Failed to use namespace '{}' and database '{}': {}",
-                    self.config.surrealdb.namespace, self.config.surrealdb.database, e
-                ))
-            })?;
-
-        info!(
-            "Using namespace '{}' and database '{}'",
-            self.config.surrealdb.namespace, self.config.surrealdb.database
-        );
-
         self.client = Some(client);
 
         info!("SurrealDB connection initialized successfully");
@@ -238,7 +696,13 @@ impl SinkConnector for SurrealDBSinkConnector {
     }
 
     async fn process_batch(&mut self, records: Vec<SinkRecord>) -> ConnectorResult<()> {
-        let mut batches: HashMap<String, Vec<SurrealDBRecord>> = HashMap::new();
+        if self.reconnect_needed {
+            self.reconnect().await?;
+        }
+
+        let mut batches: HashMap<String, Vec<(SinkRecord, SurrealDBRecord)>> = HashMap::new();
+        // Record IDs written by this batch so far, per topic (for dedup within the batch)
+        let mut batch_ids: HashMap<String, HashSet<String>> = HashMap::new();
 
         for record in records {
             let topic = record.topic().to_string();
@@ -247,15 +711,72 @@ impl SinkConnector for SurrealDBSinkConnector {
                 ConnectorError::fatal(format!("No mapping configured for topic: {}", topic))
             })?;
 
-            let surrealdb_record = to_surrealdb_record(&record, &context.mapping)?;
-            batches.entry(topic).or_default().push(surrealdb_record);
+            let converted = to_surrealdb_record(&record, &context.mapping);
+            let duplicate = converted.as_ref().map_or(false, |converted| {
+                context.is_duplicate(converted, batch_ids.entry(topic.clone()).or_default())
+            });
+            if duplicate {
+                debug!(
+                    "Dropping duplicate record for table '{}'",
+                    context.mapping.to
+                );
+                if let Some(context) = self.tables.get_mut(&topic) {
+                    context.records_deduplicated += 1;
+                }
+                continue;
+            }
+
+            let on_error = context
+                .mapping
+                .on_error
+                .clone()
+                .filter(ErrorHandling::isolates_records);
+
+            match (converted, on_error) {
+                (Ok(surrealdb_record), _) => {
+                    batches
+                        .entry(topic)
+                        .or_default()
+                        .push((record, surrealdb_record));
+                }
+                // Invalid records never succeed on retry, so they're handled right away
+                (Err(e), Some(on_error)) => {
+                    let context = self.tables.get_mut(&topic).ok_or_else(|| {
+                        ConnectorError::fatal(format!("Unknown topic: {}", topic))
+                    })?;
+                    handle_failed_record(self.dlq.as_ref(), context, &on_error, &record, &e).await?
+                }
+                (Err(e), None) => return Err(e),
+            }
         }
 
-        for (topic, batch) in batches {
-            self.flush_table(&topic, batch).await?;
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("SurrealDB client not initialized"))?;
+        let flusher = TableFlusher {
+            client,
+            surrealdb: &self.config.surrealdb,
+            dlq: self.dlq.as_ref(),
+            connection_lost: AtomicBool::new(false),
+        };
+
+        // Tables are independent, so their batches are flushed concurrently;
+        // every flush runs to completion before the first error is reported
+        let results: Vec<_> =
+            stream::iter(self.tables.iter_mut().filter_map(|(topic, context)| {
+                batches.remove(topic).map(|batch| (context, batch))
+            }))
+            .map(|(context, batch)| flusher.flush_route(context, batch))
+            .buffer_unordered(self.config.surrealdb.max_parallel_flushes)
+            .collect()
+            .await;
+
+        if flusher.connection_lost.load(Ordering::Relaxed) {
+            self.reconnect_needed = true;
         }
 
-        Ok(())
+        results.into_iter().collect()
     }
 
     async fn shutdown(&mut self) -> ConnectorResult<()> {
@@ -265,8 +786,14 @@ impl SinkConnector for SurrealDBSinkConnector {
         info!("Final statistics:");
         for (topic, context) in &self.tables {
             info!(
-                "  Topic '{}' → Table '{}': {} records ({} batches)",
-                topic, context.mapping.to, context.records_inserted, context.batches_flushed
+                "  Topic '{}' → Table '{}': {} records, {} deletes, {} failed, {} duplicates ({} batches)",
+                topic,
+                context.mapping.to,
+                context.records_inserted,
+                context.records_deleted,
+                context.records_failed,
+                context.records_deduplicated,
+                context.batches_flushed
             );
         }
 
@@ -275,11 +802,19 @@ impl SinkConnector for SurrealDBSinkConnector {
     }
 
     async fn health_check(&self) -> ConnectorResult<()> {
-        if self.client.is_none() {
-            return Err(ConnectorError::fatal(
-                "SurrealDB client not initialized. Call initialize() first.",
-            ));
-        }
+        let client = self.client.as_ref().ok_or_else(|| {
+            ConnectorError::fatal("SurrealDB client not initialized. Call initialize() first.")
+        })?;
+
+        // Verify the live connection and session with a round trip that needs
+        // no privileges beyond the connector's own
+        client
+            .query("RETURN 1")
+            .await
+            .and_then(|response| response.check())
+            .map_err(|e| {
+                ConnectorError::retryable(format!("SurrealDB connection check failed: {}", e))
+            })?;
 
         // Check for recent errors
         for (topic, context) in &self.tables {
@@ -301,7 +836,7 @@ impl Default for SurrealDBSinkConnector {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::StorageMode;
+    use crate::config::Engine;
     use danube_connect_core::SubscriptionType;
 
     #[test]
@@ -311,9 +846,23 @@ mod tests {
             subscription: "test-sub".to_string(),
             subscription_type: SubscriptionType::Shared,
             to: "events".to_string(),
+            namespace: None,
+            database: None,
             include_danube_metadata: false,
             expected_schema_subject: None,
             storage_mode: StorageMode::Document,
+            timestamp_field: None,
+            timestamp_format: None,
+            graph: None,
+            delete: None,
+            id_field: None,
+            id_template: None,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            schema: None,
+            statement: None,
+            on_error: None,
+            dedup: None,
         };
 
         let context = TableContext::new(mapping.clone());
@@ -321,10 +870,308 @@ mod tests {
         assert_eq!(context.mapping.from, mapping.from);
         assert_eq!(context.mapping.to, mapping.to);
         assert_eq!(context.records_inserted, 0);
+        assert_eq!(context.records_deleted, 0);
+        assert_eq!(context.records_failed, 0);
+        assert_eq!(context.failed_attempts, 0);
         assert_eq!(context.batches_flushed, 0);
         assert!(context.last_error.is_none());
     }
 
+    #[test]
+    fn test_is_duplicate() {
+        let mapping: TopicMapping = serde_json::from_value(json!({
+            "from": "/test/topic",
+            "subscription": "test-sub",
+            "to": "events",
+            "dedup": {"window_secs": 60}
+        }))
+        .unwrap();
+        let mut context = TableContext::new(mapping);
+        let record = |id: &str, delete: bool| SurrealDBRecord {
+            id: Some(id.to_string()),
+            data: json!({}),
+            edge: None,
+            delete,
+        };
+
+        // Repeated within the same batch
+        let mut batch_ids = HashSet::new();
+        assert!(!context.is_duplicate(&record("a", false), &mut batch_ids));
+        assert!(context.is_duplicate(&record("a", false), &mut batch_ids));
+
+        // Written by an earlier batch
+        context.remember_written(vec![("a".to_string(), false)]);
+        assert!(context.is_duplicate(&record("a", false), &mut HashSet::new()));
+
+        // Deletes always pass and make the ID writable again
+        assert!(!context.is_duplicate(&record("a", true), &mut HashSet::new()));
+        context.remember_written(vec![("a".to_string(), true)]);
+        assert!(!context.is_duplicate(&record("a", false), &mut HashSet::new()));
+    }
+
+    #[test]
+    fn test_flush_statement() {
+        let mut mapping = TopicMapping {
+            from: "/shop/purchases".to_string(),
+            subscription: "test-sub".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            to: "purchased".to_string(),
+            namespace: None,
+            database: None,
+            include_danube_metadata: false,
+            expected_schema_subject: None,
+            storage_mode: StorageMode::Document,
+            timestamp_field: None,
+            timestamp_format: None,
+            graph: None,
+            delete: None,
+            id_field: None,
+            id_template: None,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            schema: None,
+            statement: None,
+            on_error: None,
+            dedup: None,
+        };
+        let record = SurrealDBRecord {
+            id: None,
+            data: json!({"user": "u1", "product": "p9", "qty": 2}),
+            edge: Some(("u1".to_string(), "p9".to_string())),
+            delete: false,
+        };
+
+        let (query, rows) = flush_statement(&mapping, vec![record.clone()]);
+        assert_eq!(query, "INSERT IGNORE INTO purchased $records");
+        assert_eq!(rows, json!([{"user": "u1", "product": "p9", "qty": 2}]));
+
+        mapping.storage_mode = StorageMode::Graph;
+        mapping.graph = Some(crate::config::GraphConfig {
+            from_table: "user".to_string(),
+            from_field: "user".to_string(),
+            to_table: "product".to_string(),
+            to_field: "product".to_string(),
+        });
+        let (query, rows) = flush_statement(&mapping, vec![record]);
+        assert!(query.contains("type::thing('user', $edge.from)"));
+        assert!(query.contains("RELATE $from->purchased->$to CONTENT $edge.data"));
+        assert_eq!(rows[0]["from"], "u1");
+        assert_eq!(rows[0]["to"], "p9");
+        assert_eq!(rows[0]["data"]["qty"], 2);
+    }
+
+    #[test]
+    fn test_flush_statements_preserve_order() {
+        let mapping = TopicMapping {
+            from: "/cdc/users".to_string(),
+            subscription: "test-sub".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            to: "users".to_string(),
+            namespace: None,
+            database: None,
+            include_danube_metadata: false,
+            expected_schema_subject: None,
+            storage_mode: StorageMode::Document,
+            timestamp_field: None,
+            timestamp_format: None,
+            graph: None,
+            delete: None,
+            id_field: None,
+            id_template: None,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            schema: None,
+            statement: None,
+            on_error: None,
+            dedup: None,
+        };
+        let write = |id: &str| SurrealDBRecord {
+            id: Some(id.to_string()),
+            data: json!({"name": id}),
+            edge: None,
+            delete: false,
+        };
+        let delete = |id: &str| SurrealDBRecord {
+            id: Some(id.to_string()),
+            data: Value::Null,
+            edge: None,
+            delete: true,
+        };
+
+        let statements = flush_statements(
+            &mapping,
+            vec![write("u1"), write("u2"), delete("u1"), write("u3")],
+        );
+
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0].0, "INSERT IGNORE INTO users $records");
+        assert_eq!(statements[0].1.as_array().unwrap().len(), 2);
+        assert_eq!(
+            statements[1].0,
+            "FOR $id IN $records { DELETE type::thing('users', $id); }"
+        );
+        assert_eq!(statements[1].1, json!(["u1"]));
+        assert_eq!(statements[2].1, json!([{"name": "u3", "id": "u3"}]));
+    }
+
+    #[test]
+    fn test_flush_statement_redelivered_id() {
+        let mapping = TopicMapping {
+            from: "/events/orders".to_string(),
+            subscription: "test-sub".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            to: "orders".to_string(),
+            namespace: None,
+            database: None,
+            include_danube_metadata: false,
+            expected_schema_subject: None,
+            storage_mode: StorageMode::Document,
+            timestamp_field: None,
+            timestamp_format: None,
+            graph: None,
+            delete: None,
+            id_field: Some("order_id".to_string()),
+            id_template: None,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            schema: None,
+            statement: None,
+            on_error: None,
+            dedup: None,
+        };
+        let record = SurrealDBRecord {
+            id: Some("o1".to_string()),
+            data: json!({"total": 10}),
+            edge: None,
+            delete: false,
+        };
+
+        // A redelivered ID, already written by an earlier batch, is ignored
+        // rather than failing the batch's transaction
+        let (query, rows) = flush_statement(&mapping, vec![record.clone(), record]);
+        assert!(query.starts_with("INSERT IGNORE INTO orders "));
+        assert_eq!(
+            rows,
+            json!([{"total": 10, "id": "o1"}, {"total": 10, "id": "o1"}])
+        );
+    }
+
+    #[test]
+    fn test_template_statement() {
+        let record = SurrealDBRecord {
+            id: Some("u1".to_string()),
+            data: json!({"name": "Alice"}),
+            edge: None,
+            delete: false,
+        };
+
+        let (query, rows) = template_statement(
+            StatementMode::PerRecord,
+            "UPDATE type::thing('user', $id) MERGE $data;",
+            vec![record.clone()],
+        );
+        assert_eq!(
+            query,
+            "FOR $record IN $records { LET $id = $record.id; LET $data = $record.data; \
+             UPDATE type::thing('user', $id) MERGE $data; }"
+        );
+        assert_eq!(rows, json!([{"id": "u1", "data": {"name": "Alice"}}]));
+
+        let (query, rows) = template_statement(
+            StatementMode::PerBatch,
+            "INSERT IGNORE INTO user $records",
+            vec![record],
+        );
+        assert_eq!(query, "INSERT IGNORE INTO user $records");
+        assert_eq!(rows, json!([{"name": "Alice", "id": "u1"}]));
+    }
+
+    #[test]
+    fn test_transaction_query() {
+        let statements = vec![
+            (
+                "INSERT INTO users $records".to_string(),
+                json!([{"id": "u1"}]),
+            ),
+            (
+                "FOR $id IN $records { DELETE type::thing('users', $id); }".to_string(),
+                json!(["u1"]),
+            ),
+        ];
+
+        let (query, bindings) = transaction_query("", statements);
+
+        assert_eq!(
+            query,
+            "BEGIN TRANSACTION;\n\
+             LET $records = $batch0;\n\
+             INSERT INTO users $records;\n\
+             LET $records = $batch1;\n\
+             FOR $id IN $records { DELETE type::thing('users', $id); };\n\
+             COMMIT TRANSACTION;"
+        );
+        assert_eq!(bindings[0], ("batch0".to_string(), json!([{"id": "u1"}])));
+        assert_eq!(bindings[1], ("batch1".to_string(), json!(["u1"])));
+    }
+
+    #[test]
+    fn test_scope_statement() {
+        let mut surrealdb = SurrealDBConfig {
+            url: "ws://localhost:8000".to_string(),
+            engine: Engine::Ws,
+            namespace: "prod".to_string(),
+            database: "main".to_string(),
+            username: None,
+            password: None,
+            auth_level: AuthLevel::Root,
+            access: None,
+            access_params: HashMap::new(),
+            connection_timeout_secs: 30,
+            request_timeout_secs: 30,
+            reconnect_max_attempts: 10,
+            reconnect_backoff_ms: 500,
+            reconnect_max_backoff_ms: 30_000,
+            max_parallel_flushes: 4,
+            tls: None,
+            routes: vec![TopicMapping {
+                from: "/tenants/acme/events".to_string(),
+                subscription: "test-sub".to_string(),
+                subscription_type: SubscriptionType::Shared,
+                to: "events".to_string(),
+                namespace: None,
+                database: None,
+                include_danube_metadata: false,
+                expected_schema_subject: None,
+                storage_mode: StorageMode::Document,
+                timestamp_field: None,
+                timestamp_format: None,
+                graph: None,
+                delete: None,
+                id_field: None,
+                id_template: None,
+                field_map: HashMap::new(),
+                exclude_fields: Vec::new(),
+                schema: None,
+                statement: None,
+                on_error: None,
+                dedup: None,
+            }],
+        };
+        let mut tenant = surrealdb.routes[0].clone();
+        assert_eq!(scope_statement(&surrealdb, &tenant), "");
+
+        tenant.database = Some("acme".to_string());
+        surrealdb.routes.push(tenant.clone());
+        assert_eq!(
+            scope_statement(&surrealdb, &tenant),
+            "USE NS `prod` DB `acme`; "
+        );
+        assert_eq!(
+            scope_statement(&surrealdb, &surrealdb.routes[0]),
+            "USE NS `prod` DB `main`; "
+        );
+    }
+
     #[test]
     fn test_connector_creation() {
         let config = SurrealDBSinkConfig {
@@ -337,20 +1184,43 @@ mod tests {
             },
             surrealdb: crate::config::SurrealDBConfig {
                 url: "ws://localhost:8000".to_string(),
+                engine: Engine::Ws,
                 namespace: "test".to_string(),
                 database: "test".to_string(),
                 username: None,
                 password: None,
+                auth_level: AuthLevel::Root,
+                access: None,
+                access_params: HashMap::new(),
                 connection_timeout_secs: 30,
                 request_timeout_secs: 30,
+                reconnect_max_attempts: 10,
+                reconnect_backoff_ms: 500,
+                reconnect_max_backoff_ms: 30_000,
+                max_parallel_flushes: 4,
+                tls: None,
                 routes: vec![TopicMapping {
                     from: "/test/topic".to_string(),
                     subscription: "test-sub".to_string(),
                     subscription_type: SubscriptionType::Shared,
                     to: "events".to_string(),
+                    namespace: None,
+                    database: None,
                     include_danube_metadata: true,
                     expected_schema_subject: None,
                     storage_mode: StorageMode::Document,
+                    timestamp_field: None,
+                    timestamp_format: None,
+                    graph: None,
+                    delete: None,
+                    id_field: None,
+                    id_template: None,
+                    field_map: HashMap::new(),
+                    exclude_fields: Vec::new(),
+                    schema: None,
+                    statement: None,
+                    on_error: None,
+                    dedup: None,
                 }],
             },
         };
@@ -358,5 +1228,6 @@ mod tests {
         let connector = SurrealDBSinkConnector::with_config(config);
         assert_eq!(connector.tables.len(), 1);
         assert!(connector.client.is_none());
+        assert!(!connector.reconnect_needed);
     }
 }
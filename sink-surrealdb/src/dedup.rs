@@ -0,0 +1,109 @@
+//! Deduplication module for SurrealDB Sink Connector
+//!
+//! Keeps the record IDs written within a time window, so messages redelivered
+//! with the same record ID (e.g., after a consumer failover on a Shared
+//! subscription) are dropped before they reach SurrealDB.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Window of recently written record IDs, bounded by age and size
+#[derive(Debug)]
+pub struct DedupWindow {
+    /// How long a written ID is remembered
+    ttl: Duration,
+
+    /// Maximum number of IDs remembered; the oldest are evicted first
+    max_entries: usize,
+
+    /// Remembered IDs with the time they were written
+    seen: HashMap<String, Instant>,
+
+    /// IDs in write order, for eviction (entries for removed or re-written IDs are stale)
+    order: VecDeque<(String, Instant)>,
+}
+
+impl DedupWindow {
+    /// Create an empty window
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Whether the ID was written within the window
+    pub fn contains(&self, id: &str) -> bool {
+        self.seen
+            .get(id)
+            .map_or(false, |written| written.elapsed() < self.ttl)
+    }
+
+    /// Remember a written ID
+    pub fn insert(&mut self, id: String) {
+        let now = Instant::now();
+        self.seen.insert(id.clone(), now);
+        self.order.push_back((id, now));
+        self.evict(now);
+    }
+
+    /// Forget an ID (e.g., the record was deleted and may be written again)
+    pub fn remove(&mut self, id: &str) {
+        self.seen.remove(id);
+    }
+
+    /// Drop expired IDs, then the oldest ones beyond `max_entries`
+    fn evict(&mut self, now: Instant) {
+        while let Some((id, written)) = self.order.front() {
+            let current = self.seen.get(id) == Some(written);
+            let expired = now.duration_since(*written) >= self.ttl;
+            if current && !expired && self.seen.len() <= self.max_entries {
+                break;
+            }
+            if current {
+                self.seen.remove(id);
+            }
+            self.order.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_written_ids() {
+        let mut window = DedupWindow::new(Duration::from_secs(60), 10);
+        window.insert("orders:1".to_string());
+
+        assert!(window.contains("orders:1"));
+        assert!(!window.contains("orders:2"));
+
+        window.remove("orders:1");
+        assert!(!window.contains("orders:1"));
+    }
+
+    #[test]
+    fn test_evicts_oldest_beyond_max_entries() {
+        let mut window = DedupWindow::new(Duration::from_secs(60), 2);
+        window.insert("a".to_string());
+        window.insert("b".to_string());
+        window.insert("c".to_string());
+
+        assert!(!window.contains("a"));
+        assert!(window.contains("b"));
+        assert!(window.contains("c"));
+    }
+
+    #[test]
+    fn test_expired_ids() {
+        let mut window = DedupWindow::new(Duration::ZERO, 10);
+        window.insert("a".to_string());
+
+        assert!(!window.contains("a"));
+        assert!(window.seen.is_empty());
+    }
+}
@@ -5,7 +5,11 @@
 
 mod config;
 mod connector;
+mod dedup;
+mod dlq;
 mod record;
+mod schema;
+mod tls;
 
 use config::SurrealDBSinkConfig;
 use connector::SurrealDBSinkConnector;
@@ -3,14 +3,18 @@
 //! This module converts Danube messages into SurrealDB records.
 //! Payloads are already deserialized as serde_json::Value by the runtime.
 //!
-//! Supports two storage modes:
+//! Supports three storage modes:
 //! - Document: Regular document storage (default)
 //! - TimeSeries: Adds timestamp field for time-series optimization
+//! - Graph: Resolves edge endpoints for RELATE statements
+//!
+//! Messages matching a route's delete convention become tombstones (`DELETE table:id`).
 
-use crate::config::{StorageMode, TopicMapping};
-use chrono::{DateTime, Utc};
-use danube_connect_core::{ConnectorResult, SinkRecord};
+use crate::config::{DeleteConfig, StorageMode, TopicMapping};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use danube_connect_core::{ConnectorError, ConnectorResult, SinkRecord};
 use serde_json::{json, Value};
+use tracing::debug;
 
 /// Represents a SurrealDB record ready for insertion
 #[derive(Debug, Clone)]
@@ -20,6 +24,27 @@ pub struct SurrealDBRecord {
 
     /// Record data - payload wrapped based on schema type
     pub data: Value,
+
+    /// Graph edge endpoints (source ID, target ID) for the Graph storage mode
+    pub edge: Option<(String, String)>,
+
+    /// Delete the record with this ID instead of writing it (tombstone)
+    pub delete: bool,
+}
+
+impl SurrealDBRecord {
+    /// Convert into an INSERT row, carrying the record ID as the `id` field
+    ///
+    /// Rows without an ID get an auto-generated one from SurrealDB.
+    pub fn into_row(self) -> Value {
+        match (self.id, self.data) {
+            (Some(id), Value::Object(mut map)) => {
+                map.insert("id".to_string(), Value::String(id));
+                Value::Object(map)
+            }
+            (_, data) => data,
+        }
+    }
 }
 
 /// Convert a Danube SinkRecord into a SurrealDB record
@@ -27,19 +52,50 @@ pub struct SurrealDBRecord {
 /// This function uses danube-connect-core's unified deserialization method,
 /// ensuring consistent behavior across all sink connectors.
 ///
-/// Record ID comes from message attributes (set by producer).
+/// Record ID comes from the payload (`id_field` / `id_template`) when configured,
+/// otherwise from message attributes (set by producer).
 ///
 /// For TimeSeries mode, adds a timestamp field for temporal queries.
+///
+/// Messages matching the route's delete convention become delete records.
 pub fn to_surrealdb_record(
     record: &SinkRecord,
     mapping: &TopicMapping,
 ) -> ConnectorResult<SurrealDBRecord> {
-    // Get record ID from message attributes (set by producer)
-    let id = record.get_attribute("record_id").map(|s| s.to_string());
+    if let Some(delete) = &mapping.delete {
+        if is_delete(record.payload(), delete) {
+            return to_delete_record(record, mapping);
+        }
+    }
+
+    // Get record ID from the payload if configured, else from message attributes
+    let id = resolve_record_id(record, mapping)?;
 
     // Get typed payload (already deserialized by runtime)
     let mut data = record.payload().clone();
 
+    // Resolve edge endpoints for graph mode (from the original payload fields)
+    let edge = match &mapping.graph {
+        Some(graph) if mapping.storage_mode == StorageMode::Graph => {
+            let endpoints = field_as_id(&data, &graph.from_field)
+                .and_then(|from| Ok((from, field_as_id(&data, &graph.to_field)?)));
+            Some(endpoints.map_err(|e| {
+                ConnectorError::invalid_data(
+                    format!(
+                        "Failed to resolve graph edge for topic {}: {}",
+                        record.topic(),
+                        e
+                    ),
+                    serde_json::to_vec(&data).unwrap_or_default(),
+                )
+            })?)
+        }
+        _ => None,
+    };
+
+    // Drop and rename fields before the connector adds its own
+    reshape_fields(&mut data, mapping);
+
     // Add timestamp for time-series mode
     if mapping.storage_mode == StorageMode::TimeSeries {
         add_timestamp(&mut data, record, mapping)?;
@@ -50,24 +106,232 @@ pub fn to_surrealdb_record(
         add_metadata(&mut data, record);
     }
 
-    Ok(SurrealDBRecord { id, data })
+    Ok(SurrealDBRecord {
+        id,
+        data,
+        edge,
+        delete: false,
+    })
+}
+
+/// Check whether a payload matches the delete convention
+fn is_delete(payload: &Value, delete: &DeleteConfig) -> bool {
+    match payload {
+        Value::Null => delete.on_null_payload,
+        _ => delete.op_field.as_ref().map_or(false, |field| {
+            payload.get(field).and_then(Value::as_str) == Some(delete.op_value.as_str())
+        }),
+    }
+}
+
+/// Build a delete record for a tombstone message
+///
+/// A delete without a record ID can't be applied, so it is reported as invalid data.
+fn to_delete_record(
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+) -> ConnectorResult<SurrealDBRecord> {
+    // Null payloads carry no fields, so only the record_id attribute can identify them
+    let id = if record.payload().is_null() {
+        record.get_attribute("record_id").map(|s| s.to_string())
+    } else {
+        resolve_record_id(record, mapping)?
+    };
+
+    let id = id.ok_or_else(|| {
+        ConnectorError::invalid_data(
+            format!(
+                "Delete message on topic {} has no record ID",
+                record.topic()
+            ),
+            serde_json::to_vec(record.payload()).unwrap_or_default(),
+        )
+    })?;
+
+    Ok(SurrealDBRecord {
+        id: Some(id),
+        data: Value::Null,
+        edge: None,
+        delete: true,
+    })
+}
+
+/// Resolve the record ID for a message
+///
+/// Payload-derived IDs (`id_field` / `id_template`) take precedence over the
+/// `record_id` attribute. A configured field that is missing from the payload
+/// is reported as invalid data rather than silently falling back to a random ID.
+fn resolve_record_id(
+    record: &SinkRecord,
+    mapping: &TopicMapping,
+) -> ConnectorResult<Option<String>> {
+    let payload = record.payload();
+    let derived = match (&mapping.id_field, &mapping.id_template) {
+        (Some(field), _) => Some(field_as_id(payload, field)),
+        (None, Some(template)) => Some(render_id_template(template, payload, |name| {
+            message_metadata(record, name)
+        })),
+        (None, None) => None,
+    };
+
+    match derived {
+        Some(Ok(id)) => Ok(Some(id)),
+        Some(Err(e)) => Err(ConnectorError::invalid_data(
+            format!(
+                "Failed to derive record ID for topic {}: {}",
+                record.topic(),
+                e
+            ),
+            serde_json::to_vec(payload).unwrap_or_default(),
+        )),
+        None => Ok(record.get_attribute("record_id").map(|s| s.to_string())),
+    }
+}
+
+/// Render an ID template, replacing each `{field}` with the payload value
+///
+/// Placeholders not found in the payload fall back to message metadata
+/// (`{topic}`, `{publish_time}`, `{producer_name}`, `{attributes.<key>}`).
+fn render_id_template(
+    template: &str,
+    payload: &Value,
+    metadata: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut id = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed placeholder in '{}'", template))?;
+        let field = &rest[start + 1..start + end];
+        let component = match field_as_id(payload, field) {
+            Ok(component) => component,
+            Err(e) => metadata(field).filter(|value| !value.is_empty()).ok_or(e)?,
+        };
+        id.push_str(&rest[..start]);
+        id.push_str(&component);
+        rest = &rest[start + end + 1..];
+    }
+    id.push_str(rest);
+
+    Ok(id)
+}
+
+/// Read message metadata usable in ID templates
+fn message_metadata(record: &SinkRecord, name: &str) -> Option<String> {
+    match name {
+        "topic" => Some(record.topic().to_string()),
+        "publish_time" => Some(record.publish_time().to_string()),
+        "producer_name" => Some(record.producer_name().to_string()),
+        _ => name
+            .strip_prefix("attributes.")
+            .and_then(|key| record.get_attribute(key))
+            .map(|value| value.to_string()),
+    }
+}
+
+/// Read a payload field (dotted path) as an ID component
+fn field_as_id(payload: &Value, field: &str) -> Result<String, String> {
+    let value = field
+        .split('.')
+        .try_fold(payload, |current, key| current.get(key))
+        .ok_or_else(|| format!("field '{}' is missing", field))?;
+
+    match value {
+        Value::String(s) if !s.is_empty() => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(format!(
+            "field '{}' must be a non-empty string, number, or boolean",
+            field
+        )),
+    }
+}
+
+/// Apply the route's `exclude_fields` and `field_map` to the payload
+///
+/// Exclusions are applied first; mapped fields missing from the payload are skipped.
+fn reshape_fields(data: &mut Value, mapping: &TopicMapping) {
+    for field in &mapping.exclude_fields {
+        remove_path(data, field);
+    }
+
+    for (source, target) in &mapping.field_map {
+        if let Some(value) = remove_path(data, source) {
+            insert_path(data, target, value);
+        }
+    }
+}
+
+/// Remove a field (dotted path) from an object, returning its value
+fn remove_path(data: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(data, |current, part| current.get_mut(part))?,
+            key,
+        ),
+        None => (data, path),
+    };
+
+    parent.as_object_mut()?.remove(key)
+}
+
+/// Insert a field (dotted path) into an object, creating intermediate objects
+fn insert_path(data: &mut Value, path: &str, value: Value) {
+    let mut current = data;
+    let mut parts = path.split('.').peekable();
+
+    while let Some(part) = parts.next() {
+        let Value::Object(map) = current else {
+            return;
+        };
+        if parts.peek().is_none() {
+            map.insert(part.to_string(), value);
+            return;
+        }
+        current = map
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
 }
 
 /// Add timestamp for time-series mode
 ///
-/// Uses Danube publish_time (microseconds since epoch) as the timestamp
+/// Uses the payload's `timestamp_field` (event time) when configured and parseable,
+/// otherwise Danube publish_time (microseconds since epoch)
 fn add_timestamp(
     data: &mut Value,
     record: &SinkRecord,
-    _mapping: &TopicMapping,
+    mapping: &TopicMapping,
 ) -> ConnectorResult<()> {
-    // Convert publish_time (microseconds) to DateTime<Utc>
-    let publish_time_micros = record.publish_time();
-    let publish_time_secs = (publish_time_micros / 1_000_000) as i64;
-    let publish_time_nanos = ((publish_time_micros % 1_000_000) * 1000) as u32;
+    let event_time = mapping.timestamp_field.as_ref().and_then(|field| {
+        let format = mapping.timestamp_format.as_deref().unwrap_or("rfc3339");
+        let parsed = field
+            .split('.')
+            .try_fold(record.payload(), |current, key| current.get(key))
+            .and_then(|value| parse_timestamp(value, format));
+        if parsed.is_none() {
+            debug!(
+                "No valid '{}' in message on topic {}, using publish time",
+                field,
+                record.topic()
+            );
+        }
+        parsed
+    });
 
-    let timestamp = DateTime::from_timestamp(publish_time_secs, publish_time_nanos)
-        .unwrap_or_else(|| Utc::now());
+    let timestamp = event_time.unwrap_or_else(|| {
+        // Convert publish_time (microseconds) to DateTime<Utc>
+        let publish_time_micros = record.publish_time();
+        let publish_time_secs = (publish_time_micros / 1_000_000) as i64;
+        let publish_time_nanos = ((publish_time_micros % 1_000_000) * 1000) as u32;
+
+        DateTime::from_timestamp(publish_time_secs, publish_time_nanos)
+            .unwrap_or_else(|| Utc::now())
+    });
 
     // Add timestamp to data
     if let Value::Object(map) = data {
@@ -77,6 +341,43 @@ fn add_timestamp(
     Ok(())
 }
 
+/// Parse a payload timestamp with a `timestamp_format`
+///
+/// Returns None if the value can't be parsed
+fn parse_timestamp(value: &Value, format: &str) -> Option<DateTime<Utc>> {
+    let epoch = |scale: f64| -> Option<DateTime<Utc>> {
+        let n = match value {
+            Value::Number(n) => n.as_f64()?,
+            Value::String(s) => s.trim().parse::<f64>().ok()?,
+            _ => return None,
+        };
+        let micros = (n * scale).round();
+        if !micros.is_finite() || micros.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        DateTime::from_timestamp_micros(micros as i64)
+    };
+
+    match format {
+        "unix_s" => epoch(1_000_000.0),
+        "unix_ms" => epoch(1_000.0),
+        "unix_us" => epoch(1.0),
+        "rfc3339" => DateTime::parse_from_rfc3339(value.as_str()?.trim())
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc)),
+        pattern => {
+            let s = value.as_str()?.trim();
+            match DateTime::parse_from_str(s, pattern) {
+                Ok(dt) => Some(dt.with_timezone(&Utc)),
+                // Patterns without an offset are read as UTC
+                Err(_) => NaiveDateTime::parse_from_str(s, pattern)
+                    .ok()
+                    .map(|dt| dt.and_utc()),
+            }
+        }
+    }
+}
+
 /// Add Danube metadata to the record
 fn add_metadata(data: &mut Value, record: &SinkRecord) {
     // Convert publish_time (microseconds) to DateTime<Utc>
@@ -102,4 +403,141 @@ mod tests {
     // as SinkRecord::from_stream_message() is a private API in danube-connect-core v0.4.0.
     // The transformation logic (add_timestamp, add_metadata) is tested via integration tests.
     // End-to-end functionality is validated in the connector's integration test suite.
+
+    use super::*;
+
+    #[test]
+    fn test_render_id_template() {
+        let payload = json!({"user_id": "u1", "ts": 1700000000, "order": {"id": 42}});
+        let no_metadata = |_: &str| None;
+
+        assert_eq!(
+            render_id_template("{user_id}:{ts}", &payload, no_metadata),
+            Ok("u1:1700000000".to_string())
+        );
+        assert_eq!(
+            render_id_template("order-{order.id}", &payload, no_metadata),
+            Ok("order-42".to_string())
+        );
+        assert!(render_id_template("{missing}", &payload, no_metadata).is_err());
+        assert!(render_id_template("{order}", &payload, no_metadata).is_err());
+    }
+
+    #[test]
+    fn test_render_id_template_with_metadata() {
+        let payload = json!({"tenant": "acme", "device": "d7", "topic": "payload-topic"});
+        let metadata = |name: &str| match name {
+            "topic" => Some("/iot/readings".to_string()),
+            "publish_time" => Some("1700000000123456".to_string()),
+            "attributes.region" => Some("eu".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            render_id_template("{tenant}_{device}_{publish_time}", &payload, metadata),
+            Ok("acme_d7_1700000000123456".to_string())
+        );
+        assert_eq!(
+            render_id_template("{attributes.region}:{device}", &payload, metadata),
+            Ok("eu:d7".to_string())
+        );
+        // Payload fields take precedence over metadata of the same name
+        assert_eq!(
+            render_id_template("{topic}", &payload, metadata),
+            Ok("payload-topic".to_string())
+        );
+        assert!(render_id_template("{attributes.missing}", &payload, metadata).is_err());
+    }
+
+    #[test]
+    fn test_into_row() {
+        let with_id = SurrealDBRecord {
+            id: Some("user_123".to_string()),
+            data: json!({"name": "Alice"}),
+            edge: None,
+            delete: false,
+        };
+        assert_eq!(
+            with_id.into_row(),
+            json!({"name": "Alice", "id": "user_123"})
+        );
+
+        let auto_id = SurrealDBRecord {
+            id: None,
+            data: json!({"name": "Bob"}),
+            edge: None,
+            delete: false,
+        };
+        assert_eq!(auto_id.into_row(), json!({"name": "Bob"}));
+    }
+
+    #[test]
+    fn test_is_delete() {
+        let delete = DeleteConfig {
+            on_null_payload: true,
+            op_field: Some("op".to_string()),
+            op_value: "delete".to_string(),
+        };
+
+        assert!(is_delete(&Value::Null, &delete));
+        assert!(is_delete(&json!({"op": "delete", "id": "u1"}), &delete));
+        assert!(!is_delete(&json!({"op": "update", "id": "u1"}), &delete));
+        assert!(!is_delete(&json!({"id": "u1"}), &delete));
+
+        let op_only = DeleteConfig {
+            on_null_payload: false,
+            ..delete
+        };
+        assert!(!is_delete(&Value::Null, &op_only));
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = DateTime::parse_from_rfc3339("2024-01-15T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            parse_timestamp(&json!("2024-01-15T11:30:00+01:00"), "rfc3339"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp(&json!(1705314600), "unix_s"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp(&json!("1705314600000"), "unix_ms"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_timestamp(&json!("2024-01-15 10:30:00"), "%Y-%m-%d %H:%M:%S"),
+            Some(expected)
+        );
+        assert_eq!(parse_timestamp(&json!("yesterday"), "rfc3339"), None);
+        assert_eq!(parse_timestamp(&json!(true), "unix_ms"), None);
+    }
+
+    #[test]
+    fn test_reshape_fields() {
+        let mapping: TopicMapping = serde_json::from_value(json!({
+            "from": "/events/users",
+            "subscription": "test-sub",
+            "to": "users",
+            "field_map": {"usr_nm": "name", "geo.cty": "address.city"},
+            "exclude_fields": ["debug", "geo.raw"]
+        }))
+        .unwrap();
+        let mut data = json!({
+            "usr_nm": "Alice",
+            "debug": {"trace": "abc"},
+            "geo": {"cty": "Paris", "raw": "48.85,2.35"}
+        });
+
+        reshape_fields(&mut data, &mapping);
+
+        assert_eq!(
+            data,
+            json!({"name": "Alice", "geo": {}, "address": {"city": "Paris"}})
+        );
+    }
 }
@@ -0,0 +1,144 @@
+//! Schema bootstrap module for SurrealDB Sink Connector
+//!
+//! Builds `DEFINE TABLE` / `DEFINE FIELD` / `DEFINE INDEX` statements from a
+//! route's optional schema definition. The statements are applied at
+//! `initialize()` so tables aren't created implicitly schemaless.
+
+use crate::config::{StorageMode, TableSchema, TopicMapping};
+
+/// Build the DEFINE statements for a route's table schema
+///
+/// Statements use `IF NOT EXISTS`, so existing definitions are left untouched
+/// and restarts are safe. For SCHEMAFULL tables, the fields the connector adds
+/// itself (`_timestamp`, `_danube_metadata`) are defined too, otherwise
+/// SurrealDB would silently drop them.
+pub fn define_statements(mapping: &TopicMapping, schema: &TableSchema) -> Vec<String> {
+    let table = &mapping.to;
+    let mut statements = Vec::new();
+
+    let mut define_table = format!("DEFINE TABLE IF NOT EXISTS {}", table);
+    if let (StorageMode::Graph, Some(graph)) = (mapping.storage_mode, &mapping.graph) {
+        define_table.push_str(&format!(
+            " TYPE RELATION IN {} OUT {}",
+            graph.from_table, graph.to_table
+        ));
+    }
+    define_table.push_str(if schema.schemafull {
+        " SCHEMAFULL"
+    } else {
+        " SCHEMALESS"
+    });
+    statements.push(define_table);
+
+    for field in &schema.fields {
+        let mut define_field = format!(
+            "DEFINE FIELD IF NOT EXISTS {} ON TABLE {} TYPE {}",
+            field.name, table, field.field_type
+        );
+        if let Some(default) = &field.default {
+            define_field.push_str(&format!(" DEFAULT {}", default));
+        }
+        if let Some(assert) = &field.assert {
+            define_field.push_str(&format!(" ASSERT {}", assert));
+        }
+        statements.push(define_field);
+    }
+
+    if schema.schemafull {
+        if mapping.storage_mode == StorageMode::TimeSeries {
+            statements.push(format!(
+                "DEFINE FIELD IF NOT EXISTS _timestamp ON TABLE {} TYPE string",
+                table
+            ));
+        }
+        if mapping.include_danube_metadata {
+            statements.push(format!(
+                "DEFINE FIELD IF NOT EXISTS _danube_metadata ON TABLE {} FLEXIBLE TYPE object",
+                table
+            ));
+        }
+    }
+
+    for index in &schema.indexes {
+        let mut define_index = format!(
+            "DEFINE INDEX IF NOT EXISTS {} ON TABLE {} FIELDS {}",
+            index.name,
+            table,
+            index.fields.join(", ")
+        );
+        if index.unique {
+            define_index.push_str(" UNIQUE");
+        }
+        statements.push(define_index);
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FieldDefinition, IndexDefinition};
+    use danube_connect_core::SubscriptionType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_define_statements() {
+        let mapping = TopicMapping {
+            from: "/events/orders".to_string(),
+            subscription: "test-sub".to_string(),
+            subscription_type: SubscriptionType::Shared,
+            to: "orders".to_string(),
+            namespace: None,
+            database: None,
+            include_danube_metadata: true,
+            expected_schema_subject: None,
+            storage_mode: StorageMode::Document,
+            timestamp_field: None,
+            timestamp_format: None,
+            graph: None,
+            delete: None,
+            id_field: None,
+            id_template: None,
+            field_map: HashMap::new(),
+            exclude_fields: Vec::new(),
+            schema: None,
+            statement: None,
+            on_error: None,
+            dedup: None,
+        };
+        let schema = TableSchema {
+            schemafull: true,
+            fields: vec![
+                FieldDefinition {
+                    name: "customer_id".to_string(),
+                    field_type: "string".to_string(),
+                    default: None,
+                    assert: None,
+                },
+                FieldDefinition {
+                    name: "total".to_string(),
+                    field_type: "number".to_string(),
+                    default: Some("0".to_string()),
+                    assert: Some("$value >= 0".to_string()),
+                },
+            ],
+            indexes: vec![IndexDefinition {
+                name: "orders_customer".to_string(),
+                fields: vec!["customer_id".to_string()],
+                unique: false,
+            }],
+        };
+
+        assert_eq!(
+            define_statements(&mapping, &schema),
+            vec![
+                "DEFINE TABLE IF NOT EXISTS orders SCHEMAFULL",
+                "DEFINE FIELD IF NOT EXISTS customer_id ON TABLE orders TYPE string",
+                "DEFINE FIELD IF NOT EXISTS total ON TABLE orders TYPE number DEFAULT 0 ASSERT $value >= 0",
+                "DEFINE FIELD IF NOT EXISTS _danube_metadata ON TABLE orders FLEXIBLE TYPE object",
+                "DEFINE INDEX IF NOT EXISTS orders_customer ON TABLE orders FIELDS customer_id",
+            ]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! TLS module for SurrealDB Sink Connector
+//!
+//! Builds the rustls client configuration used for `wss://` connections:
+//! public web roots, an optional custom CA bundle (e.g., for managed or
+//! self-signed deployments), and an opt-out of certificate verification.
+
+use crate::config::TlsConfig;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Build the rustls client configuration for a TLS connection
+pub fn client_config(tls: &TlsConfig) -> ConnectorResult<ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| ConnectorError::config(format!("Invalid TLS settings: {}", e)))?;
+
+    if !tls.verify_certificates {
+        warn!("TLS certificate verification is disabled for SurrealDB, use only for testing");
+        return Ok(builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerification(provider)))
+            .with_no_client_auth());
+    }
+
+    let mut roots = RootCertStore::empty();
+    if tls.trust_public_roots {
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    }
+    if let Some(path) = &tls.ca_cert_path {
+        let certs = load_certs(path)?;
+        for cert in &certs {
+            roots.add(cert.clone()).map_err(|e| {
+                ConnectorError::config(format!("Invalid CA certificate in '{}': {}", path, e))
+            })?;
+        }
+        info!("Loaded {} CA certificate(s) from '{}'", certs.len(), path);
+    }
+
+    Ok(builder.with_root_certificates(roots).with_no_client_auth())
+}
+
+/// Read all certificates from a PEM bundle
+fn load_certs(path: &str) -> ConnectorResult<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).map_err(|e| {
+        ConnectorError::config(format!("Failed to open CA bundle '{}': {}", path, e))
+    })?;
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            ConnectorError::config(format!("Failed to parse CA bundle '{}': {}", path, e))
+        })?;
+
+    if certs.is_empty() {
+        return Err(ConnectorError::config(format!(
+            "CA bundle '{}' contains no certificates",
+            path
+        )));
+    }
+
+    Ok(certs)
+}
+
+/// Certificate verifier accepting any server certificate
+///
+/// Handshake signatures are still checked, so the connection is encrypted,
+/// but the server's identity is not.
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_config() {
+        let tls = TlsConfig {
+            ca_cert_path: None,
+            trust_public_roots: true,
+            verify_certificates: true,
+        };
+        assert!(client_config(&tls).is_ok());
+
+        let insecure = TlsConfig {
+            verify_certificates: false,
+            ..tls.clone()
+        };
+        assert!(client_config(&insecure).is_ok());
+
+        let missing_ca = TlsConfig {
+            ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+            ..tls
+        };
+        assert!(client_config(&missing_ca).is_err());
+    }
+}
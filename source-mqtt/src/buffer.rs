@@ -0,0 +1,209 @@
+//! Bounded buffer between the MQTT event loop and the source runtime
+//!
+//! Decouples receiving from publishing, so a burst of device traffic is
+//! absorbed up to `buffer_capacity` messages. When the buffer is full, the
+//! backpressure policy decides whether the event loop waits or a message is
+//! dropped (and counted).
+
+use crate::config::BackpressurePolicy;
+use danube_connect_core::SourceRecord;
+use rumqttc::Publish;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use tracing::warn;
+
+/// A record waiting to be handed to the source runtime
+pub struct BufferedRecord {
+    /// Record to publish
+    pub record: SourceRecord,
+    /// Message to acknowledge once the record is handed off (manual acks only)
+    pub publish: Option<Publish>,
+    /// Duplicate suppression key (QoS 2 messages with dedup enabled)
+    pub dedup_key: Option<u64>,
+}
+
+/// Bounded FIFO of records with a backpressure policy
+pub struct RecordBuffer {
+    capacity: usize,
+    policy: BackpressurePolicy,
+    records: Mutex<VecDeque<BufferedRecord>>,
+    /// Signalled when a record is pushed or the buffer is closed
+    not_empty: Notify,
+    /// Signalled when a record is popped or the buffer is closed
+    not_full: Notify,
+    closed: AtomicBool,
+    dropped: AtomicU64,
+}
+
+impl RecordBuffer {
+    /// Create an empty buffer
+    pub fn new(capacity: usize, policy: BackpressurePolicy) -> Self {
+        Self {
+            capacity,
+            policy,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+            not_empty: Notify::new(),
+            not_full: Notify::new(),
+            closed: AtomicBool::new(false),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Add a record, applying the backpressure policy if the buffer is full
+    ///
+    /// Returns the dropped record (the new or the oldest one) so its message
+    /// can still be acknowledged, or Err with the record if the buffer is closed.
+    pub async fn push(
+        &self,
+        record: BufferedRecord,
+    ) -> Result<Option<BufferedRecord>, BufferedRecord> {
+        loop {
+            if self.is_closed() {
+                return Err(record);
+            }
+
+            {
+                let mut records = self.records.lock().unwrap();
+                if records.len() < self.capacity {
+                    records.push_back(record);
+                    self.not_empty.notify_one();
+                    return Ok(None);
+                }
+
+                match self.policy {
+                    BackpressurePolicy::Block => {}
+                    BackpressurePolicy::DropNewest => {
+                        self.count_drop();
+                        return Ok(Some(record));
+                    }
+                    BackpressurePolicy::DropOldest => {
+                        let oldest = records.pop_front();
+                        records.push_back(record);
+                        self.not_empty.notify_one();
+                        self.count_drop();
+                        return Ok(oldest);
+                    }
+                }
+            }
+
+            self.not_full.notified().await;
+        }
+    }
+
+    /// Take the oldest record, waiting until one is available
+    ///
+    /// Returns None once the buffer is closed and drained.
+    pub async fn pop(&self) -> Option<BufferedRecord> {
+        loop {
+            {
+                let mut records = self.records.lock().unwrap();
+                if let Some(record) = records.pop_front() {
+                    self.not_full.notify_one();
+                    return Some(record);
+                }
+            }
+
+            if self.is_closed() {
+                return None;
+            }
+            self.not_empty.notified().await;
+        }
+    }
+
+    /// Stop accepting records (buffered records can still be taken)
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.not_empty.notify_one();
+        self.not_full.notify_one();
+    }
+
+    /// Whether the buffer was closed
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Number of records dropped by the backpressure policy
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn count_drop(&self) {
+        let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        if dropped == 1 || dropped % 1000 == 0 {
+            warn!(
+                "MQTT buffer full ({} records), {} messages dropped so far ({:?})",
+                self.capacity, dropped, self.policy
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(n: u64) -> BufferedRecord {
+        BufferedRecord {
+            record: SourceRecord::new("/iot/test", json!({ "n": n })),
+            publish: Some(Publish::new(
+                format!("sensors/{}", n),
+                rumqttc::QoS::AtLeastOnce,
+                vec![],
+            )),
+            dedup_key: None,
+        }
+    }
+
+    /// Close the buffer and return the MQTT topics of the remaining records
+    async fn drain(buffer: &RecordBuffer) -> Vec<String> {
+        buffer.close();
+        let mut topics = Vec::new();
+        while let Some(buffered) = buffer.pop().await {
+            topics.push(buffered.publish.unwrap().topic);
+        }
+        topics
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest() {
+        let buffer = RecordBuffer::new(2, BackpressurePolicy::DropNewest);
+        for n in 1..=3 {
+            buffer.push(record(n)).await.ok().unwrap();
+        }
+
+        assert_eq!(buffer.dropped(), 1);
+        assert_eq!(drain(&buffer).await, vec!["sensors/1", "sensors/2"]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() {
+        let buffer = RecordBuffer::new(2, BackpressurePolicy::DropOldest);
+        for n in 1..=3 {
+            buffer.push(record(n)).await.ok().unwrap();
+        }
+
+        assert_eq!(buffer.dropped(), 1);
+        assert_eq!(drain(&buffer).await, vec!["sensors/2", "sensors/3"]);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let buffer = std::sync::Arc::new(RecordBuffer::new(1, BackpressurePolicy::Block));
+        buffer.push(record(1)).await.ok().unwrap();
+
+        let pusher = {
+            let buffer = buffer.clone();
+            tokio::spawn(async move { buffer.push(record(2)).await.is_ok() })
+        };
+        tokio::task::yield_now().await;
+        assert!(!pusher.is_finished());
+
+        assert!(buffer.pop().await.is_some());
+        assert!(pusher.await.unwrap());
+        assert_eq!(buffer.dropped(), 0);
+        assert_eq!(drain(&buffer).await, vec!["sensors/2"]);
+    }
+}
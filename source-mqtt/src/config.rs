@@ -4,7 +4,9 @@ use danube_connect_core::{
     ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorResult,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
+use std::path::Path;
 use std::time::Duration;
 
 /// Unified configuration for MQTT Source Connector
@@ -87,6 +89,18 @@ impl ConfigEnvOverrides for MqttSourceConfig {
             }
         }
 
+        if let Ok(ca_path) = env::var("MQTT_TLS_CA_PATH") {
+            self.mqtt.tls_ca_path = Some(ca_path);
+        }
+
+        if let Ok(cert_path) = env::var("MQTT_TLS_CLIENT_CERT_PATH") {
+            self.mqtt.tls_client_cert_path = Some(cert_path);
+        }
+
+        if let Ok(key_path) = env::var("MQTT_TLS_CLIENT_KEY_PATH") {
+            self.mqtt.tls_client_key_path = Some(key_path);
+        }
+
         Ok(())
     }
 }
@@ -124,6 +138,10 @@ pub struct MqttConfig {
     #[serde(default = "default_port")]
     pub broker_port: u16,
 
+    /// Failover brokers, tried in order after a connection error (then back to the primary)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failover_brokers: Vec<BrokerAddress>,
+
     /// Client ID for MQTT connection
     pub client_id: String,
 
@@ -137,6 +155,22 @@ pub struct MqttConfig {
     #[serde(default)]
     pub use_tls: bool,
 
+    /// CA bundle (PEM) to verify the broker certificate (system roots if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_ca_path: Option<String>,
+
+    /// Client certificate (PEM) for mutual TLS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_client_cert_path: Option<String>,
+
+    /// Client private key (PEM) for mutual TLS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_client_key_path: Option<String>,
+
+    /// Skip broker certificate verification (testing only)
+    #[serde(default)]
+    pub tls_insecure_skip_verify: bool,
+
     /// Keep alive interval in seconds
     #[serde(default = "default_keep_alive")]
     pub keep_alive_secs: u64,
@@ -149,6 +183,26 @@ pub struct MqttConfig {
     #[serde(default = "default_max_packet_size")]
     pub max_packet_size: usize,
 
+    /// Delay before the first reconnect attempt in milliseconds (doubles per failed attempt)
+    #[serde(default = "default_reconnect_backoff_ms")]
+    pub reconnect_backoff_ms: u64,
+
+    /// Upper bound for the reconnect delay in milliseconds
+    #[serde(default = "default_max_reconnect_backoff_ms")]
+    pub max_reconnect_backoff_ms: u64,
+
+    /// Consecutive failed reconnect attempts before the event loop gives up (0 = unlimited)
+    #[serde(default)]
+    pub max_reconnect_attempts: u32,
+
+    /// Seconds the broker may be unreachable before the health check fails (0 = never)
+    #[serde(default = "default_health_max_disconnected_secs")]
+    pub health_max_disconnected_secs: u64,
+
+    /// Seconds without messages or ping responses before the health check fails (0 = never)
+    #[serde(default)]
+    pub health_max_silence_secs: u64,
+
     /// Routes (MQTT topic -> Danube topic)
     pub routes: Vec<TopicMapping>,
 
@@ -156,16 +210,69 @@ pub struct MqttConfig {
     #[serde(default = "default_true")]
     pub clean_session: bool,
 
+    /// Acknowledge QoS 1/2 messages only once their record is handed to the source
+    /// runtime, instead of on receipt (use with clean_session = false); records the
+    /// runtime hasn't published yet are still lost on a crash
+    #[serde(default)]
+    pub manual_ack: bool,
+
     /// Add MQTT metadata as message attributes
     #[serde(default = "default_true")]
     pub include_metadata: bool,
 
+    /// Messages buffered between the MQTT event loop and the source runtime
+    #[serde(default = "default_buffer_capacity")]
+    pub buffer_capacity: usize,
+
+    /// What to do when the buffer is full
+    #[serde(default)]
+    pub backpressure: BackpressurePolicy,
+
+    /// Suppress duplicates of QoS 2 messages across reconnects and restarts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup: Option<DedupConfig>,
+
     /// Enable TCP_NODELAY for reduced latency (disables Nagle's algorithm)
     /// Beneficial for real-time messaging scenarios
     #[serde(default = "default_true")]
     pub tcp_nodelay: bool,
 }
 
+/// Behavior when the message buffer is full
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Stop reading from the broker until there is room (no message loss)
+    #[default]
+    Block,
+    /// Drop the oldest buffered message to make room
+    DropOldest,
+    /// Drop the incoming message
+    DropNewest,
+}
+
+/// Persistent duplicate suppression of QoS 2 messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// File remembering the messages published within the TTL
+    pub path: String,
+
+    /// Seconds a published message is remembered
+    #[serde(default = "default_dedup_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+/// Address of a failover MQTT broker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerAddress {
+    /// Broker host
+    pub host: String,
+
+    /// Broker port
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
 fn default_port() -> u16 {
     1883
 }
@@ -182,6 +289,26 @@ fn default_max_packet_size() -> usize {
     10 * 1024 * 1024 // 10MB
 }
 
+fn default_buffer_capacity() -> usize {
+    1000
+}
+
+fn default_dedup_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_reconnect_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_reconnect_backoff_ms() -> u64 {
+    60_000
+}
+
+fn default_health_max_disconnected_secs() -> u64 {
+    120
+}
+
 fn default_true() -> bool {
     true
 }
@@ -195,6 +322,16 @@ impl MqttConfig {
             ));
         }
 
+        if self
+            .failover_brokers
+            .iter()
+            .any(|broker| broker.host.is_empty())
+        {
+            return Err(danube_connect_core::ConnectorError::config(
+                "failover_brokers host cannot be empty",
+            ));
+        }
+
         if self.client_id.is_empty() {
             return Err(danube_connect_core::ConnectorError::config(
                 "client_id cannot be empty",
@@ -207,6 +344,50 @@ impl MqttConfig {
             ));
         }
 
+        if self.health_max_silence_secs > 0 && self.health_max_silence_secs <= self.keep_alive_secs
+        {
+            return Err(danube_connect_core::ConnectorError::config(
+                "health_max_silence_secs must be greater than keep_alive_secs (idle connections are only pinged every keep_alive_secs)",
+            ));
+        }
+
+        if let Some(dedup) = &self.dedup {
+            if dedup.path.is_empty() || dedup.ttl_secs == 0 {
+                return Err(danube_connect_core::ConnectorError::config(
+                    "dedup requires a 'path' and a 'ttl_secs' greater than 0",
+                ));
+            }
+            if !self
+                .routes
+                .iter()
+                .any(|mapping| mapping.qos == QoS::ExactlyOnce)
+            {
+                tracing::warn!("dedup only applies to QoS 2 (ExactlyOnce) routes, none configured");
+            }
+        }
+
+        if self.buffer_capacity == 0 {
+            return Err(danube_connect_core::ConnectorError::config(
+                "buffer_capacity must be at least 1",
+            ));
+        }
+
+        if self.manual_ack && self.clean_session {
+            tracing::warn!(
+                "manual_ack with clean_session = true: unacknowledged messages are not redelivered after a reconnect"
+            );
+        }
+
+        if self.reconnect_backoff_ms == 0
+            || self.max_reconnect_backoff_ms < self.reconnect_backoff_ms
+        {
+            return Err(danube_connect_core::ConnectorError::config(
+                "reconnect_backoff_ms must be at least 1 and at most max_reconnect_backoff_ms",
+            ));
+        }
+
+        self.validate_tls()?;
+
         for mapping in &self.routes {
             if mapping.from.is_empty() {
                 return Err(danube_connect_core::ConnectorError::config(
@@ -218,15 +399,173 @@ impl MqttConfig {
                     "Route 'to' cannot be empty",
                 ));
             }
+            if mapping
+                .exclude_topics
+                .iter()
+                .any(|pattern| pattern.is_empty())
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route '{}': exclude_topics cannot contain empty patterns",
+                    mapping.from
+                )));
+            }
+            if let Some(rate_limit) = &mapping.rate_limit {
+                if rate_limit.messages_per_sec == 0 || rate_limit.burst == Some(0) {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': rate_limit 'messages_per_sec' and 'burst' must be greater than 0",
+                        mapping.from
+                    )));
+                }
+            }
+            if mapping.event_time_field.as_deref() == Some("")
+                || (mapping.event_time_format.is_some() && mapping.event_time_field.is_none())
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route '{}': event_time_format requires a non-empty event_time_field",
+                    mapping.from
+                )));
+            }
+            if mapping.key_field.as_deref() == Some("") {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route '{}': key_field cannot be empty",
+                    mapping.from
+                )));
+            }
+            for condition in &mapping.filter {
+                if condition.field.is_empty() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': filter 'field' cannot be empty",
+                        mapping.from
+                    )));
+                }
+                if condition.op != FilterOp::Exists && condition.value.is_none() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': filter on '{}' requires a 'value'",
+                        mapping.from, condition.field
+                    )));
+                }
+            }
+            if let Some(transform) = &mapping.transform {
+                if transform.rename.values().any(|name| name.is_empty()) {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': transform cannot rename a field to an empty name",
+                        mapping.from
+                    )));
+                }
+            }
+            if let Some(validation) = &mapping.validation {
+                if validation.schema.is_some() == validation.schema_file.is_some() {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': validation requires either 'schema' or 'schema_file'",
+                        mapping.from
+                    )));
+                }
+                if validation
+                    .dead_letter_topic
+                    .as_ref()
+                    .is_some_and(|topic| !topic.starts_with('/') || topic == &mapping.to)
+                {
+                    return Err(danube_connect_core::ConnectorError::config(format!(
+                        "Route '{}': dead_letter_topic must be a Danube topic other than 'to'",
+                        mapping.from
+                    )));
+                }
+            }
+            if mapping.is_dynamic() && !is_valid_template(&mapping.to) {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route 'to' template '{}' must be /{{namespace}}/{{topic}} with {{n}} level placeholders (n >= 1)",
+                    mapping.to
+                )));
+            }
+            if (mapping.max_topics.is_some() || mapping.overflow_topic.is_some())
+                && (!mapping.is_dynamic() || mapping.max_topics.unwrap_or(0) == 0)
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route '{}': max_topics (at least 1) and overflow_topic require a 'to' template",
+                    mapping.from
+                )));
+            }
+            if mapping
+                .overflow_topic
+                .as_ref()
+                .is_some_and(|topic| !topic.starts_with('/') || topic.contains('{'))
+            {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "Route '{}': overflow_topic must be a Danube topic, not a template",
+                    mapping.from
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the TLS settings (files must exist, client cert and key come in pairs)
+    fn validate_tls(&self) -> ConnectorResult<()> {
+        let tls_set = self.tls_ca_path.is_some()
+            || self.tls_client_cert_path.is_some()
+            || self.tls_client_key_path.is_some()
+            || self.tls_insecure_skip_verify;
+        if tls_set && !self.use_tls {
+            return Err(danube_connect_core::ConnectorError::config(
+                "TLS settings require use_tls = true",
+            ));
+        }
+
+        if self.tls_client_cert_path.is_some() != self.tls_client_key_path.is_some() {
+            return Err(danube_connect_core::ConnectorError::config(
+                "tls_client_cert_path and tls_client_key_path must be set together",
+            ));
+        }
+
+        if self.tls_client_cert_path.is_some()
+            && self.tls_ca_path.is_none()
+            && !self.tls_insecure_skip_verify
+        {
+            return Err(danube_connect_core::ConnectorError::config(
+                "tls_client_cert_path requires tls_ca_path",
+            ));
+        }
+
+        for path in [
+            &self.tls_ca_path,
+            &self.tls_client_cert_path,
+            &self.tls_client_key_path,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !Path::new(path).is_file() {
+                return Err(danube_connect_core::ConnectorError::config(format!(
+                    "TLS file not found: {}",
+                    path
+                )));
+            }
         }
 
         Ok(())
     }
 
     /// Get MQTT connection options
-    pub fn mqtt_options(&self) -> rumqttc::MqttOptions {
-        let mut options =
-            rumqttc::MqttOptions::new(&self.client_id, &self.broker_host, self.broker_port);
+    pub fn mqtt_options(&self) -> ConnectorResult<rumqttc::MqttOptions> {
+        self.mqtt_options_for(&self.broker_host, self.broker_port)
+    }
+
+    /// Brokers in connection order: the primary, then the failover brokers
+    pub fn broker_addresses(&self) -> Vec<(String, u16)> {
+        std::iter::once((self.broker_host.clone(), self.broker_port))
+            .chain(
+                self.failover_brokers
+                    .iter()
+                    .map(|broker| (broker.host.clone(), broker.port)),
+            )
+            .collect()
+    }
+
+    /// Get MQTT connection options for a specific broker
+    pub fn mqtt_options_for(&self, host: &str, port: u16) -> ConnectorResult<rumqttc::MqttOptions> {
+        let mut options = rumqttc::MqttOptions::new(&self.client_id, host, port);
+        options.set_manual_acks(self.manual_ack);
 
         options.set_keep_alive(Duration::from_secs(self.keep_alive_secs));
         options.set_clean_session(self.clean_session);
@@ -236,7 +575,21 @@ impl MqttConfig {
             options.set_credentials(username, password);
         }
 
-        options
+        if self.use_tls {
+            options.set_transport(crate::tls::transport(self)?);
+        }
+
+        Ok(options)
+    }
+
+    /// Delay before a reconnect attempt (1-based), doubling up to the maximum
+    pub fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.reconnect_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_reconnect_backoff_ms),
+        )
     }
 
     /// Get network options for the MQTT connection
@@ -252,6 +605,33 @@ impl MqttConfig {
     }
 }
 
+/// Check a Danube topic template: "/{namespace}/{topic}" with well-formed `{n}` placeholders
+fn is_valid_template(template: &str) -> bool {
+    let Some(path) = template.strip_prefix('/') else {
+        return false;
+    };
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() != 2 || segments.iter().any(|segment| segment.is_empty()) {
+        return false;
+    }
+
+    let mut rest = path;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return false;
+        }
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            return false;
+        };
+        if !matches!(rest[start + 1..end].parse::<usize>(), Ok(index) if index >= 1) {
+            return false;
+        }
+        rest = &rest[end + 1..];
+    }
+
+    true
+}
+
 /// MQTT Quality of Service level
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[allow(clippy::enum_variant_names)] // MQTT spec naming convention
@@ -280,7 +660,8 @@ pub struct TopicMapping {
     /// MQTT topic pattern (supports wildcards: +, #)
     pub from: String,
 
-    /// Target Danube topic
+    /// Target Danube topic, or a template with `{n}` placeholders for the n-th
+    /// MQTT topic level (e.g., "/iot/{2}_{4}" for "factory/+/sensors/#")
     pub to: String,
 
     /// QoS level for MQTT subscription
@@ -297,9 +678,266 @@ pub struct TopicMapping {
     /// - QoS 1/2 (AtLeastOnce/ExactlyOnce) → reliable (default: true)
     #[serde(default)]
     pub reliable_dispatch: Option<bool>,
+
+    /// Compression of the MQTT payloads, undone before decoding
+    #[serde(default)]
+    pub decompression: Decompression,
+
+    /// Conditions on the decoded JSON payload; messages not matching all of them are dropped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter: Vec<FilterCondition>,
+
+    /// Payload transformation applied after the filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+
+    /// JSON Schema validation of the decoded payload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<SchemaValidation>,
+
+    /// Maximum number of Danube topics a `to` template may render (unlimited if not set)
+    ///
+    /// Topics keep their slot until restart: producers are owned by the runtime and
+    /// can't be closed, so evicting idle topics would not bound them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_topics: Option<usize>,
+
+    /// Danube topic for messages of new topic values once `max_topics` is reached
+    /// (dropped if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow_topic: Option<String>,
+
+    /// MQTT topic patterns under `from` that this route ignores (e.g. "sensors/+/debug")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_topics: Vec<String>,
+
+    /// Payload field (dotted path) used as routing key instead of the MQTT topic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_field: Option<String>,
+
+    /// Payload field (dotted path) with the device-reported event time, published as
+    /// the `event_time` attribute instead of the receive time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_time_field: Option<String>,
+
+    /// Format of the event time field: "rfc3339" (default), "unix_s", "unix_ms",
+    /// "unix_us", or a chrono pattern (e.g. "%Y-%m-%d %H:%M:%S")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_time_format: Option<String>,
+
+    /// How records are keyed (if no `key_field` value), e.g. "mqtt_topic", "client_segment:2" or "none"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_key: Option<PartitionKey>,
+
+    /// Ingest rate limit of the route
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Routing key strategy for partitioned Danube topics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PartitionKey {
+    /// The full MQTT topic
+    MqttTopic,
+    /// The n-th level (1-based) of the MQTT topic, e.g. the device id
+    ClientSegment(usize),
+    /// No key (records are spread across partitions)
+    None,
+}
+
+impl PartitionKey {
+    /// Routing key for a received MQTT topic (None if the level doesn't exist)
+    pub fn key(&self, mqtt_topic: &str) -> Option<String> {
+        match self {
+            PartitionKey::MqttTopic => Some(mqtt_topic.to_string()),
+            PartitionKey::ClientSegment(n) => mqtt_topic
+                .split('/')
+                .nth(n - 1)
+                .filter(|level| !level.is_empty())
+                .map(str::to_string),
+            PartitionKey::None => None,
+        }
+    }
+}
+
+impl TryFrom<String> for PartitionKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "mqtt_topic" => Ok(PartitionKey::MqttTopic),
+            "none" => Ok(PartitionKey::None),
+            _ => value
+                .strip_prefix("client_segment:")
+                .and_then(|n| n.parse().ok())
+                .filter(|&n| n >= 1)
+                .map(PartitionKey::ClientSegment)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid partition_key '{}', expected \"mqtt_topic\", \"client_segment:<n>\" (n >= 1) or \"none\"",
+                        value
+                    )
+                }),
+        }
+    }
+}
+
+impl From<PartitionKey> for String {
+    fn from(key: PartitionKey) -> Self {
+        match key {
+            PartitionKey::MqttTopic => "mqtt_topic".to_string(),
+            PartitionKey::ClientSegment(n) => format!("client_segment:{}", n),
+            PartitionKey::None => "none".to_string(),
+        }
+    }
+}
+
+/// Token bucket rate limit of a route's messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Sustained messages per second
+    pub messages_per_sec: u32,
+
+    /// Messages accepted in a burst (defaults to `messages_per_sec`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+
+    /// What happens to messages over the limit
+    #[serde(default)]
+    pub on_limit: RateLimitAction,
+}
+
+/// Behavior for messages over a route's rate limit
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAction {
+    /// Drop the message
+    #[default]
+    Drop,
+    /// Wait for the limit, pausing reads from the broker
+    Defer,
+}
+
+/// JSON Schema validation of a route's payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaValidation {
+    /// Inline JSON Schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+
+    /// Path to a JSON Schema file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_file: Option<String>,
+
+    /// Danube topic for invalid messages, with the validation errors (dropped if not set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_topic: Option<String>,
+}
+
+/// Payload compression applied by the publisher
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Decompression {
+    /// Payloads are not compressed
+    #[default]
+    None,
+    /// gzip-compressed payloads
+    Gzip,
+    /// Zstandard-compressed payloads
+    Zstd,
+}
+
+/// Condition on a payload field (e.g., `{ field = "temperature", op = "gt", value = 30 }`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterCondition {
+    /// Payload field (dotted path for nested fields, e.g. "reading.value")
+    pub field: String,
+
+    /// Comparison operator
+    pub op: FilterOp,
+
+    /// Value to compare with (not used by `exists`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+/// Filter comparison operator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterOp {
+    /// Field equals the value
+    Eq,
+    /// Field differs from the value (or is missing)
+    Ne,
+    /// Field is greater than the value (numbers or strings)
+    Gt,
+    /// Field is greater than or equal to the value
+    Gte,
+    /// Field is less than the value
+    Lt,
+    /// Field is less than or equal to the value
+    Lte,
+    /// Field is present (and not null)
+    Exists,
+}
+
+/// Payload transformation, applied in order: select, rename, add_fields
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transform {
+    /// Top-level fields to keep (all if empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub select: Vec<String>,
+
+    /// Top-level fields to rename (old name → new name)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rename: HashMap<String, String>,
+
+    /// Static fields to add (overwriting existing ones)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub add_fields: HashMap<String, serde_json::Value>,
 }
 
 impl TopicMapping {
+    /// Whether the Danube topic is rendered per message from MQTT topic levels
+    pub fn is_dynamic(&self) -> bool {
+        self.to.contains('{')
+    }
+
+    /// Render the Danube topic for a received MQTT topic
+    ///
+    /// Characters of a topic level that are not allowed in Danube topic names
+    /// are replaced with '_'. Returns None if a referenced level doesn't exist.
+    pub fn danube_topic(&self, mqtt_topic: &str) -> Option<String> {
+        if !self.is_dynamic() {
+            return Some(self.to.clone());
+        }
+
+        let levels: Vec<&str> = mqtt_topic.split('/').collect();
+        let mut topic = String::with_capacity(self.to.len());
+        let mut rest = self.to.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}')?;
+            let index: usize = rest[start + 1..end].parse().ok()?;
+            let level = levels
+                .get(index.checked_sub(1)?)
+                .filter(|level| !level.is_empty())?;
+
+            topic.push_str(&rest[..start]);
+            topic.extend(level.chars().map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            }));
+            rest = &rest[end + 1..];
+        }
+        topic.push_str(rest);
+
+        Some(topic)
+    }
+
     /// Get the effective reliable dispatch setting based on QoS if not explicitly set
     pub fn effective_reliable_dispatch(&self) -> bool {
         self.reliable_dispatch.unwrap_or_else(|| {
@@ -317,38 +955,262 @@ fn default_qos() -> QoS {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_partition_key() {
+        let key = |value: &str| PartitionKey::try_from(value.to_string());
+
+        assert_eq!(key("mqtt_topic"), Ok(PartitionKey::MqttTopic));
+        assert_eq!(key("client_segment:2"), Ok(PartitionKey::ClientSegment(2)));
+        assert_eq!(key("none"), Ok(PartitionKey::None));
+        assert!(key("client_segment:0").is_err());
+        assert!(key("client_segment").is_err());
+        assert!(key("payload").is_err());
+
+        let topic = "factory/device-7/telemetry";
+        assert_eq!(
+            PartitionKey::MqttTopic.key(topic).as_deref(),
+            Some("factory/device-7/telemetry")
+        );
+        assert_eq!(
+            PartitionKey::ClientSegment(2).key(topic).as_deref(),
+            Some("device-7")
+        );
+        assert_eq!(PartitionKey::ClientSegment(4).key(topic), None);
+        assert_eq!(PartitionKey::None.key(topic), None);
+        assert_eq!(
+            String::from(PartitionKey::ClientSegment(2)),
+            "client_segment:2"
+        );
+    }
+
+    #[test]
+    fn test_danube_topic_template() {
+        let mut mapping = TopicMapping {
+            from: "factory/+/sensors/#".to_string(),
+            to: "/iot/{2}_{4}".to_string(),
+            qos: QoS::AtLeastOnce,
+            partitions: 0,
+            reliable_dispatch: None,
+            decompression: Decompression::None,
+            filter: vec![],
+            transform: None,
+            validation: None,
+            max_topics: None,
+            overflow_topic: None,
+            exclude_topics: vec![],
+            key_field: None,
+            event_time_field: None,
+            event_time_format: None,
+            rate_limit: None,
+            partition_key: None,
+        };
+
+        assert_eq!(
+            mapping.danube_topic("factory/line1/sensors/temp"),
+            Some("/iot/line1_temp".to_string())
+        );
+        assert_eq!(
+            mapping.danube_topic("factory/line 2/sensors/temp.c/zone1"),
+            Some("/iot/line_2_temp_c".to_string())
+        );
+        assert_eq!(mapping.danube_topic("factory/line1/sensors"), None);
+
+        mapping.to = "/iot/sensors".to_string();
+        assert!(!mapping.is_dynamic());
+        assert_eq!(
+            mapping.danube_topic("factory/line1/sensors/temp"),
+            Some("/iot/sensors".to_string())
+        );
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = MqttConfig {
             broker_host: "localhost".to_string(),
             broker_port: 1883,
+            failover_brokers: vec![],
             client_id: "test-client".to_string(),
             username: None,
             password: None,
             use_tls: false,
+            tls_ca_path: None,
+            tls_client_cert_path: None,
+            tls_client_key_path: None,
+            tls_insecure_skip_verify: false,
             keep_alive_secs: 60,
             connection_timeout_secs: 30,
             max_packet_size: 1024 * 1024,
+            reconnect_backoff_ms: 1000,
+            max_reconnect_backoff_ms: 60_000,
+            max_reconnect_attempts: 0,
+            health_max_disconnected_secs: 120,
+            health_max_silence_secs: 0,
             routes: vec![TopicMapping {
                 from: "sensors/#".to_string(),
                 to: "/mqtt/sensors".to_string(),
                 qos: QoS::AtLeastOnce,
                 partitions: 0,
                 reliable_dispatch: None,
+                decompression: Decompression::None,
+                filter: vec![],
+                transform: None,
+                validation: None,
+                max_topics: None,
+                overflow_topic: None,
+                exclude_topics: vec![],
+                key_field: None,
+                event_time_field: None,
+                event_time_format: None,
+                event_time_field: None,
+                event_time_format: None,
+                rate_limit: None,
+                partition_key: None,
             }],
             clean_session: true,
+            manual_ack: false,
             include_metadata: true,
+            buffer_capacity: 1000,
+            backpressure: BackpressurePolicy::Block,
+            dedup: None,
             tcp_nodelay: true,
         };
 
         assert!(config.validate().is_ok());
 
+        assert_eq!(
+            config.broker_addresses(),
+            vec![("localhost".to_string(), 1883)]
+        );
+        config.failover_brokers = vec![BrokerAddress {
+            host: "standby".to_string(),
+            port: 8883,
+        }];
+        assert_eq!(
+            config.broker_addresses(),
+            vec![
+                ("localhost".to_string(), 1883),
+                ("standby".to_string(), 8883)
+            ]
+        );
+        config.failover_brokers[0].host = "".to_string();
+        assert!(config.validate().is_err());
+        config.failover_brokers.clear();
+
+        // Reconnect backoff must be a non-empty range
+        config.max_reconnect_backoff_ms = 500;
+        assert!(config.validate().is_err());
+        config.max_reconnect_backoff_ms = 60_000;
+
+        // Idle connections are only pinged every keep_alive_secs
+        config.health_max_silence_secs = 60;
+        assert!(config.validate().is_err());
+        config.health_max_silence_secs = 180;
+        assert!(config.validate().is_ok());
+        config.health_max_silence_secs = 0;
+
+        config.dedup = Some(DedupConfig {
+            path: "/var/lib/danube/mqtt-dedup.log".to_string(),
+            ttl_secs: 0,
+        });
+        assert!(config.validate().is_err());
+        config.dedup.as_mut().unwrap().ttl_secs = 3600;
+        assert!(config.validate().is_ok());
+        config.dedup = None;
+
+        assert_eq!(config.reconnect_delay(1), Duration::from_millis(1000));
+        assert_eq!(config.reconnect_delay(3), Duration::from_millis(4000));
+        assert_eq!(config.reconnect_delay(40), Duration::from_millis(60_000));
+
         // Test empty broker host
         config.broker_host = "".to_string();
         assert!(config.validate().is_err());
 
-        // Test empty topic mappings
+        // TLS settings require use_tls, and a client cert requires its key
         config.broker_host = "localhost".to_string();
+        config.tls_insecure_skip_verify = true;
+        assert!(config.validate().is_err());
+        config.use_tls = true;
+        assert!(config.validate().is_ok());
+        config.tls_client_cert_path = Some("Cargo.toml".to_string());
+        assert!(config.validate().is_err());
+        config.tls_client_key_path = Some("Cargo.toml".to_string());
+        assert!(config.validate().is_ok());
+        config.tls_ca_path = Some("/nonexistent/ca.pem".to_string());
+        assert!(config.validate().is_err());
+
+        // Danube topic templates
+        config.tls_ca_path = None;
+        config.routes[0].to = "/iot/{2}_{3}".to_string();
+        assert!(config.validate().is_ok());
+        for invalid in ["/iot/{0}", "/iot/{x}", "/iot/{1", "/iot/1}", "/iot/{1}/{2}"] {
+            config.routes[0].to = invalid.to_string();
+            assert!(config.validate().is_err(), "{}", invalid);
+        }
+        config.routes[0].to = "/mqtt/sensors".to_string();
+
+        // Filters other than 'exists' need a value
+        config.routes[0].filter = vec![FilterCondition {
+            field: "temperature".to_string(),
+            op: FilterOp::Gt,
+            value: None,
+        }];
+        assert!(config.validate().is_err());
+        config.routes[0].filter[0].op = FilterOp::Exists;
+        assert!(config.validate().is_ok());
+
+        // Validation needs exactly one schema source
+        config.routes[0].validation = Some(SchemaValidation {
+            schema: None,
+            schema_file: None,
+            dead_letter_topic: Some("/mqtt/invalid".to_string()),
+        });
+        assert!(config.validate().is_err());
+        config.routes[0].validation.as_mut().unwrap().schema =
+            Some(serde_json::json!({"type": "object"}));
+        assert!(config.validate().is_ok());
+        config.routes[0].validation = None;
+
+        config.routes[0].key_field = Some(String::new());
+        assert!(config.validate().is_err());
+        config.routes[0].key_field = Some("device.id".to_string());
+        assert!(config.validate().is_ok());
+        config.routes[0].key_field = None;
+
+        config.routes[0].event_time_format = Some("unix_ms".to_string());
+        assert!(config.validate().is_err());
+        config.routes[0].event_time_field = Some("ts".to_string());
+        assert!(config.validate().is_ok());
+        config.routes[0].event_time_field = None;
+        config.routes[0].event_time_format = None;
+
+        // The topic cap only applies to templates
+        config.routes[0].max_topics = Some(100);
+        assert!(config.validate().is_err());
+        config.routes[0].to = "/mqtt/{2}".to_string();
+        assert!(config.validate().is_ok());
+        config.routes[0].overflow_topic = Some("/mqtt/{1}".to_string());
+        assert!(config.validate().is_err());
+        config.routes[0].overflow_topic = Some("/mqtt/overflow".to_string());
+        assert!(config.validate().is_ok());
+        config.routes[0].to = "/mqtt/sensors".to_string();
+        config.routes[0].max_topics = None;
+        config.routes[0].overflow_topic = None;
+
+        config.routes[0].rate_limit = Some(RateLimit {
+            messages_per_sec: 0,
+            burst: None,
+            on_limit: RateLimitAction::Drop,
+        });
+        assert!(config.validate().is_err());
+        config.routes[0]
+            .rate_limit
+            .as_mut()
+            .unwrap()
+            .messages_per_sec = 100;
+        assert!(config.validate().is_ok());
+        config.routes[0].rate_limit = None;
+
+        // Test empty topic mappings
         config.routes = vec![];
         assert!(config.validate().is_err());
     }
@@ -1,12 +1,21 @@
 //! MQTT source connector implementation.
 
-use crate::config::{MqttConfig, TopicMapping};
+use crate::buffer::{BufferedRecord, RecordBuffer};
+use crate::config::{BackpressurePolicy, MqttConfig, TopicMapping};
+use crate::dedup::{message_key, DedupStore};
+use crate::health::ConnectionHealth;
+use crate::rate_limit::{route_limiters, RateLimiter};
+use crate::transform;
+use crate::validation::{compile_validators, PayloadValidator};
 use async_trait::async_trait;
 use danube_connect_core::{
     ConnectorConfig, ConnectorError, ConnectorResult, Offset, ProducerConfig, SchemaMapping,
     SourceConnector, SourceConnectorMode, SourceRecord, SourceSender,
 };
 use rumqttc::{AsyncClient, Event, Packet, Publish};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::AbortHandle;
 use tracing::{debug, error, info, warn};
 
@@ -18,6 +27,16 @@ pub struct MqttSourceConnector {
     schemas: Vec<SchemaMapping>,
     mqtt_client: Option<AsyncClient>,
     event_loop_abort: Option<AbortHandle>,
+    forwarder_abort: Option<AbortHandle>,
+    buffer: Option<Arc<RecordBuffer>>,
+    /// Connection state tracked by the event loop
+    health: Arc<ConnectionHealth>,
+    /// QoS 2 messages already published, if dedup is enabled
+    dedup: Option<Arc<DedupStore>>,
+    /// Compiled JSON Schemas, keyed by route `from`
+    validators: Arc<HashMap<String, PayloadValidator>>,
+    /// Rate limiters, keyed by route `from`
+    limiters: Arc<HashMap<String, RateLimiter>>,
 }
 
 impl MqttSourceConnector {
@@ -28,6 +47,12 @@ impl MqttSourceConnector {
             schemas,
             mqtt_client: None,
             event_loop_abort: None,
+            forwarder_abort: None,
+            buffer: None,
+            health: Arc::default(),
+            dedup: None,
+            validators: Arc::default(),
+            limiters: Arc::default(),
         }
     }
 
@@ -38,21 +63,41 @@ impl MqttSourceConnector {
             config: MqttConfig {
                 broker_host: String::new(),
                 broker_port: 1883,
+                failover_brokers: vec![],
                 client_id: String::new(),
                 username: None,
                 password: None,
                 use_tls: false,
+                tls_ca_path: None,
+                tls_client_cert_path: None,
+                tls_client_key_path: None,
+                tls_insecure_skip_verify: false,
                 keep_alive_secs: 60,
                 connection_timeout_secs: 30,
                 max_packet_size: 10 * 1024 * 1024,
+                reconnect_backoff_ms: 1000,
+                max_reconnect_backoff_ms: 60_000,
+                max_reconnect_attempts: 0,
+                health_max_disconnected_secs: 120,
+                health_max_silence_secs: 0,
                 routes: vec![],
                 clean_session: true,
+                manual_ack: false,
                 include_metadata: true,
+                buffer_capacity: 1000,
+                backpressure: BackpressurePolicy::Block,
+                dedup: None,
                 tcp_nodelay: true,
             },
             schemas: vec![],
             mqtt_client: None,
             event_loop_abort: None,
+            forwarder_abort: None,
+            buffer: None,
+            health: Arc::default(),
+            dedup: None,
+            validators: Arc::default(),
+            limiters: Arc::default(),
         }
     }
 
@@ -96,21 +141,60 @@ impl MqttSourceConnector {
         }
     }
 
+    /// Subscribe to the MQTT topics of all routes
+    async fn subscribe_all(client: &AsyncClient, routes: &[TopicMapping]) -> ConnectorResult<()> {
+        for mapping in routes {
+            info!(
+                "Subscribing to MQTT topic: {} (QoS: {:?})",
+                mapping.from, mapping.qos
+            );
+
+            client
+                .subscribe(&mapping.from, mapping.qos.into())
+                .await
+                .map_err(|e| {
+                    ConnectorError::fatal_with_source(
+                        format!("Failed to subscribe to topic: {}", mapping.from),
+                        e,
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
     /// Spawn MQTT event loop task
+    ///
+    /// Connection errors are retried with exponential backoff. rumqttc
+    /// reconnects on the next poll, and when the broker has no session for the
+    /// client anymore (e.g., after a restart with clean_session), the
+    /// subscriptions are re-issued.
     fn spawn_event_loop(
         mut event_loop: rumqttc::EventLoop,
-        sender: SourceSender,
-        topic_mappings: Vec<TopicMapping>,
-        include_metadata: bool,
+        client: AsyncClient,
+        buffer: Arc<RecordBuffer>,
+        health: Arc<ConnectionHealth>,
+        validators: Arc<HashMap<String, PayloadValidator>>,
+        limiters: Arc<HashMap<String, RateLimiter>>,
+        config: MqttConfig,
     ) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             info!("MQTT event loop started");
 
+            let topic_mappings = &config.routes;
+            let include_metadata = config.include_metadata;
+            let mut connected_once = false;
+            let mut failed_attempts = 0u32;
+            let brokers = config.broker_addresses();
+            let mut broker = 0;
+            // Danube topics rendered by capped templates, per route
+            let mut fan_out = HashMap::new();
+
             loop {
                 match event_loop.poll().await {
                     Ok(event) => {
                         match event {
                             Event::Incoming(Packet::Publish(publish)) => {
+                                health.message_received();
                                 debug!(
                                     "Received MQTT message: topic={}, qos={}, size={}",
                                     publish.topic,
@@ -118,26 +202,44 @@ impl MqttSourceConnector {
                                     publish.payload.len()
                                 );
 
-                                // Find matching Danube topic mapping
-                                let mapping =
-                                    Self::find_mapping_static(&publish.topic, &topic_mappings);
-
-                                if let Some(mapping) = mapping {
-                                    let record = Self::publish_to_record_static(
-                                        &publish,
-                                        mapping,
-                                        include_metadata,
-                                    );
-
-                                    if let Err(e) = sender.send(record).await {
-                                        error!("Failed to send message to source runtime: {}", e);
+                                let Some(record) = Self::route_publish(
+                                    &publish,
+                                    topic_mappings,
+                                    &validators,
+                                    &limiters,
+                                    &mut fan_out,
+                                    include_metadata,
+                                )
+                                .await
+                                else {
+                                    // Skipped messages are acknowledged right away
+                                    if config.manual_ack {
+                                        Self::ack(&client, &publish);
+                                    }
+                                    continue;
+                                };
+
+                                // QoS 2 deliveries are checked against the dedup store
+                                let dedup_key = (config.dedup.is_some()
+                                    && publish.qos == rumqttc::QoS::ExactlyOnce)
+                                    .then(|| message_key(&publish.topic, &publish.payload));
+                                let buffered = BufferedRecord {
+                                    record,
+                                    publish: config.manual_ack.then_some(publish),
+                                    dedup_key,
+                                };
+                                match buffer.push(buffered).await {
+                                    Ok(None) => {}
+                                    // Dropped by the backpressure policy, don't let it be redelivered
+                                    Ok(Some(dropped)) => {
+                                        if let Some(publish) = dropped.publish {
+                                            Self::ack(&client, &publish);
+                                        }
+                                    }
+                                    Err(_) => {
+                                        error!("Source runtime stopped accepting messages");
                                         break;
                                     }
-                                } else {
-                                    warn!(
-                                        "No Danube topic mapping found for MQTT topic: {}",
-                                        publish.topic
-                                    );
                                 }
                             }
                             Event::Incoming(Packet::ConnAck(connack)) => {
@@ -145,14 +247,34 @@ impl MqttSourceConnector {
                                     "MQTT connected: session_present={}",
                                     connack.session_present
                                 );
+                                failed_attempts = 0;
+                                health.connected();
+
+                                // Initial subscriptions are queued by start_streaming
+                                if connected_once && !connack.session_present {
+                                    info!("MQTT session lost, re-subscribing to all topics");
+                                    // Subscribe from another task: the request channel is
+                                    // drained by this loop and could fill up
+                                    let client = client.clone();
+                                    let routes = topic_mappings.clone();
+                                    tokio::spawn(async move {
+                                        if let Err(e) = Self::subscribe_all(&client, &routes).await
+                                        {
+                                            error!("Failed to re-subscribe: {}", e);
+                                        }
+                                    });
+                                }
+                                connected_once = true;
                             }
                             Event::Incoming(Packet::SubAck(suback)) => {
                                 info!("MQTT subscription acknowledged: {:?}", suback.return_codes);
                             }
                             Event::Incoming(Packet::PingResp) => {
+                                health.ping_received();
                                 debug!("MQTT ping response received");
                             }
                             Event::Incoming(Packet::Disconnect) => {
+                                health.disconnected();
                                 warn!("MQTT disconnected");
                             }
                             Event::Outgoing(_) => {
@@ -165,41 +287,301 @@ impl MqttSourceConnector {
                     }
                     Err(e) => {
                         error!("MQTT event loop error: {}", e);
-                        if sender.is_closed() {
+                        health.disconnected();
+                        if buffer.is_closed() {
                             break;
                         }
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+                        failed_attempts += 1;
+                        if config.max_reconnect_attempts > 0
+                            && failed_attempts > config.max_reconnect_attempts
+                        {
+                            error!(
+                                "MQTT reconnect failed {} times, giving up",
+                                config.max_reconnect_attempts
+                            );
+                            break;
+                        }
+
+                        // Fail over to the next broker; rumqttc uses the options on reconnect
+                        if brokers.len() > 1 {
+                            broker = (broker + 1) % brokers.len();
+                            let (host, port) = &brokers[broker];
+                            match config.mqtt_options_for(host, *port) {
+                                Ok(options) => {
+                                    info!("Failing over to MQTT broker {}:{}", host, port);
+                                    event_loop.mqtt_options = options;
+                                }
+                                Err(e) => error!(
+                                    "Invalid options for MQTT broker {}:{}: {}",
+                                    host, port, e
+                                ),
+                            }
+                        }
+
+                        let delay = config.reconnect_delay(failed_attempts);
+                        info!(
+                            "Reconnecting to MQTT broker in {:?} (attempt {})",
+                            delay, failed_attempts
+                        );
+                        tokio::time::sleep(delay).await;
                     }
                 }
             }
 
+            buffer.close();
             info!("MQTT event loop stopped");
         })
     }
 
+    /// Spawn the task handing buffered records to the source runtime
+    fn spawn_forwarder(
+        buffer: Arc<RecordBuffer>,
+        sender: SourceSender,
+        client: AsyncClient,
+        dedup: Option<Arc<DedupStore>>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(buffered) = buffer.pop().await {
+                // Records are forwarded one at a time, so a duplicate still in
+                // the buffer is caught once its original is recorded
+                let dedup = dedup.as_ref().zip(buffered.dedup_key);
+                if let Some((store, key)) = dedup {
+                    if store.contains(key) {
+                        debug!("Skipping duplicate QoS 2 message (key {:016x})", key);
+                        if let Some(publish) = buffered.publish {
+                            Self::ack(&client, &publish);
+                        }
+                        continue;
+                    }
+                }
+
+                if let Err(e) = sender.send(buffered.record).await {
+                    error!("Failed to send message to source runtime: {}", e);
+                    break;
+                }
+
+                if let Some((store, key)) = dedup {
+                    if let Err(e) = store.insert(key) {
+                        warn!("Failed to record QoS 2 message in dedup store: {}", e);
+                    }
+                }
+
+                // With manual acks, the broker redelivers the message unless
+                // its record reached the runtime (not yet Danube, see commit)
+                if let Some(publish) = buffered.publish {
+                    Self::ack(&client, &publish);
+                }
+            }
+
+            buffer.close();
+            debug!("MQTT forwarder stopped");
+        })
+    }
+
+    /// Find the route of an MQTT message and build its record
+    ///
+    /// Returns None if the message is skipped: no route matches, the topic
+    /// template can't be rendered or is over its topic cap, or the route's rate
+    /// limit or filter drops it.
+    async fn route_publish(
+        publish: &Publish,
+        routes: &[TopicMapping],
+        validators: &HashMap<String, PayloadValidator>,
+        limiters: &HashMap<String, RateLimiter>,
+        fan_out: &mut HashMap<String, HashSet<String>>,
+        include_metadata: bool,
+    ) -> Option<SourceRecord> {
+        // Find matching Danube topic mapping
+        let Some(mapping) = Self::find_mapping_static(&publish.topic, routes) else {
+            if routes
+                .iter()
+                .any(|mapping| Self::topic_matches(&mapping.from, &publish.topic))
+            {
+                debug!("MQTT topic {} excluded by its routes", publish.topic);
+            } else {
+                warn!(
+                    "No Danube topic mapping found for MQTT topic: {}",
+                    publish.topic
+                );
+            }
+            return None;
+        };
+
+        if let Some(limiter) = limiters.get(&mapping.from) {
+            if !limiter.admit().await {
+                return None;
+            }
+        }
+
+        let Some(topic) = mapping.danube_topic(&publish.topic) else {
+            warn!(
+                "MQTT topic {} has no levels for Danube topic template {}",
+                publish.topic, mapping.to
+            );
+            return None;
+        };
+        let topic = match mapping.max_topics {
+            Some(max_topics) => {
+                let topics = fan_out.entry(mapping.from.clone()).or_default();
+                Self::cap_topic(mapping, max_topics, topic, topics)?
+            }
+            None => topic,
+        };
+
+        Self::publish_to_record_static(
+            publish,
+            mapping,
+            validators.get(&mapping.from),
+            &topic,
+            include_metadata,
+        )
+    }
+
+    /// Apply a route's topic cap to a rendered Danube topic
+    ///
+    /// Topics seen before and new ones under the cap pass through; once the cap
+    /// is reached, new topics are replaced by the overflow topic or dropped.
+    /// Topics are never evicted: the runtime keeps their producers open.
+    fn cap_topic(
+        mapping: &TopicMapping,
+        max_topics: usize,
+        topic: String,
+        topics: &mut HashSet<String>,
+    ) -> Option<String> {
+        if topics.contains(&topic) {
+            return Some(topic);
+        }
+
+        if topics.len() < max_topics {
+            topics.insert(topic.clone());
+            if topics.len() == max_topics {
+                warn!(
+                    "Route '{}' reached max_topics ({}), new topics go to {}",
+                    mapping.from,
+                    max_topics,
+                    mapping
+                        .overflow_topic
+                        .as_deref()
+                        .unwrap_or("nowhere (dropped)")
+                );
+            }
+            return Some(topic);
+        }
+
+        match &mapping.overflow_topic {
+            Some(overflow_topic) => Some(overflow_topic.clone()),
+            None => {
+                debug!(
+                    "Dropping message for {}: route '{}' is at max_topics",
+                    topic, mapping.from
+                );
+                None
+            }
+        }
+    }
+
+    /// Acknowledge a QoS 1/2 message to the broker (no-op for QoS 0)
+    fn ack(client: &AsyncClient, publish: &Publish) {
+        // The request channel is drained by the event loop itself, so don't
+        // block it when the channel is full
+        if client.try_ack(publish).is_err() {
+            let client = client.clone();
+            let publish = publish.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.ack(&publish).await {
+                    error!("Failed to acknowledge MQTT message: {}", e);
+                }
+            });
+        }
+    }
+
     /// Static version of publish_to_record for use in spawned task
-    /// Creates a SourceRecord from MQTT message and topic mapping
+    /// Creates a SourceRecord from MQTT message for the (rendered) Danube topic,
+    /// or None if the payload can't be decompressed or the route's filter drops it.
+    /// Payloads failing the route's JSON Schema become dead-letter records.
     fn publish_to_record_static(
         publish: &Publish,
         mapping: &TopicMapping,
+        validator: Option<&PayloadValidator>,
+        danube_topic: &str,
         include_metadata: bool,
-    ) -> SourceRecord {
+    ) -> Option<SourceRecord> {
+        let payload = match transform::decompress(mapping.decompression, &publish.payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!(
+                    "Dropping MQTT message on {}: {:?} decompression failed: {}",
+                    publish.topic, mapping.decompression, e
+                );
+                return None;
+            }
+        };
+
         // Convert MQTT payload to typed data
         // Try JSON first, fallback to base64-encoded bytes
-        let payload_value = match serde_json::from_slice::<serde_json::Value>(&publish.payload) {
+        let payload_value = match serde_json::from_slice::<serde_json::Value>(&payload) {
             Ok(json_value) => json_value,
             Err(_) => {
                 // Not JSON - encode as base64 bytes object
                 use serde_json::json;
                 json!({
-                    "data": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &publish.payload),
-                    "size": publish.payload.len(),
+                    "data": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &payload),
+                    "size": payload.len(),
                     "encoding": "base64"
                 })
             }
         };
 
-        let mut record = SourceRecord::new(&mapping.to, payload_value);
+        if let Some(validator) = validator {
+            if let Err(errors) = validator.validate(&payload_value) {
+                warn!(
+                    "MQTT message on {} failed schema validation: {}",
+                    publish.topic,
+                    errors.join("; ")
+                );
+                return validator.dead_letter(&publish.topic, payload_value, errors);
+            }
+        }
+
+        if !transform::matches(&mapping.filter, &payload_value) {
+            debug!("MQTT message on {} dropped by route filter", publish.topic);
+            return None;
+        }
+        // Taken before the transform, like the filter fields
+        let key = mapping.key_field.as_ref().and_then(|key_field| {
+            let key = transform::key(&payload_value, key_field);
+            if key.is_none() {
+                debug!(
+                    "MQTT message on {} has no '{}' key field, using the partition key",
+                    publish.topic, key_field
+                );
+            }
+            key
+        });
+        // Device-reported time, falling back to the receive time
+        let event_time = mapping.event_time_field.as_ref().map(|event_time_field| {
+            let format = mapping.event_time_format.as_deref().unwrap_or("rfc3339");
+            transform::event_time(&payload_value, event_time_field, format).unwrap_or_else(|| {
+                debug!(
+                    "MQTT message on {} has no {} '{}' event time, using the receive time",
+                    publish.topic, format, event_time_field
+                );
+                chrono::Utc::now()
+            })
+        });
+        let payload_value = match &mapping.transform {
+            Some(transform) => transform::apply(transform, payload_value),
+            None => payload_value,
+        };
+
+        let mut record = SourceRecord::new(danube_topic, payload_value);
+        if let Some(event_time) = event_time {
+            record = record.with_attribute(
+                "event_time",
+                event_time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            );
+        }
 
         // Add MQTT metadata as attributes
         if include_metadata {
@@ -209,12 +591,20 @@ impl MqttSourceConnector {
                 .with_attribute("mqtt.retain", publish.retain.to_string())
                 .with_attribute("mqtt.dup", publish.dup.to_string())
                 .with_attribute("source", "mqtt");
+        }
 
-            // Use MQTT topic as routing key for partitioned topics
-            record = record.with_key(&publish.topic);
+        // Routing key for partitioned topics: the key field, else the partition key
+        // strategy (by default the MQTT topic, if metadata or a key field is enabled)
+        let key = key.or_else(|| match mapping.partition_key {
+            Some(partition_key) => partition_key.key(&publish.topic),
+            None if include_metadata || mapping.key_field.is_some() => Some(publish.topic.clone()),
+            None => None,
+        });
+        if let Some(key) = key {
+            record = record.with_key(&key);
         }
 
-        record
+        Some(record)
     }
 
     /// Find the matching topic mapping for an MQTT topic
@@ -222,10 +612,14 @@ impl MqttSourceConnector {
         mqtt_topic: &str,
         routes: &'a [TopicMapping],
     ) -> Option<&'a TopicMapping> {
-        // Find first matching mapping (exact or wildcard)
+        // Find first matching mapping (exact or wildcard) that doesn't exclude the topic
         routes.iter().find(|mapping| {
             // Exact match or wildcard match
-            mapping.from == mqtt_topic || Self::topic_matches(&mapping.from, mqtt_topic)
+            (mapping.from == mqtt_topic || Self::topic_matches(&mapping.from, mqtt_topic))
+                && !mapping
+                    .exclude_topics
+                    .iter()
+                    .any(|pattern| Self::topic_matches(pattern, mqtt_topic))
         })
     }
 
@@ -260,6 +654,23 @@ impl SourceConnector for MqttSourceConnector {
 
         // Validate configuration (already loaded in main)
         self.config.validate()?;
+        self.validators = Arc::new(compile_validators(&self.config.routes)?);
+        self.limiters = Arc::new(route_limiters(&self.config.routes));
+        if let Some(dedup) = &self.config.dedup {
+            let store = DedupStore::open(dedup).map_err(|e| {
+                ConnectorError::config(format!(
+                    "Failed to open dedup store '{}': {}",
+                    dedup.path, e
+                ))
+            })?;
+            info!(
+                "MQTT dedup store {} loaded with {} messages (TTL {}s)",
+                dedup.path,
+                store.len(),
+                dedup.ttl_secs
+            );
+            self.dedup = Some(Arc::new(store));
+        }
 
         info!(
             "MQTT Configuration: broker={}:{}, client_id={}, topics={}",
@@ -268,6 +679,9 @@ impl SourceConnector for MqttSourceConnector {
             self.config.client_id,
             self.config.routes.len()
         );
+        for broker in &self.config.failover_brokers {
+            info!("Failover MQTT broker: {}:{}", broker.host, broker.port);
+        }
 
         // Log topic mappings
         for mapping in &self.config.routes {
@@ -297,39 +711,37 @@ impl SourceConnector for MqttSourceConnector {
         }
 
         // Create MQTT client
-        let mqtt_options = self.config.mqtt_options();
+        let mqtt_options = self.config.mqtt_options()?;
         let (client, mut event_loop) = AsyncClient::new(mqtt_options, 100);
 
         event_loop.network_options = self.config.network_options();
 
         // Subscribe to MQTT topics
-        for mapping in &self.config.routes {
-            info!(
-                "Subscribing to MQTT topic: {} (QoS: {:?})",
-                mapping.from, mapping.qos
-            );
+        Self::subscribe_all(&client, &self.config.routes).await?;
 
-            client
-                .subscribe(&mapping.from, mapping.qos.into())
-                .await
-                .map_err(|e| {
-                    ConnectorError::fatal_with_source(
-                        format!("Failed to subscribe to topic: {}", mapping.from),
-                        e,
-                    )
-                })?;
-        }
-
-        // Spawn event loop in background task
+        // Spawn event loop and forwarder in background tasks, connected by the buffer
+        let buffer = Arc::new(RecordBuffer::new(
+            self.config.buffer_capacity,
+            self.config.backpressure,
+        ));
+        let health = Arc::new(ConnectionHealth::new());
+        let forwarder_handle =
+            Self::spawn_forwarder(buffer.clone(), sender, client.clone(), self.dedup.clone());
         let event_loop_handle = Self::spawn_event_loop(
             event_loop,
-            sender,
-            self.config.routes.clone(),
-            self.config.include_metadata,
+            client.clone(),
+            buffer.clone(),
+            health.clone(),
+            self.validators.clone(),
+            self.limiters.clone(),
+            self.config.clone(),
         );
 
         self.mqtt_client = Some(client);
         self.event_loop_abort = Some(event_loop_handle.abort_handle());
+        self.forwarder_abort = Some(forwarder_handle.abort_handle());
+        self.buffer = Some(buffer);
+        self.health = health;
 
         info!("MQTT Source Connector streaming started successfully");
         Ok(())
@@ -338,10 +750,12 @@ impl SourceConnector for MqttSourceConnector {
     async fn producer_configs(&self) -> ConnectorResult<Vec<ProducerConfig>> {
         // Extract all unique Danube topics from the topic mappings
         // and create producer configurations for each
-        let producer_configs: Vec<_> = self
+        let mut producer_configs: Vec<_> = self
             .config
             .routes
             .iter()
+            // Templated topics are only known once messages arrive
+            .filter(|mapping| !mapping.is_dynamic())
             .map(|mapping| {
                 // Find matching schema configuration for this Danube topic
                 let schema_config = self.find_schema_config(&mapping.to);
@@ -355,7 +769,29 @@ impl SourceConnector for MqttSourceConnector {
             })
             .collect();
 
-        if producer_configs.is_empty() {
+        // Dead-letter topics of routes with schema validation, and overflow topics
+        // of capped templates
+        let extra_topics = self.config.routes.iter().flat_map(|mapping| {
+            let dead_letter_topic = mapping
+                .validation
+                .as_ref()
+                .and_then(|validation| validation.dead_letter_topic.clone());
+            dead_letter_topic
+                .into_iter()
+                .chain(mapping.overflow_topic.clone())
+        });
+        for topic in extra_topics {
+            if producer_configs.iter().all(|config| config.topic != topic) {
+                producer_configs.push(ProducerConfig {
+                    topic,
+                    partitions: 0,
+                    reliable_dispatch: true,
+                    schema_config: None,
+                });
+            }
+        }
+
+        if self.config.routes.is_empty() {
             return Err(ConnectorError::config(
                 "No routes configured. Please add routes in the configuration.",
             ));
@@ -365,8 +801,10 @@ impl SourceConnector for MqttSourceConnector {
     }
 
     async fn commit(&mut self, offsets: Vec<Offset>) -> ConnectorResult<()> {
-        // MQTT doesn't require explicit offset commits
-        // Messages are acknowledged automatically by rumqttc
+        // Nothing to do: MQTT messages are acknowledged when their record is
+        // handed to the runtime (manual_ack) or on receipt by rumqttc, never
+        // here. Streaming records carry no offset of ours, so these offsets
+        // can't be matched to the pending Publish packets they'd acknowledge.
         debug!("Committed {} offsets", offsets.len());
         Ok(())
     }
@@ -377,6 +815,28 @@ impl SourceConnector for MqttSourceConnector {
         if let Some(abort_handle) = self.event_loop_abort.take() {
             abort_handle.abort();
         }
+        if let Some(abort_handle) = self.forwarder_abort.take() {
+            abort_handle.abort();
+        }
+        if let Some(buffer) = self.buffer.take() {
+            if buffer.dropped() > 0 {
+                warn!(
+                    "{} MQTT messages were dropped by the {:?} backpressure policy",
+                    buffer.dropped(),
+                    self.config.backpressure
+                );
+            }
+        }
+        for limiter in self.limiters.values() {
+            if limiter.dropped() > 0 || limiter.deferred() > 0 {
+                warn!(
+                    "Route '{}' rate limit: {} MQTT messages dropped, {} deferred",
+                    limiter.route(),
+                    limiter.dropped(),
+                    limiter.deferred()
+                );
+            }
+        }
 
         // Disconnect MQTT client
         if let Some(client) = self.mqtt_client.take() {
@@ -395,10 +855,29 @@ impl SourceConnector for MqttSourceConnector {
             return Err(ConnectorError::fatal("MQTT client not initialized"));
         }
 
-        // Could add more sophisticated health checks here
-        // (e.g., last message received time, connection state)
+        // The event loop stops once it runs out of reconnect attempts
+        if self
+            .event_loop_abort
+            .as_ref()
+            .is_some_and(|abort_handle| abort_handle.is_finished())
+        {
+            return Err(ConnectorError::fatal("MQTT event loop stopped"));
+        }
+        if self
+            .forwarder_abort
+            .as_ref()
+            .is_some_and(|abort_handle| abort_handle.is_finished())
+        {
+            return Err(ConnectorError::fatal("MQTT forwarder stopped"));
+        }
 
-        Ok(())
+        // Connection state and activity reported by the event loop
+        self.health
+            .check(
+                Duration::from_secs(self.config.health_max_disconnected_secs),
+                Duration::from_secs(self.config.health_max_silence_secs),
+            )
+            .map_err(ConnectorError::retryable)
     }
 }
 
@@ -461,10 +940,68 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_exclude_topics() {
+        let routes: Vec<TopicMapping> = vec![
+            toml::from_str(
+                r#"
+                from = "sensors/#"
+                to = "/iot/sensors"
+                partitions = 0
+                exclude_topics = ["sensors/+/debug", "sensors/lab/#"]
+                "#,
+            )
+            .unwrap(),
+            toml::from_str(
+                r#"
+                from = "sensors/+/debug"
+                to = "/iot/debug"
+                partitions = 0
+                "#,
+            )
+            .unwrap(),
+        ];
+
+        let route = |topic| MqttSourceConnector::find_mapping_static(topic, &routes);
+        assert_eq!(route("sensors/d1/temp").unwrap().to, "/iot/sensors");
+        // Excluded topics fall through to the next matching route
+        assert_eq!(route("sensors/d1/debug").unwrap().to, "/iot/debug");
+        assert!(route("sensors/lab/d2/temp").is_none());
+    }
+
+    #[test]
+    fn test_cap_topic() {
+        let mut mapping: TopicMapping = toml::from_str(
+            r#"
+            from = "devices/+/telemetry"
+            to = "/devices/{2}"
+            max_topics = 2
+            "#,
+        )
+        .unwrap();
+        let mut topics = HashSet::new();
+        let mut cap = |mapping: &TopicMapping, topic: &str| {
+            MqttSourceConnector::cap_topic(mapping, 2, topic.to_string(), &mut topics)
+        };
+
+        assert_eq!(cap(&mapping, "/devices/d1").as_deref(), Some("/devices/d1"));
+        assert_eq!(cap(&mapping, "/devices/d2").as_deref(), Some("/devices/d2"));
+        assert_eq!(cap(&mapping, "/devices/d3"), None);
+        assert_eq!(cap(&mapping, "/devices/d1").as_deref(), Some("/devices/d1"));
+
+        mapping.overflow_topic = Some("/devices/overflow".to_string());
+        assert_eq!(
+            cap(&mapping, "/devices/d3").as_deref(),
+            Some("/devices/overflow")
+        );
+    }
+
     #[test]
     fn test_connector_creation() {
         let connector = MqttSourceConnector::new();
         assert!(connector.mqtt_client.is_none());
         assert!(connector.event_loop_abort.is_none());
+        assert!(connector.forwarder_abort.is_none());
+        assert!(connector.buffer.is_none());
     }
 }
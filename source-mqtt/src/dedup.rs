@@ -0,0 +1,203 @@
+//! Persistent duplicate suppression for QoS 2 messages
+//!
+//! MQTT's exactly-once handshake only holds within a session: after a
+//! reconnect or a connector crash, the broker may redeliver messages that
+//! were already published to Danube. Messages are identified by a hash of
+//! their MQTT topic and payload, remembered for `ttl_secs` in an append-only
+//! file so the window survives restarts.
+
+use crate::config::DedupConfig;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Appended entries after which the file is rewritten without expired ones
+const COMPACT_EVERY: usize = 10_000;
+
+/// Key of an MQTT message (64-bit FNV-1a of topic and payload, stable across builds)
+pub fn message_key(topic: &str, payload: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    topic
+        .as_bytes()
+        .iter()
+        .chain(&[0])
+        .chain(payload)
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(PRIME)
+        })
+}
+
+/// Message keys seen within the TTL, backed by a file
+pub struct DedupStore {
+    path: PathBuf,
+    ttl_secs: u64,
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    /// Key → expiry (Unix seconds)
+    seen: HashMap<u64, u64>,
+    file: File,
+    appended: usize,
+}
+
+impl DedupStore {
+    /// Open the store, loading the unexpired keys of a previous run
+    pub fn open(config: &DedupConfig) -> std::io::Result<Self> {
+        let path = PathBuf::from(&config.path);
+        let now = now();
+
+        let mut seen = HashMap::new();
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                let line = line?;
+                // Skip lines torn by a crash mid-write
+                let Some((key, expires)) = line.split_once(' ') else {
+                    continue;
+                };
+                let (Ok(key), Ok(expires)) = (u64::from_str_radix(key, 16), expires.parse()) else {
+                    continue;
+                };
+                if expires > now {
+                    seen.insert(key, expires);
+                }
+            }
+        }
+
+        let file = rewrite(&path, &seen)?;
+        Ok(Self {
+            path,
+            ttl_secs: config.ttl_secs,
+            inner: Mutex::new(Inner {
+                seen,
+                file,
+                appended: 0,
+            }),
+        })
+    }
+
+    /// Whether the message was seen within the TTL
+    pub fn contains(&self, key: u64) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.seen.get(&key).is_some_and(|&expires| expires > now())
+    }
+
+    /// Remember a message published to Danube
+    pub fn insert(&self, key: u64) -> std::io::Result<()> {
+        let now = now();
+        let expires = now + self.ttl_secs;
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.seen.insert(key, expires);
+        writeln!(inner.file, "{:016x} {}", key, expires)?;
+
+        inner.appended += 1;
+        if inner.appended >= COMPACT_EVERY {
+            inner.seen.retain(|_, expires| *expires > now);
+            inner.file = rewrite(&self.path, &inner.seen)?;
+            inner.appended = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Number of remembered messages (including expired ones not compacted yet)
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().seen.len()
+    }
+}
+
+/// Replace the file with the given entries, returning it opened for appending
+fn rewrite(path: &Path, seen: &HashMap<u64, u64>) -> std::io::Result<File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    {
+        let mut file = std::io::BufWriter::new(File::create(&tmp)?);
+        for (key, expires) in seen {
+            writeln!(file, "{:016x} {}", key, expires)?;
+        }
+        file.into_inner()?.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+
+    OpenOptions::new().append(true).open(path)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str) -> DedupConfig {
+        let path = std::env::temp_dir().join(format!(
+            "danube-mqtt-dedup-{}-{}.log",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        DedupConfig {
+            path: path.to_string_lossy().into_owned(),
+            ttl_secs: 3600,
+        }
+    }
+
+    #[test]
+    fn test_message_key() {
+        assert_eq!(message_key("a/b", b"1"), message_key("a/b", b"1"));
+        assert_ne!(message_key("a/b", b"1"), message_key("a/b", b"2"));
+        // The separator keeps topic and payload apart
+        assert_ne!(message_key("a/b", b"c"), message_key("a/bc", b""));
+    }
+
+    #[test]
+    fn test_survives_restart() {
+        let config = config("restart");
+        let key = message_key("sensors/d1", br#"{"seq": 1}"#);
+
+        let store = DedupStore::open(&config).unwrap();
+        assert!(!store.contains(key));
+        store.insert(key).unwrap();
+        assert!(store.contains(key));
+        drop(store);
+
+        let store = DedupStore::open(&config).unwrap();
+        assert!(store.contains(key));
+        assert!(!store.contains(message_key("sensors/d1", br#"{"seq": 2}"#)));
+
+        std::fs::remove_file(&config.path).unwrap();
+    }
+
+    #[test]
+    fn test_skips_expired_and_torn_entries() {
+        let config = config("expired");
+        std::fs::write(
+            &config.path,
+            format!("{:016x} 1\n{:016x} {}\n00ab", 1, 2, now() + 60),
+        )
+        .unwrap();
+
+        let store = DedupStore::open(&config).unwrap();
+        assert!(!store.contains(1));
+        assert!(store.contains(2));
+        assert_eq!(store.len(), 1);
+
+        std::fs::remove_file(&config.path).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+//! MQTT connection health tracking
+//!
+//! The event loop records connection state changes, received messages and
+//! ping responses. The health check fails when the broker has been
+//! unreachable, or connected but silent, for longer than the configured
+//! thresholds.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Connection state shared between the event loop and the health check
+pub struct ConnectionHealth {
+    /// Reference point of the timestamps below (milliseconds since `origin`)
+    origin: Instant,
+    connected: AtomicBool,
+    disconnected_since: AtomicU64,
+    last_message: AtomicU64,
+    last_ping: AtomicU64,
+}
+
+impl Default for ConnectionHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionHealth {
+    /// Not yet connected, with the disconnection starting now
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            connected: AtomicBool::new(false),
+            disconnected_since: AtomicU64::new(0),
+            last_message: AtomicU64::new(0),
+            last_ping: AtomicU64::new(0),
+        }
+    }
+
+    /// The broker accepted the connection
+    pub fn connected(&self) {
+        // Silence is measured from the (re)connection
+        self.last_ping.store(self.now(), Ordering::Relaxed);
+        self.connected.store(true, Ordering::Relaxed);
+    }
+
+    /// The connection was lost (keeps the time of the first failure)
+    pub fn disconnected(&self) {
+        if self.connected.swap(false, Ordering::Relaxed) {
+            self.disconnected_since.store(self.now(), Ordering::Relaxed);
+        }
+    }
+
+    /// A message was received
+    pub fn message_received(&self) {
+        self.last_message.store(self.now(), Ordering::Relaxed);
+    }
+
+    /// A ping response was received
+    pub fn ping_received(&self) {
+        self.last_ping.store(self.now(), Ordering::Relaxed);
+    }
+
+    /// Check the thresholds (zero disables a threshold)
+    pub fn check(&self, max_disconnected: Duration, max_silence: Duration) -> Result<(), String> {
+        self.check_at(Instant::now(), max_disconnected, max_silence)
+    }
+
+    fn check_at(
+        &self,
+        now: Instant,
+        max_disconnected: Duration,
+        max_silence: Duration,
+    ) -> Result<(), String> {
+        let now = now.saturating_duration_since(self.origin).as_millis() as u64;
+        let elapsed = |since: &AtomicU64| {
+            Duration::from_millis(now.saturating_sub(since.load(Ordering::Relaxed)))
+        };
+
+        if !self.connected.load(Ordering::Relaxed) {
+            let disconnected = elapsed(&self.disconnected_since);
+            if !max_disconnected.is_zero() && disconnected > max_disconnected {
+                return Err(format!(
+                    "MQTT broker unreachable for {}s",
+                    disconnected.as_secs()
+                ));
+            }
+            return Ok(());
+        }
+
+        let silence = elapsed(&self.last_message).min(elapsed(&self.last_ping));
+        if !max_silence.is_zero() && silence > max_silence {
+            return Err(format!(
+                "No MQTT messages or ping responses for {}s",
+                silence.as_secs()
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn now(&self) -> u64 {
+        self.origin.elapsed().as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_unreachable_broker() {
+        let health = ConnectionHealth::new();
+        let start = health.origin;
+
+        assert!(health.check_at(start + MINUTE / 2, MINUTE, MINUTE).is_ok());
+        assert!(health.check_at(start + 2 * MINUTE, MINUTE, MINUTE).is_err());
+        assert!(health
+            .check_at(start + 2 * MINUTE, Duration::ZERO, MINUTE)
+            .is_ok());
+
+        health.connected();
+        assert!(health.check_at(Instant::now(), MINUTE, MINUTE).is_ok());
+        health.disconnected();
+        assert!(health.check_at(Instant::now(), MINUTE, MINUTE).is_ok());
+        assert!(health
+            .check_at(Instant::now() + 2 * MINUTE, MINUTE, MINUTE)
+            .is_err());
+    }
+
+    #[test]
+    fn test_silent_broker() {
+        let health = ConnectionHealth::new();
+        health.connected();
+        let now = Instant::now();
+
+        assert!(health.check_at(now, MINUTE, MINUTE).is_ok());
+        assert!(health.check_at(now + 2 * MINUTE, MINUTE, MINUTE).is_err());
+        assert!(health
+            .check_at(now + 2 * MINUTE, MINUTE, Duration::ZERO)
+            .is_ok());
+
+        health.message_received();
+        assert!(health
+            .check_at(Instant::now() + MINUTE / 2, MINUTE, MINUTE)
+            .is_ok());
+    }
+}
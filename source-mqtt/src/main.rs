@@ -3,8 +3,15 @@
 //! This connector subscribes to MQTT topics and publishes messages to Danube topics.
 //! Perfect for IoT use cases where devices publish telemetry via MQTT.
 
+mod buffer;
 mod config;
 mod connector;
+mod dedup;
+mod health;
+mod rate_limit;
+mod tls;
+mod transform;
+mod validation;
 
 use config::MqttSourceConfig;
 use connector::MqttSourceConnector;
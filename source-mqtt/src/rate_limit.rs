@@ -0,0 +1,193 @@
+//! Per-route ingest rate limiting
+//!
+//! Each route with a `rate_limit` gets a token bucket refilled at
+//! `messages_per_sec` up to `burst` tokens. Messages over the limit are
+//! dropped, or deferred until a token is available (which pauses reads from
+//! the broker for all routes).
+
+use crate::config::{RateLimit, RateLimitAction, TopicMapping};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Token bucket of a route
+pub struct RateLimiter {
+    route: String,
+    rate: f64,
+    burst: f64,
+    action: RateLimitAction,
+    bucket: Mutex<Bucket>,
+    dropped: AtomicU64,
+    deferred: AtomicU64,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    /// Create a full bucket for a route
+    pub fn new(route: &str, rate_limit: &RateLimit) -> Self {
+        let burst = rate_limit.burst.unwrap_or(rate_limit.messages_per_sec) as f64;
+        Self {
+            route: route.to_string(),
+            rate: rate_limit.messages_per_sec as f64,
+            burst,
+            action: rate_limit.on_limit,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled: Instant::now(),
+            }),
+            dropped: AtomicU64::new(0),
+            deferred: AtomicU64::new(0),
+        }
+    }
+
+    /// Admit a message, applying the route's action when over the limit
+    ///
+    /// Returns false if the message is dropped.
+    pub async fn admit(&self) -> bool {
+        let mut deferred = false;
+        loop {
+            let Err(wait) = self.try_acquire(Instant::now()) else {
+                return true;
+            };
+
+            match self.action {
+                RateLimitAction::Drop => {
+                    let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    self.log_limited("dropped", dropped);
+                    return false;
+                }
+                RateLimitAction::Defer => {
+                    if !deferred {
+                        deferred = true;
+                        let count = self.deferred.fetch_add(1, Ordering::Relaxed) + 1;
+                        self.log_limited("deferred", count);
+                    }
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.refilled = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    /// Route the limiter belongs to
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    /// Number of messages dropped over the limit
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages delayed by the limit
+    pub fn deferred(&self) -> u64 {
+        self.deferred.load(Ordering::Relaxed)
+    }
+
+    fn log_limited(&self, outcome: &str, count: u64) {
+        if count == 1 || count % 1000 == 0 {
+            warn!(
+                "Route '{}' over its rate limit ({} msg/s), {} messages {} so far",
+                self.route, self.rate, count, outcome
+            );
+        }
+    }
+}
+
+/// Create the limiters of all routes with a rate limit, keyed by route `from`
+pub fn route_limiters(routes: &[TopicMapping]) -> HashMap<String, RateLimiter> {
+    routes
+        .iter()
+        .filter_map(|mapping| {
+            let rate_limit = mapping.rate_limit.as_ref()?;
+            Some((
+                mapping.from.clone(),
+                RateLimiter::new(&mapping.from, rate_limit),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(
+        messages_per_sec: u32,
+        burst: Option<u32>,
+        on_limit: RateLimitAction,
+    ) -> RateLimiter {
+        RateLimiter::new(
+            "sensors/#",
+            &RateLimit {
+                messages_per_sec,
+                burst,
+                on_limit,
+            },
+        )
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = limiter(10, Some(2), RateLimitAction::Drop);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(start).is_ok());
+        assert!(limiter.try_acquire(start).is_ok());
+        let wait = limiter.try_acquire(start).unwrap_err();
+        assert!(wait <= Duration::from_millis(100));
+
+        // One token per 100ms, capped at the burst
+        assert!(limiter
+            .try_acquire(start + Duration::from_millis(100))
+            .is_ok());
+        assert!(limiter
+            .try_acquire(start + Duration::from_millis(100))
+            .is_err());
+        assert!(limiter.try_acquire(start + Duration::from_secs(10)).is_ok());
+        assert!(limiter.try_acquire(start + Duration::from_secs(10)).is_ok());
+        assert!(limiter
+            .try_acquire(start + Duration::from_secs(10))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drop_over_limit() {
+        let limiter = limiter(1, None, RateLimitAction::Drop);
+
+        assert!(limiter.admit().await);
+        assert!(!limiter.admit().await);
+        assert_eq!(limiter.dropped(), 1);
+        assert_eq!(limiter.deferred(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_defer_over_limit() {
+        let limiter = limiter(50, Some(1), RateLimitAction::Defer);
+
+        assert!(limiter.admit().await);
+        assert!(limiter.admit().await);
+        assert_eq!(limiter.deferred(), 1);
+        assert_eq!(limiter.dropped(), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! TLS transport for the MQTT connection
+//!
+//! Builds the rumqttc transport from the TLS settings: a custom CA bundle,
+//! an optional client certificate for mutual TLS, or (for testing only)
+//! no server certificate verification at all.
+
+use crate::config::MqttConfig;
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use rumqttc::{TlsConfiguration, Transport};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Build the TLS transport for the configured broker
+///
+/// Without a CA bundle, the server certificate is verified against the
+/// system's root certificates.
+pub fn transport(config: &MqttConfig) -> ConnectorResult<Transport> {
+    let client_auth = match (&config.tls_client_cert_path, &config.tls_client_key_path) {
+        (Some(cert_path), Some(key_path)) => Some((read_pem(cert_path)?, read_pem(key_path)?)),
+        _ => None,
+    };
+
+    if config.tls_insecure_skip_verify {
+        warn!("MQTT TLS server certificate verification is disabled, do not use in production");
+        let tls_config = insecure_client_config(client_auth)?;
+        return Ok(Transport::tls_with_config(TlsConfiguration::Rustls(
+            Arc::new(tls_config),
+        )));
+    }
+
+    match &config.tls_ca_path {
+        Some(ca_path) => Ok(Transport::tls(read_pem(ca_path)?, client_auth, None)),
+        // Required by config validation
+        None if client_auth.is_some() => Err(ConnectorError::config(
+            "MQTT client certificate requires 'tls_ca_path'",
+        )),
+        None => Ok(Transport::tls_with_default_config()),
+    }
+}
+
+/// Client config that accepts any server certificate
+fn insecure_client_config(
+    client_auth: Option<(Vec<u8>, Vec<u8>)>,
+) -> ConnectorResult<ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| ConnectorError::config(format!("Invalid MQTT TLS configuration: {}", e)))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(NoServerVerification(provider)));
+
+    let Some((cert, key)) = client_auth else {
+        return Ok(builder.with_no_client_auth());
+    };
+
+    let certs = CertificateDer::pem_slice_iter(&cert)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ConnectorError::config(format!("Invalid MQTT client certificate: {}", e)))?;
+    let key = PrivateKeyDer::from_pem_slice(&key)
+        .map_err(|e| ConnectorError::config(format!("Invalid MQTT client key: {}", e)))?;
+
+    builder
+        .with_client_auth_cert(certs, key)
+        .map_err(|e| ConnectorError::config(format!("Invalid MQTT client certificate: {}", e)))
+}
+
+fn read_pem(path: &str) -> ConnectorResult<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| ConnectorError::config(format!("Failed to read '{}': {}", path, e)))
+}
+
+/// Server certificate verifier that skips verification (signatures are still checked)
+#[derive(Debug)]
+struct NoServerVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,288 @@
+//! Per-route payload decompression, filters and transformations
+//!
+//! Compressed payloads are inflated before decoding. Filters drop messages
+//! whose decoded JSON payload doesn't match the route's conditions;
+//! transformations reshape the payload before it is published. Routing keys
+//! and event times can be taken from payload fields.
+
+use crate::config::{Decompression, FilterCondition, FilterOp, Transform};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::io::Read;
+
+/// Upper bound for a decompressed payload, against decompression bombs
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Decompress a payload with the route's decompression
+pub fn decompress(decompression: Decompression, payload: &[u8]) -> std::io::Result<Cow<'_, [u8]>> {
+    let decoder: Box<dyn Read + '_> = match decompression {
+        Decompression::None => return Ok(Cow::Borrowed(payload)),
+        Decompression::Gzip => Box::new(flate2::read::GzDecoder::new(payload)),
+        Decompression::Zstd => Box::new(zstd::stream::read::Decoder::new(payload)?),
+    };
+
+    let mut decompressed = Vec::new();
+    decoder
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "decompressed payload exceeds {} bytes",
+                MAX_DECOMPRESSED_SIZE
+            ),
+        ));
+    }
+
+    Ok(Cow::Owned(decompressed))
+}
+
+/// Whether the payload matches all filter conditions
+pub fn matches(conditions: &[FilterCondition], payload: &Value) -> bool {
+    conditions
+        .iter()
+        .all(|condition| condition_matches(condition, payload))
+}
+
+fn condition_matches(condition: &FilterCondition, payload: &Value) -> bool {
+    let field = field(payload, &condition.field).filter(|value| !value.is_null());
+    let expected = condition.value.as_ref();
+
+    match condition.op {
+        FilterOp::Exists => field.is_some(),
+        FilterOp::Eq => matches!((field, expected), (Some(a), Some(b)) if equals(a, b)),
+        FilterOp::Ne => !matches!((field, expected), (Some(a), Some(b)) if equals(a, b)),
+        op => {
+            let (Some(a), Some(b)) = (field, expected) else {
+                return false;
+            };
+            match (compare(a, b), op) {
+                (Some(Ordering::Greater), FilterOp::Gt | FilterOp::Gte) => true,
+                (Some(Ordering::Less), FilterOp::Lt | FilterOp::Lte) => true,
+                (Some(Ordering::Equal), FilterOp::Gte | FilterOp::Lte) => true,
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Apply a route's transformation to the payload (non-object payloads are left as-is)
+pub fn apply(transform: &Transform, payload: Value) -> Value {
+    let Value::Object(mut object) = payload else {
+        return payload;
+    };
+
+    if !transform.select.is_empty() {
+        object.retain(|key, _| transform.select.contains(key));
+    }
+
+    for (from, to) in &transform.rename {
+        if let Some(value) = object.remove(from) {
+            object.insert(to.clone(), value);
+        }
+    }
+
+    for (name, value) in &transform.add_fields {
+        object.insert(name.clone(), value.clone());
+    }
+
+    Value::Object(object)
+}
+
+/// Routing key from a payload field: strings as-is, numbers and booleans as text
+pub fn key(payload: &Value, path: &str) -> Option<String> {
+    match field(payload, path)? {
+        Value::String(key) => Some(key.clone()),
+        value @ (Value::Number(_) | Value::Bool(_)) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Event time from a payload field
+///
+/// `format` is "rfc3339", "unix_s", "unix_ms", "unix_us" or a chrono pattern;
+/// patterns without an offset are read as UTC. Returns None if the field is
+/// missing or doesn't match the format.
+pub fn event_time(payload: &Value, path: &str, format: &str) -> Option<DateTime<Utc>> {
+    let value = field(payload, path)?;
+
+    let epoch = |scale: f64| -> Option<DateTime<Utc>> {
+        let n = match value {
+            Value::Number(n) => n.as_f64()?,
+            Value::String(s) => s.trim().parse::<f64>().ok()?,
+            _ => return None,
+        };
+        let micros = (n * scale).round();
+        if !micros.is_finite() || micros.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        Utc.timestamp_micros(micros as i64).single()
+    };
+
+    match format {
+        "unix_s" => epoch(1_000_000.0),
+        "unix_ms" => epoch(1_000.0),
+        "unix_us" => epoch(1.0),
+        "rfc3339" => DateTime::parse_from_rfc3339(value.as_str()?.trim())
+            .ok()
+            .map(|time| time.with_timezone(&Utc)),
+        pattern => {
+            let s = value.as_str()?.trim();
+            match DateTime::parse_from_str(s, pattern) {
+                Ok(time) => Some(time.with_timezone(&Utc)),
+                Err(_) => Some(NaiveDateTime::parse_from_str(s, pattern).ok()?.and_utc()),
+            }
+        }
+    }
+}
+
+/// Read a payload field by dotted path
+fn field<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(payload, |current, key| current.get(key))
+}
+
+/// JSON equality, with numbers compared by value (30 == 30.0)
+fn equals(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(_), Value::Number(_)) => compare(a, b) == Some(Ordering::Equal),
+        _ => a == b,
+    }
+}
+
+/// Order two numbers or two strings; other values are not comparable
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn condition(field: &str, op: FilterOp, value: Option<Value>) -> FilterCondition {
+        FilterCondition {
+            field: field.to_string(),
+            op,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_filter_conditions() {
+        let payload = json!({"temperature": 31.5, "status": "ok", "reading": {"unit": "C"}});
+
+        assert!(matches(&[], &payload));
+        assert!(matches(
+            &[condition("temperature", FilterOp::Gt, Some(json!(30)))],
+            &payload
+        ));
+        assert!(!matches(
+            &[condition("temperature", FilterOp::Lte, Some(json!(30)))],
+            &payload
+        ));
+        assert!(matches(
+            &[
+                condition("status", FilterOp::Eq, Some(json!("ok"))),
+                condition("reading.unit", FilterOp::Ne, Some(json!("F"))),
+                condition("reading", FilterOp::Exists, None),
+            ],
+            &payload
+        ));
+        assert!(!matches(
+            &[condition("humidity", FilterOp::Exists, None)],
+            &payload
+        ));
+        assert!(!matches(
+            &[condition("humidity", FilterOp::Gt, Some(json!(0)))],
+            &payload
+        ));
+        assert!(matches(
+            &[condition("temperature", FilterOp::Eq, Some(json!(31.5)))],
+            &json!({"temperature": 31.5})
+        ));
+        assert!(matches(
+            &[condition("count", FilterOp::Eq, Some(json!(3.0)))],
+            &json!({"count": 3})
+        ));
+    }
+
+    #[test]
+    fn test_key() {
+        let payload = json!({"device_id": "d1", "meta": {"line": 3}, "tags": ["a"]});
+
+        assert_eq!(key(&payload, "device_id").as_deref(), Some("d1"));
+        assert_eq!(key(&payload, "meta.line").as_deref(), Some("3"));
+        assert_eq!(key(&payload, "tags"), None);
+        assert_eq!(key(&payload, "serial"), None);
+    }
+
+    #[test]
+    fn test_event_time() {
+        let payload = json!({
+            "ts": "2024-03-15T23:30:00-02:00",
+            "meta": {"epoch_ms": 1_710_552_600_000i64, "epoch_s": "1710552600"},
+            "local": "2024-03-16 01:30:00",
+        });
+        let expected = Utc.with_ymd_and_hms(2024, 3, 16, 1, 30, 0).unwrap();
+
+        assert_eq!(event_time(&payload, "ts", "rfc3339"), Some(expected));
+        assert_eq!(
+            event_time(&payload, "meta.epoch_ms", "unix_ms"),
+            Some(expected)
+        );
+        assert_eq!(
+            event_time(&payload, "meta.epoch_s", "unix_s"),
+            Some(expected)
+        );
+        assert_eq!(
+            event_time(&payload, "local", "%Y-%m-%d %H:%M:%S"),
+            Some(expected)
+        );
+        assert_eq!(event_time(&payload, "local", "rfc3339"), None);
+        assert_eq!(event_time(&payload, "missing", "rfc3339"), None);
+    }
+
+    #[test]
+    fn test_decompress() {
+        use std::io::Write;
+
+        let payload = br#"{"temperature": 21}"#;
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(payload).unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert_eq!(&*decompress(Decompression::Gzip, &gzip).unwrap(), payload);
+
+        let zstd = zstd::encode_all(&payload[..], 0).unwrap();
+        assert_eq!(&*decompress(Decompression::Zstd, &zstd).unwrap(), payload);
+
+        assert_eq!(&*decompress(Decompression::None, payload).unwrap(), payload);
+        assert!(decompress(Decompression::Gzip, payload).is_err());
+    }
+
+    #[test]
+    fn test_apply_transform() {
+        let transform = Transform {
+            select: vec!["temp".to_string(), "device".to_string()],
+            rename: [("temp".to_string(), "temperature".to_string())].into(),
+            add_fields: [("site".to_string(), json!("berlin"))].into(),
+        };
+
+        assert_eq!(
+            apply(
+                &transform,
+                json!({"temp": 21, "device": "d1", "debug": true})
+            ),
+            json!({"temperature": 21, "device": "d1", "site": "berlin"})
+        );
+        assert_eq!(apply(&transform, json!([1, 2])), json!([1, 2]));
+    }
+}
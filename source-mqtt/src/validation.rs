@@ -0,0 +1,142 @@
+//! Per-route JSON Schema validation of decoded payloads
+//!
+//! Routes with a `validation` section check each payload against a JSON
+//! Schema before it is published. Invalid messages are published to the
+//! route's dead-letter topic with the validation errors, or dropped.
+
+use crate::config::{SchemaValidation, TopicMapping};
+use danube_connect_core::{ConnectorError, ConnectorResult, SourceRecord};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Compiled JSON Schema of a route
+pub struct PayloadValidator {
+    #[cfg(feature = "schema-validation")]
+    schema: jsonschema::JSONSchema,
+    dead_letter_topic: Option<String>,
+}
+
+impl PayloadValidator {
+    /// Compile the schema of a route's validation settings
+    #[cfg(feature = "schema-validation")]
+    pub fn new(validation: &SchemaValidation) -> ConnectorResult<Self> {
+        let schema = match (&validation.schema, &validation.schema_file) {
+            (Some(schema), _) => schema.clone(),
+            (None, Some(path)) => {
+                let content = std::fs::read_to_string(path).map_err(|e| {
+                    ConnectorError::config(format!("Failed to read schema '{}': {}", path, e))
+                })?;
+                serde_json::from_str(&content).map_err(|e| {
+                    ConnectorError::config(format!("Invalid JSON in schema '{}': {}", path, e))
+                })?
+            }
+            // Required by config validation
+            (None, None) => return Err(ConnectorError::config("Validation requires a schema")),
+        };
+
+        let schema = jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| ConnectorError::config(format!("Invalid JSON Schema: {}", e)))?;
+
+        Ok(Self {
+            schema,
+            dead_letter_topic: validation.dead_letter_topic.clone(),
+        })
+    }
+
+    #[cfg(not(feature = "schema-validation"))]
+    pub fn new(_validation: &SchemaValidation) -> ConnectorResult<Self> {
+        Err(ConnectorError::config(
+            "Route validation requires the connector built with the 'schema-validation' feature",
+        ))
+    }
+
+    /// Validate a payload, returning the validation errors if it is invalid
+    #[cfg(feature = "schema-validation")]
+    pub fn validate(&self, payload: &Value) -> Result<(), Vec<String>> {
+        self.schema.validate(payload).map_err(|errors| {
+            errors
+                .map(|error| format!("{}: {}", error.instance_path, error))
+                .collect()
+        })
+    }
+
+    #[cfg(not(feature = "schema-validation"))]
+    pub fn validate(&self, _payload: &Value) -> Result<(), Vec<String>> {
+        Ok(())
+    }
+
+    /// Dead-letter record for an invalid payload, or None if invalid messages are dropped
+    pub fn dead_letter(
+        &self,
+        mqtt_topic: &str,
+        payload: Value,
+        errors: Vec<String>,
+    ) -> Option<SourceRecord> {
+        let topic = self.dead_letter_topic.as_ref()?;
+        let record = SourceRecord::new(
+            topic,
+            json!({
+                "mqtt_topic": mqtt_topic,
+                "errors": errors,
+                "payload": payload,
+            }),
+        )
+        .with_attribute("mqtt.topic", mqtt_topic)
+        .with_attribute("error", "schema_validation")
+        .with_attribute("source", "mqtt");
+
+        Some(record)
+    }
+}
+
+/// Compile the validators of all routes with validation, keyed by route `from`
+pub fn compile_validators(
+    routes: &[TopicMapping],
+) -> ConnectorResult<HashMap<String, PayloadValidator>> {
+    routes
+        .iter()
+        .filter_map(|mapping| {
+            let validation = mapping.validation.as_ref()?;
+            Some(
+                PayloadValidator::new(validation)
+                    .map(|validator| (mapping.from.clone(), validator))
+                    .map_err(|e| {
+                        ConnectorError::config(format!("Route '{}': {}", mapping.from, e))
+                    }),
+            )
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "schema-validation"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_payload() {
+        let validator = PayloadValidator::new(&SchemaValidation {
+            schema: Some(json!({
+                "type": "object",
+                "required": ["device_id"],
+                "properties": {"temperature": {"type": "number"}}
+            })),
+            schema_file: None,
+            dead_letter_topic: Some("/iot/invalid".to_string()),
+        })
+        .unwrap();
+
+        assert!(validator
+            .validate(&json!({"device_id": "d1", "temperature": 21.5}))
+            .is_ok());
+
+        let errors = validator
+            .validate(&json!({"temperature": "hot"}))
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|error| error.starts_with("/temperature")));
+
+        assert!(validator
+            .dead_letter("sensors/d1", json!({}), errors)
+            .is_some());
+    }
+}
@@ -0,0 +1,307 @@
+//! Processed-object checkpoint
+//!
+//! Remembers which objects (and which version of them) have been published, so
+//! a poll or a redelivered notification doesn't publish them again. An object
+//! is checkpointed after all its rows are handed to the runtime, so rows are
+//! published at least once: a restart in the middle of a file publishes the
+//! whole file again. An overwritten object gets a new version and is read again.
+//!
+//! The state file is rewritten in batches rather than after every object, and
+//! when the connector goes idle or shuts down; a crash in between only reads the
+//! objects of the unsaved batch again.
+
+use crate::config::in_folder;
+use object_store::ObjectMeta;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Changes after which the state file is saved
+const SAVE_EVERY_CHANGES: usize = 100;
+/// Time after which pending changes are saved
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A processed object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessedObject {
+    /// Version of the object (ETag, or modification time and size)
+    pub version: String,
+    /// When the object was processed (Unix seconds)
+    pub processed_at: u64,
+}
+
+/// Processed objects by key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(default)]
+    pub objects: BTreeMap<String, ProcessedObject>,
+}
+
+impl Checkpoint {
+    /// Load the state file, or start fresh
+    pub fn load(path: &str) -> Self {
+        match std::fs::read(path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid state file {}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let path = Path::new(path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Whether this version of the object was already processed
+    pub fn is_processed(&self, meta: &ObjectMeta) -> bool {
+        self.objects
+            .get(meta.location.as_ref())
+            .is_some_and(|object| object.version == object_version(meta))
+    }
+
+    /// Record the object as processed
+    pub fn mark_processed(&mut self, meta: &ObjectMeta) {
+        self.objects.insert(
+            meta.location.to_string(),
+            ProcessedObject {
+                version: object_version(meta),
+                processed_at: now_secs(),
+            },
+        );
+    }
+
+    /// Forget the objects of a folder that a complete listing no longer returned
+    ///
+    /// Returns whether anything was removed.
+    pub fn retain_listed(&mut self, folder: &str, listed: &HashSet<String>) -> bool {
+        let before = self.objects.len();
+        self.objects
+            .retain(|key, _| !in_folder(folder, key) || listed.contains(key));
+        self.objects.len() != before
+    }
+
+    /// Forget objects processed more than `window_secs` ago
+    ///
+    /// Returns whether anything was removed.
+    pub fn expire(&mut self, window_secs: u64) -> bool {
+        let before = self.objects.len();
+        let cutoff = now_secs().saturating_sub(window_secs);
+        self.objects
+            .retain(|_, object| object.processed_at >= cutoff);
+        self.objects.len() != before
+    }
+}
+
+/// Checkpoint shared by the discovery task and the connector, saved to the
+/// state file (if any) in batches
+pub struct CheckpointStore {
+    path: Option<String>,
+    state: Mutex<StoreState>,
+}
+
+struct StoreState {
+    checkpoint: Checkpoint,
+    /// Changes since the last save
+    unsaved: usize,
+    last_saved: Instant,
+}
+
+impl CheckpointStore {
+    /// Load the state file, or start fresh (in memory only without a path)
+    pub fn open(path: Option<String>) -> Self {
+        let checkpoint = path.as_deref().map(Checkpoint::load).unwrap_or_default();
+        Self {
+            path,
+            state: Mutex::new(StoreState {
+                checkpoint,
+                unsaved: 0,
+                last_saved: Instant::now(),
+            }),
+        }
+    }
+
+    /// Whether this version of the object was already processed
+    pub fn is_processed(&self, meta: &ObjectMeta) -> bool {
+        self.lock().checkpoint.is_processed(meta)
+    }
+
+    /// Record the object as processed
+    pub fn mark_processed(&self, meta: &ObjectMeta) {
+        self.update(|checkpoint| {
+            checkpoint.mark_processed(meta);
+            true
+        });
+    }
+
+    /// Forget the objects of a folder that a complete listing no longer returned
+    pub fn retain_listed(&self, folder: &str, listed: &HashSet<String>) {
+        self.update(|checkpoint| checkpoint.retain_listed(folder, listed));
+    }
+
+    /// Forget objects processed more than `window_secs` ago
+    pub fn expire(&self, window_secs: u64) {
+        self.update(|checkpoint| checkpoint.expire(window_secs));
+    }
+
+    /// Save the pending changes
+    pub fn flush(&self) {
+        let mut state = self.lock();
+        if state.unsaved > 0 {
+            self.save(&mut state);
+        }
+    }
+
+    /// Apply a change, saving once enough changes or time have accumulated
+    fn update(&self, change: impl FnOnce(&mut Checkpoint) -> bool) {
+        let mut state = self.lock();
+        if !change(&mut state.checkpoint) {
+            return;
+        }
+        state.unsaved += 1;
+        if state.unsaved >= SAVE_EVERY_CHANGES || state.last_saved.elapsed() >= SAVE_INTERVAL {
+            self.save(&mut state);
+        }
+    }
+
+    fn save(&self, state: &mut StoreState) {
+        state.unsaved = 0;
+        state.last_saved = Instant::now();
+        if let Some(path) = &self.path {
+            if let Err(e) = state.checkpoint.save(path) {
+                warn!("Failed to save state to {}: {}", path, e);
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StoreState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Version of an object: its ETag, or its modification time and size
+fn object_version(meta: &ObjectMeta) -> String {
+    match &meta.e_tag {
+        Some(e_tag) => e_tag.clone(),
+        None => format!("{}-{}", meta.last_modified.timestamp_millis(), meta.size),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::path::Path as ObjectPath;
+
+    fn meta(key: &str, e_tag: &str) -> ObjectMeta {
+        ObjectMeta {
+            location: ObjectPath::from(key),
+            last_modified: chrono::Utc::now(),
+            size: 10,
+            e_tag: Some(e_tag.to_string()),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_processed_versions() {
+        let mut checkpoint = Checkpoint::default();
+        assert!(!checkpoint.is_processed(&meta("events/a.jsonl", "v1")));
+
+        checkpoint.mark_processed(&meta("events/a.jsonl", "v1"));
+        assert!(checkpoint.is_processed(&meta("events/a.jsonl", "v1")));
+
+        // An overwritten object is read again
+        assert!(!checkpoint.is_processed(&meta("events/a.jsonl", "v2")));
+    }
+
+    #[test]
+    fn test_retain_listed() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.mark_processed(&meta("events/a.jsonl", "v1"));
+        checkpoint.mark_processed(&meta("events/b.jsonl", "v1"));
+        checkpoint.mark_processed(&meta("logs/a.jsonl", "v1"));
+
+        let listed = HashSet::from(["events/b.jsonl".to_string()]);
+        assert!(checkpoint.retain_listed("events/", &listed));
+        assert!(!checkpoint.objects.contains_key("events/a.jsonl"));
+        assert!(checkpoint.objects.contains_key("events/b.jsonl"));
+        assert!(checkpoint.objects.contains_key("logs/a.jsonl"));
+
+        assert!(!checkpoint.retain_listed("events", &listed));
+    }
+
+    #[test]
+    fn test_expire() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.mark_processed(&meta("events/a.jsonl", "v1"));
+        assert!(!checkpoint.expire(3600));
+
+        checkpoint
+            .objects
+            .get_mut("events/a.jsonl")
+            .unwrap()
+            .processed_at -= 7200;
+        assert!(checkpoint.expire(3600));
+        assert!(checkpoint.objects.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path =
+            std::env::temp_dir().join(format!("s3-source-state-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.mark_processed(&meta("events/a.jsonl", "v1"));
+        checkpoint.save(path).unwrap();
+        assert_eq!(Checkpoint::load(path), checkpoint);
+
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(Checkpoint::load(path), Checkpoint::default());
+    }
+
+    #[test]
+    fn test_store_batches_saves() {
+        let path =
+            std::env::temp_dir().join(format!("s3-source-store-{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+
+        let store = CheckpointStore::open(Some(path.clone()));
+        store.mark_processed(&meta("events/a.jsonl", "v1"));
+        assert!(store.is_processed(&meta("events/a.jsonl", "v1")));
+        assert!(Checkpoint::load(&path).objects.is_empty());
+
+        // A full batch is saved
+        for index in 1..SAVE_EVERY_CHANGES {
+            store.mark_processed(&meta(&format!("events/{}.jsonl", index), "v1"));
+        }
+        assert_eq!(Checkpoint::load(&path).objects.len(), SAVE_EVERY_CHANGES);
+
+        // Pending changes are saved on flush
+        store.mark_processed(&meta("events/b.jsonl", "v1"));
+        assert_eq!(Checkpoint::load(&path).objects.len(), SAVE_EVERY_CHANGES);
+        store.flush();
+        assert_eq!(
+            Checkpoint::load(&path).objects.len(),
+            SAVE_EVERY_CHANGES + 1
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
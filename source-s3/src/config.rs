@@ -0,0 +1,635 @@
+//! Configuration module for the object storage Source Connector
+//!
+//! This module handles all configuration aspects including:
+//! - Storage backend selection (S3, Azure, or GCS) and connection settings
+//! - Object discovery (bucket polling or S3 event notifications through SQS)
+//! - Prefix-to-topic routes with file formats
+//! - Environment variable overrides
+
+use danube_connect_core::{
+    ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorError,
+    ConnectorResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+
+/// Complete configuration for the object storage Source Connector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3SourceConfig {
+    /// Core connector configuration (Danube connection, schemas, etc.)
+    #[serde(flatten)]
+    pub core: ConnectorConfig,
+
+    /// Object storage configuration
+    pub s3: S3Config,
+}
+
+/// Object storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Config {
+    /// Storage backend (s3, azure, or gcs)
+    #[serde(default)]
+    pub storage_backend: StorageBackend,
+
+    /// Bucket to read from (the container for Azure)
+    pub bucket: String,
+
+    /// AWS region (S3 and SQS)
+    /// Credentials from environment: AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// S3 endpoint URL (optional, for MinIO or custom S3-compatible storage)
+    /// Example: "http://localhost:9000"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Allow plain HTTP connections (useful for MinIO local testing)
+    #[serde(default)]
+    pub allow_http: bool,
+
+    /// Use path-style addressing (bucket in path instead of hostname)
+    /// Required for MinIO, Ceph, and other S3-compatible stores without virtual-host DNS
+    #[serde(default)]
+    pub path_style: bool,
+
+    /// Azure storage account name (required if storage_backend = "azure")
+    /// Credentials from environment: AZURE_STORAGE_ACCOUNT_KEY or AZURE_STORAGE_SAS_KEY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub azure_storage_account: Option<String>,
+
+    /// Path to a GCS service account JSON key file
+    /// Otherwise GOOGLE_APPLICATION_CREDENTIALS or GOOGLE_SERVICE_ACCOUNT is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gcs_service_account_path: Option<String>,
+
+    /// Additional object store options passed through as-is
+    /// Applied last, so they take precedence over the typed settings above
+    /// Example: { "aws_server_side_encryption" = "aws:kms", "timeout" = "60s" }
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub storage_options: HashMap<String, String>,
+
+    /// How new objects are found
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+
+    /// File keeping the processed objects across restarts (in memory only when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_path: Option<String>,
+
+    /// Delete processed objects (not supported yet: rejected by validation, since
+    /// objects would be deleted before the runtime confirms their rows are published)
+    #[serde(default)]
+    pub delete_processed: bool,
+
+    /// Add object metadata (bucket, key, row) as message attributes
+    #[serde(default = "default_true")]
+    pub include_metadata: bool,
+
+    /// Routes: object prefixes → Danube topics
+    #[serde(default)]
+    pub routes: Vec<TopicMapping>,
+}
+
+/// Cloud storage backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// Amazon S3 (or S3-compatible like MinIO)
+    S3,
+    /// Azure Blob Storage
+    Azure,
+    /// Google Cloud Storage
+    GCS,
+}
+
+impl Default for StorageBackend {
+    fn default() -> Self {
+        StorageBackend::S3
+    }
+}
+
+/// Object discovery settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    /// Discovery mode (default: Polling)
+    #[serde(default)]
+    pub mode: DiscoveryMode,
+
+    /// Interval between listings of the route prefixes, in seconds (Polling)
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_secs: u64,
+
+    /// SQS queue receiving the bucket's event notifications (Sqs)
+    /// Example: "https://sqs.us-east-1.amazonaws.com/123456789012/events-bucket"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_url: Option<String>,
+
+    /// SQS endpoint URL (optional, for LocalStack or ElasticMQ)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sqs_endpoint: Option<String>,
+
+    /// Long polling wait of a receive request, in seconds (Sqs, max 20)
+    #[serde(default = "default_wait_time")]
+    pub wait_time_secs: u32,
+
+    /// Messages received per request (Sqs, 1-10)
+    #[serde(default = "default_max_messages")]
+    pub max_messages: u32,
+
+    /// How long processed objects are remembered to drop duplicate notifications,
+    /// in seconds (Sqs)
+    #[serde(default = "default_dedup_window")]
+    pub dedup_window_secs: u64,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            mode: DiscoveryMode::default(),
+            poll_interval_secs: default_poll_interval(),
+            queue_url: None,
+            sqs_endpoint: None,
+            wait_time_secs: default_wait_time(),
+            max_messages: default_max_messages(),
+            dedup_window_secs: default_dedup_window(),
+        }
+    }
+}
+
+/// How new objects are found
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum DiscoveryMode {
+    /// List the route prefixes periodically
+    Polling,
+    /// Receive S3 event notifications from an SQS queue
+    Sqs,
+}
+
+impl Default for DiscoveryMode {
+    fn default() -> Self {
+        DiscoveryMode::Polling
+    }
+}
+
+/// File format of a route's objects
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum FileFormat {
+    /// One JSON document per line
+    Jsonl,
+    /// Comma-separated values, one message per row
+    Csv,
+    /// Apache Parquet, one message per row
+    Parquet,
+}
+
+/// Compression of a route's objects
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum Compression {
+    /// Gzip when the key ends with `.gz`, otherwise none
+    Auto,
+    /// Uncompressed
+    None,
+    /// Gzip
+    Gzip,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Auto
+    }
+}
+
+/// CSV parsing options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvOptions {
+    /// Field delimiter (default: ',')
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+
+    /// The first row holds the column names (default: true)
+    #[serde(default = "default_true")]
+    pub has_header: bool,
+
+    /// Column names, replacing the header row (required without a header)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            has_header: true,
+            columns: Vec::new(),
+        }
+    }
+}
+
+/// Mapping from an object prefix to a Danube topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicMapping {
+    /// Folder of the objects to read (e.g., "events/"; empty = whole bucket)
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Only read keys ending with this suffix (e.g., ".jsonl.gz")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+
+    /// File format of the objects
+    pub format: FileFormat,
+
+    /// Compression of the objects (default: Auto)
+    #[serde(default)]
+    pub compression: Compression,
+
+    /// CSV parsing options (Csv format)
+    #[serde(default)]
+    pub csv: CsvOptions,
+
+    /// Top-level field used as message key (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_field: Option<String>,
+
+    /// Target Danube topic
+    pub to: String,
+
+    /// Number of partitions for the Danube topic (0 = non-partitioned)
+    #[serde(default)]
+    pub partitions: usize,
+
+    /// Use reliable dispatch for this topic (WAL + Cloud persistence)
+    #[serde(default = "default_true")]
+    pub reliable_dispatch: bool,
+}
+
+impl TopicMapping {
+    /// Check whether an object key belongs to this route
+    pub fn matches(&self, key: &str) -> bool {
+        in_folder(&self.prefix, key)
+            && self
+                .suffix
+                .as_deref()
+                .is_none_or(|suffix| key.ends_with(suffix))
+    }
+
+    /// Whether objects of this route are gzip-compressed
+    pub fn is_gzip(&self, key: &str) -> bool {
+        match self.compression {
+            Compression::Auto => key.ends_with(".gz"),
+            Compression::None => false,
+            Compression::Gzip => true,
+        }
+    }
+}
+
+/// Check whether an object key is inside a folder (an empty folder is the whole bucket)
+pub fn in_folder(folder: &str, key: &str) -> bool {
+    let folder = folder.trim_end_matches('/');
+    folder.is_empty()
+        || key
+            .strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+impl S3Config {
+    /// Find the route of an object key (the first matching route wins)
+    pub fn route_for(&self, key: &str) -> Option<usize> {
+        self.routes.iter().position(|route| route.matches(key))
+    }
+}
+
+// Default value functions
+fn default_true() -> bool {
+    true
+}
+
+fn default_poll_interval() -> u64 {
+    60
+}
+
+fn default_wait_time() -> u32 {
+    20
+}
+
+fn default_max_messages() -> u32 {
+    10
+}
+
+fn default_dedup_window() -> u64 {
+    86400
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+impl S3SourceConfig {
+    /// Load configuration from TOML file
+    ///
+    /// The config file path must be specified via CONNECTOR_CONFIG_PATH environment variable.
+    /// Environment variables can override the bucket, endpoints and queue URL.
+    pub fn load() -> ConnectorResult<Self> {
+        ConnectorConfigLoader::new().load()
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> ConnectorResult<()> {
+        self.validate_config()
+    }
+}
+
+impl ConfigEnvOverrides for S3SourceConfig {
+    fn apply_env_overrides(&mut self) -> ConnectorResult<()> {
+        if let Ok(danube_url) = env::var("DANUBE_SERVICE_URL") {
+            self.core.danube_service_url = danube_url;
+        }
+
+        if let Ok(connector_name) = env::var("CONNECTOR_NAME") {
+            self.core.connector_name = connector_name;
+        }
+
+        if let Ok(bucket) = env::var("S3_BUCKET") {
+            self.s3.bucket = bucket;
+        }
+
+        if let Ok(region) = env::var("AWS_REGION") {
+            self.s3.region = Some(region);
+        }
+
+        if let Ok(endpoint) = env::var("S3_ENDPOINT") {
+            self.s3.endpoint = Some(endpoint);
+        }
+
+        if let Ok(account) = env::var("AZURE_STORAGE_ACCOUNT") {
+            self.s3.azure_storage_account = Some(account);
+        }
+
+        if let Ok(queue_url) = env::var("SQS_QUEUE_URL") {
+            self.s3.discovery.queue_url = Some(queue_url);
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigValidate for S3SourceConfig {
+    fn validate_config(&self) -> ConnectorResult<()> {
+        let s3 = &self.s3;
+
+        if s3.bucket.is_empty() {
+            return Err(ConnectorError::config("S3_BUCKET cannot be empty"));
+        }
+
+        if s3.storage_backend == StorageBackend::Azure && s3.azure_storage_account.is_none() {
+            return Err(ConnectorError::config(
+                "azure_storage_account is required for the azure storage backend",
+            ));
+        }
+
+        match s3.discovery.mode {
+            DiscoveryMode::Polling => {
+                if s3.discovery.poll_interval_secs == 0 {
+                    return Err(ConnectorError::config(
+                        "discovery.poll_interval_secs must be greater than 0",
+                    ));
+                }
+            }
+            DiscoveryMode::Sqs => {
+                if s3.storage_backend != StorageBackend::S3 {
+                    return Err(ConnectorError::config(
+                        "Sqs discovery is only available for the s3 storage backend",
+                    ));
+                }
+                if s3.discovery.queue_url.as_deref().is_none_or(str::is_empty) {
+                    return Err(ConnectorError::config(
+                        "discovery.queue_url is required for Sqs discovery",
+                    ));
+                }
+                if s3.discovery.wait_time_secs > 20 {
+                    return Err(ConnectorError::config(
+                        "discovery.wait_time_secs cannot exceed 20",
+                    ));
+                }
+                if !(1..=10).contains(&s3.discovery.max_messages) {
+                    return Err(ConnectorError::config(
+                        "discovery.max_messages must be between 1 and 10",
+                    ));
+                }
+            }
+        }
+
+        if s3.delete_processed {
+            return Err(ConnectorError::config(
+                "delete_processed is not supported yet: objects would be deleted once their rows \
+                 are handed to the runtime, before they are published",
+            ));
+        }
+
+        if s3.routes.is_empty() {
+            return Err(ConnectorError::config("At least one route is required"));
+        }
+
+        for mapping in &s3.routes {
+            if mapping.prefix.starts_with('/') {
+                return Err(ConnectorError::config(format!(
+                    "Route prefix '{}' must not start with '/'",
+                    mapping.prefix
+                )));
+            }
+            if mapping.to.is_empty() {
+                return Err(ConnectorError::config("Route 'to' cannot be empty"));
+            }
+            if mapping.format == FileFormat::Parquet && mapping.compression == Compression::Gzip {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': Parquet files are compressed internally, compression must be \
+                     Auto or None",
+                    mapping.to
+                )));
+            }
+            if mapping.format == FileFormat::Csv {
+                if !mapping.csv.delimiter.is_ascii() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': csv.delimiter must be an ASCII character",
+                        mapping.to
+                    )));
+                }
+                if !mapping.csv.has_header && mapping.csv.columns.is_empty() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': csv.columns is required when csv.has_header = false",
+                        mapping.to
+                    )));
+                }
+            }
+        }
+
+        for schema in &self.core.schemas {
+            let topic_exists = s3.routes.iter().any(|mapping| mapping.to == schema.topic);
+
+            if !topic_exists {
+                tracing::warn!(
+                    "Schema configured for topic '{}' but no route exists for it",
+                    schema.topic
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_mapping(prefix: &str, format: FileFormat) -> TopicMapping {
+        TopicMapping {
+            prefix: prefix.to_string(),
+            suffix: None,
+            format,
+            compression: Compression::Auto,
+            csv: CsvOptions::default(),
+            key_field: None,
+            to: "/s3/events".to_string(),
+            partitions: 0,
+            reliable_dispatch: true,
+        }
+    }
+
+    fn test_config() -> S3SourceConfig {
+        S3SourceConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(),
+            },
+            s3: S3Config {
+                storage_backend: StorageBackend::S3,
+                bucket: "events".to_string(),
+                region: Some("us-east-1".to_string()),
+                endpoint: None,
+                allow_http: false,
+                path_style: false,
+                azure_storage_account: None,
+                gcs_service_account_path: None,
+                storage_options: HashMap::new(),
+                discovery: DiscoveryConfig::default(),
+                state_path: None,
+                delete_processed: false,
+                include_metadata: true,
+                routes: vec![test_mapping("events/", FileFormat::Jsonl)],
+            },
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = test_config();
+        assert!(config.validate().is_ok());
+
+        config.s3.bucket.clear();
+        assert!(config.validate().is_err());
+        config.s3.bucket = "events".to_string();
+
+        config.s3.storage_backend = StorageBackend::Azure;
+        assert!(config.validate().is_err());
+        config.s3.azure_storage_account = Some("account".to_string());
+        assert!(config.validate().is_ok());
+        config.s3.storage_backend = StorageBackend::S3;
+
+        config.s3.routes[0].format = FileFormat::Parquet;
+        config.s3.routes[0].compression = Compression::Gzip;
+        assert!(config.validate().is_err());
+
+        config.s3.routes[0].format = FileFormat::Csv;
+        config.s3.routes[0].csv.has_header = false;
+        assert!(config.validate().is_err());
+        config.s3.routes[0].csv.columns = vec!["id".to_string()];
+        assert!(config.validate().is_ok());
+
+        config.s3.routes[0].prefix = "/events".to_string();
+        assert!(config.validate().is_err());
+        config.s3.routes[0].prefix = "events/".to_string();
+
+        config.s3.delete_processed = true;
+        assert!(config.validate().is_err());
+
+        config.s3.routes.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_sqs_validation() {
+        let mut config = test_config();
+        config.s3.discovery.mode = DiscoveryMode::Sqs;
+        assert!(config.validate().is_err());
+
+        config.s3.discovery.queue_url =
+            Some("https://sqs.us-east-1.amazonaws.com/123456789012/events".to_string());
+        assert!(config.validate().is_ok());
+
+        config.s3.discovery.max_messages = 11;
+        assert!(config.validate().is_err());
+        config.s3.discovery.max_messages = 10;
+
+        config.s3.storage_backend = StorageBackend::GCS;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_route_matching() {
+        let mut mapping = test_mapping("events/", FileFormat::Jsonl);
+        assert!(mapping.matches("events/2024/06/01.jsonl"));
+        assert!(!mapping.matches("events-archive/01.jsonl"));
+        assert!(!mapping.matches("logs/01.jsonl"));
+
+        // The prefix is a folder, with or without the trailing slash
+        mapping.prefix = "events".to_string();
+        assert!(mapping.matches("events/01.jsonl"));
+        assert!(!mapping.matches("events.jsonl"));
+
+        mapping.suffix = Some(".jsonl.gz".to_string());
+        assert!(mapping.matches("events/01.jsonl.gz"));
+        assert!(!mapping.matches("events/01.jsonl"));
+        assert!(mapping.is_gzip("events/01.jsonl.gz"));
+
+        mapping.prefix.clear();
+        assert!(mapping.matches("01.jsonl.gz"));
+
+        let mut config = test_config();
+        config.s3.routes.push(test_mapping("", FileFormat::Csv));
+        assert_eq!(config.s3.route_for("events/01.jsonl"), Some(0));
+        assert_eq!(config.s3.route_for("other/01.csv"), Some(1));
+    }
+
+    #[test]
+    fn test_default_values() {
+        let config: S3Config = toml::from_str(
+            r#"
+            bucket = "events"
+
+            [[routes]]
+            prefix = "events/"
+            format = "Parquet"
+            to = "/s3/events"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.storage_backend, StorageBackend::S3);
+        assert_eq!(config.discovery.mode, DiscoveryMode::Polling);
+        assert_eq!(config.discovery.poll_interval_secs, 60);
+        assert!(config.include_metadata);
+        assert!(!config.delete_processed);
+        assert_eq!(config.routes[0].compression, Compression::Auto);
+        assert_eq!(config.routes[0].csv.delimiter, ',');
+        assert!(config.routes[0].reliable_dispatch);
+    }
+}
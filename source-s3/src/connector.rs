@@ -0,0 +1,620 @@
+//! Object storage source connector implementation
+//!
+//! A single discovery task finds new objects, either by listing the route
+//! prefixes periodically or by receiving the bucket's event notifications
+//! from SQS, and publishes the rows of each object to its route's topic.
+//! Objects are read one at a time, in key order when polling.
+
+use crate::checkpoint::CheckpointStore;
+use crate::config::{DiscoveryMode, S3Config, TopicMapping};
+use crate::format;
+use crate::notification;
+use crate::storage;
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_sqs::error::DisplayErrorContext;
+use danube_connect_core::{
+    ConnectorConfig, ConnectorError, ConnectorResult, Offset, ProducerConfig, SchemaMapping,
+    SourceConnector, SourceConnectorMode, SourceRecord, SourceSender,
+};
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectMeta, ObjectStore};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::AbortHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, info, warn};
+
+/// Delay before receiving again after a failed SQS request
+const SQS_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Object Storage Source Connector
+///
+/// Streams the rows of JSONL, CSV and Parquet objects to Danube topics.
+pub struct S3SourceConnector {
+    config: S3Config,
+    schemas: Vec<SchemaMapping>,
+    store: Option<Arc<dyn ObjectStore>>,
+    sqs: Option<aws_sdk_sqs::Client>,
+    /// Processed objects, shared with the discovery task
+    checkpoint: Option<Arc<CheckpointStore>>,
+    discovery_abort: Option<AbortHandle>,
+}
+
+impl S3SourceConnector {
+    /// Create a new object storage source connector with provided configuration
+    pub fn with_config(config: S3Config, schemas: Vec<SchemaMapping>) -> Self {
+        Self {
+            config,
+            schemas,
+            store: None,
+            sqs: None,
+            checkpoint: None,
+            discovery_abort: None,
+        }
+    }
+
+    /// Find schema configuration for a Danube topic
+    fn find_schema_config(&self, danube_topic: &str) -> Option<danube_connect_core::SchemaConfig> {
+        self.schemas
+            .iter()
+            .find(|s| s.topic == danube_topic)
+            .map(|schema| danube_connect_core::SchemaConfig {
+                subject: schema.subject.clone(),
+                schema_type: schema.schema_type.clone(),
+                schema_file: schema.schema_file.clone(),
+                auto_register: schema.auto_register,
+                version_strategy: schema.version_strategy.clone(),
+            })
+    }
+}
+
+/// Why reading an object stopped
+enum ReadError {
+    /// The object store failed (the object is read again later)
+    Storage(String),
+    /// The source runtime is gone
+    Closed,
+}
+
+impl From<object_store::Error> for ReadError {
+    fn from(error: object_store::Error) -> Self {
+        ReadError::Storage(error.to_string())
+    }
+}
+
+/// State of the discovery task
+struct ObjectReader {
+    store: Arc<dyn ObjectStore>,
+    config: S3Config,
+    checkpoint: Arc<CheckpointStore>,
+    sender: SourceSender,
+}
+
+impl ObjectReader {
+    fn new(
+        store: Arc<dyn ObjectStore>,
+        config: S3Config,
+        checkpoint: Arc<CheckpointStore>,
+        sender: SourceSender,
+    ) -> Self {
+        Self {
+            store,
+            config,
+            checkpoint,
+            sender,
+        }
+    }
+
+    /// List the route prefixes every `poll_interval_secs` until the runtime is gone
+    async fn run_polling(mut self) {
+        let interval_secs = self.config.discovery.poll_interval_secs;
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        info!(
+            "Polling bucket '{}' every {}s",
+            self.config.bucket, interval_secs
+        );
+
+        loop {
+            interval.tick().await;
+
+            for index in 0..self.config.routes.len() {
+                match self.poll_route(index).await {
+                    Ok(()) => {}
+                    Err(ReadError::Closed) => {
+                        info!("Source runtime stopped, ending bucket polling");
+                        return;
+                    }
+                    Err(ReadError::Storage(e)) => {
+                        warn!(
+                            "Failed to list '{}': {}",
+                            self.config.routes[index].prefix, e
+                        );
+                    }
+                }
+            }
+            self.checkpoint.flush();
+        }
+    }
+
+    /// Read the new objects of a route's prefix
+    async fn poll_route(&mut self, index: usize) -> Result<(), ReadError> {
+        let prefix = self.config.routes[index].prefix.clone();
+        let folder = prefix.trim_end_matches('/');
+        let folder_path = (!folder.is_empty()).then(|| ObjectPath::from(folder));
+
+        let mut objects: Vec<ObjectMeta> =
+            self.store.list(folder_path.as_ref()).try_collect().await?;
+        objects.sort_by(|a, b| a.location.cmp(&b.location));
+
+        let listed: HashSet<String> = objects.iter().map(|o| o.location.to_string()).collect();
+        for meta in &objects {
+            // Objects matching several routes belong to the first one
+            if self.config.route_for(meta.location.as_ref()) != Some(index)
+                || self.checkpoint.is_processed(meta)
+            {
+                continue;
+            }
+            match self.read_object(index, meta).await {
+                Ok(()) => {}
+                Err(ReadError::Storage(e)) => {
+                    warn!(
+                        "Failed to read '{}', retrying on the next poll: {}",
+                        meta.location, e
+                    );
+                }
+                Err(ReadError::Closed) => return Err(ReadError::Closed),
+            }
+        }
+
+        // Forget deleted objects, so the state only holds the prefix's current objects
+        self.checkpoint.retain_listed(&prefix, &listed);
+        Ok(())
+    }
+
+    /// Receive the bucket's event notifications until the runtime is gone
+    async fn run_sqs(mut self, client: aws_sdk_sqs::Client) {
+        let discovery = self.config.discovery.clone();
+        let queue_url = discovery.queue_url.clone().unwrap_or_default();
+        info!("Receiving object notifications from {}", queue_url);
+
+        loop {
+            if self.sender.is_closed() {
+                info!("Source runtime stopped, ending notification processing");
+                return;
+            }
+            self.checkpoint.expire(discovery.dedup_window_secs);
+
+            let response = client
+                .receive_message()
+                .queue_url(&queue_url)
+                .max_number_of_messages(discovery.max_messages as i32)
+                .wait_time_seconds(discovery.wait_time_secs as i32)
+                .send()
+                .await;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("Failed to receive from SQS: {}", DisplayErrorContext(&e));
+                    tokio::time::sleep(SQS_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            for message in response.messages() {
+                match self
+                    .handle_notification(message.body().unwrap_or_default())
+                    .await
+                {
+                    Ok(()) => {}
+                    Err(ReadError::Storage(e)) => {
+                        // Redelivered after the visibility timeout
+                        warn!("Failed to process notification, will be redelivered: {}", e);
+                        continue;
+                    }
+                    Err(ReadError::Closed) => {
+                        info!("Source runtime stopped, ending notification processing");
+                        return;
+                    }
+                }
+
+                if let Some(receipt_handle) = message.receipt_handle() {
+                    if let Err(e) = client
+                        .delete_message()
+                        .queue_url(&queue_url)
+                        .receipt_handle(receipt_handle)
+                        .send()
+                        .await
+                    {
+                        warn!("Failed to delete SQS message: {}", DisplayErrorContext(&e));
+                    }
+                }
+            }
+            self.checkpoint.flush();
+        }
+    }
+
+    /// Read the objects created according to a notification
+    async fn handle_notification(&mut self, body: &str) -> Result<(), ReadError> {
+        let objects = match notification::created_objects(body) {
+            Ok(objects) => objects,
+            Err(e) => {
+                warn!("Dropping SQS message: {}", e);
+                return Ok(());
+            }
+        };
+
+        for object in objects {
+            if object.bucket != self.config.bucket {
+                debug!("Ignoring notification of bucket '{}'", object.bucket);
+                continue;
+            }
+            let Some(index) = self.config.route_for(&object.key) else {
+                debug!("No route for '{}'", object.key);
+                continue;
+            };
+
+            let location = ObjectPath::parse(&object.key)
+                .unwrap_or_else(|_| ObjectPath::from(object.key.as_str()));
+            let meta = match self.store.head(&location).await {
+                Ok(meta) => meta,
+                Err(object_store::Error::NotFound { .. }) => {
+                    debug!("Object '{}' was deleted, skipping", object.key);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if self.checkpoint.is_processed(&meta) {
+                debug!("Object '{}' already processed", object.key);
+                continue;
+            }
+            self.read_object(index, &meta).await?;
+        }
+        Ok(())
+    }
+
+    /// Publish the rows of an object and checkpoint it
+    ///
+    /// Objects that can't be decoded as the route's format are logged and
+    /// checkpointed, so they don't block the route.
+    async fn read_object(&mut self, index: usize, meta: &ObjectMeta) -> Result<(), ReadError> {
+        let route = self.config.routes[index].clone();
+        let key = meta.location.to_string();
+
+        let result = self.store.get(&meta.location).await?;
+        // Checkpoint the version actually read
+        let meta = result.meta.clone();
+        let content = result.bytes().await?;
+
+        let rows = match format::read_rows(content, &key, &route) {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("Skipping object '{}': {}", key, e);
+                self.checkpoint.mark_processed(&meta);
+                return Ok(());
+            }
+        };
+
+        let mut published = 0;
+        let mut invalid = 0;
+        for (row_index, row) in rows.enumerate() {
+            match row {
+                Ok(row) => {
+                    let record = object_record(&self.config, &route, &key, row_index + 1, row);
+                    self.sender
+                        .send(record)
+                        .await
+                        .map_err(|_| ReadError::Closed)?;
+                    published += 1;
+                }
+                Err(e) => {
+                    warn!("Skipping row of '{}': {}", key, e);
+                    invalid += 1;
+                }
+            }
+        }
+
+        info!(
+            "Published {} rows of '{}' to '{}' ({} invalid)",
+            published, key, route.to, invalid
+        );
+        self.checkpoint.mark_processed(&meta);
+        Ok(())
+    }
+}
+
+/// Create a SourceRecord from a row of an object
+fn object_record(
+    config: &S3Config,
+    route: &TopicMapping,
+    key: &str,
+    row_number: usize,
+    row: Value,
+) -> SourceRecord {
+    let message_key = message_key(route, &row);
+    let mut record = SourceRecord::new(&route.to, row);
+
+    if config.include_metadata {
+        record = record
+            .with_attribute("s3.bucket", &config.bucket)
+            .with_attribute("s3.key", key)
+            .with_attribute("s3.row", row_number.to_string())
+            .with_attribute("source", "s3");
+    }
+
+    if let Some(message_key) = message_key {
+        record = record.with_key(&message_key);
+    }
+
+    record
+}
+
+/// Read the message key of a row from the route's `key_field`
+fn message_key(route: &TopicMapping, row: &Value) -> Option<String> {
+    match row.get(route.key_field.as_deref()?)? {
+        Value::String(value) => Some(value.clone()),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// Check that a route's prefix can be listed
+async fn check_access(store: &dyn ObjectStore, prefix: &str) -> ConnectorResult<()> {
+    let folder = prefix.trim_end_matches('/');
+    let folder_path = (!folder.is_empty()).then(|| ObjectPath::from(folder));
+    match store.list(folder_path.as_ref()).next().await {
+        Some(Err(e)) => Err(ConnectorError::retryable(format!(
+            "Failed to list '{}': {}",
+            prefix, e
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Create the SQS client and check that the queue is accessible
+async fn connect_sqs(config: &S3Config) -> ConnectorResult<aws_sdk_sqs::Client> {
+    let mut loader = aws_config::defaults(BehaviorVersion::latest());
+    if let Some(region) = &config.region {
+        loader = loader.region(Region::new(region.clone()));
+    }
+    if let Some(endpoint) = &config.discovery.sqs_endpoint {
+        loader = loader.endpoint_url(endpoint);
+    }
+    let client = aws_sdk_sqs::Client::new(&loader.load().await);
+
+    let queue_url = config.discovery.queue_url.clone().unwrap_or_default();
+    client
+        .get_queue_attributes()
+        .queue_url(&queue_url)
+        .send()
+        .await
+        .map_err(|e| {
+            ConnectorError::retryable(format!(
+                "Failed to access SQS queue {}: {}",
+                queue_url,
+                DisplayErrorContext(&e)
+            ))
+        })?;
+
+    Ok(client)
+}
+
+#[async_trait]
+impl SourceConnector for S3SourceConnector {
+    async fn initialize(&mut self, _config: ConnectorConfig) -> ConnectorResult<()> {
+        info!("Initializing Object Storage Source Connector");
+        info!(
+            "Reading bucket '{}' ({:?})",
+            self.config.bucket, self.config.storage_backend
+        );
+
+        let store = storage::build_store(&self.config)?;
+        for mapping in &self.config.routes {
+            check_access(store.as_ref(), &mapping.prefix).await?;
+            info!(
+                "Route: '{}' ({:?}) → topic '{}' (partitions: {})",
+                mapping.prefix, mapping.format, mapping.to, mapping.partitions
+            );
+        }
+
+        if self.config.discovery.mode == DiscoveryMode::Sqs {
+            self.sqs = Some(connect_sqs(&self.config).await?);
+        }
+        if self.config.state_path.is_none() {
+            warn!("No state_path configured, all objects are read again after a restart");
+        }
+
+        self.store = Some(store);
+
+        info!("Object Storage Source Connector initialized successfully");
+        Ok(())
+    }
+
+    fn mode(&self) -> SourceConnectorMode {
+        SourceConnectorMode::Streaming
+    }
+
+    async fn start_streaming(&mut self, sender: SourceSender) -> ConnectorResult<()> {
+        if self.discovery_abort.is_some() {
+            return Err(ConnectorError::config(
+                "Object storage source streaming has already been started",
+            ));
+        }
+
+        let store = self
+            .store
+            .clone()
+            .ok_or_else(|| ConnectorError::fatal("Object store not initialized"))?;
+        let checkpoint = Arc::new(CheckpointStore::open(self.config.state_path.clone()));
+        self.checkpoint = Some(checkpoint.clone());
+        let reader = ObjectReader::new(store, self.config.clone(), checkpoint, sender);
+
+        let handle = match self.config.discovery.mode {
+            DiscoveryMode::Polling => tokio::spawn(reader.run_polling()),
+            DiscoveryMode::Sqs => {
+                let client = self
+                    .sqs
+                    .clone()
+                    .ok_or_else(|| ConnectorError::fatal("SQS client not initialized"))?;
+                tokio::spawn(reader.run_sqs(client))
+            }
+        };
+        self.discovery_abort = Some(handle.abort_handle());
+
+        info!(
+            "Object Storage Source Connector streaming started ({:?} discovery)",
+            self.config.discovery.mode
+        );
+        Ok(())
+    }
+
+    async fn producer_configs(&self) -> ConnectorResult<Vec<ProducerConfig>> {
+        let producer_configs: Vec<_> = self
+            .config
+            .routes
+            .iter()
+            .map(|mapping| ProducerConfig {
+                topic: mapping.to.clone(),
+                partitions: mapping.partitions,
+                reliable_dispatch: mapping.reliable_dispatch,
+                schema_config: self.find_schema_config(&mapping.to),
+            })
+            .collect();
+
+        if producer_configs.is_empty() {
+            return Err(ConnectorError::config(
+                "No routes configured. Please add routes in the configuration.",
+            ));
+        }
+
+        Ok(producer_configs)
+    }
+
+    async fn commit(&mut self, offsets: Vec<Offset>) -> ConnectorResult<()> {
+        // Objects are checkpointed, and their SQS notifications deleted, by the
+        // discovery task once handed to the runtime: streaming records carry no
+        // offset of ours, so these offsets can't be matched to objects or messages
+        debug!("Committed {} offsets", offsets.len());
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> ConnectorResult<()> {
+        info!("Shutting down Object Storage Source Connector");
+
+        // Objects completed so far are saved, an interrupted object is read again
+        if let Some(abort_handle) = self.discovery_abort.take() {
+            abort_handle.abort();
+        }
+        if let Some(checkpoint) = self.checkpoint.take() {
+            checkpoint.flush();
+        }
+        self.store = None;
+        self.sqs = None;
+
+        info!("Object Storage Source Connector stopped");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> ConnectorResult<()> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("Object store not initialized"))?;
+
+        match self.config.routes.first() {
+            Some(mapping) => check_access(store.as_ref(), &mapping.prefix).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Compression, CsvOptions, DiscoveryConfig, FileFormat, StorageBackend};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn test_config() -> S3Config {
+        S3Config {
+            storage_backend: StorageBackend::S3,
+            bucket: "events".to_string(),
+            region: Some("us-east-1".to_string()),
+            endpoint: None,
+            allow_http: false,
+            path_style: false,
+            azure_storage_account: None,
+            gcs_service_account_path: None,
+            storage_options: HashMap::new(),
+            discovery: DiscoveryConfig::default(),
+            state_path: None,
+            delete_processed: false,
+            include_metadata: true,
+            routes: vec![TopicMapping {
+                prefix: "events/".to_string(),
+                suffix: None,
+                format: FileFormat::Jsonl,
+                compression: Compression::Auto,
+                csv: CsvOptions::default(),
+                key_field: Some("id".to_string()),
+                to: "/s3/events".to_string(),
+                partitions: 0,
+                reliable_dispatch: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_message_key() {
+        let mut route = test_config().routes.remove(0);
+        assert_eq!(
+            message_key(&route, &json!({"id": "evt-1"})),
+            Some("evt-1".to_string())
+        );
+        assert_eq!(
+            message_key(&route, &json!({"id": 42})),
+            Some("42".to_string())
+        );
+
+        // Rows without the key field have no key
+        assert_eq!(message_key(&route, &json!({"id": null})), None);
+        assert_eq!(message_key(&route, &json!({"type": "view"})), None);
+
+        route.key_field = None;
+        assert_eq!(message_key(&route, &json!({"id": "evt-1"})), None);
+    }
+
+    #[tokio::test]
+    async fn test_local_objects() {
+        let store: Arc<dyn ObjectStore> = Arc::new(object_store::memory::InMemory::new());
+        store
+            .put(
+                &ObjectPath::from("events/a.jsonl"),
+                "{\"id\":1}\n{\"id\":2}\n".into(),
+            )
+            .await
+            .unwrap();
+        assert!(check_access(store.as_ref(), "events/").await.is_ok());
+
+        let meta = store
+            .head(&ObjectPath::from("events/a.jsonl"))
+            .await
+            .unwrap();
+        let checkpoint = CheckpointStore::open(None);
+        assert!(!checkpoint.is_processed(&meta));
+        checkpoint.mark_processed(&meta);
+        assert!(checkpoint.is_processed(&meta));
+    }
+
+    #[test]
+    fn test_connector_creation() {
+        let connector = S3SourceConnector::with_config(test_config(), vec![]);
+        assert!(connector.store.is_none());
+        assert!(connector.sqs.is_none());
+        assert!(connector.discovery_abort.is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! File decoding
+//!
+//! Turns the content of an object into a stream of JSON rows according to its
+//! route's format. Rows are produced lazily so only the object's bytes and one
+//! Parquet batch are held in memory. A row that can't be decoded (a malformed
+//! JSONL line or CSV record) is reported without ending the stream; a Parquet
+//! error ends it.
+
+use crate::config::{CsvOptions, FileFormat, TopicMapping};
+use arrow_json::writer::{JsonArray, WriterBuilder};
+use bytes::{Buf, Bytes};
+use flate2::read::MultiGzDecoder;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde_json::{Map, Value};
+use std::io::{BufRead, BufReader, Read};
+
+/// Rows of an object, or the error of a row that couldn't be decoded
+pub type Rows = Box<dyn Iterator<Item = Result<Value, String>> + Send>;
+
+/// Decode the rows of an object
+///
+/// Fails when the object can't be opened as the route's format (e.g., an
+/// invalid Parquet footer or CSV header).
+pub fn read_rows(content: Bytes, key: &str, route: &TopicMapping) -> Result<Rows, String> {
+    match route.format {
+        FileFormat::Parquet => parquet_rows(content),
+        FileFormat::Jsonl => Ok(jsonl_rows(decompressed(content, route.is_gzip(key)))),
+        FileFormat::Csv => csv_rows(decompressed(content, route.is_gzip(key)), &route.csv),
+    }
+}
+
+fn decompressed(content: Bytes, gzip: bool) -> Box<dyn Read + Send> {
+    if gzip {
+        Box::new(MultiGzDecoder::new(content.reader()))
+    } else {
+        Box::new(content.reader())
+    }
+}
+
+fn jsonl_rows(reader: Box<dyn Read + Send>) -> Rows {
+    let rows = BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => Some(
+                serde_json::from_str(&line)
+                    .map_err(|e| format!("Invalid JSON on line {}: {}", index + 1, e)),
+            ),
+            Err(e) => Some(Err(format!("Failed to read line {}: {}", index + 1, e))),
+        });
+    Box::new(rows)
+}
+
+fn csv_rows(reader: Box<dyn Read + Send>, options: &CsvOptions) -> Result<Rows, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter as u8)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .from_reader(reader);
+
+    let columns: Vec<String> = if options.columns.is_empty() {
+        reader
+            .headers()
+            .map_err(|e| format!("Failed to read CSV header: {}", e))?
+            .iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        options.columns.clone()
+    };
+
+    let rows = reader.into_records().map(move |record| {
+        let record = record.map_err(|e| format!("Invalid CSV record: {}", e))?;
+        let row: Map<String, Value> = columns
+            .iter()
+            .zip(record.iter())
+            .map(|(column, value)| (column.clone(), Value::String(value.to_string())))
+            .collect();
+        Ok(Value::Object(row))
+    });
+    Ok(Box::new(rows))
+}
+
+fn parquet_rows(content: Bytes) -> Result<Rows, String> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(content)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Invalid Parquet file: {}", e))?;
+
+    let mut failed = false;
+    let rows = reader
+        .map_while(move |batch| {
+            // Stop after the first failed batch
+            if failed {
+                return None;
+            }
+            let rows = batch
+                .map_err(|e| format!("Failed to read Parquet batch: {}", e))
+                .and_then(|batch| {
+                    let mut writer = WriterBuilder::new()
+                        .with_explicit_nulls(true)
+                        .build::<_, JsonArray>(Vec::new());
+                    writer
+                        .write(&batch)
+                        .and_then(|_| writer.finish())
+                        .map_err(|e| format!("Failed to convert Parquet rows: {}", e))?;
+                    let json = writer.into_inner();
+                    if json.is_empty() {
+                        return Ok(Vec::new());
+                    }
+                    serde_json::from_slice::<Vec<Value>>(&json)
+                        .map_err(|e| format!("Failed to convert Parquet rows: {}", e))
+                });
+            failed = rows.is_err();
+            Some(match rows {
+                Ok(rows) => rows.into_iter().map(Ok).collect::<Vec<_>>(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+        .flatten();
+    Ok(Box::new(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Compression;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use flate2::write::GzEncoder;
+    use parquet::arrow::ArrowWriter;
+    use serde_json::json;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    fn route(format: FileFormat) -> TopicMapping {
+        TopicMapping {
+            prefix: "events/".to_string(),
+            suffix: None,
+            format,
+            compression: Compression::Auto,
+            csv: CsvOptions::default(),
+            key_field: None,
+            to: "/s3/events".to_string(),
+            partitions: 0,
+            reliable_dispatch: true,
+        }
+    }
+
+    fn collect(rows: Rows) -> Vec<Result<Value, String>> {
+        rows.collect()
+    }
+
+    #[test]
+    fn test_jsonl_rows() {
+        let content = Bytes::from("{\"id\":1}\n\n{invalid}\n{\"id\":2}\n");
+        let rows =
+            collect(read_rows(content, "events/a.jsonl", &route(FileFormat::Jsonl)).unwrap());
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], Ok(json!({"id": 1})));
+        assert!(rows[1].as_ref().unwrap_err().contains("line 3"));
+        assert_eq!(rows[2], Ok(json!({"id": 2})));
+    }
+
+    #[test]
+    fn test_gzip_jsonl_rows() {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"{\"id\":1}\n{\"id\":2}\n").unwrap();
+        let content = Bytes::from(encoder.finish().unwrap());
+
+        let rows =
+            collect(read_rows(content, "events/a.jsonl.gz", &route(FileFormat::Jsonl)).unwrap());
+        assert_eq!(rows, vec![Ok(json!({"id": 1})), Ok(json!({"id": 2}))]);
+    }
+
+    #[test]
+    fn test_csv_rows() {
+        let content = Bytes::from("id,name\n1,alice\n2,\"bob, jr\"\n");
+        let rows = collect(read_rows(content, "events/a.csv", &route(FileFormat::Csv)).unwrap());
+        assert_eq!(
+            rows,
+            vec![
+                Ok(json!({"id": "1", "name": "alice"})),
+                Ok(json!({"id": "2", "name": "bob, jr"})),
+            ]
+        );
+
+        // Headerless files with configured column names
+        let mut route = route(FileFormat::Csv);
+        route.csv.delimiter = ';';
+        route.csv.has_header = false;
+        route.csv.columns = vec!["id".to_string(), "name".to_string()];
+        let content = Bytes::from("1;alice\n");
+        let rows = collect(read_rows(content, "events/a.csv", &route).unwrap());
+        assert_eq!(rows, vec![Ok(json!({"id": "1", "name": "alice"}))]);
+    }
+
+    #[test]
+    fn test_parquet_rows() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("alice"), None])),
+            ],
+        )
+        .unwrap();
+
+        let mut content = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut content, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let rows = collect(
+            read_rows(
+                Bytes::from(content),
+                "events/a.parquet",
+                &route(FileFormat::Parquet),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            rows,
+            vec![
+                Ok(json!({"id": 1, "name": "alice"})),
+                Ok(json!({"id": 2, "name": null})),
+            ]
+        );
+
+        let invalid = Bytes::from("not parquet");
+        assert!(read_rows(invalid, "events/a.parquet", &route(FileFormat::Parquet)).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Object Storage Source Connector for Danube Connect
+//!
+//! This connector reads JSONL, CSV and Parquet files from S3, MinIO, GCS or
+//! Azure Blob Storage and publishes their rows to Danube topics. New objects
+//! are found by polling the bucket or through S3 event notifications (SQS),
+//! and processed objects are checkpointed so each file is published once.
+
+mod checkpoint;
+mod config;
+mod connector;
+mod format;
+mod notification;
+mod storage;
+
+use config::S3SourceConfig;
+use connector::S3SourceConnector;
+use danube_connect_core::{ConnectorResult, SourceRuntime};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> ConnectorResult<()> {
+    // Initialize logging first
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info,danube_source_s3=debug"));
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_target(true))
+        .try_init()
+        .ok(); // Ignore error if already initialized
+
+    tracing::info!("Starting Object Storage Source Connector");
+    tracing::info!("Version: {}", env!("CARGO_PKG_VERSION"));
+
+    // Load unified configuration from single file (TOML + ENV overrides)
+    let config = S3SourceConfig::load().map_err(|e| {
+        tracing::error!("Failed to load configuration: {}", e);
+        e
+    })?;
+
+    // Validate configuration
+    config.validate()?;
+
+    tracing::info!("Configuration loaded and validated successfully");
+    tracing::info!("Connector: {}", config.core.connector_name);
+    tracing::info!("Danube URL: {}", config.core.danube_service_url);
+    tracing::info!("Storage Backend: {:?}", config.s3.storage_backend);
+    tracing::info!("Bucket: {}", config.s3.bucket);
+    tracing::info!("Discovery: {:?}", config.s3.discovery.mode);
+    tracing::info!("Routes: {} configured", config.s3.routes.len());
+
+    for (idx, mapping) in config.s3.routes.iter().enumerate() {
+        tracing::info!(
+            "  Route {}: '{}' ({:?}) → Topic '{}'",
+            idx + 1,
+            mapping.prefix,
+            mapping.format,
+            mapping.to
+        );
+    }
+
+    // Create connector instance with object storage configuration and schemas
+    let connector = S3SourceConnector::with_config(config.s3, config.core.schemas.clone());
+
+    // Create and run the source runtime
+    let mut runtime = SourceRuntime::new(connector, config.core).await?;
+
+    // Run until shutdown signal
+    runtime.run().await?;
+
+    tracing::info!("Object Storage Source Connector stopped");
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! S3 event notifications
+//!
+//! Extracts the created objects from the SQS messages of a bucket's event
+//! notifications. Three layouts are accepted: S3 notifications sent to the
+//! queue directly, the same notifications wrapped by SNS, and EventBridge
+//! "Object Created" events.
+
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+
+/// An object referenced by a notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectRef {
+    pub bucket: String,
+    pub key: String,
+}
+
+/// Read the created objects of an SQS message body
+///
+/// Messages that aren't object creation events (e.g., `s3:TestEvent` or
+/// deletions) yield no objects. Fails when the body isn't JSON.
+pub fn created_objects(body: &str) -> Result<Vec<ObjectRef>, String> {
+    let message: Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid notification: {}", e))?;
+
+    // SNS envelope: the notification is a JSON string in "Message"
+    if message.get("Type").and_then(Value::as_str) == Some("Notification") {
+        if let Some(inner) = message.get("Message").and_then(Value::as_str) {
+            return created_objects(inner);
+        }
+    }
+
+    // EventBridge event
+    if message.get("detail-type").and_then(Value::as_str) == Some("Object Created") {
+        let detail = &message["detail"];
+        let bucket = detail["bucket"]["name"].as_str();
+        let key = detail["object"]["key"].as_str();
+        return Ok(match (bucket, key) {
+            (Some(bucket), Some(key)) => vec![ObjectRef {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+            }],
+            _ => Vec::new(),
+        });
+    }
+
+    // S3 notification
+    let records = message
+        .get("Records")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    Ok(records
+        .iter()
+        .filter(|record| {
+            record["eventName"]
+                .as_str()
+                .is_some_and(|name| name.starts_with("ObjectCreated:"))
+        })
+        .filter_map(|record| {
+            let bucket = record["s3"]["bucket"]["name"].as_str()?;
+            let key = record["s3"]["object"]["key"].as_str()?;
+            Some(ObjectRef {
+                bucket: bucket.to_string(),
+                key: decode_key(key),
+            })
+        })
+        .collect())
+}
+
+/// Decode an S3 notification key (URL-encoded, with '+' for spaces)
+fn decode_key(key: &str) -> String {
+    let key = key.replace('+', " ");
+    percent_decode_str(&key).decode_utf8_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn s3_notification(event_name: &str, key: &str) -> Value {
+        json!({
+            "Records": [{
+                "eventSource": "aws:s3",
+                "eventName": event_name,
+                "s3": {
+                    "bucket": {"name": "events"},
+                    "object": {"key": key, "size": 42, "eTag": "abc"}
+                }
+            }]
+        })
+    }
+
+    #[test]
+    fn test_s3_notification() {
+        let body = s3_notification("ObjectCreated:Put", "events/dt%3D2024-06-01/my+file.jsonl");
+        assert_eq!(
+            created_objects(&body.to_string()).unwrap(),
+            vec![ObjectRef {
+                bucket: "events".to_string(),
+                key: "events/dt=2024-06-01/my file.jsonl".to_string(),
+            }]
+        );
+
+        let body = s3_notification("ObjectRemoved:Delete", "events/a.jsonl");
+        assert!(created_objects(&body.to_string()).unwrap().is_empty());
+
+        let test_event = json!({"Service": "Amazon S3", "Event": "s3:TestEvent"});
+        assert!(created_objects(&test_event.to_string()).unwrap().is_empty());
+
+        assert!(created_objects("not json").is_err());
+    }
+
+    #[test]
+    fn test_sns_envelope() {
+        let inner = s3_notification("ObjectCreated:CompleteMultipartUpload", "events/a.jsonl");
+        let body = json!({"Type": "Notification", "Message": inner.to_string()});
+        assert_eq!(
+            created_objects(&body.to_string()).unwrap(),
+            vec![ObjectRef {
+                bucket: "events".to_string(),
+                key: "events/a.jsonl".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_eventbridge_event() {
+        let body = json!({
+            "source": "aws.s3",
+            "detail-type": "Object Created",
+            "detail": {
+                "bucket": {"name": "events"},
+                "object": {"key": "events/my file.jsonl", "size": 42}
+            }
+        });
+        assert_eq!(
+            created_objects(&body.to_string()).unwrap(),
+            vec![ObjectRef {
+                bucket: "events".to_string(),
+                key: "events/my file.jsonl".to_string(),
+            }]
+        );
+    }
+}
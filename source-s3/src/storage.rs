@@ -0,0 +1,113 @@
+//! Object store client construction
+//!
+//! Builds the `object_store` client of the configured backend. Credentials are
+//! read from the environment by the builders (`AWS_*`, `AZURE_*`, `GOOGLE_*`),
+//! and `storage_options` are applied last so they can override everything.
+
+use crate::config::{S3Config, StorageBackend};
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
+use object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
+use object_store::ObjectStore;
+use std::sync::Arc;
+
+/// Create the object store client for the configured bucket
+pub fn build_store(config: &S3Config) -> ConnectorResult<Arc<dyn ObjectStore>> {
+    let store: Arc<dyn ObjectStore> = match config.storage_backend {
+        StorageBackend::S3 => {
+            let mut builder = AmazonS3Builder::from_env()
+                .with_bucket_name(&config.bucket)
+                .with_allow_http(config.allow_http)
+                .with_virtual_hosted_style_request(!config.path_style);
+            if let Some(region) = &config.region {
+                builder = builder.with_region(region);
+            }
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            for (key, value) in &config.storage_options {
+                let key: AmazonS3ConfigKey = key.parse().map_err(|e| option_error(key, e))?;
+                builder = builder.with_config(key, value);
+            }
+            Arc::new(builder.build().map_err(store_error)?)
+        }
+        StorageBackend::Azure => {
+            let mut builder = MicrosoftAzureBuilder::from_env()
+                .with_container_name(&config.bucket)
+                .with_allow_http(config.allow_http);
+            if let Some(account) = &config.azure_storage_account {
+                builder = builder.with_account(account);
+            }
+            for (key, value) in &config.storage_options {
+                let key: AzureConfigKey = key.parse().map_err(|e| option_error(key, e))?;
+                builder = builder.with_config(key, value);
+            }
+            Arc::new(builder.build().map_err(store_error)?)
+        }
+        StorageBackend::GCS => {
+            let mut builder =
+                GoogleCloudStorageBuilder::from_env().with_bucket_name(&config.bucket);
+            if let Some(path) = &config.gcs_service_account_path {
+                builder = builder.with_service_account_path(path);
+            }
+            for (key, value) in &config.storage_options {
+                let key: GoogleConfigKey = key.parse().map_err(|e| option_error(key, e))?;
+                builder = builder.with_config(key, value);
+            }
+            Arc::new(builder.build().map_err(store_error)?)
+        }
+    };
+
+    Ok(store)
+}
+
+fn option_error(key: &str, error: object_store::Error) -> ConnectorError {
+    ConnectorError::config(format!("Invalid storage option '{}': {}", key, error))
+}
+
+fn store_error(error: object_store::Error) -> ConnectorError {
+    ConnectorError::config(format!("Failed to configure object store: {}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DiscoveryConfig;
+    use std::collections::HashMap;
+
+    fn test_config() -> S3Config {
+        S3Config {
+            storage_backend: StorageBackend::S3,
+            bucket: "events".to_string(),
+            region: Some("us-east-1".to_string()),
+            endpoint: Some("http://localhost:9000".to_string()),
+            allow_http: true,
+            path_style: true,
+            azure_storage_account: None,
+            gcs_service_account_path: None,
+            storage_options: HashMap::new(),
+            discovery: DiscoveryConfig::default(),
+            state_path: None,
+            delete_processed: false,
+            include_metadata: true,
+            routes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_store() {
+        let mut config = test_config();
+        assert!(build_store(&config).is_ok());
+
+        config
+            .storage_options
+            .insert("aws_skip_signature".to_string(), "true".to_string());
+        assert!(build_store(&config).is_ok());
+
+        config
+            .storage_options
+            .insert("not_an_option".to_string(), "true".to_string());
+        assert!(build_store(&config).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Configuration module for SurrealDB Source Connector
+//!
+//! This module handles all configuration aspects including:
+//! - SurrealDB connection settings (URL, namespace, database, credentials)
+//! - Table-to-topic routes with live query filters
+//! - Environment variable overrides
+
+use danube_connect_core::{
+    ConfigEnvOverrides, ConfigValidate, ConnectorConfig, ConnectorConfigLoader, ConnectorError,
+    ConnectorResult,
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// Complete configuration for the SurrealDB Source Connector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurrealDBSourceConfig {
+    /// Core connector configuration (Danube connection, schemas, etc.)
+    #[serde(flatten)]
+    pub core: ConnectorConfig,
+
+    /// SurrealDB-specific configuration
+    pub surrealdb: SurrealDBConfig,
+}
+
+/// SurrealDB-specific configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurrealDBConfig {
+    /// SurrealDB WebSocket URL (e.g., "ws://localhost:8000")
+    /// Live queries require a WebSocket connection
+    pub url: String,
+
+    /// SurrealDB namespace
+    pub namespace: String,
+
+    /// SurrealDB database
+    pub database: String,
+
+    /// Optional username for authentication (root user)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Optional password for authentication (root user)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// Connection timeout in seconds
+    #[serde(default = "default_connection_timeout")]
+    pub connection_timeout_secs: u64,
+
+    /// Delay before a live query is restarted after its stream ends, in milliseconds
+    #[serde(default = "default_restart_backoff_ms")]
+    pub restart_backoff_ms: u64,
+
+    /// Add SurrealDB metadata (table, action, record ID) as message attributes
+    #[serde(default = "default_true")]
+    pub include_metadata: bool,
+
+    /// Routes: SurrealDB tables → Danube topics
+    #[serde(default)]
+    pub routes: Vec<TopicMapping>,
+}
+
+/// Change notification type of a live query
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "PascalCase")]
+pub enum LiveAction {
+    /// Record created
+    Create,
+    /// Record updated
+    Update,
+    /// Record deleted
+    Delete,
+}
+
+impl LiveAction {
+    /// Lowercase name used in message attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiveAction::Create => "create",
+            LiveAction::Update => "update",
+            LiveAction::Delete => "delete",
+        }
+    }
+}
+
+/// Mapping from a SurrealDB table to a Danube topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicMapping {
+    /// SurrealDB table to watch
+    pub from: String,
+
+    /// Optional live query condition (e.g., "status = 'paid'")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// Target Danube topic
+    pub to: String,
+
+    /// Notifications to publish (default: Create, Update, Delete)
+    #[serde(default = "default_actions")]
+    pub actions: Vec<LiveAction>,
+
+    /// Number of partitions for the Danube topic (0 = non-partitioned)
+    #[serde(default)]
+    pub partitions: usize,
+
+    /// Use reliable dispatch for this topic (WAL + Cloud persistence)
+    #[serde(default = "default_true")]
+    pub reliable_dispatch: bool,
+}
+
+impl TopicMapping {
+    /// Build the LIVE SELECT statement for this route
+    pub fn live_query(&self) -> String {
+        match &self.filter {
+            Some(filter) => format!("LIVE SELECT * FROM {} WHERE {}", self.from, filter),
+            None => format!("LIVE SELECT * FROM {}", self.from),
+        }
+    }
+}
+
+// Default value functions
+fn default_connection_timeout() -> u64 {
+    30
+}
+
+fn default_restart_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_actions() -> Vec<LiveAction> {
+    vec![LiveAction::Create, LiveAction::Update, LiveAction::Delete]
+}
+
+impl SurrealDBSourceConfig {
+    /// Load configuration from TOML file
+    ///
+    /// The config file path must be specified via CONNECTOR_CONFIG_PATH environment variable.
+    /// Environment variables can override secrets (username, password) and URLs.
+    pub fn load() -> ConnectorResult<Self> {
+        ConnectorConfigLoader::new().load()
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> ConnectorResult<()> {
+        self.validate_config()
+    }
+}
+
+impl ConfigEnvOverrides for SurrealDBSourceConfig {
+    fn apply_env_overrides(&mut self) -> ConnectorResult<()> {
+        if let Ok(danube_url) = env::var("DANUBE_SERVICE_URL") {
+            self.core.danube_service_url = danube_url;
+        }
+
+        if let Ok(connector_name) = env::var("CONNECTOR_NAME") {
+            self.core.connector_name = connector_name;
+        }
+
+        if let Ok(url) = env::var("SURREALDB_URL") {
+            self.surrealdb.url = url;
+        }
+
+        if let Ok(username) = env::var("SURREALDB_USERNAME") {
+            self.surrealdb.username = Some(username);
+        }
+        if let Ok(password) = env::var("SURREALDB_PASSWORD") {
+            self.surrealdb.password = Some(password);
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigValidate for SurrealDBSourceConfig {
+    fn validate_config(&self) -> ConnectorResult<()> {
+        if self.surrealdb.url.is_empty() {
+            return Err(ConnectorError::config("SURREALDB_URL cannot be empty"));
+        }
+        if !self.surrealdb.url.starts_with("ws://") && self.surrealdb.url.contains("://") {
+            return Err(ConnectorError::config(
+                "Live queries require a WebSocket URL (ws://)",
+            ));
+        }
+
+        if self.surrealdb.namespace.is_empty() {
+            return Err(ConnectorError::config(
+                "SURREALDB_NAMESPACE cannot be empty",
+            ));
+        }
+        if self.surrealdb.database.is_empty() {
+            return Err(ConnectorError::config("SURREALDB_DATABASE cannot be empty"));
+        }
+
+        if self.surrealdb.routes.is_empty() {
+            return Err(ConnectorError::config("At least one route is required"));
+        }
+
+        for mapping in &self.surrealdb.routes {
+            if !is_valid_identifier(&mapping.from) {
+                return Err(ConnectorError::config(format!(
+                    "Route 'from' must be a table name (letters, digits, '_'), got '{}'",
+                    mapping.from
+                )));
+            }
+            if mapping.to.is_empty() {
+                return Err(ConnectorError::config("Route 'to' cannot be empty"));
+            }
+            if mapping.actions.is_empty() {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': at least one action is required",
+                    mapping.from
+                )));
+            }
+            if let Some(filter) = &mapping.filter {
+                if filter.trim().is_empty() || filter.contains(';') {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': invalid filter '{}'",
+                        mapping.from, filter
+                    )));
+                }
+            }
+        }
+
+        for schema in &self.core.schemas {
+            let topic_exists = self
+                .surrealdb
+                .routes
+                .iter()
+                .any(|mapping| mapping.to == schema.topic);
+
+            if !topic_exists {
+                tracing::warn!(
+                    "Schema configured for topic '{}' but no route exists for it",
+                    schema.topic
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a name is a plain SurrealQL identifier (letters, digits, '_')
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> SurrealDBSourceConfig {
+        SurrealDBSourceConfig {
+            core: ConnectorConfig {
+                connector_name: "test".to_string(),
+                danube_service_url: "http://localhost:6650".to_string(),
+                retry: Default::default(),
+                processing: Default::default(),
+                schemas: Vec::new(),
+            },
+            surrealdb: SurrealDBConfig {
+                url: "ws://localhost:8000".to_string(),
+                namespace: "test".to_string(),
+                database: "test".to_string(),
+                username: None,
+                password: None,
+                connection_timeout_secs: 30,
+                restart_backoff_ms: 1000,
+                include_metadata: true,
+                routes: vec![TopicMapping {
+                    from: "orders".to_string(),
+                    filter: None,
+                    to: "/surrealdb/orders".to_string(),
+                    actions: default_actions(),
+                    partitions: 0,
+                    reliable_dispatch: true,
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = test_config();
+        assert!(config.validate().is_ok());
+
+        // Live queries need a WebSocket connection
+        config.surrealdb.url = "http://localhost:8000".to_string();
+        assert!(config.validate().is_err());
+        config.surrealdb.url = "ws://localhost:8000".to_string();
+
+        // Table names are interpolated into the live query
+        config.surrealdb.routes[0].from = "orders; REMOVE TABLE orders".to_string();
+        assert!(config.validate().is_err());
+        config.surrealdb.routes[0].from = "orders".to_string();
+
+        config.surrealdb.routes[0].actions.clear();
+        assert!(config.validate().is_err());
+
+        config.surrealdb.routes.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_live_query() {
+        let mut mapping = test_config().surrealdb.routes.remove(0);
+        assert_eq!(mapping.live_query(), "LIVE SELECT * FROM orders");
+
+        mapping.filter = Some("status = 'paid'".to_string());
+        assert_eq!(
+            mapping.live_query(),
+            "LIVE SELECT * FROM orders WHERE status = 'paid'"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! SurrealDB Live Query source connector implementation
+//!
+//! Each route runs a `LIVE SELECT` on its table and publishes the change
+//! notifications (create/update/delete) to a Danube topic. Live queries are
+//! restarted when their stream ends (e.g., after a dropped connection).
+
+use crate::config::{LiveAction, SurrealDBConfig, TopicMapping};
+use async_trait::async_trait;
+use danube_connect_core::{
+    ConnectorConfig, ConnectorError, ConnectorResult, Offset, ProducerConfig, SchemaMapping,
+    SourceConnector, SourceConnectorMode, SourceRecord, SourceSender,
+};
+use futures::StreamExt;
+use serde_json::Value;
+use std::time::Duration;
+use surrealdb::engine::remote::ws::{Client, Ws};
+use surrealdb::opt::auth::Root;
+use surrealdb::{Action, Notification, Surreal};
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info, warn};
+
+/// SurrealDB Live Query Source Connector
+///
+/// Streams table changes from SurrealDB to Danube topics.
+pub struct SurrealDBSourceConnector {
+    config: SurrealDBConfig,
+    schemas: Vec<SchemaMapping>,
+    client: Option<Surreal<Client>>,
+    live_query_aborts: Vec<AbortHandle>,
+}
+
+impl SurrealDBSourceConnector {
+    /// Create a new SurrealDB source connector with provided configuration
+    pub fn with_config(config: SurrealDBConfig, schemas: Vec<SchemaMapping>) -> Self {
+        Self {
+            config,
+            schemas,
+            client: None,
+            live_query_aborts: Vec::new(),
+        }
+    }
+
+    /// Spawn the live query task for a route
+    ///
+    /// The task restarts the live query whenever its stream ends, and stops
+    /// once the source runtime is gone.
+    fn spawn_live_query(
+        client: Surreal<Client>,
+        mapping: TopicMapping,
+        sender: SourceSender,
+        include_metadata: bool,
+        restart_backoff: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let query = mapping.live_query();
+
+            loop {
+                info!("Starting live query: {}", query);
+
+                let stream = match client.query(&query).await {
+                    Ok(mut response) => response.stream::<Notification<Value>>(0),
+                    Err(e) => Err(e),
+                };
+
+                match stream {
+                    Ok(mut stream) => {
+                        while let Some(notification) = stream.next().await {
+                            let notification = match notification {
+                                Ok(notification) => notification,
+                                Err(e) => {
+                                    error!("Live query on '{}' failed: {}", mapping.from, e);
+                                    break;
+                                }
+                            };
+
+                            let Some(action) = live_action(notification.action) else {
+                                debug!("Ignoring live query notification on '{}'", mapping.from);
+                                continue;
+                            };
+                            if !mapping.actions.contains(&action) {
+                                continue;
+                            }
+
+                            let record = Self::notification_to_record(
+                                &mapping,
+                                action,
+                                notification.data,
+                                include_metadata,
+                            );
+                            if let Err(e) = sender.send(record).await {
+                                error!("Failed to send message to source runtime: {}", e);
+                                return;
+                            }
+                        }
+                        warn!("Live query on '{}' ended", mapping.from);
+                    }
+                    Err(e) => error!("Failed to start live query on '{}': {}", mapping.from, e),
+                }
+
+                if sender.is_closed() {
+                    break;
+                }
+                tokio::time::sleep(restart_backoff).await;
+            }
+
+            info!("Live query on '{}' stopped", mapping.from);
+        })
+    }
+
+    /// Create a SourceRecord from a live query notification
+    ///
+    /// The payload is the record content; for deletes, the record as it was
+    /// before deletion. The record ID is used as routing key.
+    fn notification_to_record(
+        mapping: &TopicMapping,
+        action: LiveAction,
+        data: Value,
+        include_metadata: bool,
+    ) -> SourceRecord {
+        let record_id = record_key(&data);
+        let mut record = SourceRecord::new(&mapping.to, data);
+
+        if include_metadata {
+            record = record
+                .with_attribute("surrealdb.table", &mapping.from)
+                .with_attribute("surrealdb.action", action.as_str())
+                .with_attribute("source", "surrealdb");
+        }
+
+        if let Some(record_id) = record_id {
+            if include_metadata {
+                record = record.with_attribute("record_id", &record_id);
+            }
+            record = record.with_key(&record_id);
+        }
+
+        record
+    }
+
+    /// Find schema configuration for a Danube topic
+    fn find_schema_config(&self, danube_topic: &str) -> Option<danube_connect_core::SchemaConfig> {
+        self.schemas
+            .iter()
+            .find(|s| s.topic == danube_topic)
+            .map(|schema| danube_connect_core::SchemaConfig {
+                subject: schema.subject.clone(),
+                schema_type: schema.schema_type.clone(),
+                schema_file: schema.schema_file.clone(),
+                auto_register: schema.auto_register,
+                version_strategy: schema.version_strategy.clone(),
+            })
+    }
+}
+
+/// Map a SurrealDB notification action to a route action
+fn live_action(action: Action) -> Option<LiveAction> {
+    match action {
+        Action::Create => Some(LiveAction::Create),
+        Action::Update => Some(LiveAction::Update),
+        Action::Delete => Some(LiveAction::Delete),
+        _ => None,
+    }
+}
+
+/// Read the record ID (e.g., "orders:abc") from a notification payload
+fn record_key(data: &Value) -> Option<String> {
+    match data.get("id")? {
+        Value::String(id) => Some(id.clone()),
+        Value::Null => None,
+        id => Some(id.to_string()),
+    }
+}
+
+/// Open a SurrealDB connection: connect, sign in, and select namespace/database
+async fn connect(config: &SurrealDBConfig) -> ConnectorResult<Surreal<Client>> {
+    let address = config.url.strip_prefix("ws://").unwrap_or(&config.url);
+    let client = tokio::time::timeout(
+        Duration::from_secs(config.connection_timeout_secs),
+        Surreal::new::<Ws>(address),
+    )
+    .await
+    .map_err(|_| {
+        ConnectorError::retryable(format!(
+            "Timed out connecting to SurrealDB after {}s",
+            config.connection_timeout_secs
+        ))
+    })?
+    .map_err(|e| ConnectorError::retryable(format!("Failed to connect to SurrealDB: {}", e)))?;
+
+    // Authenticate if credentials provided
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        client
+            .signin(Root { username, password })
+            .await
+            .map_err(|e| {
+                ConnectorError::fatal(format!("SurrealDB authentication failed: {}", e))
+            })?;
+        info!("Authenticated with SurrealDB as user '{}'", username);
+    }
+
+    client
+        .use_ns(&config.namespace)
+        .use_db(&config.database)
+        .await
+        .map_err(|e| {
+            ConnectorError::retryable(format!(
+                "Failed to use namespace '{}' and database '{}': {}",
+                config.namespace, config.database, e
+            ))
+        })?;
+
+    Ok(client)
+}
+
+#[async_trait]
+impl SourceConnector for SurrealDBSourceConnector {
+    async fn initialize(&mut self, _config: ConnectorConfig) -> ConnectorResult<()> {
+        info!("Initializing SurrealDB Source Connector");
+        info!("Connecting to SurrealDB at: {}", self.config.url);
+
+        let client = connect(&self.config).await?;
+        info!(
+            "Using namespace '{}' and database '{}'",
+            self.config.namespace, self.config.database
+        );
+
+        for mapping in &self.config.routes {
+            info!(
+                "Route: table '{}' → topic '{}' (actions: {:?}, partitions: {})",
+                mapping.from, mapping.to, mapping.actions, mapping.partitions
+            );
+        }
+
+        self.client = Some(client);
+
+        info!("SurrealDB Source Connector initialized successfully");
+        Ok(())
+    }
+
+    fn mode(&self) -> SourceConnectorMode {
+        SourceConnectorMode::Streaming
+    }
+
+    async fn start_streaming(&mut self, sender: SourceSender) -> ConnectorResult<()> {
+        if !self.live_query_aborts.is_empty() {
+            return Err(ConnectorError::config(
+                "SurrealDB source streaming has already been started",
+            ));
+        }
+
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("SurrealDB client not initialized"))?;
+
+        for mapping in &self.config.routes {
+            let handle = Self::spawn_live_query(
+                client.clone(),
+                mapping.clone(),
+                sender.clone(),
+                self.config.include_metadata,
+                Duration::from_millis(self.config.restart_backoff_ms),
+            );
+            self.live_query_aborts.push(handle.abort_handle());
+        }
+
+        info!(
+            "SurrealDB Source Connector streaming started ({} live queries)",
+            self.live_query_aborts.len()
+        );
+        Ok(())
+    }
+
+    async fn producer_configs(&self) -> ConnectorResult<Vec<ProducerConfig>> {
+        let producer_configs: Vec<_> = self
+            .config
+            .routes
+            .iter()
+            .map(|mapping| ProducerConfig {
+                topic: mapping.to.clone(),
+                partitions: mapping.partitions,
+                reliable_dispatch: mapping.reliable_dispatch,
+                schema_config: self.find_schema_config(&mapping.to),
+            })
+            .collect();
+
+        if producer_configs.is_empty() {
+            return Err(ConnectorError::config(
+                "No routes configured. Please add routes in the configuration.",
+            ));
+        }
+
+        Ok(producer_configs)
+    }
+
+    async fn commit(&mut self, offsets: Vec<Offset>) -> ConnectorResult<()> {
+        // Live queries have no resumable position, nothing to acknowledge
+        debug!("Committed {} offsets", offsets.len());
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> ConnectorResult<()> {
+        info!("Shutting down SurrealDB Source Connector");
+
+        // Dropping the live query streams kills the queries on the server
+        for abort_handle in self.live_query_aborts.drain(..) {
+            abort_handle.abort();
+        }
+        self.client = None;
+
+        info!("SurrealDB Source Connector stopped");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> ConnectorResult<()> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| ConnectorError::fatal("SurrealDB client not initialized"))?;
+
+        client.health().await.map_err(|e| {
+            ConnectorError::retryable(format!("SurrealDB connection check failed: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_live_action() {
+        assert_eq!(live_action(Action::Create), Some(LiveAction::Create));
+        assert_eq!(live_action(Action::Update), Some(LiveAction::Update));
+        assert_eq!(live_action(Action::Delete), Some(LiveAction::Delete));
+    }
+
+    #[test]
+    fn test_record_key() {
+        assert_eq!(
+            record_key(&json!({"id": "orders:abc", "total": 10})),
+            Some("orders:abc".to_string())
+        );
+        assert_eq!(
+            record_key(&json!({"id": {"tb": "orders", "id": 42}})),
+            Some(r#"{"id":42,"tb":"orders"}"#.to_string())
+        );
+        assert_eq!(record_key(&json!({"total": 10})), None);
+    }
+
+    #[test]
+    fn test_connector_creation() {
+        let config = SurrealDBConfig {
+            url: "ws://localhost:8000".to_string(),
+            namespace: "test".to_string(),
+            database: "test".to_string(),
+            username: None,
+            password: None,
+            connection_timeout_secs: 30,
+            restart_backoff_ms: 1000,
+            include_metadata: true,
+            routes: vec![],
+        };
+
+        let connector = SurrealDBSourceConnector::with_config(config, vec![]);
+        assert!(connector.client.is_none());
+        assert!(connector.live_query_aborts.is_empty());
+    }
+}
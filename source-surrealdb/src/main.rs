@@ -0,0 +1,69 @@
+//! SurrealDB Live Query Source Connector for Danube Connect
+//!
+//! This connector subscribes to SurrealDB `LIVE SELECT` queries and publishes the
+//! change notifications (create/update/delete) to Danube topics.
+//! The reverse direction of the SurrealDB sink: stream database changes to consumers.
+
+mod config;
+mod connector;
+
+use config::SurrealDBSourceConfig;
+use connector::SurrealDBSourceConnector;
+use danube_connect_core::{ConnectorResult, SourceRuntime};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> ConnectorResult<()> {
+    // Initialize logging first
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        tracing_subscriber::EnvFilter::new("info,danube_source_surrealdb=debug")
+    });
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_target(true))
+        .try_init()
+        .ok(); // Ignore error if already initialized
+
+    tracing::info!("Starting SurrealDB Source Connector");
+    tracing::info!("Version: {}", env!("CARGO_PKG_VERSION"));
+
+    // Load unified configuration from single file (TOML + ENV overrides)
+    let config = SurrealDBSourceConfig::load().map_err(|e| {
+        tracing::error!("Failed to load configuration: {}", e);
+        e
+    })?;
+
+    // Validate configuration
+    config.validate()?;
+
+    tracing::info!("Configuration loaded and validated successfully");
+    tracing::info!("Connector: {}", config.core.connector_name);
+    tracing::info!("Danube URL: {}", config.core.danube_service_url);
+    tracing::info!("SurrealDB URL: {}", config.surrealdb.url);
+    tracing::info!("SurrealDB Namespace: {}", config.surrealdb.namespace);
+    tracing::info!("SurrealDB Database: {}", config.surrealdb.database);
+    tracing::info!("Routes: {} configured", config.surrealdb.routes.len());
+
+    for (idx, mapping) in config.surrealdb.routes.iter().enumerate() {
+        tracing::info!(
+            "  Route {}: Table '{}' → Topic '{}'",
+            idx + 1,
+            mapping.from,
+            mapping.to
+        );
+    }
+
+    // Create connector instance with SurrealDB configuration and schemas
+    let connector =
+        SurrealDBSourceConnector::with_config(config.surrealdb, config.core.schemas.clone());
+
+    // Create and run the source runtime
+    let mut runtime = SourceRuntime::new(connector, config.core).await?;
+
+    // Run until shutdown signal
+    runtime.run().await?;
+
+    tracing::info!("SurrealDB Source Connector stopped");
+    Ok(())
+}
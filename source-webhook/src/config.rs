@@ -8,6 +8,7 @@ use danube_connect_core::{
     ConnectorResult,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 
 /// Root configuration for the webhook source connector
@@ -23,8 +24,24 @@ pub struct WebhookSourceConfig {
     /// Optional platform-wide rate limiting
     #[serde(default)]
     pub rate_limit: Option<RateLimitConfig>,
+    /// Optional replay protection (applies to all endpoints)
+    #[serde(default)]
+    pub replay_protection: Option<ReplayProtectionConfig>,
+    /// Optional write-ahead spool, so accepted webhooks survive restarts
+    #[serde(default)]
+    pub spool: Option<SpoolConfig>,
+    /// Optional audit log of requests (to a file and/or a Danube topic)
+    #[serde(default)]
+    pub audit: Option<AuditConfig>,
     /// Route definitions (multiple endpoints for different event types)
+    #[serde(default)]
     pub routes: Vec<EndpointConfig>,
+    /// REST APIs polled for new items
+    #[serde(default)]
+    pub polls: Vec<PollConfig>,
+    /// Server-Sent Events streams to consume
+    #[serde(default)]
+    pub sse: Vec<SseConfig>,
 }
 
 /// HTTP server configuration
@@ -40,12 +57,31 @@ pub struct ServerConfig {
     pub tls_cert_path: Option<String>,
     /// Optional TLS key path
     pub tls_key_path: Option<String>,
+    /// Check the TLS files for changes every N seconds and reload them (default: 0 = disabled)
+    #[serde(default)]
+    pub tls_reload_interval_secs: u64,
     /// Request timeout in seconds (default: 30)
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
     /// Maximum request body size in bytes (default: 1MB)
     #[serde(default = "default_max_body_size")]
     pub max_body_size: usize,
+    /// Client networks allowed to call any endpoint, in CIDR notation (default: all)
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
+    /// Take the client IP from X-Forwarded-For / X-Real-IP instead of the connection
+    /// (only behind a proxy that overwrites them; default: false)
+    #[serde(default)]
+    pub trust_proxy_headers: bool,
+    /// Maximum number of async deliveries whose status is kept (default: 100000)
+    #[serde(default = "default_max_tracked_deliveries")]
+    pub max_tracked_deliveries: usize,
+    /// How long async delivery statuses are kept in seconds (default: 3600)
+    #[serde(default = "default_delivery_status_ttl")]
+    pub delivery_status_ttl_secs: u64,
+    /// Deadline in seconds for draining in-flight requests and the spool on shutdown (default: 30)
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout_secs: u64,
 }
 
 fn default_host() -> String {
@@ -64,6 +100,18 @@ fn default_max_body_size() -> usize {
     1024 * 1024 // 1MB
 }
 
+fn default_max_tracked_deliveries() -> usize {
+    100_000
+}
+
+fn default_delivery_status_ttl() -> u64 {
+    3600
+}
+
+fn default_shutdown_timeout() -> u64 {
+    30
+}
+
 /// Authentication configuration (platform-wide)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AuthConfig {
@@ -76,8 +124,18 @@ pub struct AuthConfig {
     pub header: Option<String>,
     /// Algorithm for HMAC (sha256, sha512)
     pub algorithm: Option<String>,
-    /// Public key path for JWT verification
+    /// Public key path for JWT verification (PEM, RS256 or ES256)
     pub public_key_path: Option<String>,
+    /// JWKS URL to fetch JWT verification keys from (RS256 or ES256, selected by `kid`)
+    pub jwks_url: Option<String>,
+    /// Seconds after which the JWKS is fetched again (default: 3600)
+    pub jwks_refresh_secs: Option<u64>,
+    /// Required JWT issuer (`iss` claim)
+    pub issuer: Option<String>,
+    /// Required JWT audience (`aud` claim)
+    pub audience: Option<String>,
+    /// Maximum age of signed timestamps in seconds (Stripe, Slack; default: 300, 0 = unchecked)
+    pub tolerance_secs: Option<u64>,
 }
 
 /// Authentication type
@@ -92,6 +150,19 @@ pub enum AuthType {
     Hmac,
     /// JWT token verification
     Jwt,
+    /// GitHub `X-Hub-Signature-256` signature verification
+    Github,
+    /// Stripe `Stripe-Signature` signature verification with timestamp tolerance
+    Stripe,
+    /// Slack `X-Slack-Signature` (v0) signature verification with timestamp tolerance
+    Slack,
+}
+
+impl AuthType {
+    /// Whether the scheme signs the raw request body (which must be buffered to verify it)
+    pub fn signs_body(&self) -> bool {
+        matches!(self, AuthType::Github | AuthType::Stripe | AuthType::Slack)
+    }
 }
 
 /// Rate limiting configuration
@@ -106,6 +177,249 @@ pub struct RateLimitConfig {
     pub per_ip_enabled: bool,
     /// Per-IP requests per second (if per_ip_enabled)
     pub per_ip_requests_per_second: Option<u32>,
+    /// Maximum number of IPs tracked for per-IP limiting; the least recently seen are
+    /// forgotten beyond it (default: 10000)
+    #[serde(default = "default_max_tracked_ips")]
+    pub max_tracked_ips: usize,
+}
+
+fn default_max_tracked_ips() -> usize {
+    10_000
+}
+
+/// Replay protection configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReplayProtectionConfig {
+    /// Header with the request timestamp, Unix seconds or RFC 3339 (default: x-webhook-timestamp)
+    #[serde(default = "default_timestamp_header")]
+    pub timestamp_header: String,
+    /// Maximum distance of the timestamp from the connector's clock in seconds (default: 300)
+    #[serde(default = "default_replay_tolerance")]
+    pub tolerance_secs: u64,
+    /// Header carrying a unique request ID (default: requests are identified by timestamp and body)
+    pub nonce_header: Option<String>,
+    /// Maximum number of requests remembered within the window (default: 100000)
+    #[serde(default = "default_max_nonces")]
+    pub max_nonces: usize,
+}
+
+fn default_timestamp_header() -> String {
+    "x-webhook-timestamp".to_string()
+}
+
+fn default_replay_tolerance() -> u64 {
+    300
+}
+
+fn default_max_nonces() -> usize {
+    100_000
+}
+
+/// Request audit log configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditConfig {
+    /// File to append entries to, one JSON object per line
+    pub file: Option<String>,
+    /// Danube topic to publish entries to
+    pub topic: Option<String>,
+    /// Share of requests (0.0 to 1.0) whose payload snippet is recorded (default: 0)
+    #[serde(default)]
+    pub payload_sample_rate: f64,
+    /// Maximum bytes of a payload snippet (default: 512)
+    #[serde(default = "default_audit_payload_max_bytes")]
+    pub payload_max_bytes: usize,
+    /// Entries waiting to be written before new ones are dropped (default: 10000)
+    #[serde(default = "default_audit_queue_size")]
+    pub queue_size: usize,
+}
+
+fn default_audit_payload_max_bytes() -> usize {
+    512
+}
+
+fn default_audit_queue_size() -> usize {
+    10_000
+}
+
+/// Write-ahead spool configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpoolConfig {
+    /// Spool file path (e.g., "/var/lib/danube-webhook/spool.log")
+    pub path: String,
+    /// Flush each accepted webhook to disk before responding (default: true)
+    #[serde(default = "default_spool_sync")]
+    pub sync: bool,
+    /// Maximum webhooks waiting to be forwarded; further requests get 503 (default: 100000)
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+}
+
+fn default_spool_sync() -> bool {
+    true
+}
+
+fn default_max_pending() -> usize {
+    100_000
+}
+
+/// A REST API polled for new items
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PollConfig {
+    /// Name of the poll (logs, record attributes)
+    pub name: String,
+    /// URL to GET
+    pub url: String,
+    /// Danube topic to publish items to
+    pub to: String,
+    /// Seconds between polls (default: 60)
+    #[serde(default = "default_poll_interval")]
+    pub interval_secs: u64,
+    /// Extra request headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Environment variable holding a bearer token for the Authorization header
+    pub bearer_token_env: Option<String>,
+    /// Dot path to the items array in the response (default: the response is the array)
+    pub items_field: Option<String>,
+    /// Dot path to the next page's URL, or its cursor with `page_param` (default: Link header)
+    pub next_page_field: Option<String>,
+    /// Query parameter the next page's cursor is sent as
+    pub page_param: Option<String>,
+    /// Maximum pages fetched per poll (default: 10)
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+    /// Dot path to an increasing item field (e.g., "updated_at"); items up to the last seen
+    /// value are skipped
+    pub cursor_field: Option<String>,
+    /// Query parameter the last seen cursor value is sent as (e.g., "since")
+    pub cursor_param: Option<String>,
+    /// File keeping the cursor and cache validators across restarts
+    pub state_path: Option<String>,
+    /// Number of partitions for the topic (0 or omitted = non-partitioned)
+    #[serde(default)]
+    pub partitions: u32,
+    /// Reliable dispatch for this topic (default: false)
+    #[serde(default)]
+    pub reliable_dispatch: bool,
+}
+
+fn default_poll_interval() -> u64 {
+    60
+}
+
+fn default_max_pages() -> u32 {
+    10
+}
+
+/// A Server-Sent Events stream to consume
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SseConfig {
+    /// Name of the stream (logs, record attributes)
+    pub name: String,
+    /// URL of the event stream
+    pub url: String,
+    /// Danube topic to publish events to
+    pub to: String,
+    /// Event types to publish (default: all)
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Extra request headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Environment variable holding a bearer token for the Authorization header
+    pub bearer_token_env: Option<String>,
+    /// Initial reconnection delay in seconds, unless the server sends `retry` (default: 1)
+    #[serde(default = "default_reconnect_secs")]
+    pub reconnect_secs: u64,
+    /// Maximum reconnection delay in seconds after repeated failures (default: 60)
+    #[serde(default = "default_max_reconnect_secs")]
+    pub max_reconnect_secs: u64,
+    /// Reconnect when nothing (not even a keep-alive comment) is received for this many
+    /// seconds (default: 300)
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+    /// File keeping the last event ID across restarts, to resume with `Last-Event-ID`
+    pub state_path: Option<String>,
+    /// Number of partitions for the topic (0 or omitted = non-partitioned)
+    #[serde(default)]
+    pub partitions: u32,
+    /// Reliable dispatch for this topic (default: false)
+    #[serde(default)]
+    pub reliable_dispatch: bool,
+}
+
+fn default_reconnect_secs() -> u64 {
+    1
+}
+
+fn default_max_reconnect_secs() -> u64 {
+    60
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    300
+}
+
+/// JSON Schema validation of an endpoint's payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaValidation {
+    /// Inline JSON Schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<serde_json::Value>,
+
+    /// Path to a JSON Schema file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_file: Option<String>,
+
+    /// Danube topic for a copy of invalid payloads, with the validation errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_topic: Option<String>,
+}
+
+/// Capture of URL query parameters as `webhook.query.<name>` attributes
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct QueryParamsConfig {
+    /// Parameters to capture (empty or omitted = all)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Also add the captured parameters to JSON object payloads under this field
+    pub payload_field: Option<String>,
+}
+
+impl QueryParamsConfig {
+    /// Captured parameters of a query (repeated parameters are joined with ',')
+    pub fn capture(&self, query: &[(String, String)]) -> BTreeMap<String, String> {
+        let mut params = BTreeMap::new();
+        for (name, value) in query {
+            if !self.include.is_empty() && !self.include.contains(name) {
+                continue;
+            }
+            params
+                .entry(name.clone())
+                .and_modify(|values: &mut String| {
+                    values.push(',');
+                    values.push_str(value);
+                })
+                .or_insert_with(|| value.clone());
+        }
+        params
+    }
+}
+
+/// Custom response to an endpoint's accepted webhooks
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseConfig {
+    /// Status code, 2xx (default: 200, or 202 with accept_async)
+    pub status: Option<u16>,
+    /// Body template with `{{...}}` placeholders (default: no body)
+    pub body: Option<String>,
+    /// Content type of the body (default: application/json)
+    #[serde(default = "default_response_content_type")]
+    pub content_type: String,
+}
+
+fn default_response_content_type() -> String {
+    "application/json".to_string()
 }
 
 /// Endpoint configuration
@@ -113,8 +427,15 @@ pub struct RateLimitConfig {
 pub struct EndpointConfig {
     /// HTTP path for this endpoint (e.g., "/webhooks/payments")
     pub from: String,
-    /// Danube topic to publish to
+    /// Danube topic to publish to (the default when `topic_template` is set)
     pub to: String,
+    /// Payload field selecting the topic, as a dot path (e.g., "event.type" or "$.event.type")
+    pub route_field: Option<String>,
+    /// Topic for payloads with `route_field`, with a `{value}` placeholder (e.g., "/events/{value}")
+    pub topic_template: Option<String>,
+    /// `route_field` values routed through `topic_template`; other values go to `to`
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
     /// Number of partitions for the topic (0 or omitted = non-partitioned)
     #[serde(default)]
     pub partitions: u32,
@@ -123,6 +444,82 @@ pub struct EndpointConfig {
     pub reliable_dispatch: bool,
     /// Optional per-endpoint rate limiting (overrides platform-wide)
     pub rate_limit: Option<RateLimitConfig>,
+    /// Optional client networks allowed to call this endpoint (overrides server-wide)
+    pub allowed_cidrs: Option<Vec<String>>,
+    /// Answer 202 with a delivery ID before publishing, trackable at /status/{id} (default: false)
+    #[serde(default)]
+    pub accept_async: bool,
+    /// Optional JSON Schema check of the body; invalid requests get 400
+    pub validation: Option<SchemaValidation>,
+    /// Optional capture of URL query parameters (e.g., "?tenant=acme")
+    pub query_params: Option<QueryParamsConfig>,
+    /// Maximum request body size in bytes (overrides the server's max_body_size)
+    pub max_body_size: Option<usize>,
+    /// Request timeout in seconds (overrides the server's timeout_seconds)
+    pub timeout_seconds: Option<u64>,
+    /// Optional custom response to accepted webhooks
+    pub response: Option<ResponseConfig>,
+    /// Also accept WebSocket connections streaming events on this path (default: false)
+    #[serde(default)]
+    pub websocket: bool,
+}
+
+impl EndpointConfig {
+    /// Danube topic of a payload: the rendered `topic_template` if the payload's
+    /// `route_field` (strings, numbers or booleans) is in `allowed_values`,
+    /// otherwise `to`
+    pub fn topic_for(&self, payload: &serde_json::Value) -> String {
+        let (Some(field), Some(template)) = (&self.route_field, &self.topic_template) else {
+            return self.to.clone();
+        };
+
+        let path = field.strip_prefix("$.").unwrap_or(field);
+        let value = path
+            .split('.')
+            .try_fold(payload, |value, key| value.get(key));
+        let value = match value {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => {
+                value.to_string()
+            }
+            _ => return self.to.clone(),
+        };
+        if !self.allowed_values.contains(&value) {
+            return self.to.clone();
+        }
+
+        // Keep the value within one topic segment
+        let value: String = value
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        template.replace("{value}", &value)
+    }
+}
+
+/// Longest `allowed_values` entry, keeping rendered topic names short
+const MAX_ROUTE_VALUE_LEN: usize = 64;
+
+/// Check a topic template: "/{namespace}/{topic}" with the `{value}` placeholder
+/// in the topic name, so payloads can't pick the namespace
+fn is_valid_template(template: &str) -> bool {
+    let Some((namespace, topic)) = template
+        .strip_prefix('/')
+        .and_then(|path| path.split_once('/'))
+    else {
+        return false;
+    };
+    !namespace.is_empty()
+        && !namespace.contains(['/', '{', '}'])
+        && !topic.contains('/')
+        && topic.contains("{value}")
+        && !topic.replace("{value}", "").contains(['{', '}'])
 }
 
 impl WebhookSourceConfig {
@@ -171,10 +568,26 @@ impl WebhookSourceConfig {
                     ));
                 }
             }
+            AuthType::Github | AuthType::Stripe | AuthType::Slack => {
+                if self.auth.secret_env.is_none() {
+                    return Err(ConnectorError::config(format!(
+                        "secret_env is required for {:?} signature verification",
+                        self.auth.auth_type
+                    )));
+                }
+            }
             AuthType::Jwt => {
-                if self.auth.secret_env.is_none() && self.auth.public_key_path.is_none() {
+                if self.auth.secret_env.is_none()
+                    && self.auth.public_key_path.is_none()
+                    && self.auth.jwks_url.is_none()
+                {
                     return Err(ConnectorError::config(
-                        "Either secret_env or public_key_path is required for JWT authentication",
+                        "JWT authentication requires secret_env, public_key_path or jwks_url",
+                    ));
+                }
+                if self.auth.jwks_refresh_secs == Some(0) {
+                    return Err(ConnectorError::config(
+                        "jwks_refresh_secs must be greater than 0",
                     ));
                 }
             }
@@ -187,6 +600,28 @@ impl WebhookSourceConfig {
     pub fn bind_address(&self) -> String {
         format!("{}:{}", self.server.host, self.server.port)
     }
+
+    /// Maximum body size of an endpoint (its own, or the server's)
+    pub fn max_body_size_for(&self, endpoint_path: &str) -> usize {
+        self.endpoint(endpoint_path)
+            .and_then(|endpoint| endpoint.max_body_size)
+            .unwrap_or(self.server.max_body_size)
+    }
+
+    /// Request timeout of an endpoint (its own, or the server's)
+    pub fn timeout_for(&self, endpoint_path: &str) -> std::time::Duration {
+        let seconds = self
+            .endpoint(endpoint_path)
+            .and_then(|endpoint| endpoint.timeout_seconds)
+            .unwrap_or(self.server.timeout_seconds);
+        std::time::Duration::from_secs(seconds)
+    }
+
+    fn endpoint(&self, endpoint_path: &str) -> Option<&EndpointConfig> {
+        self.routes
+            .iter()
+            .find(|endpoint| endpoint.from == endpoint_path)
+    }
 }
 
 impl ConfigEnvOverrides for WebhookSourceConfig {
@@ -228,12 +663,135 @@ impl ConfigValidate for WebhookSourceConfig {
             return Err(ConnectorError::config("connector_name cannot be empty"));
         }
 
-        if self.routes.is_empty() {
+        if self.server.tls_cert_path.is_some() != self.server.tls_key_path.is_some() {
+            return Err(ConnectorError::config(
+                "tls_cert_path and tls_key_path must be set together",
+            ));
+        }
+
+        if let Some(spool) = &self.spool {
+            if spool.path.is_empty() || spool.max_pending == 0 {
+                return Err(ConnectorError::config(
+                    "spool.path cannot be empty and spool.max_pending must be greater than 0",
+                ));
+            }
+        }
+
+        if let Some(replay) = &self.replay_protection {
+            if replay.tolerance_secs == 0 {
+                return Err(ConnectorError::config(
+                    "replay_protection.tolerance_secs must be greater than 0",
+                ));
+            }
+            if replay.max_nonces == 0 {
+                return Err(ConnectorError::config(
+                    "replay_protection.max_nonces must be greater than 0",
+                ));
+            }
+        }
+
+        crate::ip_filter::IpAllowlists::from_config(self).map_err(ConnectorError::config)?;
+
+        if self.routes.is_empty() && self.polls.is_empty() && self.sse.is_empty() {
             return Err(ConnectorError::config(
-                "At least one route must be configured",
+                "At least one route, poll or SSE stream must be configured",
             ));
         }
 
+        if let Some(audit) = &self.audit {
+            if audit.file.is_none() && audit.topic.is_none() {
+                return Err(ConnectorError::config(
+                    "Audit log requires a file and/or a topic",
+                ));
+            }
+            if audit
+                .topic
+                .as_ref()
+                .is_some_and(|topic| !topic.starts_with('/'))
+            {
+                return Err(ConnectorError::config(
+                    "Audit topic must be a Danube topic starting with '/'",
+                ));
+            }
+            if !(0.0..=1.0).contains(&audit.payload_sample_rate) || audit.queue_size == 0 {
+                return Err(ConnectorError::config(
+                    "Audit payload_sample_rate must be within 0-1 and queue_size greater than 0",
+                ));
+            }
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for poll in &self.polls {
+            if poll.name.is_empty() || !names.insert(&poll.name) {
+                return Err(ConnectorError::config(format!(
+                    "Poll names must be unique and non-empty: '{}'",
+                    poll.name
+                )));
+            }
+            if reqwest::Url::parse(&poll.url).is_err() {
+                return Err(ConnectorError::config(format!(
+                    "Poll '{}': invalid url '{}'",
+                    poll.name, poll.url
+                )));
+            }
+            if !poll.to.starts_with('/') {
+                return Err(ConnectorError::config(format!(
+                    "Poll '{}': 'to' must be a Danube topic starting with '/'",
+                    poll.name
+                )));
+            }
+            if poll.interval_secs == 0 || poll.max_pages == 0 {
+                return Err(ConnectorError::config(format!(
+                    "Poll '{}': interval_secs and max_pages must be greater than 0",
+                    poll.name
+                )));
+            }
+            if poll.page_param.is_some() && poll.next_page_field.is_none() {
+                return Err(ConnectorError::config(format!(
+                    "Poll '{}': page_param requires next_page_field",
+                    poll.name
+                )));
+            }
+            if poll.cursor_param.is_some() && poll.cursor_field.is_none() {
+                return Err(ConnectorError::config(format!(
+                    "Poll '{}': cursor_param requires cursor_field",
+                    poll.name
+                )));
+            }
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for stream in &self.sse {
+            if stream.name.is_empty() || !names.insert(&stream.name) {
+                return Err(ConnectorError::config(format!(
+                    "SSE stream names must be unique and non-empty: '{}'",
+                    stream.name
+                )));
+            }
+            if reqwest::Url::parse(&stream.url).is_err() {
+                return Err(ConnectorError::config(format!(
+                    "SSE stream '{}': invalid url '{}'",
+                    stream.name, stream.url
+                )));
+            }
+            if !stream.to.starts_with('/') {
+                return Err(ConnectorError::config(format!(
+                    "SSE stream '{}': 'to' must be a Danube topic starting with '/'",
+                    stream.name
+                )));
+            }
+            if stream.reconnect_secs == 0
+                || stream.idle_timeout_secs == 0
+                || stream.max_reconnect_secs < stream.reconnect_secs
+            {
+                return Err(ConnectorError::config(format!(
+                    "SSE stream '{}': reconnect_secs and idle_timeout_secs must be greater than 0, \
+                     and max_reconnect_secs at least reconnect_secs",
+                    stream.name
+                )));
+            }
+        }
+
         let mut paths = std::collections::HashSet::new();
         for endpoint in &self.routes {
             if !paths.insert(&endpoint.from) {
@@ -256,6 +814,111 @@ impl ConfigValidate for WebhookSourceConfig {
                     endpoint.from
                 )));
             }
+
+            match (&endpoint.route_field, &endpoint.topic_template) {
+                (Some(_), Some(template)) if !is_valid_template(template) => {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': topic_template '{}' must be /{{namespace}}/{{topic}} with a {{value}} placeholder",
+                        endpoint.from, template
+                    )));
+                }
+                (Some(_), Some(_)) if endpoint.allowed_values.is_empty() => {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': allowed_values is required with topic_template",
+                        endpoint.from
+                    )));
+                }
+                (Some(_), None) | (None, Some(_)) => {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': route_field and topic_template must be set together",
+                        endpoint.from
+                    )));
+                }
+                _ => {}
+            }
+
+            if let Some(value) = endpoint
+                .allowed_values
+                .iter()
+                .find(|value| value.is_empty() || value.len() > MAX_ROUTE_VALUE_LEN)
+            {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': allowed_values entry '{}' must be 1 to {} characters",
+                    endpoint.from, value, MAX_ROUTE_VALUE_LEN
+                )));
+            }
+
+            if let Some(validation) = &endpoint.validation {
+                if validation.schema.is_some() == validation.schema_file.is_some() {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': validation requires either 'schema' or 'schema_file'",
+                        endpoint.from
+                    )));
+                }
+                if validation
+                    .dead_letter_topic
+                    .as_ref()
+                    .is_some_and(|topic| !topic.starts_with('/') || topic == &endpoint.to)
+                {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': dead_letter_topic must be a Danube topic other than 'to'",
+                        endpoint.from
+                    )));
+                }
+            }
+
+            if endpoint.max_body_size == Some(0) || endpoint.timeout_seconds == Some(0) {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': max_body_size and timeout_seconds must be greater than 0",
+                    endpoint.from
+                )));
+            }
+
+            if endpoint.websocket && self.auth.auth_type.signs_body() {
+                return Err(ConnectorError::config(format!(
+                    "Route '{}': WebSocket routes need header-based auth (none, apikey or jwt)",
+                    endpoint.from
+                )));
+            }
+
+            if let Some(response) = &endpoint.response {
+                if response
+                    .status
+                    .is_some_and(|status| !(200..300).contains(&status))
+                {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': response status must be 2xx",
+                        endpoint.from
+                    )));
+                }
+                if let Some(body) = &response.body {
+                    if response.status == Some(204) {
+                        return Err(ConnectorError::config(format!(
+                            "Route '{}': a 204 response cannot have a body",
+                            endpoint.from
+                        )));
+                    }
+                    crate::response::validate_template(body).map_err(|e| {
+                        ConnectorError::config(format!(
+                            "Route '{}': invalid response body template: {}",
+                            endpoint.from, e
+                        ))
+                    })?;
+                }
+            }
+
+            if let Some(query_params) = &endpoint.query_params {
+                if query_params
+                    .payload_field
+                    .as_ref()
+                    .is_some_and(|field| field.is_empty())
+                {
+                    return Err(ConnectorError::config(format!(
+                        "Route '{}': query_params payload_field cannot be empty",
+                        endpoint.from
+                    )));
+                }
+            }
         }
 
         self.validate_auth()
@@ -273,13 +936,20 @@ mod tests {
             port: default_port(),
             tls_cert_path: None,
             tls_key_path: None,
+            tls_reload_interval_secs: 0,
             timeout_seconds: default_timeout(),
             max_body_size: default_max_body_size(),
+            allowed_cidrs: Vec::new(),
+            trust_proxy_headers: false,
+            max_tracked_deliveries: default_max_tracked_deliveries(),
+            delivery_status_ttl_secs: default_delivery_status_ttl(),
+            shutdown_timeout_secs: default_shutdown_timeout(),
         };
         assert_eq!(server.host, "0.0.0.0");
         assert_eq!(server.port, 8080);
         assert_eq!(server.timeout_seconds, 30);
         assert_eq!(server.max_body_size, 1024 * 1024);
+        assert_eq!(server.shutdown_timeout_secs, 30);
     }
 
     #[test]
@@ -291,5 +961,86 @@ mod tests {
         let json = r#"{"type": "hmac"}"#;
         let auth: AuthConfig = serde_json::from_str(json).unwrap();
         assert_eq!(auth.auth_type, AuthType::Hmac);
+
+        let json = r#"{"type": "github", "secret_env": "GITHUB_WEBHOOK_SECRET"}"#;
+        let auth: AuthConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(auth.auth_type, AuthType::Github);
+        assert!(auth.auth_type.signs_body());
+    }
+
+    #[test]
+    fn test_content_based_topic() {
+        let endpoint: EndpointConfig = toml::from_str(
+            r#"
+            from = "/webhooks/stripe"
+            to = "/stripe/other"
+            route_field = "$.type"
+            topic_template = "/stripe/{value}"
+            allowed_values = ["payment_intent.succeeded", "42"]
+            "#,
+        )
+        .unwrap();
+
+        let topic = |payload| endpoint.topic_for(&payload);
+        assert_eq!(
+            topic(serde_json::json!({"type": "payment_intent.succeeded"})),
+            "/stripe/payment_intent_succeeded"
+        );
+        assert_eq!(topic(serde_json::json!({"type": 42})), "/stripe/42");
+        assert_eq!(topic(serde_json::json!({"type": ""})), "/stripe/other");
+        // Values outside the allowlist can't create topics
+        assert_eq!(
+            topic(serde_json::json!({"type": "charge.refunded"})),
+            "/stripe/other"
+        );
+        assert_eq!(topic(serde_json::json!({"type": {}})), "/stripe/other");
+        assert_eq!(topic(serde_json::json!({"id": "evt_1"})), "/stripe/other");
+
+        let nested = EndpointConfig {
+            route_field: Some("event.type".to_string()),
+            topic_template: Some("/events/{value}".to_string()),
+            allowed_values: vec!["push".to_string()],
+            ..endpoint.clone()
+        };
+        assert_eq!(
+            nested.topic_for(&serde_json::json!({"event": {"type": "push"}})),
+            "/events/push"
+        );
+    }
+
+    #[test]
+    fn test_query_params_capture() {
+        let query = [
+            ("tenant", "acme"),
+            ("tag", "a"),
+            ("token", "secret"),
+            ("tag", "b"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let all = QueryParamsConfig::default().capture(&query);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all["tag"], "a,b");
+
+        let selected = QueryParamsConfig {
+            include: vec!["tenant".to_string(), "tag".to_string()],
+            payload_field: None,
+        }
+        .capture(&query);
+        assert_eq!(selected.get("tenant").map(String::as_str), Some("acme"));
+        assert!(!selected.contains_key("token"));
+    }
+
+    #[test]
+    fn test_topic_template_validation() {
+        assert!(is_valid_template("/stripe/{value}"));
+        assert!(is_valid_template("/events/gh_{value}"));
+        // The payload can't pick the namespace
+        assert!(!is_valid_template("/{value}/events"));
+        assert!(!is_valid_template("/ns_{value}/{value}"));
+        assert!(!is_valid_template("/stripe/events"));
+        assert!(!is_valid_template("/stripe/{type}"));
+        assert!(!is_valid_template("stripe/{value}"));
+        assert!(!is_valid_template("/stripe/{value}/extra"));
     }
 }
@@ -6,12 +6,49 @@ use danube_connect_core::{
     ConnectorConfig, ConnectorError, ConnectorResult, Offset, ProducerConfig, SchemaConfig,
     SchemaMapping, SourceConnector, SourceConnectorMode, SourceRecord, SourceSender,
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info};
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+use tokio::time::Instant;
+use tokio_util::task::TaskTracker;
+use tracing::{error, info, warn};
+
+use crate::audit::AuditLog;
+use crate::auth::PublicKey;
+use crate::config::{AuthType, EndpointConfig, WebhookSourceConfig};
+use crate::delivery::{DeliveryStatus, DeliveryTracker, DELIVERY_ID_ATTRIBUTE};
+use crate::spool::Spool;
+use crate::validation::{compile_validators, PayloadValidator};
+
+/// An accepted webhook, ready to become a SourceRecord (and to be spooled)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// Danube topic to publish to
+    pub topic: String,
+    /// Typed payload
+    pub payload: serde_json::Value,
+    /// Record attributes
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl WebhookEvent {
+    /// Delivery ID of an asynchronously accepted webhook
+    pub fn delivery_id(&self) -> Option<&str> {
+        self.attributes
+            .get(DELIVERY_ID_ATTRIBUTE)
+            .map(String::as_str)
+    }
 
-use crate::config::{EndpointConfig, WebhookSourceConfig};
+    /// Convert into a SourceRecord
+    pub fn into_record(self) -> SourceRecord {
+        self.attributes.into_iter().fold(
+            SourceRecord::new(self.topic, self.payload),
+            |record, (key, value)| record.with_attribute(key, value),
+        )
+    }
+}
 
 /// Webhook connector state
 pub struct WebhookConnector {
@@ -23,6 +60,24 @@ pub struct WebhookConnector {
     endpoints: Arc<RwLock<HashMap<String, EndpointConfig>>>,
     /// HTTP server handle
     server_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Spool forwarder handle (if the spool is enabled)
+    forwarder_handle: Option<tokio::task::JoinHandle<()>>,
+    /// JSON Schema validators of endpoints with validation, keyed by path
+    validators: Arc<HashMap<String, PayloadValidator>>,
+    /// JWT public key read from `auth.public_key_path` (JWT auth only)
+    jwt_public_key: Option<Arc<PublicKey>>,
+    /// Poller task handles
+    poller_handles: Vec<tokio::task::JoinHandle<()>>,
+    /// SSE client task handles
+    sse_handles: Vec<tokio::task::JoinHandle<()>>,
+    /// Audit log writer task handle
+    audit_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Write-ahead spool (if enabled), drained on shutdown
+    spool: Option<Arc<Spool>>,
+    /// Tells the HTTP server to stop accepting requests
+    shutdown_tx: Option<watch::Sender<bool>>,
+    /// Background sends of asynchronously accepted webhooks (without the spool)
+    async_sends: TaskTracker,
 }
 
 impl WebhookConnector {
@@ -39,22 +94,22 @@ impl WebhookConnector {
             schemas,
             endpoints: Arc::new(RwLock::new(endpoints)),
             server_handle: None,
+            forwarder_handle: None,
+            validators: Arc::new(HashMap::new()),
+            jwt_public_key: None,
+            poller_handles: Vec::new(),
+            sse_handles: Vec::new(),
+            audit_handle: None,
+            spool: None,
+            shutdown_tx: None,
+            async_sends: TaskTracker::new(),
         }
     }
 
-    /// Create a SourceRecord from webhook data
-    /// This is called by the HTTP server to convert webhook payloads to SourceRecords
-    pub fn create_source_record(
-        endpoint_config: &EndpointConfig,
-        connector_name: &str,
-        endpoint_path: &str,
-        payload: Vec<u8>,
-        headers: &HashMap<String, String>,
-        client_ip: Option<&str>,
-    ) -> SourceRecord {
-        // Convert webhook payload to typed data
+    /// Convert a webhook payload to typed data
+    pub fn decode_payload(payload: &[u8]) -> serde_json::Value {
         // Try JSON first, fallback to base64-encoded bytes
-        let payload_value = match serde_json::from_slice::<serde_json::Value>(&payload) {
+        match serde_json::from_slice::<serde_json::Value>(payload) {
             Ok(json_value) => json_value,
             Err(_) => {
                 // Not JSON - encode as base64 bytes object
@@ -65,30 +120,70 @@ impl WebhookConnector {
                     "encoding": "base64"
                 })
             }
-        };
+        }
+    }
 
-        // Create source record with typed payload
-        let mut record = SourceRecord::new(endpoint_config.to.clone(), payload_value)
-            .with_attribute("webhook.source", connector_name)
-            .with_attribute("webhook.endpoint", endpoint_path)
-            .with_attribute("webhook.timestamp", Utc::now().to_rfc3339());
+    /// Create a webhook event from webhook data
+    /// This is called by the HTTP server to convert webhook payloads to SourceRecords
+    pub fn create_event(
+        topic: &str,
+        connector_name: &str,
+        endpoint_path: &str,
+        payload_value: serde_json::Value,
+        headers: &HashMap<String, String>,
+        client_ip: Option<&str>,
+    ) -> WebhookEvent {
+        let mut attributes = BTreeMap::from([
+            ("webhook.source".to_string(), connector_name.to_string()),
+            ("webhook.endpoint".to_string(), endpoint_path.to_string()),
+            ("webhook.timestamp".to_string(), Utc::now().to_rfc3339()),
+        ]);
 
         // Add client IP if available
         if let Some(ip) = client_ip {
-            record = record.with_attribute("webhook.ip", ip);
+            attributes.insert("webhook.ip".to_string(), ip.to_string());
         }
 
         // Add user agent if available
         if let Some(user_agent) = headers.get("user-agent") {
-            record = record.with_attribute("webhook.user_agent", user_agent);
+            attributes.insert("webhook.user_agent".to_string(), user_agent.clone());
         }
 
         // Add content type if available
         if let Some(content_type) = headers.get("content-type") {
-            record = record.with_attribute("webhook.content_type", content_type);
+            attributes.insert("webhook.content_type".to_string(), content_type.clone());
+        }
+
+        WebhookEvent {
+            topic: topic.to_string(),
+            payload: payload_value,
+            attributes,
         }
+    }
 
-        record
+    /// Spawn the task handing spooled events to the source runtime, in order
+    fn spawn_forwarder(
+        spool: Arc<Spool>,
+        sender: SourceSender,
+        deliveries: Arc<DeliveryTracker>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let (seq, event) = spool.next().await;
+                let delivery_id = event.delivery_id().map(str::to_string);
+                // Left in the spool if the runtime is gone: forwarded on the next start
+                if let Err(e) = sender.send(event.into_record()).await {
+                    error!("Failed to forward spooled webhook: {}", e);
+                    break;
+                }
+                if let Some(id) = delivery_id {
+                    deliveries.update(&id, DeliveryStatus::Delivered);
+                }
+                if let Err(e) = spool.delivered(seq) {
+                    warn!("Failed to record spooled webhook delivery: {}", e);
+                }
+            }
+        })
     }
 }
 
@@ -101,6 +196,18 @@ impl SourceConnector for WebhookConnector {
         self.config.validate().map_err(|e| {
             ConnectorError::config(format!("Configuration validation failed: {}", e))
         })?;
+        self.validators = Arc::new(compile_validators(&self.config.routes)?);
+
+        // Parse the JWT public key once, rather than on every request
+        let auth = &self.config.auth;
+        if let Some(path) = auth
+            .public_key_path
+            .as_ref()
+            .filter(|_| auth.auth_type == AuthType::Jwt)
+        {
+            let public_key = PublicKey::load(path).map_err(ConnectorError::config)?;
+            self.jwt_public_key = Some(Arc::new(public_key));
+        }
 
         info!(
             "Webhook Configuration: connector={}, routes={}",
@@ -115,6 +222,18 @@ impl SourceConnector for WebhookConnector {
                 endpoint.from, endpoint.to, endpoint.partitions
             );
         }
+        for poll in &self.config.polls {
+            info!(
+                "Poll: {} ({}) -> {} every {}s",
+                poll.name, poll.url, poll.to, poll.interval_secs
+            );
+        }
+        for stream in &self.config.sse {
+            info!(
+                "SSE stream: {} ({}) -> {}",
+                stream.name, stream.url, stream.to
+            );
+        }
 
         info!("Webhook Source Connector initialized successfully");
         Ok(())
@@ -131,16 +250,85 @@ impl SourceConnector for WebhookConnector {
             ));
         }
 
+        let deliveries = Arc::new(DeliveryTracker::new(
+            self.config.server.max_tracked_deliveries,
+            Duration::from_secs(self.config.server.delivery_status_ttl_secs),
+        ));
+
+        // Open the spool before accepting requests, resuming undelivered webhooks
+        let spool = match &self.config.spool {
+            Some(spool_config) => {
+                let spool = Arc::new(Spool::open(spool_config).map_err(|e| {
+                    ConnectorError::fatal(format!(
+                        "Failed to open webhook spool {}: {}",
+                        spool_config.path, e
+                    ))
+                })?);
+                if spool.len() > 0 {
+                    info!(
+                        "Resuming {} spooled webhooks from {}",
+                        spool.len(),
+                        spool_config.path
+                    );
+                }
+                self.forwarder_handle = Some(Self::spawn_forwarder(
+                    spool.clone(),
+                    sender.clone(),
+                    deliveries.clone(),
+                ));
+                self.spool = Some(spool.clone());
+                Some(spool)
+            }
+            None => None,
+        };
+
+        // Start the audit log writer before requests come in
+        let audit = match &self.config.audit {
+            Some(audit_config) => {
+                let (audit, handle) = AuditLog::start(audit_config, sender.clone()).await?;
+                self.audit_handle = Some(handle);
+                Some(audit)
+            }
+            None => None,
+        };
+
+        // Poll the configured APIs
+        for poll in &self.config.polls {
+            self.poller_handles
+                .push(crate::poll::spawn_poller(poll.clone(), sender.clone()));
+        }
+
+        // Consume the configured event streams
+        for stream in &self.config.sse {
+            self.sse_handles
+                .push(crate::sse::spawn_client(stream.clone(), sender.clone()));
+        }
+
         // Start HTTP server in background task
         // We need to create a shared state for the server
         let server_config = self.config.clone();
         let server_endpoints = Arc::clone(&self.endpoints);
         let server_tx = sender;
+        let validators = Arc::clone(&self.validators);
+        let jwt_public_key = self.jwt_public_key.clone();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        self.shutdown_tx = Some(shutdown_tx);
+        let async_sends = self.async_sends.clone();
 
         let server_handle = tokio::spawn(async move {
-            if let Err(e) =
-                crate::server::start_server_with_state(server_config, server_endpoints, server_tx)
-                    .await
+            if let Err(e) = crate::server::start_server_with_state(
+                server_config,
+                server_endpoints,
+                server_tx,
+                spool,
+                deliveries,
+                validators,
+                jwt_public_key,
+                audit,
+                shutdown_rx,
+                async_sends,
+            )
+            .await
             {
                 error!("HTTP server error: {}", e);
             }
@@ -169,8 +357,20 @@ impl SourceConnector for WebhookConnector {
 
             topics.insert(endpoint.to.clone(), (partitions, reliable_dispatch));
         }
+        for poll in &self.config.polls {
+            topics.insert(
+                poll.to.clone(),
+                (poll.partitions as usize, poll.reliable_dispatch),
+            );
+        }
+        for stream in &self.config.sse {
+            topics.insert(
+                stream.to.clone(),
+                (stream.partitions as usize, stream.reliable_dispatch),
+            );
+        }
 
-        let producer_configs: Vec<_> = topics
+        let mut producer_configs: Vec<_> = topics
             .into_iter()
             .map(|(topic, (partitions, reliable_dispatch))| {
                 // Find schema configuration for this topic
@@ -195,9 +395,32 @@ impl SourceConnector for WebhookConnector {
             })
             .collect();
 
+        // Dead-letter topics of endpoints with schema validation, and the audit topic
+        let dead_letter_topics = self.config.routes.iter().filter_map(|endpoint| {
+            endpoint
+                .validation
+                .as_ref()
+                .and_then(|validation| validation.dead_letter_topic.clone())
+        });
+        let audit_topic = self
+            .config
+            .audit
+            .as_ref()
+            .and_then(|audit| audit.topic.clone());
+        for topic in dead_letter_topics.chain(audit_topic) {
+            if producer_configs.iter().all(|config| config.topic != topic) {
+                producer_configs.push(ProducerConfig {
+                    topic,
+                    partitions: 0,
+                    reliable_dispatch: true,
+                    schema_config: None,
+                });
+            }
+        }
+
         if producer_configs.is_empty() {
             return Err(ConnectorError::config(
-                "No routes configured. Please add route mappings in the configuration.",
+                "No routes, polls or SSE streams configured. Please add them to the configuration.",
             ));
         }
 
@@ -211,14 +434,75 @@ impl SourceConnector for WebhookConnector {
     }
 
     async fn shutdown(&mut self) -> ConnectorResult<()> {
-        info!("Shutting down Webhook Source Connector");
+        let drain_timeout = Duration::from_secs(self.config.server.shutdown_timeout_secs);
+        info!(
+            "Shutting down Webhook Source Connector (draining for up to {:?})",
+            drain_timeout
+        );
+        let deadline = Instant::now() + drain_timeout;
+
+        // Polls and streams resume from their saved progress on the next start
+        for handle in self.poller_handles.drain(..) {
+            handle.abort();
+        }
 
-        // Stop HTTP server
-        if let Some(handle) = self.server_handle.take() {
+        for handle in self.sse_handles.drain(..) {
             handle.abort();
+        }
+
+        // Stop accepting requests and let in-flight ones hand their webhooks over
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(true);
+        }
+        if let Some(mut handle) = self.server_handle.take() {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                warn!("In-flight requests didn't finish before the shutdown deadline");
+                handle.abort();
+            }
             info!("HTTP server stopped");
         }
 
+        // Let the background sends of asynchronously accepted webhooks finish
+        self.async_sends.close();
+        if tokio::time::timeout_at(deadline, self.async_sends.wait())
+            .await
+            .is_err()
+        {
+            warn!(
+                "{} accepted webhooks not handed over before the shutdown deadline",
+                self.async_sends.len()
+            );
+        }
+
+        // Forward what's left in the spool; anything remaining is resumed on the next start
+        if let Some(spool) = self.spool.take() {
+            while spool.len() > 0 && Instant::now() < deadline {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            if spool.len() > 0 {
+                warn!(
+                    "{} spooled webhooks not forwarded before the shutdown deadline",
+                    spool.len()
+                );
+            }
+        }
+        if let Some(handle) = self.forwarder_handle.take() {
+            handle.abort();
+        }
+
+        // The audit writer ends after its queue once the server has let go of the log
+        if let Some(mut handle) = self.audit_handle.take() {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                handle.abort();
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,139 @@
+//! Delivery tracking for asynchronously accepted webhooks.
+//!
+//! Endpoints with `accept_async` answer `202 Accepted` with a delivery ID
+//! before the webhook is handed to the Danube producers; `GET /status/{id}`
+//! reports its progress. Statuses are kept in memory, bounded by count and
+//! age.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Record attribute carrying the delivery ID
+pub const DELIVERY_ID_ATTRIBUTE: &str = "webhook.delivery_id";
+
+/// Progress of an accepted webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Accepted, waiting to be handed to the Danube producers
+    Pending,
+    /// Handed to the Danube producers
+    Delivered,
+    /// Couldn't be handed to the Danube producers
+    Failed,
+}
+
+/// Statuses of recent deliveries
+pub struct DeliveryTracker {
+    capacity: usize,
+    retention: Duration,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    statuses: HashMap<String, DeliveryStatus>,
+    /// Delivery IDs with their acceptance time, oldest first
+    accepted: VecDeque<(String, Instant)>,
+}
+
+impl DeliveryTracker {
+    /// Create a tracker keeping up to `capacity` deliveries for `retention`
+    pub fn new(capacity: usize, retention: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            retention,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Start tracking a delivery as pending
+    pub fn track(&self, id: &str) {
+        self.track_at(id, Instant::now());
+    }
+
+    fn track_at(&self, id: &str, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        self.expire(&mut inner, now);
+        while inner.accepted.len() >= self.capacity {
+            let Some((oldest, _)) = inner.accepted.pop_front() else {
+                break;
+            };
+            inner.statuses.remove(&oldest);
+        }
+
+        inner
+            .statuses
+            .insert(id.to_string(), DeliveryStatus::Pending);
+        inner.accepted.push_back((id.to_string(), now));
+    }
+
+    /// Update a tracked delivery (ignored once it's forgotten)
+    pub fn update(&self, id: &str, status: DeliveryStatus) {
+        if let Some(current) = self.inner.lock().unwrap().statuses.get_mut(id) {
+            *current = status;
+        }
+    }
+
+    /// Status of a delivery, if still tracked
+    pub fn status(&self, id: &str) -> Option<DeliveryStatus> {
+        self.status_at(id, Instant::now())
+    }
+
+    fn status_at(&self, id: &str, now: Instant) -> Option<DeliveryStatus> {
+        let mut inner = self.inner.lock().unwrap();
+        self.expire(&mut inner, now);
+        inner.statuses.get(id).copied()
+    }
+
+    fn expire(&self, inner: &mut Inner, now: Instant) {
+        while let Some((id, accepted)) = inner.accepted.front() {
+            if now.saturating_duration_since(*accepted) <= self.retention {
+                break;
+            }
+            inner.statuses.remove(id);
+            inner.accepted.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_lifecycle() {
+        let tracker = DeliveryTracker::new(10, Duration::from_secs(60));
+
+        tracker.track("a");
+        assert_eq!(tracker.status("a"), Some(DeliveryStatus::Pending));
+        tracker.update("a", DeliveryStatus::Delivered);
+        assert_eq!(tracker.status("a"), Some(DeliveryStatus::Delivered));
+
+        // Unknown IDs stay unknown
+        tracker.update("b", DeliveryStatus::Failed);
+        assert_eq!(tracker.status("b"), None);
+    }
+
+    #[test]
+    fn test_bounded_by_count_and_age() {
+        let tracker = DeliveryTracker::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        tracker.track_at("a", start);
+        tracker.track_at("b", start + Duration::from_secs(30));
+        tracker.track_at("c", start + Duration::from_secs(30));
+        assert_eq!(tracker.status_at("a", start), None);
+        assert!(tracker.status_at("b", start).is_some());
+
+        assert!(tracker
+            .status_at("c", start + Duration::from_secs(90))
+            .is_some());
+        assert_eq!(
+            tracker.status_at("c", start + Duration::from_secs(91)),
+            None
+        );
+    }
+}
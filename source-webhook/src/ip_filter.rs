@@ -0,0 +1,242 @@
+//! IP allowlist middleware.
+//!
+//! Restricts endpoints to client addresses in configured CIDR ranges (e.g.,
+//! a provider's published webhook IPs). An endpoint's `allowed_cidrs`
+//! overrides the server-wide list; without either, all clients are allowed.
+//! The check runs before authentication and any other processing.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+use crate::config::WebhookSourceConfig;
+use crate::server::AppState;
+
+/// An IP network in CIDR notation (a bare address is a single-host network)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Whether the network contains the address
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(network).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(network.into(), ip.into(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let shift = bits - prefix;
+    shift >= bits || network >> shift == ip >> shift
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value, None),
+        };
+
+        let network = address
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| format!("Invalid CIDR '{}': bad address", value))?
+            .to_canonical();
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= bits)
+                .ok_or_else(|| format!("Invalid CIDR '{}': bad prefix length", value))?,
+            None => bits,
+        };
+
+        Ok(Self { network, prefix })
+    }
+}
+
+/// Compiled allowlists of the server and its endpoints
+#[derive(Debug, Default)]
+pub struct IpAllowlists {
+    global: Vec<Cidr>,
+    endpoints: HashMap<String, Vec<Cidr>>,
+}
+
+impl IpAllowlists {
+    /// Parse the configured CIDRs (validated when the configuration is loaded)
+    pub fn from_config(config: &WebhookSourceConfig) -> Result<Self, String> {
+        let parse = |cidrs: &[String]| {
+            cidrs
+                .iter()
+                .map(|cidr| cidr.parse())
+                .collect::<Result<Vec<Cidr>, _>>()
+        };
+
+        let mut endpoints = HashMap::new();
+        for endpoint in &config.routes {
+            if let Some(cidrs) = &endpoint.allowed_cidrs {
+                endpoints.insert(endpoint.from.clone(), parse(cidrs)?);
+            }
+        }
+
+        Ok(Self {
+            global: parse(&config.server.allowed_cidrs)?,
+            endpoints,
+        })
+    }
+
+    /// Whether a client may call an endpoint
+    pub fn allows(&self, endpoint: &str, ip: Option<IpAddr>) -> bool {
+        let cidrs = self.endpoints.get(endpoint).unwrap_or(&self.global);
+        if cidrs.is_empty() {
+            return true;
+        }
+        ip.is_some_and(|ip| cidrs.iter().any(|cidr| cidr.contains(ip)))
+    }
+}
+
+/// IP allowlist middleware
+pub async fn ip_filter_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, IpFilterError> {
+    let endpoint_path = request.uri().path().to_string();
+    let client_ip = client_ip(&request, state.config.server.trust_proxy_headers);
+
+    if !state.ip_allowlists.allows(&endpoint_path, client_ip) {
+        tracing::warn!(
+            endpoint = %endpoint_path,
+            ip = ?client_ip,
+            "Client IP not allowed"
+        );
+        return Err(IpFilterError::Forbidden(format!(
+            "Client IP not allowed for endpoint: {}",
+            endpoint_path
+        )));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Client IP of a request: the connection's peer, or the forwarded client if trusted
+pub fn client_ip(request: &Request, trust_proxy_headers: bool) -> Option<IpAddr> {
+    // Forwarded headers are client-controlled unless a proxy overwrites them
+    let peer_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if trust_proxy_headers {
+        crate::rate_limit::extract_client_ip_from_headers(request.headers()).or(peer_ip)
+    } else {
+        peer_ip
+    }
+}
+
+/// IP filter error
+#[derive(Debug)]
+pub enum IpFilterError {
+    /// Client IP outside the allowed ranges
+    Forbidden(String),
+}
+
+impl IntoResponse for IpFilterError {
+    fn into_response(self) -> Response {
+        let message = match self {
+            IpFilterError::Forbidden(msg) => msg,
+        };
+
+        (
+            StatusCode::FORBIDDEN,
+            axum::Json(serde_json::json!({
+                "error": "forbidden",
+                "message": message,
+            })),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let cidr: Cidr = "192.30.252.0/22".parse().unwrap();
+        assert!(cidr.contains(ip("192.30.252.1")));
+        assert!(cidr.contains(ip("192.30.255.254")));
+        assert!(!cidr.contains(ip("192.30.251.255")));
+        // IPv4-mapped addresses (dual-stack listeners) match IPv4 ranges
+        assert!(cidr.contains(ip("::ffff:192.30.253.10")));
+        assert!(!cidr.contains(ip("2a0a:a440::1")));
+
+        let cidr: Cidr = "2a0a:a440::/29".parse().unwrap();
+        assert!(cidr.contains(ip("2a0a:a447:ffff::1")));
+        assert!(!cidr.contains(ip("2a0a:a448::1")));
+
+        let host: Cidr = "10.0.0.7".parse().unwrap();
+        assert!(host.contains(ip("10.0.0.7")));
+        assert!(!host.contains(ip("10.0.0.8")));
+
+        let any: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn test_cidr_parse_errors() {
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0/8".parse::<Cidr>().is_err());
+        assert!("::/129".parse::<Cidr>().is_err());
+        assert!("10.0.0.0/".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_endpoint_overrides_global() {
+        let allowlists = IpAllowlists {
+            global: vec!["10.0.0.0/8".parse().unwrap()],
+            endpoints: HashMap::from([
+                (
+                    "/webhooks/github".to_string(),
+                    vec!["192.30.252.0/22".parse().unwrap()],
+                ),
+                ("/webhooks/open".to_string(), vec![]),
+            ]),
+        };
+
+        assert!(allowlists.allows("/webhooks/orders", Some(ip("10.1.2.3"))));
+        assert!(!allowlists.allows("/webhooks/orders", Some(ip("192.30.252.1"))));
+        assert!(allowlists.allows("/webhooks/github", Some(ip("192.30.252.1"))));
+        assert!(!allowlists.allows("/webhooks/github", Some(ip("10.1.2.3"))));
+        assert!(allowlists.allows("/webhooks/open", Some(ip("203.0.113.9"))));
+        // Unknown clients are only allowed without a list
+        assert!(!allowlists.allows("/webhooks/orders", None));
+        assert!(IpAllowlists::default().allows("/webhooks/orders", None));
+    }
+}
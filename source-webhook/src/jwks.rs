@@ -0,0 +1,104 @@
+//! JWKS key cache for JWT authentication.
+//!
+//! Verification keys are fetched from the identity provider's JWKS URL and
+//! cached by key ID (`kid`). The set is refreshed once it is older than the
+//! configured interval, and right away when a token names an unknown key
+//! (key rotation), at most once per `MIN_FETCH_INTERVAL`. If a refresh fails,
+//! the cached keys keep being used.
+
+use jsonwebtoken::{jwk::JwkSet, DecodingKey};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Minimum time between two fetches (bounds fetches triggered by unknown key IDs)
+const MIN_FETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Cached verification keys of a JWKS URL
+pub struct JwksCache {
+    url: String,
+    max_age: Duration,
+    client: reqwest::Client,
+    cached: Mutex<CachedKeys>,
+}
+
+#[derive(Default)]
+struct CachedKeys {
+    /// Key ID → verification key
+    keys: HashMap<String, DecodingKey>,
+    /// Last successful fetch
+    fetched: Option<Instant>,
+    /// Last fetch attempt
+    attempted: Option<Instant>,
+}
+
+impl JwksCache {
+    /// Create an empty cache (keys are fetched on first use)
+    pub fn new(url: String, max_age: Duration) -> Self {
+        Self {
+            url,
+            max_age,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            cached: Mutex::new(CachedKeys::default()),
+        }
+    }
+
+    /// Verification key of a key ID, fetching the key set when needed
+    pub async fn key(&self, kid: &str) -> Result<DecodingKey, String> {
+        // Held across the fetch, so concurrent requests wait for one fetch
+        let mut cached = self.cached.lock().await;
+
+        let now = Instant::now();
+        let stale = cached
+            .fetched
+            .is_none_or(|fetched| now.duration_since(fetched) >= self.max_age);
+        let can_fetch = cached
+            .attempted
+            .is_none_or(|attempted| now.duration_since(attempted) >= MIN_FETCH_INTERVAL);
+        if (stale || !cached.keys.contains_key(kid)) && can_fetch {
+            cached.attempted = Some(now);
+            match self.fetch().await {
+                Ok(keys) => {
+                    tracing::debug!(url = %self.url, keys = keys.len(), "Fetched JWKS");
+                    cached.keys = keys;
+                    cached.fetched = Some(now);
+                }
+                Err(e) => {
+                    tracing::warn!(url = %self.url, error = %e, "Failed to fetch JWKS");
+                }
+            }
+        }
+
+        cached
+            .keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| format!("Unknown JWT key ID: {}", kid))
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, DecodingKey>, String> {
+        let jwks: JwkSet = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("invalid JWKS: {}", e))?;
+
+        // Keys without an ID or of unsupported types are skipped
+        Ok(jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone()?;
+                Some((kid, DecodingKey::from_jwk(jwk).ok()?))
+            })
+            .collect())
+    }
+}
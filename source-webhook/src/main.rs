@@ -3,11 +3,23 @@
 //! A high-performance HTTP server that receives webhook events from external SaaS platforms
 //! and publishes them to Danube topics.
 
+mod audit;
 mod auth;
 mod config;
 mod connector;
+mod delivery;
+mod ip_filter;
+mod jwks;
+mod poll;
 mod rate_limit;
+mod replay;
+mod response;
 mod server;
+mod spool;
+mod sse;
+mod tls;
+mod validation;
+mod websocket;
 
 use danube_connect_core::{ConnectorResult, SourceRuntime};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
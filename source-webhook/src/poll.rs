@@ -0,0 +1,332 @@
+//! HTTP polling of REST APIs.
+//!
+//! Each `[[polls]]` entry periodically GETs a URL and publishes the items of
+//! the response to its Danube topic. A poll follows pagination (a next-page
+//! URL or cursor in the body, or the `Link` header), sends `If-None-Match` /
+//! `If-Modified-Since` so unchanged resources cost a `304`, and tracks an
+//! increasing item field (the cursor) so only new items are published. The
+//! cursor and cache validators can be kept in a state file across restarts;
+//! it is written after a poll's items are handed to the runtime, so items are
+//! published at least once.
+
+use danube_connect_core::{SourceRecord, SourceSender};
+use reqwest::{header, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use crate::config::PollConfig;
+
+/// Progress of a poll
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PollState {
+    /// ETag of the last first page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// Last-Modified of the last first page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Highest `cursor_field` value published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Value>,
+}
+
+impl PollState {
+    /// Load the state file, or start fresh
+    fn load(path: &str) -> Self {
+        match std::fs::read(path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid poll state file {}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save(&self, path: &str) -> std::io::Result<()> {
+        let path = Path::new(path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+/// Spawn the task polling an API until the source runtime is gone
+pub fn spawn_poller(poll: PollConfig, sender: SourceSender) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        let mut state = poll
+            .state_path
+            .as_deref()
+            .map(PollState::load)
+            .unwrap_or_default();
+
+        let mut interval = tokio::time::interval(Duration::from_secs(poll.interval_secs));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        info!(
+            "Polling {} every {}s for '{}'",
+            poll.url, poll.interval_secs, poll.name
+        );
+
+        loop {
+            interval.tick().await;
+
+            match poll_once(&client, &poll, &state, &sender).await {
+                Ok((published, new_state)) => {
+                    debug!("Poll '{}' published {} items", poll.name, published);
+                    if new_state != state {
+                        if let Some(path) = &poll.state_path {
+                            if let Err(e) = new_state.save(path) {
+                                warn!("Failed to save state of poll '{}': {}", poll.name, e);
+                            }
+                        }
+                        state = new_state;
+                    }
+                }
+                Err(PollError::Closed) => {
+                    info!("Source runtime stopped, ending poll '{}'", poll.name);
+                    return;
+                }
+                Err(PollError::Request(e)) => {
+                    warn!("Poll '{}' failed: {}", poll.name, e);
+                }
+            }
+        }
+    })
+}
+
+enum PollError {
+    /// The request or response failed (retried on the next tick)
+    Request(String),
+    /// The source runtime is gone
+    Closed,
+}
+
+/// Fetch the new items of one poll, returning how many were published and the new state
+async fn poll_once(
+    client: &reqwest::Client,
+    poll: &PollConfig,
+    state: &PollState,
+    sender: &SourceSender,
+) -> Result<(usize, PollState), PollError> {
+    let request_error = |e: reqwest::Error| PollError::Request(e.to_string());
+
+    let mut url = Url::parse(&poll.url).map_err(|e| PollError::Request(e.to_string()))?;
+    if let (Some(param), Some(cursor)) = (&poll.cursor_param, &state.cursor) {
+        url = with_query_param(&url, param, &cursor_text(cursor));
+    }
+
+    let mut new_state = state.clone();
+    let mut published = 0;
+    for page in 0..poll.max_pages {
+        let mut request = client.get(url.clone());
+        for (name, value) in &poll.headers {
+            request = request.header(name, value);
+        }
+        if let Some(token_env) = &poll.bearer_token_env {
+            let token = std::env::var(token_env).map_err(|_| {
+                PollError::Request(format!("Environment variable {} not set", token_env))
+            })?;
+            request = request.bearer_auth(token);
+        }
+        if page == 0 {
+            if let Some(etag) = &state.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &state.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await.map_err(request_error)?;
+        if page == 0 && response.status() == StatusCode::NOT_MODIFIED {
+            return Ok((0, new_state));
+        }
+        let response = response.error_for_status().map_err(request_error)?;
+
+        let header_value = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        if page == 0 {
+            new_state.etag = header_value(header::ETAG);
+            new_state.last_modified = header_value(header::LAST_MODIFIED);
+        }
+        let link_next = header_value(header::LINK).and_then(|link| next_link(&link));
+
+        let body: Value = response.json().await.map_err(request_error)?;
+        let items = match poll.items_field.as_deref() {
+            Some(path) => field(&body, path).cloned().unwrap_or(Value::Null),
+            None => body.clone(),
+        };
+        let items = match items {
+            Value::Array(items) => items,
+            Value::Null => Vec::new(),
+            item => vec![item],
+        };
+
+        for item in items {
+            // Skip items up to the cursor of the previous polls
+            if let Some(cursor_field) = &poll.cursor_field {
+                let Some(cursor) = field(&item, cursor_field).cloned() else {
+                    continue;
+                };
+                if state
+                    .cursor
+                    .as_ref()
+                    .is_some_and(|last| compare(&cursor, last) != Ordering::Greater)
+                {
+                    continue;
+                }
+                if new_state
+                    .cursor
+                    .as_ref()
+                    .is_none_or(|highest| compare(&cursor, highest) == Ordering::Greater)
+                {
+                    new_state.cursor = Some(cursor);
+                }
+            }
+
+            let record = SourceRecord::new(&poll.to, item)
+                .with_attribute("poll.name", &poll.name)
+                .with_attribute("poll.url", url.as_str());
+            sender.send(record).await.map_err(|_| PollError::Closed)?;
+            published += 1;
+        }
+
+        // Next page: from the body (URL, or cursor with page_param) or the Link header
+        let next = match &poll.next_page_field {
+            Some(path) => field(&body, path)
+                .filter(|value| !value.is_null())
+                .map(cursor_text)
+                .filter(|value| !value.is_empty())
+                .and_then(|value| match &poll.page_param {
+                    Some(param) => Some(with_query_param(&url, param, &value)),
+                    None => url.join(&value).ok(),
+                }),
+            None => link_next.and_then(|link| url.join(&link).ok()),
+        };
+        match next {
+            Some(next) if next != url => url = next,
+            _ => break,
+        }
+    }
+
+    Ok((published, new_state))
+}
+
+/// Value at a dot path (a leading `$.` is accepted)
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.strip_prefix("$.").unwrap_or(path);
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+/// Cursor value as sent in a query parameter
+fn cursor_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Order of cursor values: numbers numerically, anything else as text (e.g., RFC 3339 times)
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => cursor_text(a).cmp(&cursor_text(b)),
+    }
+}
+
+/// URL with a query parameter set (replacing any previous value)
+fn with_query_param(url: &Url, name: &str, value: &str) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    let mut url = url.clone();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(name, value);
+    url
+}
+
+/// Target of the `rel="next"` link of a Link header
+fn next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let target = parts.next()?.trim();
+        let is_next = parts.any(|param| {
+            let param = param.trim().replace(' ', "");
+            param == "rel=\"next\"" || param == "rel=next"
+        });
+        is_next.then(|| {
+            target
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_next_link() {
+        let link = r#"<https://api.github.com/repositories/1/issues?page=2>; rel="next", <https://api.github.com/repositories/1/issues?page=5>; rel="last""#;
+        assert_eq!(
+            next_link(link).as_deref(),
+            Some("https://api.github.com/repositories/1/issues?page=2")
+        );
+        assert_eq!(
+            next_link(r#"<https://api.example.com/?page=1>; rel="prev""#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_query_param() {
+        let url = Url::parse("https://api.example.com/events?limit=50&since=1").unwrap();
+        assert_eq!(
+            with_query_param(&url, "since", "2024-06-01T12:00:00Z").as_str(),
+            "https://api.example.com/events?limit=50&since=2024-06-01T12%3A00%3A00Z"
+        );
+    }
+
+    #[test]
+    fn test_cursor_order() {
+        assert_eq!(compare(&json!(10), &json!(9)), Ordering::Greater);
+        assert_eq!(compare(&json!(9), &json!(10)), Ordering::Less);
+        assert_eq!(
+            compare(
+                &json!("2024-06-01T12:00:00Z"),
+                &json!("2024-05-31T23:59:59Z")
+            ),
+            Ordering::Greater
+        );
+        assert_eq!(cursor_text(&json!("abc")), "abc");
+        assert_eq!(cursor_text(&json!(42)), "42");
+        assert_eq!(
+            field(&json!({"data": {"next": "c2"}}), "$.data.next"),
+            Some(&json!("c2"))
+        );
+    }
+}
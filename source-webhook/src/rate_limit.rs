@@ -1,9 +1,13 @@
 //! Rate limiting middleware using token bucket algorithm.
 //!
 //! Supports:
-//! - Per-endpoint rate limiting
-//! - Per-IP rate limiting (optional)
+//! - Platform-wide rate limiting (one budget shared by all endpoints)
+//! - Per-endpoint rate limiting (overrides the platform-wide limit)
+//! - Per-IP rate limiting (optional), with the least recently seen IPs
+//!   evicted once `max_tracked_ips` is reached
 //! - Configurable burst size
+//!
+//! Limiters are created once and shared by all requests through `AppState`.
 
 use axum::{
     extract::{Request, State},
@@ -16,185 +20,178 @@ use governor::{
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter as GovernorRateLimiter,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::IpAddr;
 use std::num::NonZeroU32;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Mutex};
 
-use crate::config::RateLimitConfig;
+use crate::config::{RateLimitConfig, WebhookSourceConfig};
+use crate::ip_filter;
 use crate::server::AppState;
 
-/// Rate limiter state
+type Limiter = GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// Rate limiter state shared by all requests
 pub struct RateLimiterState {
-    /// Per-endpoint rate limiters
-    endpoint_limiters: Arc<RwLock<HashMap<String, Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>>,
-    /// Per-IP rate limiters (if enabled)
-    ip_limiters: Arc<RwLock<HashMap<IpAddr, Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>>,
+    /// Platform-wide limit (applies to endpoints without their own)
+    platform: Option<Limit>,
+    /// Per-endpoint limits
+    endpoints: HashMap<String, Limit>,
 }
 
 impl RateLimiterState {
-    /// Create a new rate limiter state
-    pub fn new() -> Self {
+    /// Create the limiters of the platform-wide and per-endpoint configurations
+    pub fn new(config: &WebhookSourceConfig) -> Self {
         Self {
-            endpoint_limiters: Arc::new(RwLock::new(HashMap::new())),
-            ip_limiters: Arc::new(RwLock::new(HashMap::new())),
+            platform: config.rate_limit.as_ref().map(Limit::new),
+            endpoints: config
+                .routes
+                .iter()
+                .filter_map(|endpoint| {
+                    let rate_limit = endpoint.rate_limit.as_ref()?;
+                    Some((endpoint.from.clone(), Limit::new(rate_limit)))
+                })
+                .collect(),
         }
     }
 
-    /// Get or create a rate limiter for an endpoint
-    async fn get_endpoint_limiter(
-        &self,
-        endpoint: &str,
-        config: &RateLimitConfig,
-    ) -> Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
-        let mut limiters = self.endpoint_limiters.write().await;
-
-        limiters
-            .entry(endpoint.to_string())
-            .or_insert_with(|| {
-                let quota = Quota::per_second(
-                    NonZeroU32::new(config.requests_per_second).unwrap_or(NonZeroU32::new(100).unwrap()),
-                )
-                .allow_burst(NonZeroU32::new(config.burst_size).unwrap_or(NonZeroU32::new(10).unwrap()));
-
-                Arc::new(GovernorRateLimiter::direct(quota))
-            })
-            .clone()
-    }
+    /// Check the limit of an endpoint (and of the client IP, if enabled)
+    pub fn check(&self, endpoint: &str, client_ip: Option<IpAddr>) -> Result<(), RateLimitError> {
+        let Some(limit) = self.endpoints.get(endpoint).or(self.platform.as_ref()) else {
+            return Ok(());
+        };
+
+        // Check the client first, so a single flooding client doesn't use up the endpoint budget
+        if let (Some(ip_limiters), Some(ip)) = (&limit.ip_limiters, client_ip) {
+            let ip_limiter = ip_limiters.lock().unwrap().get(ip);
+            if ip_limiter.check().is_err() {
+                return Err(RateLimitError::Exceeded(format!(
+                    "Rate limit exceeded for IP: {}",
+                    ip
+                )));
+            }
+        }
+
+        if limit.limiter.check().is_err() {
+            return Err(RateLimitError::Exceeded(format!(
+                "Rate limit exceeded for endpoint: {}",
+                endpoint
+            )));
+        }
 
-    /// Get or create a rate limiter for an IP address
-    async fn get_ip_limiter(
-        &self,
-        ip: IpAddr,
-        config: &RateLimitConfig,
-    ) -> Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
-        let mut limiters = self.ip_limiters.write().await;
-
-        limiters
-            .entry(ip)
-            .or_insert_with(|| {
-                let quota = Quota::per_second(
-                    NonZeroU32::new(config.requests_per_second).unwrap_or(NonZeroU32::new(100).unwrap()),
-                )
-                .allow_burst(NonZeroU32::new(config.burst_size).unwrap_or(NonZeroU32::new(10).unwrap()));
-
-                Arc::new(GovernorRateLimiter::direct(quota))
-            })
-            .clone()
+        Ok(())
     }
 }
 
-impl Default for RateLimiterState {
-    fn default() -> Self {
-        Self::new()
+/// A configured limit with its limiters
+struct Limit {
+    limiter: Limiter,
+    ip_limiters: Option<Mutex<IpLimiters>>,
+}
+
+impl Limit {
+    fn new(config: &RateLimitConfig) -> Self {
+        let ip_limiters = config.per_ip_enabled.then(|| {
+            let requests_per_second = config
+                .per_ip_requests_per_second
+                .unwrap_or(config.requests_per_second);
+            let burst_size = if config.per_ip_requests_per_second.is_some() {
+                requests_per_second
+            } else {
+                config.burst_size
+            };
+            Mutex::new(IpLimiters::new(
+                quota(requests_per_second, burst_size),
+                config.max_tracked_ips,
+            ))
+        });
+
+        Self {
+            limiter: GovernorRateLimiter::direct(quota(
+                config.requests_per_second,
+                config.burst_size,
+            )),
+            ip_limiters,
+        }
     }
 }
 
-/// Check rate limit (called directly from handler)
-#[allow(dead_code)]
-pub async fn check_rate_limit(
-    _state: &AppState,
-    endpoint_path: &str,
-    config: &RateLimitConfig,
-    headers: &HeaderMap,
-) -> Result<(), String> {
-    // Create rate limiter state
-    let limiter_state = RateLimiterState::new();
-
-    // Check endpoint rate limit
-    let endpoint_limiter = limiter_state
-        .get_endpoint_limiter(endpoint_path, config)
-        .await;
-
-    if endpoint_limiter.check().is_err() {
-        return Err(format!("Rate limit exceeded for endpoint: {}", endpoint_path));
+fn quota(requests_per_second: u32, burst_size: u32) -> Quota {
+    // Zero falls back to the defaults
+    let or = |value, default| NonZeroU32::new(value).or(NonZeroU32::new(default)).unwrap();
+    Quota::per_second(or(requests_per_second, 100)).allow_burst(or(burst_size, 10))
+}
+
+/// Per-IP limiters, bounded by evicting the least recently used
+struct IpLimiters {
+    quota: Quota,
+    capacity: usize,
+    /// Use counter, increasing with each lookup
+    clock: u64,
+    /// IP → limiter and its last use
+    limiters: HashMap<IpAddr, (Arc<Limiter>, u64)>,
+    /// Last use → IP, oldest first
+    recency: BTreeMap<u64, IpAddr>,
+}
+
+impl IpLimiters {
+    fn new(quota: Quota, capacity: usize) -> Self {
+        Self {
+            quota,
+            capacity: capacity.max(1),
+            clock: 0,
+            limiters: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
     }
 
-    // Check per-IP rate limit if enabled
-    if config.per_ip_enabled {
-        if let Some(ip) = extract_client_ip_from_headers(headers) {
-            let ip_limiter = limiter_state.get_ip_limiter(ip, config).await;
+    /// Get or create the limiter of an IP, marking it as most recently used
+    fn get(&mut self, ip: IpAddr) -> Arc<Limiter> {
+        self.clock += 1;
 
-            if ip_limiter.check().is_err() {
-                return Err(format!("Rate limit exceeded for IP: {}", ip));
+        if let Some((limiter, last_used)) = self.limiters.get_mut(&ip) {
+            self.recency.remove(last_used);
+            *last_used = self.clock;
+            self.recency.insert(self.clock, ip);
+            return limiter.clone();
+        }
+
+        if self.limiters.len() >= self.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.limiters.remove(&oldest);
             }
         }
-    }
 
-    Ok(())
+        let limiter = Arc::new(GovernorRateLimiter::direct(self.quota));
+        self.limiters.insert(ip, (limiter.clone(), self.clock));
+        self.recency.insert(self.clock, ip);
+        limiter
+    }
 }
 
-/// Rate limiting middleware (for future use when axum 0.8 compatibility is resolved)
+/// Rate limiting middleware
 pub async fn rate_limit_middleware(
     State(state): State<AppState>,
     request: Request,
     next: Next,
 ) -> Result<Response, RateLimitError> {
-    // Extract endpoint path
     let endpoint_path = request.uri().path().to_string();
+    let client_ip = ip_filter::client_ip(&request, state.config.server.trust_proxy_headers);
 
-    // Get endpoint configuration and clone rate limit config
-    let rate_limit_config = {
-        let endpoints = state.endpoints.read().await;
-        let endpoint_config = endpoints.get(&endpoint_path);
-
-        // If no endpoint config or no rate limit config, allow request
-        match endpoint_config {
-            Some(cfg) => match &cfg.rate_limit {
-                Some(rl) => rl.clone(),
-                None => return Ok(next.run(request).await),
-            },
-            None => return Ok(next.run(request).await),
-        }
-    };
-
-    // Create rate limiter state if not exists
-    // Note: In production, this should be stored in AppState
-    let limiter_state = RateLimiterState::new();
-
-    // Check endpoint rate limit
-    let endpoint_limiter = limiter_state
-        .get_endpoint_limiter(&endpoint_path, &rate_limit_config)
-        .await;
-
-    if endpoint_limiter.check().is_err() {
+    if let Err(e) = state.rate_limiter.check(&endpoint_path, client_ip) {
         tracing::warn!(
             endpoint = %endpoint_path,
-            "Rate limit exceeded for endpoint"
+            ip = ?client_ip,
+            "Rate limit exceeded"
         );
-
-        return Err(RateLimitError::Exceeded(format!(
-            "Rate limit exceeded for endpoint: {}",
-            endpoint_path
-        )));
-    }
-
-    // Check per-IP rate limit if enabled
-    if rate_limit_config.per_ip_enabled {
-        if let Some(ip) = extract_client_ip(&request) {
-            let ip_limiter = limiter_state.get_ip_limiter(ip, &rate_limit_config).await;
-
-            if ip_limiter.check().is_err() {
-                tracing::warn!(
-                    endpoint = %endpoint_path,
-                    ip = %ip,
-                    "Rate limit exceeded for IP"
-                );
-
-                return Err(RateLimitError::Exceeded(format!(
-                    "Rate limit exceeded for IP: {}",
-                    ip
-                )));
-            }
-        }
+        return Err(e);
     }
 
     Ok(next.run(request).await)
 }
 
 /// Extract client IP from HeaderMap
-fn extract_client_ip_from_headers(headers: &HeaderMap) -> Option<IpAddr> {
+pub fn extract_client_ip_from_headers(headers: &HeaderMap) -> Option<IpAddr> {
     // Try X-Forwarded-For header first
     if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(forwarded_str) = forwarded.to_str() {
@@ -218,11 +215,6 @@ fn extract_client_ip_from_headers(headers: &HeaderMap) -> Option<IpAddr> {
     None
 }
 
-/// Extract client IP from request
-fn extract_client_ip(request: &Request) -> Option<IpAddr> {
-    extract_client_ip_from_headers(request.headers())
-}
-
 /// Rate limit error
 #[derive(Debug)]
 pub enum RateLimitError {
@@ -253,15 +245,100 @@ impl IntoResponse for RateLimitError {
 mod tests {
     use super::*;
 
+    fn rate_limit(requests_per_second: u32, burst_size: u32, per_ip: bool) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second,
+            burst_size,
+            per_ip_enabled: per_ip,
+            per_ip_requests_per_second: None,
+            max_tracked_ips: 2,
+        }
+    }
+
+    fn state(
+        platform: Option<RateLimitConfig>,
+        endpoint: Option<RateLimitConfig>,
+    ) -> RateLimiterState {
+        RateLimiterState {
+            platform: platform.as_ref().map(Limit::new),
+            endpoints: endpoint
+                .iter()
+                .map(|config| ("/webhooks/limited".to_string(), Limit::new(config)))
+                .collect(),
+        }
+    }
+
+    fn ip(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::from([10, 0, 0, last]))
+    }
+
     #[test]
-    fn test_rate_limiter_creation() {
-        let state = RateLimiterState::new();
-        assert!(state.endpoint_limiters.try_read().is_ok());
+    fn test_limits_persist_across_requests() {
+        let state = state(None, Some(rate_limit(1, 2, false)));
+
+        assert!(state.check("/webhooks/limited", ip(1)).is_ok());
+        assert!(state.check("/webhooks/limited", ip(2)).is_ok());
+        assert!(state.check("/webhooks/limited", ip(3)).is_err());
+
+        // Endpoints without a limit (and no platform-wide one) are unlimited
+        for _ in 0..10 {
+            assert!(state.check("/webhooks/other", ip(1)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_platform_limit_shared_by_endpoints() {
+        let state = state(Some(rate_limit(1, 2, false)), Some(rate_limit(1, 5, false)));
+
+        assert!(state.check("/webhooks/a", None).is_ok());
+        assert!(state.check("/webhooks/b", None).is_ok());
+        assert!(state.check("/webhooks/a", None).is_err());
+
+        // The endpoint's own limit overrides the platform-wide one
+        assert!(state.check("/webhooks/limited", None).is_ok());
+    }
+
+    #[test]
+    fn test_per_ip_limit() {
+        let state = state(
+            Some(RateLimitConfig {
+                per_ip_requests_per_second: Some(1),
+                max_tracked_ips: 10,
+                ..rate_limit(1, 3, true)
+            }),
+            None,
+        );
+        let admitted = |last| {
+            let mut admitted = 0;
+            while state.check("/webhooks/a", ip(last)).is_ok() {
+                admitted += 1;
+            }
+            admitted
+        };
+
+        // A flooding IP gets its own budget without using up the endpoint's
+        assert_eq!(admitted(1), 1);
+        assert_eq!(admitted(2), 1);
+        assert_eq!(admitted(3), 1);
+        assert_eq!(admitted(4), 0);
     }
 
     #[test]
-    fn test_ip_extraction() {
-        // Test IP extraction from headers
-        // TODO: Add comprehensive tests
+    fn test_ip_limiters_evict_least_recently_used() {
+        let mut limiters = IpLimiters::new(quota(1, 1), 2);
+        let [a, b, c] = [ip(1), ip(2), ip(3)].map(Option::unwrap);
+
+        assert!(limiters.get(a).check().is_ok());
+        assert!(limiters.get(b).check().is_ok());
+        // Using a makes b the least recently used
+        assert!(limiters.get(a).check().is_err());
+        assert!(limiters.get(c).check().is_ok());
+
+        assert_eq!(limiters.limiters.len(), 2);
+        assert!(limiters.limiters.contains_key(&a));
+        assert!(!limiters.limiters.contains_key(&b));
+        // An evicted IP starts with a fresh limiter
+        assert!(limiters.get(b).check().is_ok());
+        assert!(!limiters.limiters.contains_key(&a));
     }
 }
@@ -0,0 +1,243 @@
+//! Replay protection middleware.
+//!
+//! Authenticated requests must carry a timestamp within the tolerance window,
+//! and each request (its nonce header, or its timestamp and body) is accepted
+//! once: a captured request resent while its timestamp is still fresh is
+//! rejected. Entries expire with their timestamp, so the cache only holds
+//! requests of the last tolerance window.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::config::ReplayProtectionConfig;
+use crate::server::AppState;
+
+/// Requests seen within the tolerance window
+pub struct ReplayGuard {
+    config: ReplayProtectionConfig,
+    /// Request key → expiry (Unix seconds)
+    seen: Mutex<HashMap<[u8; 32], i64>>,
+}
+
+impl ReplayGuard {
+    /// Create an empty guard
+    pub fn new(config: ReplayProtectionConfig) -> Self {
+        Self {
+            config,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Accept a request once while its timestamp is fresh
+    fn check(&self, key: [u8; 32], timestamp: i64, now: i64) -> Result<(), ReplayError> {
+        let tolerance = self.config.tolerance_secs;
+        if now.abs_diff(timestamp) > tolerance {
+            return Err(ReplayError::Invalid(
+                "Request timestamp outside the tolerance window".to_string(),
+            ));
+        }
+
+        let mut seen = self.seen.lock().unwrap();
+        if seen.get(&key).is_some_and(|&expires| expires >= now) {
+            return Err(ReplayError::Replayed(
+                "Request already received".to_string(),
+            ));
+        }
+
+        if seen.len() >= self.config.max_nonces {
+            seen.retain(|_, expires| *expires >= now);
+            if seen.len() >= self.config.max_nonces {
+                return Err(ReplayError::Unavailable(
+                    "Too many requests to track for replay protection".to_string(),
+                ));
+            }
+        }
+
+        // Past this point the timestamp check rejects the request anyway
+        seen.insert(key, timestamp.saturating_add(tolerance as i64));
+        Ok(())
+    }
+
+    /// Accept the request again (it wasn't processed, so the sender may retry it)
+    fn forget(&self, key: &[u8; 32]) {
+        self.seen.lock().unwrap().remove(key);
+    }
+}
+
+/// Replay protection middleware (runs after authentication)
+pub async fn replay_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ReplayError> {
+    let Some(guard) = state.replay_guard.clone() else {
+        return Ok(next.run(request).await);
+    };
+    let config = &guard.config;
+
+    let timestamp_header = header(&request, &config.timestamp_header)?.to_string();
+    let timestamp = parse_timestamp(&timestamp_header)
+        .ok_or_else(|| ReplayError::Invalid("Invalid request timestamp".to_string()))?;
+
+    // Identify the request by its nonce, or by its timestamp and body
+    let endpoint_path = request.uri().path().to_string();
+    let (request, key) = match &config.nonce_header {
+        Some(nonce_header) => {
+            let key = Sha256::digest(header(&request, nonce_header)?.as_bytes()).into();
+            (request, key)
+        }
+        None => {
+            let (parts, body) = request.into_parts();
+            let max_body_size = state.config.max_body_size_for(&endpoint_path);
+            let body = axum::body::to_bytes(body, max_body_size)
+                .await
+                .map_err(|e| ReplayError::Body(format!("Failed to read request body: {}", e)))?;
+            let key = Sha256::new()
+                .chain_update(timestamp_header.as_bytes())
+                .chain_update(b"\n")
+                .chain_update(&body)
+                .finalize()
+                .into();
+            (Request::from_parts(parts, Body::from(body)), key)
+        }
+    };
+
+    if let Err(e) = guard.check(key, timestamp, chrono::Utc::now().timestamp()) {
+        tracing::warn!(
+            endpoint = %endpoint_path,
+            error = ?e,
+            "Replay protection rejected request"
+        );
+        return Err(e);
+    }
+
+    let response = next.run(request).await;
+    if !response.status().is_success() {
+        guard.forget(&key);
+    }
+    Ok(response)
+}
+
+/// Value of a required header
+fn header<'a>(request: &'a Request, name: &str) -> Result<&'a str, ReplayError> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| ReplayError::Missing(format!("Missing {} header", name)))
+}
+
+/// Parse a timestamp header (Unix seconds or RFC 3339)
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    value.parse::<i64>().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|time| time.timestamp())
+    })
+}
+
+/// Replay protection error types
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Missing timestamp or nonce header
+    Missing(String),
+    /// Malformed or stale timestamp
+    Invalid(String),
+    /// Request already received
+    Replayed(String),
+    /// Request body couldn't be read (e.g., larger than the endpoint's max_body_size)
+    Body(String),
+    /// Nonce cache full
+    Unavailable(String),
+}
+
+impl IntoResponse for ReplayError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ReplayError::Missing(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ReplayError::Invalid(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ReplayError::Replayed(msg) => (StatusCode::CONFLICT, msg),
+            ReplayError::Body(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg),
+            ReplayError::Unavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+        };
+
+        (
+            status,
+            axum::Json(serde_json::json!({
+                "error": "replay_rejected",
+                "message": message,
+            })),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(max_nonces: usize) -> ReplayGuard {
+        ReplayGuard::new(ReplayProtectionConfig {
+            timestamp_header: "x-webhook-timestamp".to_string(),
+            tolerance_secs: 300,
+            nonce_header: None,
+            max_nonces,
+        })
+    }
+
+    #[test]
+    fn test_rejects_replays() {
+        let guard = guard(100);
+        let now = 1_700_000_000;
+
+        assert!(guard.check([1; 32], now - 10, now).is_ok());
+        assert!(matches!(
+            guard.check([1; 32], now - 10, now + 60),
+            Err(ReplayError::Replayed(_))
+        ));
+        assert!(guard.check([2; 32], now - 10, now).is_ok());
+
+        // Once the timestamp is stale, the timestamp check takes over
+        assert!(matches!(
+            guard.check([1; 32], now - 10, now + 400),
+            Err(ReplayError::Invalid(_))
+        ));
+
+        // Unprocessed requests may be retried
+        guard.forget(&[2; 32]);
+        assert!(guard.check([2; 32], now - 10, now).is_ok());
+    }
+
+    #[test]
+    fn test_bounded_cache() {
+        let guard = guard(2);
+        let now = 1_700_000_000;
+
+        assert!(guard.check([1; 32], now - 290, now).is_ok());
+        assert!(guard.check([2; 32], now, now).is_ok());
+        assert!(matches!(
+            guard.check([3; 32], now, now),
+            Err(ReplayError::Unavailable(_))
+        ));
+
+        // Expired entries make room
+        assert!(guard.check([3; 32], now, now + 20).is_ok());
+        assert_eq!(guard.seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1700000000"), Some(1_700_000_000));
+        assert_eq!(parse_timestamp("2023-11-14T22:13:20Z"), Some(1_700_000_000));
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Custom responses for accepted webhooks.
+//!
+//! Routes with a `response` section answer with their own status code and
+//! body, e.g. `204 No Content` or a body echoing the event ID. Body templates
+//! reference request fields with `{{...}}` placeholders: `endpoint`, `topic`,
+//! `delivery_id`, `payload` (or `payload.<path>`), `query.<name>` and
+//! `header.<name>`.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::ResponseConfig;
+
+/// Request fields available to body templates
+pub struct RequestFields<'a> {
+    pub endpoint: &'a str,
+    pub topic: &'a str,
+    pub delivery_id: Option<&'a str>,
+    pub payload: &'a Value,
+    pub query: &'a BTreeMap<String, String>,
+    pub headers: &'a HashMap<String, String>,
+}
+
+impl RequestFields<'_> {
+    fn get(&self, field: Field) -> Option<Value> {
+        let text = |value: &str| Some(Value::String(value.to_string()));
+        match field {
+            Field::Endpoint => text(self.endpoint),
+            Field::Topic => text(self.topic),
+            Field::DeliveryId => self.delivery_id.and_then(text),
+            Field::Payload(None) => Some(self.payload.clone()),
+            Field::Payload(Some(path)) => path
+                .split('.')
+                .try_fold(self.payload, |value, key| value.get(key))
+                .cloned(),
+            Field::Query(name) => self.query.get(name).and_then(|value| text(value)),
+            Field::Header(name) => self
+                .headers
+                .get(&name.to_ascii_lowercase())
+                .and_then(|value| text(value)),
+        }
+    }
+}
+
+/// A template placeholder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field<'a> {
+    Endpoint,
+    Topic,
+    DeliveryId,
+    /// The payload, or a dot path into it
+    Payload(Option<&'a str>),
+    Query(&'a str),
+    Header(&'a str),
+}
+
+impl<'a> Field<'a> {
+    fn parse(name: &'a str) -> Option<Self> {
+        match name.split_once('.') {
+            None => match name {
+                "endpoint" => Some(Field::Endpoint),
+                "topic" => Some(Field::Topic),
+                "delivery_id" => Some(Field::DeliveryId),
+                "payload" => Some(Field::Payload(None)),
+                _ => None,
+            },
+            Some((_, "")) => None,
+            Some(("payload", path)) => Some(Field::Payload(Some(path))),
+            Some(("query", name)) => Some(Field::Query(name)),
+            Some(("header", name)) => Some(Field::Header(name)),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Build the configured response of an accepted webhook
+pub fn render(
+    config: &ResponseConfig,
+    fields: &RequestFields,
+    default_status: StatusCode,
+) -> Response {
+    let status = config
+        .status
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .unwrap_or(default_status);
+    let Some(template) = &config.body else {
+        return status.into_response();
+    };
+
+    // Text values go inside JSON strings, so escape them for JSON bodies
+    let json = config.content_type.contains("json");
+    let body = render_template(template, |field| match fields.get(field) {
+        Some(Value::String(text)) if json => {
+            let quoted = Value::String(text).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        Some(Value::String(text)) => text,
+        Some(value) => value.to_string(),
+        None => String::new(),
+    });
+
+    match body {
+        Ok(body) => (
+            status,
+            [(header::CONTENT_TYPE, config.content_type.clone())],
+            body,
+        )
+            .into_response(),
+        // Templates are checked when the configuration is loaded
+        Err(_) => status.into_response(),
+    }
+}
+
+/// Check a body template's placeholders
+pub fn validate_template(template: &str) -> Result<(), String> {
+    render_template(template, |_| String::new()).map(|_| ())
+}
+
+/// Render a template, replacing each `{{field}}` with its value
+fn render_template(
+    template: &str,
+    mut value: impl FnMut(Field) -> String,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        let end = placeholder
+            .find("}}")
+            .ok_or_else(|| "unclosed '{{' placeholder".to_string())?;
+        let name = placeholder[..end].trim();
+        let field = Field::parse(name).ok_or_else(|| format!("unknown field '{}'", name))?;
+        rendered.push_str(&value(field));
+        rest = &placeholder[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(status: Option<u16>, body: Option<&str>, content_type: &str) -> ResponseConfig {
+        ResponseConfig {
+            status,
+            body: body.map(str::to_string),
+            content_type: content_type.to_string(),
+        }
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_render_response() {
+        let payload = json!({"id": "evt_\"1\"", "data": {"amount": 100}});
+        let query = BTreeMap::from([("tenant".to_string(), "acme".to_string())]);
+        let headers = HashMap::from([("x-request-id".to_string(), "req-7".to_string())]);
+        let fields = RequestFields {
+            endpoint: "/webhooks/payments",
+            topic: "/stripe/payments",
+            delivery_id: None,
+            payload: &payload,
+            query: &query,
+            headers: &headers,
+        };
+
+        let config = response(
+            Some(201),
+            Some(
+                r#"{"id": "{{payload.id}}", "amount": {{ payload.data.amount }}, "tenant": "{{query.tenant}}", "request": "{{header.X-Request-Id}}", "missing": "{{payload.none}}"}"#,
+            ),
+            "application/json",
+        );
+        let rendered = render(&config, &fields, StatusCode::OK);
+        assert_eq!(rendered.status(), StatusCode::CREATED);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body(rendered).await).unwrap(),
+            json!({
+                "id": "evt_\"1\"",
+                "amount": 100,
+                "tenant": "acme",
+                "request": "req-7",
+                "missing": ""
+            })
+        );
+
+        // Plain text isn't escaped
+        let config = response(None, Some("ok {{payload.id}}"), "text/plain");
+        assert_eq!(
+            body(render(&config, &fields, StatusCode::OK)).await,
+            r#"ok evt_"1""#
+        );
+
+        let config = response(Some(204), None, "application/json");
+        let rendered = render(&config, &fields, StatusCode::OK);
+        assert_eq!(rendered.status(), StatusCode::NO_CONTENT);
+        assert!(body(rendered).await.is_empty());
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template(r#"{"id": "{{payload.id}}", "to": "{{topic}}"}"#).is_ok());
+        assert!(validate_template("{{delivery_id}} {{payload}} {{header.x-id}}").is_ok());
+        assert!(validate_template("{{payload.id").is_err());
+        assert!(validate_template("{{body.id}}").is_err());
+        assert!(validate_template("{{query.}}").is_err());
+    }
+}
@@ -1,26 +1,37 @@
 //! HTTP server implementation using axum framework.
 
 use axum::{
-    body::Bytes,
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
-    middleware,
+    body::Body,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tower_http::{timeout::TimeoutLayer, trace::TraceLayer};
-
-use crate::auth;
-use crate::config::{EndpointConfig, WebhookSourceConfig};
-use crate::connector::WebhookConnector;
-use crate::rate_limit;
+use tower_http::trace::TraceLayer;
+
+use crate::audit::{self, AuditLog};
+use crate::auth::{self, PublicKey};
+use crate::config::{AuthType, EndpointConfig, QueryParamsConfig, WebhookSourceConfig};
+use crate::connector::{WebhookConnector, WebhookEvent};
+use crate::delivery::{DeliveryStatus, DeliveryTracker, DELIVERY_ID_ATTRIBUTE};
+use crate::ip_filter::{self, IpAllowlists};
+use crate::jwks::JwksCache;
+use crate::rate_limit::{self, RateLimiterState};
+use crate::replay::{self, ReplayGuard};
+use crate::response;
+use crate::spool::{Spool, SpoolError};
+use crate::tls;
+use crate::validation::PayloadValidator;
+use crate::websocket;
 use danube_connect_core::SourceSender;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
+use tokio_util::task::TaskTracker;
 
 /// Shared application state
 #[derive(Clone)]
@@ -28,13 +39,43 @@ pub struct AppState {
     pub config: WebhookSourceConfig,
     pub endpoints: Arc<RwLock<HashMap<String, EndpointConfig>>>,
     pub message_tx: SourceSender,
+    /// Requests seen by replay protection (if enabled)
+    pub replay_guard: Option<Arc<ReplayGuard>>,
+    /// Allowed client networks of the server and endpoints
+    pub ip_allowlists: Arc<IpAllowlists>,
+    /// Platform-wide, per-endpoint and per-IP rate limiters
+    pub rate_limiter: Arc<RateLimiterState>,
+    /// Write-ahead spool accepted webhooks go through (if enabled)
+    pub spool: Option<Arc<Spool>>,
+    /// Statuses of asynchronously accepted webhooks
+    pub deliveries: Arc<DeliveryTracker>,
+    /// JSON Schema validators of endpoints with validation
+    pub validators: Arc<HashMap<String, PayloadValidator>>,
+    /// JWT verification keys fetched from the JWKS URL (if configured)
+    pub jwks: Option<Arc<JwksCache>>,
+    /// JWT public key read from `public_key_path` at startup (if configured)
+    pub jwt_public_key: Option<Arc<PublicKey>>,
+    /// Request audit log (if enabled)
+    pub audit: Option<Arc<AuditLog>>,
+    /// Set to true when the connector shuts down
+    pub shutdown: watch::Receiver<bool>,
+    /// Background sends of asynchronously accepted webhooks, awaited on shutdown
+    pub async_sends: TaskTracker,
 }
 
 /// Start the HTTP server with state components (called from connector initialize)
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server_with_state(
     config: WebhookSourceConfig,
     endpoints: Arc<RwLock<HashMap<String, EndpointConfig>>>,
     message_tx: SourceSender,
+    spool: Option<Arc<Spool>>,
+    deliveries: Arc<DeliveryTracker>,
+    validators: Arc<HashMap<String, PayloadValidator>>,
+    jwt_public_key: Option<Arc<PublicKey>>,
+    audit: Option<Arc<AuditLog>>,
+    shutdown: watch::Receiver<bool>,
+    async_sends: TaskTracker,
 ) -> anyhow::Result<()> {
     let bind_addr: SocketAddr = config.bind_address().parse()?;
 
@@ -43,10 +84,39 @@ pub async fn start_server_with_state(
         config: config.clone(),
         endpoints,
         message_tx,
+        replay_guard: config
+            .replay_protection
+            .clone()
+            .map(|replay| Arc::new(ReplayGuard::new(replay))),
+        ip_allowlists: Arc::new(IpAllowlists::from_config(&config).map_err(anyhow::Error::msg)?),
+        rate_limiter: Arc::new(RateLimiterState::new(&config)),
+        spool,
+        deliveries,
+        validators,
+        jwks: config
+            .auth
+            .jwks_url
+            .clone()
+            .filter(|_| config.auth.auth_type == AuthType::Jwt)
+            .map(|url| {
+                let refresh = config.auth.jwks_refresh_secs.unwrap_or(3600);
+                Arc::new(JwksCache::new(url, std::time::Duration::from_secs(refresh)))
+            }),
+        jwt_public_key,
+        audit,
+        shutdown: shutdown.clone(),
+        async_sends,
     };
 
-    // Build webhook handler with auth and rate limiting middleware
+    // Build webhook handler with IP filtering, auth, rate limiting and replay protection
+    // middleware (the last layer runs first: the IP allowlist is checked before anything
+    // else, and replay protection only sees authenticated requests)
     let webhook_handler_with_middleware = post(webhook_handler)
+        .get(websocket::websocket_handler)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            replay::replay_middleware,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             rate_limit::rate_limit_middleware,
@@ -54,6 +124,10 @@ pub async fn start_server_with_state(
         .layer(middleware::from_fn_with_state(
             state.clone(),
             auth::auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            ip_filter::ip_filter_middleware,
         ));
 
     // Build main router
@@ -61,24 +135,82 @@ pub async fn start_server_with_state(
         // Health endpoints (no auth/rate limiting)
         .route("/health", get(health_handler))
         .route("/ready", get(readiness_handler))
-        // Webhook endpoint with auth and rate limiting middleware
+        // Delivery status of asynchronously accepted webhooks (IDs are unguessable)
+        .route("/status/{id}", get(status_handler))
+        // Webhook endpoint (and WebSocket upgrades) with auth and rate limiting middleware
         .route("/{*path}", webhook_handler_with_middleware)
-        // Add global middleware
-        .layer(TimeoutLayer::with_status_code(
-            StatusCode::REQUEST_TIMEOUT,
-            std::time::Duration::from_secs(config.server.timeout_seconds),
+        // Add global middleware (the audit log also records timed out requests)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            timeout_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            audit::audit_middleware,
         ))
         .layer(TraceLayer::new_for_http())
         .with_state(state);
 
+    // Serve HTTPS when a certificate is configured
+    if let (Some(cert_path), Some(key_path)) =
+        (&config.server.tls_cert_path, &config.server.tls_key_path)
+    {
+        let tls_config = tls::load(cert_path, key_path).await?;
+        let reload_interval = config.server.tls_reload_interval_secs;
+
+        tracing::info!("Starting HTTPS server on {}", bind_addr);
+
+        // Stop accepting on shutdown, letting in-flight requests finish
+        let handle = axum_server::Handle::new();
+        let drain_deadline = std::time::Duration::from_secs(config.server.shutdown_timeout_secs);
+        let signal = shutdown_signal(shutdown);
+        let shutdown_handle = handle.clone();
+        tokio::spawn(async move {
+            signal.await;
+            shutdown_handle.graceful_shutdown(Some(drain_deadline));
+        });
+
+        let server = axum_server::bind_rustls(bind_addr, tls_config.clone())
+            .handle(handle)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>());
+
+        // Watch the certificate within this task, so it stops with the server
+        let reload = async {
+            if reload_interval > 0 {
+                tls::watch(
+                    tls_config,
+                    cert_path.clone(),
+                    key_path.clone(),
+                    std::time::Duration::from_secs(reload_interval),
+                )
+                .await;
+            } else {
+                std::future::pending::<()>().await;
+            }
+        };
+
+        tokio::select! {
+            result = server => result.map_err(|e| anyhow::anyhow!("Server error: {}", e))?,
+            _ = reload => {}
+        }
+
+        return Ok(());
+    }
+
     tracing::info!("Starting HTTP server on {}", bind_addr);
 
     // Start server
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
 
-    axum::serve(listener, app)
-        .await
-        .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+    // Connection info provides the peer address to the IP allowlist; on shutdown, stop
+    // accepting and let in-flight requests finish (bounded by the connector's drain deadline)
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(shutdown))
+    .await
+    .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
 
     Ok(())
 }
@@ -87,17 +219,12 @@ pub async fn start_server_with_state(
 async fn webhook_handler(
     State(state): State<AppState>,
     Path(path): Path<String>,
+    Query(query): Query<Vec<(String, String)>>,
     headers: HeaderMap,
-    body: Bytes,
+    body: Body,
 ) -> Result<Response, AppError> {
     let endpoint_path = format!("/{}", path);
 
-    tracing::debug!(
-        endpoint = %endpoint_path,
-        body_size = body.len(),
-        "Received webhook request"
-    );
-
     // Check if endpoint exists
     let endpoints = state.endpoints.read().await;
     let endpoint_config = endpoints
@@ -123,35 +250,164 @@ async fn webhook_handler(
         }
     }
 
-    // Check body size
-    let max_size = state.config.server.max_body_size;
-    if body.len() > max_size {
-        return Err(AppError::PayloadTooLarge(format!(
-            "Payload size {} exceeds maximum {}",
-            body.len(),
-            max_size
-        )));
+    // Read the body up to the endpoint's size limit
+    let max_size = endpoint_config
+        .max_body_size
+        .unwrap_or(state.config.server.max_body_size);
+    let body = axum::body::to_bytes(body, max_size).await.map_err(|e| {
+        AppError::PayloadTooLarge(format!(
+            "Failed to read payload (maximum {} bytes): {}",
+            max_size, e
+        ))
+    })?;
+
+    tracing::debug!(
+        endpoint = %endpoint_path,
+        body_size = body.len(),
+        "Received webhook request"
+    );
+
+    // Slack verifies a new Request URL by sending a (signed) challenge to echo back
+    if state.config.auth.auth_type == AuthType::Slack {
+        if let Some(challenge) = slack_challenge(&body) {
+            tracing::info!(endpoint = %endpoint_path, "Answered Slack URL verification");
+            return Ok((StatusCode::OK, Json(json!({ "challenge": challenge }))).into_response());
+        }
+    }
+
+    // Reject bodies not matching the endpoint's JSON Schema
+    if let Some(validator) = state.validators.get(&endpoint_path) {
+        if let Err(errors) = validator.validate(&body) {
+            tracing::warn!(
+                endpoint = %endpoint_path,
+                errors = ?errors,
+                "Webhook failed schema validation"
+            );
+            let payload = WebhookConnector::decode_payload(&body);
+            if let Some(dead_letter) = validator.dead_letter(&endpoint_path, payload, &errors) {
+                if let Err(e) = state.message_tx.send(dead_letter.into_record()).await {
+                    tracing::error!(
+                        endpoint = %endpoint_path,
+                        error = ?e,
+                        "Failed to send invalid webhook to dead-letter topic"
+                    );
+                }
+            }
+            return Err(AppError::InvalidPayload(errors));
+        }
     }
 
-    // Create SourceRecord from webhook data
-    let source_record = WebhookConnector::create_source_record(
-        &endpoint_config,
+    // Capture query parameters (added to the payload before routing, so they can pick the topic)
+    let mut payload = WebhookConnector::decode_payload(&body);
+    let query_params = match &endpoint_config.query_params {
+        Some(query_config) => {
+            let params = query_config.capture(&query);
+            if let (Some(field), Some(object)) =
+                (&query_config.payload_field, payload.as_object_mut())
+            {
+                object.insert(field.clone(), json!(params));
+            }
+            params
+        }
+        None => BTreeMap::new(),
+    };
+
+    // Pick the topic from the payload (content-based routing) or the route
+    let topic = endpoint_config.topic_for(&payload);
+
+    // Async acceptance: answer with a delivery ID before the webhook is published
+    let delivery_id = endpoint_config
+        .accept_async
+        .then(|| uuid::Uuid::new_v4().to_string());
+    let default_status = if delivery_id.is_some() {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+
+    // Render the route's custom response while the request fields are at hand
+    let custom_response = endpoint_config.response.as_ref().map(|response_config| {
+        let fields = response::RequestFields {
+            endpoint: &endpoint_path,
+            topic: &topic,
+            delivery_id: delivery_id.as_deref(),
+            payload: &payload,
+            query: &QueryParamsConfig::default().capture(&query),
+            headers: &header_map,
+        };
+        response::render(response_config, &fields, default_status)
+    });
+
+    // Create the event from webhook data
+    let mut event = WebhookConnector::create_event(
+        &topic,
         &state.config.core.connector_name,
         &endpoint_path,
-        body.to_vec(),
+        payload,
         &header_map,
         client_ip.as_deref(),
     );
+    for (name, value) in query_params {
+        event
+            .attributes
+            .insert(format!("webhook.query.{}", name), value);
+    }
 
-    // Send to channel for processing by runtime
-    state.message_tx.send(source_record).await.map_err(|e| {
-        tracing::error!(
-            endpoint = %endpoint_path,
-            error = ?e,
-            "Failed to send webhook to channel"
-        );
-        AppError::Internal("Failed to queue webhook for processing".to_string())
-    })?;
+    if let Some(id) = &delivery_id {
+        event
+            .attributes
+            .insert(DELIVERY_ID_ATTRIBUTE.to_string(), id.clone());
+        state.deliveries.track(id);
+    }
+
+    if let (Some(id), None) = (delivery_id.clone(), &state.spool) {
+        // Hand the webhook to the runtime in the background, recording the outcome;
+        // shutdown waits for these sends after the server stops
+        let sender = state.message_tx.clone();
+        let deliveries = state.deliveries.clone();
+        let endpoint = endpoint_path.clone();
+        state.async_sends.spawn(async move {
+            let status = match sender.send(event.into_record()).await {
+                Ok(()) => DeliveryStatus::Delivered,
+                Err(e) => {
+                    tracing::error!(
+                        endpoint = %endpoint,
+                        delivery_id = %id,
+                        error = ?e,
+                        "Failed to send webhook to channel"
+                    );
+                    DeliveryStatus::Failed
+                }
+            };
+            deliveries.update(&id, status);
+        });
+    } else {
+        publish(&state, &endpoint_path, event)
+            .await
+            .inspect_err(|_| {
+                if let Some(id) = &delivery_id {
+                    state.deliveries.update(id, DeliveryStatus::Failed);
+                }
+            })?;
+    }
+
+    if let Some(response) = custom_response {
+        return Ok(response);
+    }
+
+    if let Some(id) = delivery_id {
+        return Ok((
+            default_status,
+            [(header::LOCATION, format!("/status/{}", id))],
+            Json(json!({
+                "status": "accepted",
+                "delivery_id": id,
+                "endpoint": endpoint_path,
+                "topic": topic,
+            })),
+        )
+            .into_response());
+    }
 
     // Return success
     Ok((
@@ -159,7 +415,89 @@ async fn webhook_handler(
         Json(json!({
             "status": "accepted",
             "endpoint": endpoint_path,
-            "topic": endpoint_config.to,
+            "topic": topic,
+        })),
+    )
+        .into_response())
+}
+
+/// Hand an event to the runtime: through the spool if enabled, otherwise directly
+pub(crate) async fn publish(
+    state: &AppState,
+    endpoint_path: &str,
+    event: WebhookEvent,
+) -> Result<(), AppError> {
+    if let Some(spool) = state.spool.clone() {
+        // Persist before acknowledging; the spool forwarder hands it to the runtime
+        let spooled = tokio::task::spawn_blocking(move || spool.append(event))
+            .await
+            .map_err(|e| AppError::Internal(format!("Spool task failed: {}", e)))?;
+        return spooled.map_err(|e| {
+            tracing::error!(
+                endpoint = %endpoint_path,
+                error = %e,
+                "Failed to spool webhook"
+            );
+            match e {
+                SpoolError::Full => {
+                    AppError::ServiceUnavailable("Too many webhooks pending".to_string())
+                }
+                SpoolError::Io(_) => {
+                    AppError::Internal("Failed to queue webhook for processing".to_string())
+                }
+            }
+        });
+    }
+
+    // Send to channel for processing by runtime
+    state
+        .message_tx
+        .send(event.into_record())
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                endpoint = %endpoint_path,
+                error = ?e,
+                "Failed to send webhook to channel"
+            );
+            AppError::Internal("Failed to queue webhook for processing".to_string())
+        })
+}
+
+/// Resolves once the connector shuts down
+pub(crate) async fn shutdown_signal(mut shutdown: watch::Receiver<bool>) {
+    // An error means the connector is gone, which is a shutdown too
+    let _ = shutdown.wait_for(|stopping| *stopping).await;
+}
+
+/// Request timeout middleware (an endpoint's timeout_seconds overrides the server's)
+async fn timeout_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let timeout = state.config.timeout_for(request.uri().path());
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => StatusCode::REQUEST_TIMEOUT.into_response(),
+    }
+}
+
+/// Delivery status handler - progress of an asynchronously accepted webhook
+async fn status_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    let status = state
+        .deliveries
+        .status(&id)
+        .ok_or_else(|| AppError::NotFound(format!("Unknown delivery ID: {}", id)))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "delivery_id": id,
+            "status": status,
         })),
     )
         .into_response())
@@ -187,8 +525,17 @@ async fn readiness_handler() -> impl IntoResponse {
     )
 }
 
+/// Challenge of a Slack `url_verification` request
+fn slack_challenge(body: &[u8]) -> Option<String> {
+    let payload: serde_json::Value = serde_json::from_slice(body).ok()?;
+    if payload.get("type")?.as_str()? != "url_verification" {
+        return None;
+    }
+    Some(payload.get("challenge")?.as_str()?.to_string())
+}
+
 /// Extract headers as HashMap
-fn extract_headers(headers: &HeaderMap) -> HashMap<String, String> {
+pub(crate) fn extract_headers(headers: &HeaderMap) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for (key, value) in headers.iter() {
         if let Ok(value_str) = value.to_str() {
@@ -199,7 +546,7 @@ fn extract_headers(headers: &HeaderMap) -> HashMap<String, String> {
 }
 
 /// Extract client IP from headers
-fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
     // Try X-Forwarded-For first
     if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(value) = forwarded.to_str() {
@@ -231,6 +578,8 @@ pub enum AppError {
     TooManyRequests(String),
     Internal(String),
     ServiceUnavailable(String),
+    /// Body failed the endpoint's JSON Schema validation
+    InvalidPayload(Vec<String>),
 }
 
 impl IntoResponse for AppError {
@@ -243,6 +592,17 @@ impl IntoResponse for AppError {
             AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            AppError::InvalidPayload(errors) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({
+                        "error": "Payload failed schema validation",
+                        "errors": errors,
+                        "timestamp": chrono::Utc::now().to_rfc3339(),
+                    })),
+                )
+                    .into_response();
+            }
         };
 
         (
@@ -275,4 +635,19 @@ mod tests {
         let ip = extract_client_ip(&headers);
         assert_eq!(ip, Some("192.168.1.2".to_string()));
     }
+
+    #[test]
+    fn test_slack_challenge() {
+        let body = br#"{"token": "t", "challenge": "3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P", "type": "url_verification"}"#;
+        assert_eq!(
+            slack_challenge(body).as_deref(),
+            Some("3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P")
+        );
+
+        assert_eq!(
+            slack_challenge(br#"{"type": "event_callback", "challenge": "x"}"#),
+            None
+        );
+        assert_eq!(slack_challenge(b"token=x&challenge=y"), None);
+    }
 }
@@ -0,0 +1,278 @@
+//! Write-ahead spool for accepted webhooks.
+//!
+//! With a spool configured, a webhook is appended to a local file before it is
+//! acknowledged, and a forwarder task hands spooled events to the source
+//! runtime in order, recording each delivery. Events acknowledged to the
+//! sender but not yet handed off survive a crash or restart and are forwarded
+//! when the connector starts again (at-least-once: an event handed off right
+//! before a crash may be published twice).
+//!
+//! The file holds one JSON entry per line: appended events and delivery marks.
+//! It is rewritten with only the pending events on startup and periodically.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use crate::config::SpoolConfig;
+use crate::connector::WebhookEvent;
+
+/// Delivery marks after which the file is rewritten without delivered events
+const COMPACT_EVERY: usize = 10_000;
+
+/// A line of the spool file (written with borrowed events)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Entry<E = WebhookEvent> {
+    /// An accepted event
+    Append { seq: u64, event: E },
+    /// The event was handed to the source runtime
+    Delivered { seq: u64 },
+}
+
+/// Spool error types
+#[derive(Debug)]
+pub enum SpoolError {
+    /// `max_pending` events are waiting to be forwarded
+    Full,
+    /// The spool file couldn't be written
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for SpoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpoolError::Full => write!(f, "spool is full"),
+            SpoolError::Io(e) => write!(f, "spool write failed: {}", e),
+        }
+    }
+}
+
+/// Durable FIFO of accepted webhook events
+pub struct Spool {
+    path: PathBuf,
+    sync: bool,
+    max_pending: usize,
+    inner: Mutex<Inner>,
+    /// Signalled when an event is appended
+    appended: Notify,
+}
+
+struct Inner {
+    file: File,
+    next_seq: u64,
+    /// Events not yet handed to the runtime, oldest first
+    pending: VecDeque<(u64, WebhookEvent)>,
+    delivered: usize,
+}
+
+impl Spool {
+    /// Open the spool, loading the events a previous run didn't deliver
+    pub fn open(config: &SpoolConfig) -> std::io::Result<Self> {
+        let path = PathBuf::from(&config.path);
+
+        let mut pending = BTreeMap::new();
+        let mut next_seq = 1;
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                // Skip lines torn by a crash mid-write
+                let Ok(entry) = serde_json::from_str::<Entry>(&line?) else {
+                    continue;
+                };
+                match entry {
+                    Entry::Append { seq, event } => {
+                        next_seq = next_seq.max(seq + 1);
+                        pending.insert(seq, event);
+                    }
+                    Entry::Delivered { seq } => {
+                        pending.remove(&seq);
+                    }
+                }
+            }
+        }
+
+        let pending: VecDeque<_> = pending.into_iter().collect();
+        let file = rewrite(&path, &pending)?;
+        Ok(Self {
+            path,
+            sync: config.sync,
+            max_pending: config.max_pending,
+            inner: Mutex::new(Inner {
+                file,
+                next_seq,
+                pending,
+                delivered: 0,
+            }),
+            appended: Notify::new(),
+        })
+    }
+
+    /// Durably append an accepted event (blocking: call from a blocking task)
+    pub fn append(&self, event: WebhookEvent) -> Result<(), SpoolError> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.pending.len() >= self.max_pending {
+            return Err(SpoolError::Full);
+        }
+
+        let seq = inner.next_seq;
+        write_entry(&mut inner.file, &Entry::Append { seq, event: &event })
+            .map_err(SpoolError::Io)?;
+        if self.sync {
+            inner.file.sync_data().map_err(SpoolError::Io)?;
+        }
+
+        inner.next_seq += 1;
+        inner.pending.push_back((seq, event));
+        drop(inner);
+
+        self.appended.notify_one();
+        Ok(())
+    }
+
+    /// The oldest pending event, waiting until there is one
+    pub async fn next(&self) -> (u64, WebhookEvent) {
+        loop {
+            if let Some((seq, event)) = self.inner.lock().unwrap().pending.front() {
+                return (*seq, event.clone());
+            }
+            self.appended.notified().await;
+        }
+    }
+
+    /// Record that the oldest pending event was handed to the runtime
+    pub fn delivered(&self, seq: u64) -> std::io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.pending.front().map(|(front, _)| *front) != Some(seq) {
+            return Ok(());
+        }
+        inner.pending.pop_front();
+
+        // Not synced: a lost mark only means the event is forwarded again
+        write_entry(&mut inner.file, &Entry::<&WebhookEvent>::Delivered { seq })?;
+
+        inner.delivered += 1;
+        if inner.delivered >= COMPACT_EVERY {
+            inner.file = rewrite(&self.path, &inner.pending)?;
+            inner.delivered = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Number of events waiting to be forwarded
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().pending.len()
+    }
+}
+
+fn write_entry(file: &mut impl Write, entry: &Entry<&WebhookEvent>) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+/// Replace the file with the pending events, returning it opened for appending
+fn rewrite(path: &Path, pending: &VecDeque<(u64, WebhookEvent)>) -> std::io::Result<File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    {
+        let mut file = std::io::BufWriter::new(File::create(&tmp)?);
+        for (seq, event) in pending {
+            write_entry(&mut file, &Entry::Append { seq: *seq, event })?;
+        }
+        file.into_inner()?.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+
+    OpenOptions::new().append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(name: &str, max_pending: usize) -> SpoolConfig {
+        let path = std::env::temp_dir().join(format!(
+            "danube-webhook-spool-{}-{}.log",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        SpoolConfig {
+            path: path.to_string_lossy().into_owned(),
+            sync: true,
+            max_pending,
+        }
+    }
+
+    fn event(n: u64) -> WebhookEvent {
+        WebhookEvent {
+            topic: "/webhooks/test".to_string(),
+            payload: json!({ "n": n }),
+            attributes: BTreeMap::from([("webhook.endpoint".to_string(), "/test".to_string())]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_events_survive_restart() {
+        let config = config("restart", 10);
+
+        let spool = Spool::open(&config).unwrap();
+        for n in 1..=3 {
+            spool.append(event(n)).unwrap();
+        }
+        let (seq, first) = spool.next().await;
+        assert_eq!(first, event(1));
+        spool.delivered(seq).unwrap();
+        drop(spool);
+
+        // A crash mid-write leaves a torn line
+        let mut file = OpenOptions::new().append(true).open(&config.path).unwrap();
+        file.write_all(br#"{"op":"append","seq":4,"ev"#).unwrap();
+        drop(file);
+
+        let spool = Spool::open(&config).unwrap();
+        assert_eq!(spool.len(), 2);
+        let (seq, second) = spool.next().await;
+        assert_eq!(second, event(2));
+        spool.delivered(seq).unwrap();
+
+        // Sequence numbers continue after the loaded ones
+        spool.append(event(5)).unwrap();
+        drop(spool);
+        let spool = Spool::open(&config).unwrap();
+        assert_eq!(spool.next().await.1, event(3));
+        assert_eq!(spool.len(), 2);
+
+        std::fs::remove_file(&config.path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bounded_and_waits_for_events() {
+        let config = config("bounded", 1);
+        let spool = std::sync::Arc::new(Spool::open(&config).unwrap());
+
+        let next = tokio::spawn({
+            let spool = spool.clone();
+            async move { spool.next().await }
+        });
+        tokio::task::yield_now().await;
+
+        spool.append(event(1)).unwrap();
+        assert!(matches!(spool.append(event(2)), Err(SpoolError::Full)));
+        assert_eq!(next.await.unwrap().1, event(1));
+
+        std::fs::remove_file(&config.path).unwrap();
+    }
+}
@@ -0,0 +1,394 @@
+//! Server-Sent Events (SSE) client.
+//!
+//! Each `[[sse]]` entry keeps a connection to an event stream open and
+//! publishes its events to the stream's Danube topic. When the connection
+//! drops (or stays silent past the idle timeout), the client reconnects with
+//! exponential backoff, sending the last event ID as `Last-Event-ID` so the
+//! server can resume where the stream stopped. The ID can be kept in a state
+//! file across restarts; it is written after the events are handed to the
+//! runtime, so events are published at least once.
+
+use danube_connect_core::{SourceRecord, SourceSender};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::config::SseConfig;
+
+/// Minimum time between two writes of the state file while streaming
+const STATE_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A dispatched event
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    /// Event type (`message` unless the `event` field is set)
+    pub event_type: String,
+    /// Data lines, joined with newlines
+    pub data: String,
+    /// Last event ID when the event was dispatched
+    pub id: Option<String>,
+}
+
+/// Incremental parser of the `text/event-stream` format
+#[derive(Debug, Default)]
+pub struct EventParser {
+    /// Bytes of an incomplete line
+    buffer: Vec<u8>,
+    event_type: String,
+    data: String,
+    last_event_id: Option<String>,
+    /// Reconnection time requested by the server
+    retry: Option<Duration>,
+    /// Whether the start of the stream (and a possible byte order mark) was seen
+    started: bool,
+}
+
+impl EventParser {
+    /// Parser resuming from an event ID
+    pub fn new(last_event_id: Option<String>) -> Self {
+        Self {
+            last_event_id,
+            ..Self::default()
+        }
+    }
+
+    /// Parse a chunk of the stream, returning the events it completes
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        if !self.started {
+            if self.buffer.len() < 3 && b"\xEF\xBB\xBF".starts_with(&self.buffer) {
+                return Vec::new();
+            }
+            if self.buffer.starts_with(b"\xEF\xBB\xBF") {
+                self.buffer.drain(..3);
+            }
+            self.started = true;
+        }
+
+        // Lines end with CRLF, LF or CR
+        let mut events = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < self.buffer.len() {
+            let next = match self.buffer[i] {
+                b'\n' => i + 1,
+                // Wait for the next chunk to tell CR from CRLF
+                b'\r' if i + 1 == self.buffer.len() => break,
+                b'\r' if self.buffer[i + 1] == b'\n' => i + 2,
+                b'\r' => i + 1,
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let line = String::from_utf8_lossy(&self.buffer[start..i]).into_owned();
+            events.extend(self.process_line(&line));
+            start = next;
+            i = next;
+        }
+        self.buffer.drain(..start);
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        // Comments (often keep-alives)
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(millis) = value.parse() {
+                    self.retry = Some(Duration::from_millis(millis));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event_type = std::mem::take(&mut self.event_type);
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+
+        Some(SseEvent {
+            event_type: if event_type.is_empty() {
+                "message".to_string()
+            } else {
+                event_type
+            },
+            data,
+            id: self.last_event_id.clone(),
+        })
+    }
+}
+
+/// Progress of a stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SseState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_event_id: Option<String>,
+}
+
+impl SseState {
+    /// Load the state file, or start fresh
+    fn load(path: &str) -> Self {
+        match std::fs::read(path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid SSE state file {}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save(&self, path: &str) -> std::io::Result<()> {
+        let path = Path::new(path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+enum SseError {
+    /// The connection failed or dropped (reconnected after a delay)
+    Stream(String),
+    /// The source runtime is gone
+    Closed,
+}
+
+/// Connection state carried across reconnections
+struct Session {
+    last_event_id: Option<String>,
+    /// Base reconnection delay (the server's `retry`, or `reconnect_secs`)
+    retry: Duration,
+    /// Events published on the current connection
+    published: u64,
+    saved: SseState,
+    saved_at: Instant,
+}
+
+impl Session {
+    /// Persist the last event ID if it changed (at most every STATE_SAVE_INTERVAL unless forced)
+    fn save(&mut self, stream: &SseConfig, force: bool) {
+        let Some(path) = &stream.state_path else {
+            return;
+        };
+        let state = SseState {
+            last_event_id: self.last_event_id.clone(),
+        };
+        if state == self.saved || (!force && self.saved_at.elapsed() < STATE_SAVE_INTERVAL) {
+            return;
+        }
+        match state.save(path) {
+            Ok(()) => {
+                self.saved = state;
+                self.saved_at = Instant::now();
+            }
+            Err(e) => warn!(
+                "Failed to save state of SSE stream '{}': {}",
+                stream.name, e
+            ),
+        }
+    }
+}
+
+/// Spawn the task consuming an event stream until the source runtime is gone
+pub fn spawn_client(stream: SseConfig, sender: SourceSender) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        // No overall timeout: the response is a long-lived stream
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        let saved = stream
+            .state_path
+            .as_deref()
+            .map(SseState::load)
+            .unwrap_or_default();
+        let mut session = Session {
+            last_event_id: saved.last_event_id.clone(),
+            retry: Duration::from_secs(stream.reconnect_secs),
+            published: 0,
+            saved,
+            saved_at: Instant::now(),
+        };
+        let max_delay = Duration::from_secs(stream.max_reconnect_secs);
+        let mut delay = session.retry;
+
+        loop {
+            info!(
+                "Connecting to SSE stream '{}' at {} (last event ID: {:?})",
+                stream.name, stream.url, session.last_event_id
+            );
+            session.published = 0;
+            let result = consume(&client, &stream, &sender, &mut session).await;
+            session.save(&stream, true);
+
+            match result {
+                Ok(()) => info!("SSE stream '{}' ended", stream.name),
+                Err(SseError::Closed) => {
+                    info!(
+                        "Source runtime stopped, ending SSE stream '{}'",
+                        stream.name
+                    );
+                    return;
+                }
+                Err(SseError::Stream(e)) => warn!("SSE stream '{}' failed: {}", stream.name, e),
+            }
+
+            // Back off while connections keep failing without events
+            if session.published > 0 {
+                delay = session.retry;
+            }
+            debug!(
+                "Reconnecting to SSE stream '{}' in {:?}",
+                stream.name, delay
+            );
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(max_delay.max(session.retry));
+        }
+    })
+}
+
+/// Read one connection to the stream, publishing its events
+async fn consume(
+    client: &reqwest::Client,
+    stream: &SseConfig,
+    sender: &SourceSender,
+    session: &mut Session,
+) -> Result<(), SseError> {
+    let request_error = |e: reqwest::Error| SseError::Stream(e.to_string());
+
+    let mut request = client
+        .get(&stream.url)
+        .header(header::ACCEPT, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache");
+    for (name, value) in &stream.headers {
+        request = request.header(name, value);
+    }
+    if let Some(token_env) = &stream.bearer_token_env {
+        let token = std::env::var(token_env)
+            .map_err(|_| SseError::Stream(format!("Environment variable {} not set", token_env)))?;
+        request = request.bearer_auth(token);
+    }
+    if let Some(id) = &session.last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(request_error)?;
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with("text/event-stream") {
+        return Err(SseError::Stream(format!(
+            "unexpected content type '{}'",
+            content_type
+        )));
+    }
+
+    let mut parser = EventParser::new(session.last_event_id.clone());
+    let idle_timeout = Duration::from_secs(stream.idle_timeout_secs);
+    loop {
+        let chunk = tokio::time::timeout(idle_timeout, response.chunk())
+            .await
+            .map_err(|_| SseError::Stream(format!("no data for {:?}", idle_timeout)))?
+            .map_err(request_error)?;
+        let Some(chunk) = chunk else {
+            return Ok(());
+        };
+
+        for event in parser.feed(&chunk) {
+            if !stream.event_types.is_empty() && !stream.event_types.contains(&event.event_type) {
+                continue;
+            }
+
+            // JSON data is published as is, anything else as a string
+            let payload = serde_json::from_str(&event.data).unwrap_or(Value::String(event.data));
+            let mut record = SourceRecord::new(&stream.to, payload)
+                .with_attribute("sse.name", &stream.name)
+                .with_attribute("sse.url", &stream.url)
+                .with_attribute("sse.event", &event.event_type);
+            if let Some(id) = &event.id {
+                record = record.with_attribute("sse.id", id);
+            }
+            sender.send(record).await.map_err(|_| SseError::Closed)?;
+            session.published += 1;
+        }
+
+        // IDs also advance on events without data or of filtered types
+        session.last_event_id = parser.last_event_id.clone();
+        if let Some(retry) = parser.retry {
+            session.retry = retry;
+        }
+        session.save(stream, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let mut parser = EventParser::new(None);
+
+        // Split across chunks, mixed line endings, comments and multi-line data
+        assert!(parser
+            .feed(b"\xEF\xBB\xBF: keep-alive\n\nevent: order\r\nid: 1")
+            .is_empty());
+        let events = parser.feed(b"\r\ndata: {\"id\": 1,\ndata:\"total\": 5}\n\n");
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event_type: "order".to_string(),
+                data: "{\"id\": 1,\n\"total\": 5}".to_string(),
+                id: Some("1".to_string()),
+            }]
+        );
+
+        // The event type resets, the ID persists; CR alone ends a line
+        let events = parser.feed(b"retry: 2500\rdata: plain\r\r\n");
+        assert_eq!(events[0].event_type, "message");
+        assert_eq!(events[0].data, "plain");
+        assert_eq!(events[0].id.as_deref(), Some("1"));
+        assert_eq!(parser.retry, Some(Duration::from_millis(2500)));
+
+        // Events without data aren't dispatched, but their ID counts
+        assert!(parser.feed(b"id: 7\nevent: ping\n\n").is_empty());
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+
+        // An incomplete event waits for its blank line
+        assert!(parser.feed(b"data: x\n").is_empty());
+        assert_eq!(parser.feed(b"\n")[0].data, "x");
+    }
+}
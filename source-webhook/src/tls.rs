@@ -0,0 +1,118 @@
+//! TLS support for the webhook HTTP server.
+//!
+//! Loads the PEM certificate chain and private key configured in `[server]`
+//! and optionally watches them, reloading the served certificate when the
+//! files change (e.g., after a cert-manager or certbot renewal).
+
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Load the certificate and key into a rustls server configuration
+pub async fn load(cert_path: &str, key_path: &str) -> anyhow::Result<RustlsConfig> {
+    RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load TLS certificate {} / key {}: {}",
+                cert_path,
+                key_path,
+                e
+            )
+        })
+}
+
+/// Reload the certificate into `config` whenever the files change
+///
+/// Never returns; runs alongside the server. A failed reload (e.g., a renewal
+/// caught half-written) keeps serving the previous certificate and is
+/// retried on the next check.
+pub async fn watch(config: RustlsConfig, cert_path: String, key_path: String, interval: Duration) {
+    let mut files = CertificateFiles::new(&cert_path, &key_path);
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        if !files.changed() {
+            continue;
+        }
+
+        match config.reload_from_pem_file(&cert_path, &key_path).await {
+            Ok(()) => tracing::info!(cert = %cert_path, "Reloaded TLS certificate"),
+            Err(e) => {
+                tracing::warn!(cert = %cert_path, error = %e, "Failed to reload TLS certificate");
+                // Try again on the next tick even if the files don't change again
+                files.reset();
+            }
+        }
+    }
+}
+
+/// Modification times of the certificate and key files
+struct CertificateFiles {
+    paths: [PathBuf; 2],
+    modified: [Option<SystemTime>; 2],
+}
+
+impl CertificateFiles {
+    fn new(cert_path: &str, key_path: &str) -> Self {
+        let mut files = Self {
+            paths: [PathBuf::from(cert_path), PathBuf::from(key_path)],
+            modified: [None; 2],
+        };
+        files.changed();
+        files
+    }
+
+    /// Whether either file was modified since the last call
+    fn changed(&mut self) -> bool {
+        let modified = self.paths.clone().map(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        });
+        let changed = modified != self.modified;
+        self.modified = modified;
+        changed
+    }
+
+    /// Forget the modification times, so the next check reports a change
+    fn reset(&mut self) {
+        self.modified = [None; 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certificate_files_changed() {
+        let dir = std::env::temp_dir().join(format!("danube-webhook-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert = dir.join("cert.pem");
+        let key = dir.join("key.pem");
+        std::fs::write(&cert, "cert").unwrap();
+        std::fs::write(&key, "key").unwrap();
+
+        let mut files = CertificateFiles::new(cert.to_str().unwrap(), key.to_str().unwrap());
+        assert!(!files.changed());
+
+        // A renewal rewrites the key
+        let renewed = SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&key)
+            .unwrap()
+            .set_modified(renewed)
+            .unwrap();
+        assert!(files.changed());
+        assert!(!files.changed());
+
+        files.reset();
+        assert!(files.changed());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
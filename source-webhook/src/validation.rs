@@ -0,0 +1,154 @@
+//! Per-endpoint JSON Schema validation of webhook bodies.
+//!
+//! Endpoints with a `validation` section check each body against a JSON
+//! Schema before it is published. Invalid requests are rejected with a 400
+//! listing the validation errors, and optionally copied to the endpoint's
+//! dead-letter topic.
+
+use danube_connect_core::{ConnectorError, ConnectorResult};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::{EndpointConfig, SchemaValidation};
+use crate::connector::WebhookEvent;
+
+/// Compiled JSON Schema of an endpoint
+pub struct PayloadValidator {
+    #[cfg(feature = "schema-validation")]
+    schema: jsonschema::JSONSchema,
+    dead_letter_topic: Option<String>,
+}
+
+impl PayloadValidator {
+    /// Compile the schema of an endpoint's validation settings
+    #[cfg(feature = "schema-validation")]
+    pub fn new(validation: &SchemaValidation) -> ConnectorResult<Self> {
+        let schema = match (&validation.schema, &validation.schema_file) {
+            (Some(schema), _) => schema.clone(),
+            (None, Some(path)) => {
+                let content = std::fs::read_to_string(path).map_err(|e| {
+                    ConnectorError::config(format!("Failed to read schema '{}': {}", path, e))
+                })?;
+                serde_json::from_str(&content).map_err(|e| {
+                    ConnectorError::config(format!("Invalid JSON in schema '{}': {}", path, e))
+                })?
+            }
+            // Required by config validation
+            (None, None) => return Err(ConnectorError::config("Validation requires a schema")),
+        };
+
+        let schema = jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| ConnectorError::config(format!("Invalid JSON Schema: {}", e)))?;
+
+        Ok(Self {
+            schema,
+            dead_letter_topic: validation.dead_letter_topic.clone(),
+        })
+    }
+
+    #[cfg(not(feature = "schema-validation"))]
+    pub fn new(_validation: &SchemaValidation) -> ConnectorResult<Self> {
+        Err(ConnectorError::config(
+            "Route validation requires the connector built with the 'schema-validation' feature",
+        ))
+    }
+
+    /// Validate a request body, returning the validation errors if it is invalid
+    pub fn validate(&self, body: &[u8]) -> Result<(), Vec<String>> {
+        let payload = serde_json::from_slice::<Value>(body)
+            .map_err(|e| vec![format!("Body is not valid JSON: {}", e)])?;
+        self.validate_payload(&payload)
+    }
+
+    #[cfg(feature = "schema-validation")]
+    fn validate_payload(&self, payload: &Value) -> Result<(), Vec<String>> {
+        self.schema.validate(payload).map_err(|errors| {
+            errors
+                .map(|error| format!("{}: {}", error.instance_path, error))
+                .collect()
+        })
+    }
+
+    #[cfg(not(feature = "schema-validation"))]
+    fn validate_payload(&self, _payload: &Value) -> Result<(), Vec<String>> {
+        Ok(())
+    }
+
+    /// Dead-letter event for an invalid payload, or None without a dead-letter topic
+    pub fn dead_letter(
+        &self,
+        endpoint_path: &str,
+        payload: Value,
+        errors: &[String],
+    ) -> Option<WebhookEvent> {
+        let topic = self.dead_letter_topic.as_ref()?;
+        Some(WebhookEvent {
+            topic: topic.clone(),
+            payload: json!({
+                "endpoint": endpoint_path,
+                "errors": errors,
+                "payload": payload,
+            }),
+            attributes: BTreeMap::from([
+                ("webhook.endpoint".to_string(), endpoint_path.to_string()),
+                ("error".to_string(), "schema_validation".to_string()),
+                ("source".to_string(), "webhook".to_string()),
+            ]),
+        })
+    }
+}
+
+/// Compile the validators of all endpoints with validation, keyed by endpoint path
+pub fn compile_validators(
+    routes: &[EndpointConfig],
+) -> ConnectorResult<HashMap<String, PayloadValidator>> {
+    routes
+        .iter()
+        .filter_map(|endpoint| {
+            let validation = endpoint.validation.as_ref()?;
+            Some(
+                PayloadValidator::new(validation)
+                    .map(|validator| (endpoint.from.clone(), validator))
+                    .map_err(|e| {
+                        ConnectorError::config(format!("Route '{}': {}", endpoint.from, e))
+                    }),
+            )
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "schema-validation"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_body() {
+        let validator = PayloadValidator::new(&SchemaValidation {
+            schema: Some(json!({
+                "type": "object",
+                "required": ["event"],
+                "properties": {"amount": {"type": "integer"}}
+            })),
+            schema_file: None,
+            dead_letter_topic: Some("/webhooks/invalid".to_string()),
+        })
+        .unwrap();
+
+        assert!(validator
+            .validate(br#"{"event": "payment.succeeded", "amount": 100}"#)
+            .is_ok());
+
+        let errors = validator.validate(br#"{"amount": "100"}"#).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|error| error.starts_with("/amount")));
+
+        let errors = validator.validate(b"event=payment.succeeded").unwrap_err();
+        assert!(errors[0].starts_with("Body is not valid JSON"));
+
+        let dead_letter = validator
+            .dead_letter("/webhooks/payments", json!({}), &errors)
+            .unwrap();
+        assert_eq!(dead_letter.topic, "/webhooks/invalid");
+        assert_eq!(dead_letter.payload["errors"], json!(errors));
+    }
+}
@@ -0,0 +1,226 @@
+//! WebSocket ingestion.
+//!
+//! Routes with `websocket = true` also accept WebSocket connections (a `GET`
+//! upgrade on the route's path) from systems that stream events over one
+//! persistent connection instead of POSTing each of them. Every text or binary
+//! message is one event: it goes through the route's rate limit, schema
+//! validation, content-based routing and delivery (spool or direct) like a
+//! POSTed body, and is answered with a JSON acknowledgement. The IP allowlist,
+//! authentication and replay protection apply to the upgrade request. On
+//! shutdown, open connections are closed with `1001 Going Away`.
+
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, Query, Request, State,
+    },
+    response::Response,
+};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+use crate::config::EndpointConfig;
+use crate::connector::WebhookConnector;
+use crate::ip_filter;
+use crate::server::{self, AppError, AppState};
+
+/// Record attribute naming the transport of WebSocket events
+const TRANSPORT_ATTRIBUTE: &str = "webhook.transport";
+
+/// Connection state shared by the messages of a WebSocket
+struct Session {
+    state: AppState,
+    endpoint_path: String,
+    endpoint_config: EndpointConfig,
+    /// Headers of the upgrade request
+    header_map: HashMap<String, String>,
+    /// Client IP recorded on events (from proxy headers)
+    client_ip: Option<String>,
+    /// Client IP the rate limit applies to
+    rate_limit_ip: Option<IpAddr>,
+    query_params: BTreeMap<String, String>,
+}
+
+/// WebSocket handler - upgrades connections to routes with `websocket` enabled
+pub async fn websocket_handler(
+    State(state): State<AppState>,
+    Path(path): Path<String>,
+    Query(query): Query<Vec<(String, String)>>,
+    ws: WebSocketUpgrade,
+    request: Request,
+) -> Result<Response, AppError> {
+    let endpoint_path = format!("/{}", path);
+
+    let endpoints = state.endpoints.read().await;
+    let endpoint_config = endpoints
+        .get(&endpoint_path)
+        .filter(|endpoint| endpoint.websocket)
+        .ok_or_else(|| {
+            AppError::NotFound(format!("WebSocket endpoint not found: {}", endpoint_path))
+        })?
+        .clone();
+    drop(endpoints);
+
+    let max_size = endpoint_config
+        .max_body_size
+        .unwrap_or(state.config.server.max_body_size);
+    let session = Session {
+        rate_limit_ip: ip_filter::client_ip(&request, state.config.server.trust_proxy_headers),
+        client_ip: server::extract_client_ip(request.headers()),
+        header_map: server::extract_headers(request.headers()),
+        query_params: endpoint_config
+            .query_params
+            .as_ref()
+            .map(|query_config| query_config.capture(&query))
+            .unwrap_or_default(),
+        state,
+        endpoint_path,
+        endpoint_config,
+    };
+
+    tracing::info!(endpoint = %session.endpoint_path, "WebSocket connection opened");
+
+    Ok(ws
+        .max_message_size(max_size)
+        .on_upgrade(move |socket| handle_socket(socket, session)))
+}
+
+/// Publish the messages of a connection until it closes
+async fn handle_socket(mut socket: WebSocket, session: Session) {
+    let mut accepted = 0u64;
+    let stopping = server::shutdown_signal(session.state.shutdown.clone());
+    tokio::pin!(stopping);
+
+    loop {
+        let message = tokio::select! {
+            message = socket.recv() => message,
+            // Close the connection on shutdown, so the client reconnects elsewhere
+            () = &mut stopping => {
+                let close = CloseFrame {
+                    code: close_code::AWAY,
+                    reason: "Server shutting down".into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                break;
+            }
+        };
+        let Some(message) = message else {
+            break;
+        };
+        let body = match message {
+            Ok(Message::Text(text)) => Bytes::copy_from_slice(text.as_str().as_bytes()),
+            Ok(Message::Binary(data)) => data,
+            Ok(Message::Close(_)) => break,
+            // Pings are answered by the protocol layer
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::debug!(
+                    endpoint = %session.endpoint_path,
+                    error = %e,
+                    "WebSocket receive failed"
+                );
+                break;
+            }
+        };
+
+        let reply = match process_message(&session, &body).await {
+            Ok(topic) => {
+                accepted += 1;
+                json!({ "status": "accepted", "topic": topic })
+            }
+            Err(reply) => reply,
+        };
+        if socket
+            .send(Message::Text(reply.to_string().into()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+
+    tracing::info!(
+        endpoint = %session.endpoint_path,
+        accepted,
+        "WebSocket connection closed"
+    );
+}
+
+/// Publish one message, returning its topic, or the reply rejecting it
+async fn process_message(session: &Session, body: &[u8]) -> Result<String, Value> {
+    let state = &session.state;
+    let endpoint_path = &session.endpoint_path;
+
+    if state
+        .rate_limiter
+        .check(endpoint_path, session.rate_limit_ip)
+        .is_err()
+    {
+        tracing::warn!(
+            endpoint = %endpoint_path,
+            ip = ?session.rate_limit_ip,
+            "Rate limit exceeded"
+        );
+        return Err(json!({ "status": "rejected", "error": "Rate limit exceeded" }));
+    }
+
+    // Reject messages not matching the endpoint's JSON Schema
+    if let Some(validator) = state.validators.get(endpoint_path) {
+        if let Err(errors) = validator.validate(body) {
+            tracing::warn!(
+                endpoint = %endpoint_path,
+                errors = ?errors,
+                "WebSocket message failed schema validation"
+            );
+            let payload = WebhookConnector::decode_payload(body);
+            if let Some(dead_letter) = validator.dead_letter(endpoint_path, payload, &errors) {
+                if let Err(e) = state.message_tx.send(dead_letter.into_record()).await {
+                    tracing::error!(
+                        endpoint = %endpoint_path,
+                        error = ?e,
+                        "Failed to send invalid message to dead-letter topic"
+                    );
+                }
+            }
+            return Err(json!({
+                "status": "rejected",
+                "error": "Payload failed schema validation",
+                "errors": errors,
+            }));
+        }
+    }
+
+    let mut payload = WebhookConnector::decode_payload(body);
+    if let Some(query_config) = &session.endpoint_config.query_params {
+        if let (Some(field), Some(object)) = (&query_config.payload_field, payload.as_object_mut())
+        {
+            object.insert(field.clone(), json!(session.query_params));
+        }
+    }
+
+    let topic = session.endpoint_config.topic_for(&payload);
+    let mut event = WebhookConnector::create_event(
+        &topic,
+        &state.config.core.connector_name,
+        endpoint_path,
+        payload,
+        &session.header_map,
+        session.client_ip.as_deref(),
+    );
+    for (name, value) in &session.query_params {
+        event
+            .attributes
+            .insert(format!("webhook.query.{}", name), value.clone());
+    }
+    event
+        .attributes
+        .insert(TRANSPORT_ATTRIBUTE.to_string(), "websocket".to_string());
+
+    server::publish(state, endpoint_path, event).await.map_err(
+        |_| json!({ "status": "error", "error": "Failed to queue event for processing" }),
+    )?;
+
+    Ok(topic)
+}